@@ -1,18 +1,29 @@
 use std::time::Duration;
 
-use ark_serialize::CanonicalSerialize;
-use ark_std::test_rng;
-use criterion::{criterion_group, criterion_main, Criterion, Throughput};
-use mercurial_signature::{PublicKey, PublicParams, SecretKey, UniformRand, G1};
-use rand::Rng;
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use mercurial_signature::{
+    change_representation, Fr, InsecureTestRng, PublicKey, PublicParams, SecretKey, Signature,
+    SignatureBundle, UniformRand, G1,
+};
+use rand::{CryptoRng, Rng};
 
 criterion_group! {
     name = signature;
     config = Criterion::default().sample_size(10).measurement_time(Duration::from_secs(2));
-    targets = bench_sign, bench_verify,
+    targets = bench_sign, bench_verify, bench_affine_message, bench_batch_convert, bench_batch_convert_public_keys,
 }
 
-criterion_main!(signature,);
+// the operations performed constantly by a credential wallet
+criterion_group! {
+    name = wallet;
+    config = Criterion::default().sample_size(10).measurement_time(Duration::from_secs(2));
+    targets = bench_convert, bench_change_representation, bench_key_gen, bench_serialize_public_key,
+        bench_load_signature_bundle,
+}
+
+criterion_main!(signature, wallet);
 
 fn bench_sign(c: &mut Criterion) {
     let mut rng = test_rng();
@@ -25,7 +36,7 @@ fn bench_sign(c: &mut Criterion) {
         group.throughput(Throughput::Bytes(message_size as u64));
 
         group.bench_with_input(format!("size={}", size), &size, |b, _| {
-            b.iter(|| sk.sign(&mut rng, &pp, &message.as_ref()))
+            b.iter(|| sk.sign(&mut rng, &pp, message.as_ref()))
         });
     }
 }
@@ -42,12 +53,210 @@ fn bench_verify(c: &mut Criterion) {
         group.throughput(Throughput::Bytes(message_size as u64));
 
         group.bench_with_input(format!("size={}", size), &size, |b, _| {
-            b.iter(|| pk.verify(&pp, &message.as_ref(), &sig))
+            b.iter(|| pk.verify(&pp, message.as_ref(), &sig))
+        });
+
+        let prepared_bx = pk.prepared_elements();
+        group.bench_with_input(format!("prepared/size={}", size), &size, |b, _| {
+            b.iter(|| pk.verify_with_prepared_g2(&pp, message.as_ref(), &sig, &prepared_bx))
+        });
+    }
+}
+
+// the caller already has affine points, e.g. deserialized from a credential
+fn bench_affine_message(c: &mut Criterion) {
+    let mut rng = test_rng();
+
+    let mut group = c.benchmark_group("bench_affine_message");
+    for size in [10, 100] {
+        let (pp, pk, sk, message) = setup(&mut rng, size);
+        let message = G1::normalize_batch(&message);
+        let sig = sk.sign_affine(&mut rng, &pp, &message);
+
+        group.bench_function(format!("sign,projective,size={}", size), |b| {
+            b.iter(|| {
+                let message = message.iter().map(|m| m.into_group()).collect::<Vec<G1>>();
+                sk.sign(&mut rng, &pp, &message)
+            })
+        });
+        group.bench_function(format!("sign,affine,size={}", size), |b| {
+            b.iter(|| sk.sign_affine(&mut rng, &pp, &message))
+        });
+        group.bench_function(format!("verify,projective,size={}", size), |b| {
+            b.iter(|| {
+                let message = message.iter().map(|m| m.into_group()).collect::<Vec<G1>>();
+                pk.verify(&pp, &message, &sig)
+            })
+        });
+        group.bench_function(format!("verify,affine,size={}", size), |b| {
+            b.iter(|| pk.verify_affine(&pp, &message, &sig))
+        });
+    }
+}
+
+fn bench_batch_convert(c: &mut Criterion) {
+    let mut rng = test_rng();
+
+    let mut group = c.benchmark_group("bench_batch_convert");
+    let pp = PublicParams::new(&mut rng);
+    let mut keys = (0..1000)
+        .map(|_| pp.key_gen(&mut rng, 10).1)
+        .collect::<Vec<SecretKey>>();
+    let p = Fr::rand(&mut rng);
+
+    group.bench_function("keys=1000,size=10,individual", |b| {
+        b.iter(|| keys.iter_mut().for_each(|key| key.convert(p)))
+    });
+    group.bench_function("keys=1000,size=10,batch", |b| {
+        b.iter(|| SecretKey::batch_convert(&mut keys, p))
+    });
+}
+
+fn bench_batch_convert_public_keys(c: &mut Criterion) {
+    let mut rng = test_rng();
+
+    let mut group = c.benchmark_group("bench_batch_convert_public_keys");
+    let pp = PublicParams::new(&mut rng);
+    let mut keys = (0..1000)
+        .map(|_| pp.key_gen(&mut rng, 10).0)
+        .collect::<Vec<PublicKey>>();
+    let p = Fr::rand(&mut rng);
+
+    group.bench_function("keys=1000,size=10,individual", |b| {
+        b.iter(|| keys.iter_mut().for_each(|key| key.convert(p)))
+    });
+    group.bench_function("keys=1000,size=10,batch", |b| {
+        b.iter(|| PublicKey::batch_convert(&mut keys, p))
+    });
+}
+
+fn bench_convert(c: &mut Criterion) {
+    let mut rng = test_rng();
+
+    let mut group = c.benchmark_group("bench_convert");
+    for size in [10, 100, 1000] {
+        let (_, mut pk, mut sk, _) = setup(&mut rng, size);
+        let p = Fr::rand(&mut rng);
+        group.throughput(Throughput::Elements(size as u64));
+
+        group.bench_function(format!("public_key,size={}", size), |b| {
+            b.iter(|| pk.convert(p))
+        });
+        group.bench_function(format!("secret_key,size={}", size), |b| {
+            b.iter(|| sk.convert(p))
+        });
+    }
+
+    // the conversion of a signature does not depend on the length of the message
+    let (pp, _, sk, message) = setup(&mut rng, 10);
+    let mut sig = sk.sign(&mut rng, &pp, &message);
+    let p = Fr::rand(&mut rng);
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("signature", |b| b.iter(|| sig.convert(&mut rng, p)));
+}
+
+fn bench_change_representation(c: &mut Criterion) {
+    let mut rng = test_rng();
+
+    let mut group = c.benchmark_group("bench_change_representation");
+    for size in [10, 100, 1000] {
+        let (pp, _, sk, message) = setup(&mut rng, size);
+        let sig = sk.sign(&mut rng, &pp, &message);
+        let u = Fr::rand(&mut rng);
+        group.throughput(Throughput::Elements(size as u64));
+
+        group.bench_function(format!("size={}", size), |b| {
+            b.iter_batched_ref(
+                || (message.clone(), sig.clone(), test_rng()),
+                |(message, sig, rng)| change_representation(rng, message, sig, u),
+                BatchSize::SmallInput,
+            )
         });
     }
 }
 
-fn setup(rng: &mut impl Rng, size: u32) -> (PublicParams, PublicKey, SecretKey, Vec<G1>) {
+fn bench_key_gen(c: &mut Criterion) {
+    let mut rng = test_rng();
+
+    let mut group = c.benchmark_group("bench_key_gen");
+    let pp = PublicParams::new(&mut rng);
+    for size in [10, 1000, 10000] {
+        group.throughput(Throughput::Elements(size as u64));
+
+        group.bench_function(format!("size={}", size), |b| {
+            b.iter(|| pp.key_gen(&mut rng, size))
+        });
+    }
+}
+
+fn bench_serialize_public_key(c: &mut Criterion) {
+    let mut rng = test_rng();
+
+    let mut group = c.benchmark_group("bench_serialize_public_key");
+    let (_, pk, _, _) = setup(&mut rng, 100);
+    for (name, compress) in [
+        ("compressed", Compress::Yes),
+        ("uncompressed", Compress::No),
+    ] {
+        let mut bytes = Vec::new();
+        pk.serialize_with_mode(&mut bytes, compress).unwrap();
+        group.throughput(Throughput::Bytes(bytes.len() as u64));
+
+        group.bench_function(format!("serialize,{},size=100", name), |b| {
+            b.iter(|| {
+                let mut bytes = Vec::with_capacity(bytes.len());
+                pk.serialize_with_mode(&mut bytes, compress).unwrap();
+                bytes
+            })
+        });
+        group.bench_function(format!("deserialize,{},size=100", name), |b| {
+            b.iter(|| PublicKey::deserialize_with_mode(bytes.as_slice(), compress, Validate::Yes))
+        });
+    }
+}
+
+fn bench_load_signature_bundle(c: &mut Criterion) {
+    let mut rng = test_rng();
+
+    let mut group = c.benchmark_group("bench_load_signature_bundle");
+    let (pp, _, sk, message) = setup(&mut rng, 10);
+    let sigs = (0..1000)
+        .map(|_| sk.sign(&mut rng, &pp, &message))
+        .collect::<Vec<Signature>>();
+    group.throughput(Throughput::Elements(sigs.len() as u64));
+
+    let individual = sigs
+        .iter()
+        .map(|sig| {
+            let mut bytes = Vec::new();
+            sig.serialize_compressed(&mut bytes).unwrap();
+            bytes
+        })
+        .collect::<Vec<_>>();
+    group.bench_function("individual,signatures=1000", |b| {
+        b.iter(|| {
+            individual
+                .iter()
+                .map(|bytes| Signature::deserialize_compressed(bytes.as_slice()).unwrap())
+                .collect::<Vec<_>>()
+        })
+    });
+
+    let bundle = sigs.into_iter().collect::<SignatureBundle>().to_bytes();
+    group.bench_function("bundle,signatures=1000", |b| {
+        b.iter(|| SignatureBundle::from_bytes(&bundle).unwrap())
+    });
+}
+
+/// A deterministic generator, so that the inputs are the same before and after a change.
+fn test_rng() -> InsecureTestRng<impl Rng> {
+    InsecureTestRng(ark_std::test_rng())
+}
+
+fn setup(
+    rng: &mut (impl Rng + CryptoRng),
+    size: u32,
+) -> (PublicParams, PublicKey, SecretKey, Vec<G1>) {
     let pp = PublicParams::new(rng);
     let (pk, sk) = pp.key_gen(rng, size);
     let message = (0..size).map(|_| G1::rand(rng)).collect::<Vec<G1>>();
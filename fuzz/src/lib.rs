@@ -0,0 +1,139 @@
+//! Checks of the fuzz targets. They are shared with the tests of the crate, which replay the
+//! inputs under `tests/fuzz_corpus/<target>/`.
+
+use std::sync::OnceLock;
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
+use mercurial_signature::{
+    fixtures::Fixture, AggregateSignature, CoSignature, CoSigners, ConversionCommitment,
+    ConversionPass, KeyShare, OpeningKey, OpeningProof, PartialSignature, ProofOfPossession,
+    PublicKey, PublicKeyG1, PublicParams, RandomizedMessage, RegistrationRecord,
+    RepresentationChangeProof, Round1Message, Round2Message, SameMessageProof, SecretKey, ShareA,
+    ShareB, Signature, SignatureG2, SignedClaims, SplitRound1, SplitRound2, TaggedSignature,
+    TokenIssuanceRequest, TokenIssuanceResponse, TokenRedemption, VerificationShare, VerifyWitness,
+    G1,
+};
+
+/// Deserialize the bytes after the first one as the type selected by the first byte. An
+/// accepted object must be valid, and its serialization must deserialize to the same
+/// serialization again.
+pub fn deserialize(data: &[u8]) {
+    let Some((selector, bytes)) = data.split_first() else {
+        return;
+    };
+    macro_rules! dispatch {
+        ($($ty:ty),* $(,)?) => {{
+            let checks: &[fn(&[u8])] = &[$(check_deserialize::<$ty>),*];
+            checks[*selector as usize % checks.len()](bytes)
+        }};
+    }
+    dispatch!(
+        PublicParams,
+        PublicKey,
+        SecretKey,
+        Signature,
+        PublicKeyG1,
+        SignatureG2,
+        TaggedSignature,
+        AggregateSignature,
+        PartialSignature,
+        RepresentationChangeProof,
+        SameMessageProof,
+        ShareA,
+        ShareB,
+        SplitRound1,
+        SplitRound2,
+        ConversionCommitment,
+        ConversionPass,
+        ProofOfPossession,
+        CoSigners,
+        CoSignature,
+        VerifyWitness,
+        TokenIssuanceRequest,
+        TokenIssuanceResponse,
+        TokenRedemption,
+        OpeningKey,
+        RegistrationRecord,
+        OpeningProof,
+        KeyShare,
+        VerificationShare,
+        Round1Message,
+        Round2Message,
+        RandomizedMessage,
+    );
+}
+
+fn check_deserialize<T: CanonicalSerialize + CanonicalDeserialize>(bytes: &[u8]) {
+    for compress in [Compress::Yes, Compress::No] {
+        let Ok(value) = T::deserialize_with_mode(bytes, compress, Validate::Yes) else {
+            continue;
+        };
+        assert!(value.check().is_ok());
+        let encoded = encode(&value, compress);
+        let decoded = T::deserialize_with_mode(encoded.as_slice(), compress, Validate::Yes)
+            .expect("the serialization of an accepted object is accepted");
+        assert_eq!(encode(&decoded, compress), encoded);
+    }
+}
+
+/// Flip bits of a valid signature and message, chosen by pairs of (position, mask), and verify.
+/// The verification must not panic, and must accept only the original signature and message.
+pub fn verify_mutation(data: &[u8]) {
+    let fixture = fixture();
+    let sig = encode(&fixture.sig, Compress::Yes);
+    let message = fixture
+        .message
+        .iter()
+        .map(|m| encode(m, Compress::Yes))
+        .collect::<Vec<_>>();
+    let original = [sig.as_slice(), &message.concat()].concat();
+
+    let mut bytes = original.clone();
+    for pair in data.chunks_exact(2) {
+        let position = pair[0] as usize * bytes.len() / 256;
+        bytes[position] ^= pair[1];
+    }
+
+    let (sig_bytes, message_bytes) = bytes.split_at(sig.len());
+    let Ok(sig) = Signature::deserialize_compressed(sig_bytes) else {
+        return;
+    };
+    let Ok(message) = message_bytes
+        .chunks(message[0].len())
+        .map(G1::deserialize_compressed)
+        .collect::<Result<Vec<G1>, _>>()
+    else {
+        return;
+    };
+    // the message may also be shorter than the key
+    let length = data.len() % (message.len() + 1);
+    if fixture.pk.verify(&fixture.pp, &message[..length], &sig) {
+        assert_eq!(length, message.len());
+        assert_eq!(bytes, original);
+    }
+}
+
+/// Decode the text formats: `did:key` identifiers and compact claims tokens. A decoded object
+/// must encode to text that decodes to the same object.
+pub fn envelopes(data: &[u8]) {
+    let Ok(text) = core::str::from_utf8(data) else {
+        return;
+    };
+    if let Ok(pk) = PublicKey::from_did_key(text) {
+        assert!(PublicKey::from_did_key(&pk.to_did_key()).unwrap() == pk);
+    }
+    if let Ok(signed) = SignedClaims::from_compact(text) {
+        assert!(SignedClaims::from_compact(&signed.to_compact()).unwrap() == signed);
+    }
+}
+
+fn fixture() -> &'static Fixture {
+    static FIXTURE: OnceLock<Fixture> = OnceLock::new();
+    FIXTURE.get_or_init(|| Fixture::with_length(0, 3))
+}
+
+fn encode<T: CanonicalSerialize>(value: &T, compress: Compress) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    value.serialize_with_mode(&mut bytes, compress).unwrap();
+    bytes
+}
@@ -0,0 +1,276 @@
+#ifndef MERCURIAL_SIGNATURE_H
+#define MERCURIAL_SIGNATURE_H
+
+/* Generated by cbindgen from src/ffi.rs. Do not edit; regenerate with `cargo test --features ffi --test ffi`. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Status code returned by the functions. The reason of an error is available from
+ * [msig_last_error_message].
+ */
+typedef enum MsigStatus {
+  /**
+   * Success.
+   */
+  MSIG_STATUS_OK = 0,
+  /**
+   * A required pointer is null.
+   */
+  MSIG_STATUS_NULL_POINTER = 1,
+  /**
+   * An input cannot be deserialized or is not valid for the operation.
+   */
+  MSIG_STATUS_INVALID_INPUT = 2,
+  /**
+   * The output buffer is too small. The required length is written to `out_len`.
+   */
+  MSIG_STATUS_BUFFER_TOO_SMALL = 3,
+} MsigStatus;
+
+/**
+ * Public parameters.
+ */
+typedef struct MsigParams MsigParams;
+
+/**
+ * Public key.
+ */
+typedef struct MsigPublicKey MsigPublicKey;
+
+/**
+ * Secret key.
+ */
+typedef struct MsigSecretKey MsigSecretKey;
+
+/**
+ * Signature.
+ */
+typedef struct MsigSignature MsigSignature;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * The message of the last error on the current thread, or an empty string. The string is
+ * owned by the library and valid until the next call on the same thread.
+ */
+const char *msig_last_error_message(void);
+
+/**
+ * Derive the public parameters from a seed, see [PublicParams::derive_from_ceremony_transcript].
+ *
+ * ## Safety
+ * `seed` must point to `seed_len` readable bytes and `out` to a writable pointer.
+ */
+enum MsigStatus msig_params_new_from_seed(const uint8_t *seed,
+                                          size_t seed_len,
+                                          struct MsigParams **out);
+
+/**
+ * Generate a key pair for messages of `size` elements.
+ *
+ * ## Safety
+ * `params` must be a valid pointer, and `out_pk` and `out_sk` writable pointers.
+ */
+enum MsigStatus msig_keygen(const struct MsigParams *params,
+                            uint32_t size,
+                            struct MsigPublicKey **out_pk,
+                            struct MsigSecretKey **out_sk);
+
+/**
+ * Sign the message.
+ *
+ * ## Safety
+ * `params` and `sk` must be valid pointers, `message` must point to `message_len` readable
+ * bytes and `out` to a writable pointer.
+ */
+enum MsigStatus msig_sign(const struct MsigParams *params,
+                          const struct MsigSecretKey *sk,
+                          const uint8_t *message,
+                          size_t message_len,
+                          struct MsigSignature **out);
+
+/**
+ * Verify the signature on the message. The result is written to `out_valid`.
+ *
+ * ## Safety
+ * `params`, `pk` and `sig` must be valid pointers, `message` must point to `message_len`
+ * readable bytes and `out_valid` to a writable bool.
+ */
+enum MsigStatus msig_verify(const struct MsigParams *params,
+                            const struct MsigPublicKey *pk,
+                            const uint8_t *message,
+                            size_t message_len,
+                            const struct MsigSignature *sig,
+                            bool *out_valid);
+
+/**
+ * Convert the public key by the scalar `p`.
+ *
+ * ## Safety
+ * `pk` must be a valid pointer and `p` must point to `p_len` readable bytes.
+ */
+enum MsigStatus msig_convert_pk(struct MsigPublicKey *pk, const uint8_t *p, size_t p_len);
+
+/**
+ * Convert the secret key by the scalar `p`.
+ *
+ * ## Safety
+ * `sk` must be a valid pointer and `p` must point to `p_len` readable bytes.
+ */
+enum MsigStatus msig_convert_sk(struct MsigSecretKey *sk, const uint8_t *p, size_t p_len);
+
+/**
+ * Convert the signature by the scalar `p`.
+ *
+ * ## Safety
+ * `sig` must be a valid pointer and `p` must point to `p_len` readable bytes.
+ */
+enum MsigStatus msig_convert_sig(struct MsigSignature *sig, const uint8_t *p, size_t p_len);
+
+/**
+ * Change the representation of the message and the signature by the scalar `u`. The new
+ * message is written to `out`, and the signature is changed in place only on success.
+ *
+ * ## Safety
+ * `sig` must be a valid pointer, `message` and `u` must point to `message_len` and `u_len`
+ * readable bytes, `out` to `out_cap` writable bytes and `out_len` to a writable length.
+ */
+enum MsigStatus msig_change_representation(const uint8_t *message,
+                                           size_t message_len,
+                                           struct MsigSignature *sig,
+                                           const uint8_t *u,
+                                           size_t u_len,
+                                           uint8_t *out,
+                                           size_t out_cap,
+                                           size_t *out_len);
+
+/**
+ * Serialize the public parameters.
+ *
+ * ## Safety
+ * `params` must be a valid pointer, `out` must point to `out_cap` writable bytes and
+ * `out_len` to a writable length.
+ */
+enum MsigStatus msig_params_to_bytes(const struct MsigParams *params,
+                                     uint8_t *out,
+                                     size_t out_cap,
+                                     size_t *out_len);
+
+/**
+ * Deserialize the public parameters.
+ *
+ * ## Safety
+ * `bytes` must point to `len` readable bytes and `out` to a writable pointer.
+ */
+enum MsigStatus msig_params_from_bytes(const uint8_t *bytes, size_t len, struct MsigParams **out);
+
+/**
+ * Serialize the public key.
+ *
+ * ## Safety
+ * `pk` must be a valid pointer, `out` must point to `out_cap` writable bytes and `out_len` to a
+ * writable length.
+ */
+enum MsigStatus msig_public_key_to_bytes(const struct MsigPublicKey *pk,
+                                         uint8_t *out,
+                                         size_t out_cap,
+                                         size_t *out_len);
+
+/**
+ * Deserialize the public key.
+ *
+ * ## Safety
+ * `bytes` must point to `len` readable bytes and `out` to a writable pointer.
+ */
+enum MsigStatus msig_public_key_from_bytes(const uint8_t *bytes,
+                                           size_t len,
+                                           struct MsigPublicKey **out);
+
+/**
+ * Serialize the secret key.
+ *
+ * ## Safety
+ * `sk` must be a valid pointer, `out` must point to `out_cap` writable bytes and `out_len` to a
+ * writable length.
+ */
+enum MsigStatus msig_secret_key_to_bytes(const struct MsigSecretKey *sk,
+                                         uint8_t *out,
+                                         size_t out_cap,
+                                         size_t *out_len);
+
+/**
+ * Deserialize the secret key.
+ *
+ * ## Safety
+ * `bytes` must point to `len` readable bytes and `out` to a writable pointer.
+ */
+enum MsigStatus msig_secret_key_from_bytes(const uint8_t *bytes,
+                                           size_t len,
+                                           struct MsigSecretKey **out);
+
+/**
+ * Serialize the signature.
+ *
+ * ## Safety
+ * `sig` must be a valid pointer, `out` must point to `out_cap` writable bytes and `out_len` to
+ * a writable length.
+ */
+enum MsigStatus msig_signature_to_bytes(const struct MsigSignature *sig,
+                                        uint8_t *out,
+                                        size_t out_cap,
+                                        size_t *out_len);
+
+/**
+ * Deserialize the signature.
+ *
+ * ## Safety
+ * `bytes` must point to `len` readable bytes and `out` to a writable pointer.
+ */
+enum MsigStatus msig_signature_from_bytes(const uint8_t *bytes,
+                                          size_t len,
+                                          struct MsigSignature **out);
+
+/**
+ * Release the public parameters. A null pointer is ignored.
+ *
+ * ## Safety
+ * `params` must be null or created by this library and not released yet.
+ */
+void msig_params_free(struct MsigParams *params);
+
+/**
+ * Release the public key. A null pointer is ignored.
+ *
+ * ## Safety
+ * `pk` must be null or created by this library and not released yet.
+ */
+void msig_public_key_free(struct MsigPublicKey *pk);
+
+/**
+ * Release the secret key. A null pointer is ignored.
+ *
+ * ## Safety
+ * `sk` must be null or created by this library and not released yet.
+ */
+void msig_secret_key_free(struct MsigSecretKey *sk);
+
+/**
+ * Release the signature. A null pointer is ignored.
+ *
+ * ## Safety
+ * `sig` must be null or created by this library and not released yet.
+ */
+void msig_signature_free(struct MsigSignature *sig);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* MERCURIAL_SIGNATURE_H */
@@ -0,0 +1,87 @@
+//! Deserialize and verify a signature without std. The inputs are placeholders, as the point
+//! is that the verification links for the target.
+
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use core::{
+    alloc::{GlobalAlloc, Layout},
+    cell::UnsafeCell,
+    hint::black_box,
+    panic::PanicInfo,
+    ptr::null_mut,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use alloc::vec::Vec;
+use ark_serialize::CanonicalDeserialize;
+use mercurial_signature::{PublicKey, PublicParams, Signature, G1};
+
+const HEAP_SIZE: usize = 64 * 1024;
+
+/// Bump allocator over a static heap. Memory is never freed, which is enough for one
+/// verification.
+struct BumpAllocator {
+    heap: UnsafeCell<[u8; HEAP_SIZE]>,
+    next: AtomicUsize,
+}
+
+unsafe impl Sync for BumpAllocator {}
+
+unsafe impl GlobalAlloc for BumpAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let base = self.heap.get() as usize;
+        let mut result = null_mut();
+        let _ = self
+            .next
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |next| {
+                let start = (base + next).next_multiple_of(layout.align()) - base;
+                let end = start.checked_add(layout.size())?;
+                if end > HEAP_SIZE {
+                    return None;
+                }
+                result = (base + start) as *mut u8;
+                Some(end)
+            });
+        result
+    }
+
+    unsafe fn dealloc(&self, _ptr: *mut u8, _layout: Layout) {}
+}
+
+#[global_allocator]
+static ALLOCATOR: BumpAllocator = BumpAllocator {
+    heap: UnsafeCell::new([0; HEAP_SIZE]),
+    next: AtomicUsize::new(0),
+};
+
+static PARAMS: [u8; 144] = [0; 144];
+static PUBLIC_KEY: [u8; 8 + 96 * 4] = [0; 8 + 96 * 4];
+static MESSAGE: [u8; 8 + 48 * 4] = [0; 8 + 48 * 4];
+static SIGNATURE: [u8; 192] = [0; 192];
+
+fn verify(pp: &[u8], pk: &[u8], message: &[u8], sig: &[u8]) -> Option<bool> {
+    let pp = PublicParams::deserialize_compressed(pp).ok()?;
+    let pk = PublicKey::deserialize_compressed(pk).ok()?;
+    let message = Vec::<G1>::deserialize_compressed(message).ok()?;
+    let sig = Signature::deserialize_compressed(sig).ok()?;
+    Some(pk.verify(&pp, &message, &sig))
+}
+
+#[no_mangle]
+pub extern "C" fn _start() -> ! {
+    black_box(verify(
+        black_box(&PARAMS),
+        black_box(&PUBLIC_KEY),
+        black_box(&MESSAGE),
+        black_box(&SIGNATURE),
+    ));
+    loop {}
+}
+
+#[panic_handler]
+fn panic(_info: &PanicInfo) -> ! {
+    loop {}
+}
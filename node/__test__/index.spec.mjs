@@ -0,0 +1,66 @@
+import assert from 'node:assert/strict'
+import { createRequire } from 'node:module'
+import { test } from 'node:test'
+
+const require = createRequire(import.meta.url)
+const {
+  PublicParams,
+  PublicKey,
+  Keypair,
+  Signature,
+  verify,
+  batchVerify,
+  randomScalar,
+  randomMessage,
+} = require('../index.js')
+
+test('sign, convert, change representation and verify', async () => {
+  const pp = PublicParams.fromBytes(new PublicParams().toBytes())
+  const keypair = new Keypair(pp, 5)
+  const message = randomMessage(5)
+  const sig = Signature.fromBytes(keypair.sign(pp, message).toBytes())
+  assert.equal(await verify(pp, keypair.publicKey, message, sig), true)
+
+  const p = randomScalar()
+  keypair.convert(p)
+  sig.convert(p)
+  assert.equal(await verify(pp, keypair.publicKey, message, sig), true)
+
+  const newMessage = sig.changeRepresentation(message, randomScalar())
+  assert.notDeepEqual(newMessage, message)
+  const pk = PublicKey.fromBytes(keypair.publicKey.toBytes())
+  assert.equal(await verify(pp, pk, newMessage, sig), true)
+  assert.equal(await verify(pp, pk, message, sig), false)
+})
+
+test('batch verification of 1000 signatures with one corrupted entry', async () => {
+  const pp = new PublicParams()
+  const keypairs = Array.from({ length: 10 }, () => new Keypair(pp, 3))
+  const entries = Array.from({ length: 1000 }, (_, i) => {
+    const keypair = keypairs[i % keypairs.length]
+    const message = randomMessage(3)
+    return {
+      publicKey: keypair.publicKey.toBytes(),
+      message,
+      signature: keypair.sign(pp, message).toBytes(),
+    }
+  })
+  assert.deepEqual(await batchVerify(pp, entries), [])
+
+  entries[421].message = randomMessage(3)
+  assert.deepEqual(await batchVerify(pp, entries), [421])
+
+  entries[7].signature = Buffer.from([1, 2, 3])
+  assert.deepEqual(await batchVerify(pp, entries), [7, 421])
+})
+
+test('invalid inputs are errors', async () => {
+  const pp = new PublicParams()
+  const keypair = new Keypair(pp, 2)
+  assert.throws(() => PublicParams.fromBytes(Buffer.from([1, 2, 3])), Error)
+  assert.throws(() => keypair.sign(pp, randomMessage(3)), /longer than the key/)
+  assert.throws(() => keypair.sign(pp, Buffer.alloc(10, 0xff)), /invalid message/)
+  assert.throws(() => keypair.convert(Buffer.alloc(32, 0xff)), /invalid scalar/)
+  const sig = keypair.sign(pp, randomMessage(2))
+  await assert.rejects(verify(pp, keypair.publicKey, Buffer.alloc(10, 0xff), sig), /invalid message/)
+})
@@ -0,0 +1,2 @@
+// The addon built by `npm run build`, see src/lib.rs.
+module.exports = require('./mercurial-signature.node')
@@ -0,0 +1,339 @@
+//! Bindings for Node.js with napi-rs.
+//!
+//! The values cross the boundary as `Buffer`s in the compressed serialization, i.e. a message
+//! is a serialized `Vec<G1>` and a scalar is a serialized `Fr`. Invalid inputs throw an `Error`
+//! with the reason, or reject the promise of an asynchronous function.
+//!
+//! [verify] and [batch_verify] return promises and run on the libuv thread pool, so they do
+//! not block the event loop. [batch_verify] also splits the entries over the available cores.
+
+use std::thread;
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use mercurial_signature::{change_representation, Fr, G1};
+use napi::{bindgen_prelude::*, Task};
+use napi_derive::napi;
+use rand_core::OsRng;
+
+/// Public parameters.
+#[napi]
+pub struct PublicParams {
+    inner: mercurial_signature::PublicParams,
+}
+
+/// Public key, e.g. received from an issuer.
+#[napi]
+pub struct PublicKey {
+    inner: mercurial_signature::PublicKey,
+}
+
+/// Key pair of a signer.
+#[napi]
+pub struct Keypair {
+    pk: mercurial_signature::PublicKey,
+    sk: mercurial_signature::SecretKey,
+}
+
+/// Signature.
+#[napi]
+pub struct Signature {
+    inner: mercurial_signature::Signature,
+}
+
+/// An entry of [batch_verify].
+#[napi(object)]
+pub struct BatchEntry {
+    pub public_key: Buffer,
+    pub message: Buffer,
+    pub signature: Buffer,
+}
+
+#[napi]
+impl PublicParams {
+    /// Generate public parameters.
+    #[napi(constructor)]
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        PublicParams {
+            inner: mercurial_signature::PublicParams::new(&mut OsRng),
+        }
+    }
+
+    #[napi(factory)]
+    pub fn from_bytes(bytes: Buffer) -> Result<Self> {
+        Ok(PublicParams {
+            inner: deserialize(&bytes, "public parameters")?,
+        })
+    }
+
+    #[napi]
+    pub fn to_bytes(&self) -> Buffer {
+        serialize(&self.inner).into()
+    }
+}
+
+#[napi]
+impl PublicKey {
+    #[napi(factory)]
+    pub fn from_bytes(bytes: Buffer) -> Result<Self> {
+        Ok(PublicKey {
+            inner: deserialize(&bytes, "public key")?,
+        })
+    }
+
+    #[napi]
+    pub fn to_bytes(&self) -> Buffer {
+        serialize(&self.inner).into()
+    }
+
+    /// Convert the public key by the scalar `p`.
+    #[napi]
+    pub fn convert(&mut self, p: Buffer) -> Result<()> {
+        self.inner.convert(deserialize(&p, "scalar")?);
+        Ok(())
+    }
+}
+
+#[napi]
+impl Keypair {
+    /// Generate a key pair for messages of `size` elements.
+    #[napi(constructor)]
+    pub fn new(pp: &PublicParams, size: u32) -> Self {
+        let (pk, sk) = pp.inner.key_gen(&mut OsRng, size);
+        Keypair { pk, sk }
+    }
+
+    #[napi(factory)]
+    pub fn from_bytes(public_key: Buffer, secret_key: Buffer) -> Result<Self> {
+        let pk: mercurial_signature::PublicKey = deserialize(&public_key, "public key")?;
+        let sk: mercurial_signature::SecretKey = deserialize(&secret_key, "secret key")?;
+        if pk.length() != sk.length() {
+            return Err(invalid(
+                "the public key and the secret key have different lengths",
+            ));
+        }
+        Ok(Keypair { pk, sk })
+    }
+
+    #[napi(getter)]
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey {
+            inner: self.pk.clone(),
+        }
+    }
+
+    #[napi]
+    pub fn secret_key_bytes(&self) -> Buffer {
+        serialize(&self.sk).into()
+    }
+
+    /// Sign the message.
+    #[napi]
+    pub fn sign(&self, pp: &PublicParams, message: Buffer) -> Result<Signature> {
+        let message = deserialize_message(&message)?;
+        if message.len() > self.sk.length() {
+            return Err(invalid("the message is longer than the key"));
+        }
+        Ok(Signature {
+            inner: self.sk.sign(&mut OsRng, &pp.inner, &message),
+        })
+    }
+
+    /// Convert both keys by the scalar `p`.
+    #[napi]
+    pub fn convert(&mut self, p: Buffer) -> Result<()> {
+        let p: Fr = deserialize(&p, "scalar")?;
+        self.pk.convert(p);
+        self.sk.convert(p);
+        Ok(())
+    }
+}
+
+#[napi]
+impl Signature {
+    #[napi(factory)]
+    pub fn from_bytes(bytes: Buffer) -> Result<Self> {
+        Ok(Signature {
+            inner: deserialize(&bytes, "signature")?,
+        })
+    }
+
+    #[napi]
+    pub fn to_bytes(&self) -> Buffer {
+        serialize(&self.inner).into()
+    }
+
+    /// Convert the signature by the scalar `p`.
+    #[napi]
+    pub fn convert(&mut self, p: Buffer) -> Result<()> {
+        self.inner.convert(&mut OsRng, deserialize(&p, "scalar")?);
+        Ok(())
+    }
+
+    /// Change the representation of the message and the signature by the scalar `u`. Returns
+    /// the new message.
+    #[napi]
+    pub fn change_representation(&mut self, message: Buffer, u: Buffer) -> Result<Buffer> {
+        let mut message = deserialize_message(&message)?;
+        change_representation(
+            &mut OsRng,
+            &mut message,
+            &mut self.inner,
+            deserialize(&u, "scalar")?,
+        );
+        Ok(serialize(&message).into())
+    }
+}
+
+pub struct Verify {
+    pp: mercurial_signature::PublicParams,
+    pk: mercurial_signature::PublicKey,
+    message: Vec<u8>,
+    sig: mercurial_signature::Signature,
+}
+
+impl Task for Verify {
+    type Output = bool;
+    type JsValue = bool;
+
+    fn compute(&mut self) -> Result<bool> {
+        let message = deserialize_message(&self.message)?;
+        Ok(self.pk.verify(&self.pp, &message, &self.sig))
+    }
+
+    fn resolve(&mut self, _env: Env, output: bool) -> Result<bool> {
+        Ok(output)
+    }
+}
+
+/// Verify the signature on the message on the thread pool.
+#[napi]
+pub fn verify(
+    pp: &PublicParams,
+    pk: &PublicKey,
+    message: Buffer,
+    sig: &Signature,
+) -> AsyncTask<Verify> {
+    AsyncTask::new(Verify {
+        pp: pp.inner.clone(),
+        pk: pk.inner.clone(),
+        message: message.to_vec(),
+        sig: sig.inner.clone(),
+    })
+}
+
+pub struct BatchVerify {
+    pp: mercurial_signature::PublicParams,
+    entries: Vec<[Vec<u8>; 3]>,
+}
+
+impl Task for BatchVerify {
+    type Output = Vec<u32>;
+    type JsValue = Vec<u32>;
+
+    fn compute(&mut self) -> Result<Vec<u32>> {
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+        let chunk_size = self.entries.len().div_ceil(threads).max(1);
+        let pp = &self.pp;
+        let failed = thread::scope(|s| {
+            let handles = self
+                .entries
+                .chunks(chunk_size)
+                .enumerate()
+                .map(|(i, chunk)| {
+                    s.spawn(move || {
+                        chunk
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, entry)| !verify_entry(pp, entry))
+                            .map(|(j, _)| (i * chunk_size + j) as u32)
+                            .collect::<Vec<u32>>()
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap())
+                .collect()
+        });
+        Ok(failed)
+    }
+
+    fn resolve(&mut self, _env: Env, output: Vec<u32>) -> Result<Vec<u32>> {
+        Ok(output)
+    }
+}
+
+/// Verify the entries on the thread pool. Returns the indices of the entries which fail,
+/// including those which cannot be deserialized, in ascending order.
+#[napi]
+pub fn batch_verify(pp: &PublicParams, entries: Vec<BatchEntry>) -> AsyncTask<BatchVerify> {
+    AsyncTask::new(BatchVerify {
+        pp: pp.inner.clone(),
+        entries: entries
+            .into_iter()
+            .map(|entry| {
+                [
+                    entry.public_key.to_vec(),
+                    entry.message.to_vec(),
+                    entry.signature.to_vec(),
+                ]
+            })
+            .collect(),
+    })
+}
+
+/// A random scalar, e.g. for [Signature::convert] or [Signature::change_representation].
+#[napi]
+pub fn random_scalar() -> Buffer {
+    serialize(&<Fr as ark_std::UniformRand>::rand(&mut OsRng)).into()
+}
+
+/// A random message of `size` elements.
+#[napi]
+pub fn random_message(size: u32) -> Buffer {
+    let message = (0..size)
+        .map(|_| <G1 as ark_std::UniformRand>::rand(&mut OsRng))
+        .collect::<Vec<G1>>();
+    serialize(&message).into()
+}
+
+fn verify_entry(pp: &mercurial_signature::PublicParams, [pk, message, sig]: &[Vec<u8>; 3]) -> bool {
+    let pk = deserialize::<mercurial_signature::PublicKey>(pk, "public key");
+    let message = deserialize_message(message);
+    let sig = deserialize::<mercurial_signature::Signature>(sig, "signature");
+    match (pk, message, sig) {
+        (Ok(pk), Ok(message), Ok(sig)) => pk.verify(pp, &message, &sig),
+        _ => false,
+    }
+}
+
+fn invalid(reason: &str) -> Error {
+    Error::new(Status::InvalidArg, reason.to_string())
+}
+
+fn serialize<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes).unwrap();
+    bytes
+}
+
+fn deserialize<T: CanonicalDeserialize>(bytes: &[u8], name: &str) -> Result<T> {
+    T::deserialize_compressed(bytes)
+        .map_err(|e| Error::new(Status::InvalidArg, format!("invalid {}: {}", name, e)))
+}
+
+/// Deserialize a message, checking its length prefix against the input first so that a
+/// corrupted prefix cannot request a huge allocation.
+fn deserialize_message(bytes: &[u8]) -> Result<Vec<G1>> {
+    let element_size = G1::default().compressed_size();
+    let length = bytes
+        .get(..8)
+        .map(|prefix| u64::from_le_bytes(prefix.try_into().unwrap()));
+    match length {
+        Some(length) if length.checked_mul(element_size as u64) == Some(bytes.len() as u64 - 8) => {
+            deserialize(bytes, "message")
+        }
+        _ => Err(invalid("invalid message: wrong length")),
+    }
+}
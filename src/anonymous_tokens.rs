@@ -0,0 +1,352 @@
+//! Single-use anonymous tokens in the style of Privacy Pass.
+//!
+//! A token is a signature on the class of the message `(p1, s p1)`, where `s` is a random
+//! secret of the client.
+//!
+//! 1. Issuance: the client sends random representatives `(r p1, r s p1)` of its messages in an
+//!    [IssuanceRequest], and the [Issuer] signs each of them with its own randomness `y`. An
+//!    issuer whose key is held by an [AsyncSigner] signs them with [issue_with_signer]
+//!    instead.
+//! 2. Redemption: the client changes the representation of a token to `(u p1, u s p1)` with a
+//!    fresh `u` and presents it in a [Redemption] with the show tag `T = s H`, where `H` is
+//!    hashed from the public parameters, and a Chaum-Pedersen proof that `log_{p1'}(s p1') ==
+//!    log_H(T)`. The [Redeemer] checks the signature and the proof, and rejects a tag it has
+//!    seen before.
+//!
+//! The tag is deterministic for a token, so a double redemption is detected. The signatures of
+//! a batch must not share `y`: a linear combination of signatures sharing `y` verifies on the
+//! same combination of their messages, which is a fresh token of a secret known to the client. The presented
+//! representative and the tag are unlinkable to the issuance under the DDH assumption in G1,
+//! since the issuer sees only `(r p1, r s p1)` and the signature is randomized with the
+//! representation.
+
+use alloc::{collections::BTreeSet, vec, vec::Vec};
+use core::ops::Mul;
+
+use ark_ec::pairing::Pairing;
+use ark_serialize::CanonicalSerialize;
+use ark_std::{UniformRand, Zero};
+use rand_core::{CryptoRng, RngCore};
+
+use crate::{
+    hash::hash_to_g1,
+    params::PublicParams,
+    public_key::PublicKey,
+    representation::change_representation,
+    secret_key::SecretKey,
+    serialization::impl_bounded_deserialize,
+    signature::Signature,
+    signer::{sign_many_with_signer, AsyncSigner},
+    transcript::Transcript,
+};
+
+/// The issuer of tokens.
+#[derive(Clone)]
+pub struct Issuer<E: Pairing> {
+    pub(crate) sk: SecretKey<E>,
+    pub(crate) pk: PublicKey<E>,
+}
+
+/// The client obtaining and redeeming tokens.
+#[derive(Clone)]
+pub struct Client<E: Pairing> {
+    // secrets of the requested tokens not issued yet, with the requested messages
+    pending: Vec<(E::ScalarField, Vec<E::G1>)>,
+    tokens: Vec<Token<E>>,
+}
+
+/// The verifier of redemptions, keeping the tags of the redeemed tokens.
+#[derive(Clone)]
+pub struct Redeemer<E: Pairing> {
+    pub(crate) pk: PublicKey<E>,
+    pub(crate) seen: BTreeSet<Vec<u8>>,
+}
+
+/// The messages to be signed, sent by the client to the issuer.
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize)]
+pub struct IssuanceRequest<E: Pairing> {
+    // (r p1, r s p1) for each token
+    pub(crate) messages: Vec<Vec<E::G1>>,
+}
+
+impl_bounded_deserialize!(IssuanceRequest {
+    messages: nested_vec
+});
+
+/// The signatures on the requested messages, sent by the issuer to the client.
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize)]
+pub struct IssuanceResponse<E: Pairing> {
+    pub(crate) sigs: Vec<Signature<E>>,
+}
+
+impl_bounded_deserialize!(IssuanceResponse { sigs: vec });
+
+/// A token presented for redemption.
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize)]
+pub struct Redemption<E: Pairing> {
+    // (u p1, u s p1)
+    pub(crate) message: Vec<E::G1>,
+    pub(crate) sig: Signature<E>,
+    // s H
+    pub(crate) tag: E::G1,
+    // commitments k p1', k H
+    pub(crate) a: E::G1,
+    pub(crate) b: E::G1,
+    // response k + c s
+    pub(crate) z: E::ScalarField,
+}
+
+impl_bounded_deserialize!(Redemption {
+    message: vec,
+    sig: value,
+    tag: value,
+    a: value,
+    b: value,
+    z: value
+});
+
+#[derive(Clone)]
+struct Token<E: Pairing> {
+    s: E::ScalarField,
+    message: Vec<E::G1>,
+    sig: Signature<E>,
+}
+
+impl<E: Pairing> Issuer<E> {
+    /// Create an issuer with a fresh key pair.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::{PublicParams, TokenClient, TokenIssuer, TokenRedeemer};
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let issuer = TokenIssuer::new(&mut rng, &pp);
+    /// let mut redeemer = TokenRedeemer::new(issuer.public_key());
+    ///
+    /// let mut client = TokenClient::new();
+    /// let request = client.request(&mut rng, &pp, 5);
+    /// let response = issuer.issue(&mut rng, &pp, &request);
+    /// assert!(client.receive(&pp, issuer.public_key(), &response));
+    ///
+    /// let redemption = client.redeem(&mut rng, &pp).unwrap();
+    /// assert!(redeemer.redeem(&pp, &redemption));
+    /// assert!(!redeemer.redeem(&pp, &redemption));
+    /// ```
+    pub fn new<R: RngCore + CryptoRng>(rng: &mut R, pp: &PublicParams<E>) -> Self {
+        let (pk, sk) = pp.key_gen(rng, 2);
+        Issuer { sk, pk }
+    }
+
+    /// The public key of the issuer.
+    pub fn public_key(&self) -> &PublicKey<E> {
+        &self.pk
+    }
+
+    /// Sign each of the requested messages with fresh randomness `y`.
+    ///
+    /// ## Safety
+    /// This function panics if a requested message has more than two elements.
+    pub fn issue<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        pp: &PublicParams<E>,
+        request: &IssuanceRequest<E>,
+    ) -> IssuanceResponse<E> {
+        IssuanceResponse {
+            sigs: request
+                .messages
+                .iter()
+                .map(|message| self.sk.sign(rng, pp, message))
+                .collect(),
+        }
+    }
+}
+
+/// Sign the requested messages with the signer, pipelining the requests, see
+/// [sign_many_with_signer]. The client receives the response with the public key of the
+/// signer.
+pub async fn issue_with_signer<E: Pairing, S: AsyncSigner<E>>(
+    signer: &S,
+    pp: &PublicParams<E>,
+    request: &IssuanceRequest<E>,
+) -> Result<IssuanceResponse<E>, S::Error> {
+    let messages = request
+        .messages
+        .iter()
+        .map(|m| m.as_slice())
+        .collect::<Vec<_>>();
+    Ok(IssuanceResponse {
+        sigs: sign_many_with_signer(signer, pp, &messages).await?,
+    })
+}
+
+impl<E: Pairing> Client<E> {
+    /// Create a client without tokens.
+    pub fn new() -> Self {
+        Client {
+            pending: Vec::new(),
+            tokens: Vec::new(),
+        }
+    }
+
+    /// Number of tokens available for redemption.
+    pub fn length(&self) -> usize {
+        self.tokens.len()
+    }
+
+    /// Request `n` tokens. The request replaces any previous request without response.
+    pub fn request<R: RngCore + CryptoRng>(
+        &mut self,
+        rng: &mut R,
+        pp: &PublicParams<E>,
+        n: usize,
+    ) -> IssuanceRequest<E> {
+        self.pending = (0..n)
+            .map(|_| {
+                let s = E::ScalarField::rand(rng);
+                let r = E::ScalarField::rand(rng);
+                let m = pp.p1.mul(r);
+                (s, vec![m, m.mul(s)])
+            })
+            .collect();
+        IssuanceRequest {
+            messages: self.pending.iter().map(|(_, m)| m.clone()).collect(),
+        }
+    }
+
+    /// Receive the signatures on the pending request. Returns `false` and keeps the request
+    /// pending if any signature does not verify.
+    pub fn receive(
+        &mut self,
+        pp: &PublicParams<E>,
+        pk: &PublicKey<E>,
+        response: &IssuanceResponse<E>,
+    ) -> bool {
+        if response.sigs.len() != self.pending.len()
+            || !self
+                .pending
+                .iter()
+                .zip(response.sigs.iter())
+                .all(|((_, m), sig)| pk.verify(pp, m, sig))
+        {
+            return false;
+        }
+
+        let pending = core::mem::take(&mut self.pending);
+        self.tokens
+            .extend(
+                pending
+                    .into_iter()
+                    .zip(response.sigs.iter())
+                    .map(|((s, message), sig)| Token {
+                        s,
+                        message,
+                        sig: sig.clone(),
+                    }),
+            );
+        true
+    }
+
+    /// Redeem a token. Returns `None` if there is no token left.
+    pub fn redeem<R: RngCore + CryptoRng>(
+        &mut self,
+        rng: &mut R,
+        pp: &PublicParams<E>,
+    ) -> Option<Redemption<E>> {
+        let Token {
+            s,
+            mut message,
+            mut sig,
+        } = self.tokens.pop()?;
+
+        let u = E::ScalarField::rand(rng);
+        change_representation(rng, &mut message, &mut sig, u);
+
+        let h = tag_base(pp);
+        let tag = h.mul(s);
+        let k = E::ScalarField::rand(rng);
+        let a = message[0].mul(k);
+        let b = h.mul(k);
+        let c = challenge(pp, &message, tag, a, b);
+        Some(Redemption {
+            message,
+            sig,
+            tag,
+            a,
+            b,
+            z: k + c * s,
+        })
+    }
+}
+
+impl<E: Pairing> Default for Client<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Pairing> Redeemer<E> {
+    /// Create a redeemer for the tokens of the issuer with the public key.
+    pub fn new(pk: &PublicKey<E>) -> Self {
+        Redeemer {
+            pk: pk.clone(),
+            seen: BTreeSet::new(),
+        }
+    }
+
+    /// Check the redemption and record its tag. Returns `false` if the token is invalid or
+    /// its tag has been redeemed before.
+    pub fn redeem(&mut self, pp: &PublicParams<E>, redemption: &Redemption<E>) -> bool {
+        if redemption.message.len() != 2
+            || redemption.message[0].is_zero()
+            || !self.pk.verify(pp, &redemption.message, &redemption.sig)
+        {
+            return false;
+        }
+
+        // z p1' == A + c (s p1') and z H == B + c T
+        let h = tag_base(pp);
+        let c = challenge(
+            pp,
+            &redemption.message,
+            redemption.tag,
+            redemption.a,
+            redemption.b,
+        );
+        if redemption.message[0].mul(redemption.z) != redemption.a + redemption.message[1].mul(c)
+            || h.mul(redemption.z) != redemption.b + redemption.tag.mul(c)
+        {
+            return false;
+        }
+
+        let mut tag = Vec::new();
+        redemption.tag.serialize_compressed(&mut tag).unwrap();
+        self.seen.insert(tag)
+    }
+}
+
+/// The base `H` of the show tags.
+fn tag_base<E: Pairing>(pp: &PublicParams<E>) -> E::G1 {
+    let mut bytes = Vec::new();
+    pp.serialize_compressed(&mut bytes).unwrap();
+    hash_to_g1::<E>(b"mercurial-signature/anonymous-tokens/tag", &bytes)
+}
+
+/// Fiat-Shamir challenge of the proof in the redemption.
+fn challenge<E: Pairing>(
+    pp: &PublicParams<E>,
+    message: &[E::G1],
+    tag: E::G1,
+    a: E::G1,
+    b: E::G1,
+) -> E::ScalarField {
+    let mut transcript = Transcript::new(b"mercurial-signature/anonymous-tokens");
+    transcript.append(b"pp", pp);
+    transcript.append(b"message", message);
+    transcript.append_point_g1::<E>(b"tag", &tag);
+    transcript.append_point_g1::<E>(b"commitment_message", &a);
+    transcript.append_point_g1::<E>(b"commitment_tag", &b);
+    transcript.challenge_scalar::<E>(b"challenge")
+}
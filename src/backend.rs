@@ -0,0 +1,263 @@
+//! Signing with the secret scalars held by a backend, e.g. a secure element which does scalar
+//! multiplications and field arithmetic on request.
+//!
+//! The secrets are referenced by opaque handles of the [ScalarBackend], so that they never
+//! leave it. [SecretKey](crate::SecretKey) signs and converts through [InMemoryBackend], and a
+//! [BackendKey] does the same operations against any backend, producing the same signatures.
+
+use alloc::vec::Vec;
+use core::{marker::PhantomData, ops::Mul};
+
+use ark_ec::pairing::Pairing;
+use ark_std::{One, UniformRand, Zero};
+use rand_core::{CryptoRng, RngCore};
+
+use crate::{params::PublicParams, public_key::PublicKey, signature::Signature};
+
+/// Operations on secret scalars referenced by handles.
+pub trait ScalarBackend<E: Pairing> {
+    /// Opaque reference to a secret scalar in the backend.
+    type Handle;
+
+    /// A new random secret.
+    fn random<R: RngCore + CryptoRng>(&self, rng: &mut R) -> Self::Handle;
+
+    /// Import a scalar, e.g. the public scalar of a conversion.
+    fn import(&self, s: E::ScalarField) -> Self::Handle;
+
+    /// The secret `a * b`.
+    fn mul_scalars(&self, a: &Self::Handle, b: &Self::Handle) -> Self::Handle;
+
+    /// The secret `1 / a`.
+    fn invert(&self, a: &Self::Handle) -> Self::Handle;
+
+    /// The element `secret * base` of G1.
+    fn mul_g1(&self, base: &E::G1, secret: &Self::Handle) -> E::G1;
+
+    /// The element `secret * base` of G2.
+    fn mul_g2(&self, base: &E::G2, secret: &Self::Handle) -> E::G2;
+}
+
+/// The backend whose handles are the scalars in memory.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct InMemoryBackend;
+
+impl<E: Pairing> ScalarBackend<E> for InMemoryBackend {
+    type Handle = E::ScalarField;
+
+    fn random<R: RngCore + CryptoRng>(&self, rng: &mut R) -> E::ScalarField {
+        E::ScalarField::rand(rng)
+    }
+
+    fn import(&self, s: E::ScalarField) -> E::ScalarField {
+        s
+    }
+
+    fn mul_scalars(&self, a: &E::ScalarField, b: &E::ScalarField) -> E::ScalarField {
+        *a * b
+    }
+
+    fn invert(&self, a: &E::ScalarField) -> E::ScalarField {
+        E::ScalarField::one() / a
+    }
+
+    fn mul_g1(&self, base: &E::G1, secret: &E::ScalarField) -> E::G1 {
+        base.mul(secret)
+    }
+
+    fn mul_g2(&self, base: &E::G2, secret: &E::ScalarField) -> E::G2 {
+        base.mul(secret)
+    }
+}
+
+/// Secret key whose elements are held by a backend.
+pub struct BackendKey<E: Pairing, B: ScalarBackend<E>> {
+    // handles of (x1,...,xl)
+    x: Vec<B::Handle>,
+    _curve: PhantomData<E>,
+}
+
+impl<E: Pairing, B: ScalarBackend<E>> BackendKey<E, B> {
+    /// The key with the elements referenced by the handles.
+    pub fn new(x: Vec<B::Handle>) -> Self {
+        BackendKey {
+            x,
+            _curve: PhantomData,
+        }
+    }
+
+    /// Generate a key of `size` elements in the backend, with the public key. With
+    /// [InMemoryBackend], it draws from the generator as [PublicParams::key_gen] does.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::{BackendKey, InMemoryBackend, PublicParams, UniformRand, G1};
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let (pk, sk) = BackendKey::<InMemoryBackend>::generate(&InMemoryBackend, &mut rng, &pp, 10);
+    ///
+    /// let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    /// let sig = sk.sign(&InMemoryBackend, &mut rng, &pp, &message);
+    /// assert!(pk.verify(&pp, &message, &sig));
+    /// ```
+    pub fn generate<R: RngCore + CryptoRng>(
+        backend: &B,
+        rng: &mut R,
+        pp: &PublicParams<E>,
+        size: u32,
+    ) -> (PublicKey<E>, Self) {
+        let x = (0..size).map(|_| backend.random(rng)).collect::<Vec<_>>();
+        let bx = x.iter().map(|xi| backend.mul_g2(&pp.p2, xi)).collect();
+        (PublicKey { bx }, BackendKey::new(x))
+    }
+
+    /// Length of the key.
+    pub fn length(&self) -> usize {
+        self.x.len()
+    }
+
+    /// The handles of the elements of the key.
+    pub fn handles(&self) -> &[B::Handle] {
+        &self.x
+    }
+
+    /// Sign a message, as [SecretKey::sign](crate::SecretKey::sign) does.
+    ///
+    /// ## Safety
+    /// This function panics if the length of the key is less than the length of the message.
+    pub fn sign<R: RngCore + CryptoRng>(
+        &self,
+        backend: &B,
+        rng: &mut R,
+        pp: &PublicParams<E>,
+        message: &[E::G1],
+    ) -> Signature<E> {
+        let y = backend.random(rng);
+        sign_with_backend(backend, &self.x, pp, message, &y)
+    }
+
+    /// Convert the key, as [SecretKey::convert](crate::SecretKey::convert) does.
+    pub fn convert(&mut self, backend: &B, p: E::ScalarField) {
+        convert_with_backend(backend, &mut self.x, p);
+    }
+}
+
+/// Sign a message with the secret key `x` and the randomness `y` in the backend.
+pub(crate) fn sign_with_backend<E: Pairing, B: ScalarBackend<E>>(
+    backend: &B,
+    x: &[B::Handle],
+    pp: &PublicParams<E>,
+    message: &[E::G1],
+    y: &B::Handle,
+) -> Signature<E> {
+    if x.len() < message.len() {
+        panic!(
+            "The length of the secret key must be equal or greater than the length of the message."
+        );
+    }
+
+    // z = (x1 M1 + ... + xl Ml) * y
+    let z = message
+        .iter()
+        .zip(x.iter())
+        .fold(E::G1::zero(), |acc, (m, xi)| {
+            acc + backend.mul_g1(m, &backend.mul_scalars(y, xi))
+        });
+    let y_inv = backend.invert(y);
+    // y1 = p1^(1/y)
+    let y1 = backend.mul_g1(&pp.p1, &y_inv);
+    // y2 = p2^(1/y)
+    let y2 = backend.mul_g2(&pp.p2, &y_inv);
+    Signature { z, y1, y2 }
+}
+
+/// Convert the secret key `x` in the backend by the scalar `p`.
+pub(crate) fn convert_with_backend<E: Pairing, B: ScalarBackend<E>>(
+    backend: &B,
+    x: &mut [B::Handle],
+    p: E::ScalarField,
+) {
+    let p = backend.import(p);
+    x.iter_mut()
+        .for_each(|xi| *xi = backend.mul_scalars(xi, &p));
+}
+
+/// Number of operations requested from a [CountingBackend].
+#[cfg(feature = "test-utils")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OperationCounts {
+    pub random: usize,
+    pub import: usize,
+    pub mul_scalars: usize,
+    pub invert: usize,
+    pub mul_g1: usize,
+    pub mul_g2: usize,
+}
+
+/// An in-memory backend counting the requested operations, e.g. for cost models.
+#[cfg(feature = "test-utils")]
+#[derive(Debug, Default)]
+pub struct CountingBackend {
+    counts: core::cell::Cell<OperationCounts>,
+}
+
+#[cfg(feature = "test-utils")]
+impl CountingBackend {
+    /// Create a backend with all counts zero.
+    pub fn new() -> Self {
+        CountingBackend::default()
+    }
+
+    /// The counts of the operations so far.
+    pub fn counts(&self) -> OperationCounts {
+        self.counts.get()
+    }
+
+    /// Reset all counts to zero.
+    pub fn reset(&self) {
+        self.counts.set(OperationCounts::default());
+    }
+
+    fn count(&self, f: impl FnOnce(&mut OperationCounts)) {
+        let mut counts = self.counts.get();
+        f(&mut counts);
+        self.counts.set(counts);
+    }
+}
+
+#[cfg(feature = "test-utils")]
+impl<E: Pairing> ScalarBackend<E> for CountingBackend {
+    type Handle = E::ScalarField;
+
+    fn random<R: RngCore + CryptoRng>(&self, rng: &mut R) -> E::ScalarField {
+        self.count(|c| c.random += 1);
+        ScalarBackend::<E>::random(&InMemoryBackend, rng)
+    }
+
+    fn import(&self, s: E::ScalarField) -> E::ScalarField {
+        self.count(|c| c.import += 1);
+        s
+    }
+
+    fn mul_scalars(&self, a: &E::ScalarField, b: &E::ScalarField) -> E::ScalarField {
+        self.count(|c| c.mul_scalars += 1);
+        ScalarBackend::<E>::mul_scalars(&InMemoryBackend, a, b)
+    }
+
+    fn invert(&self, a: &E::ScalarField) -> E::ScalarField {
+        self.count(|c| c.invert += 1);
+        ScalarBackend::<E>::invert(&InMemoryBackend, a)
+    }
+
+    fn mul_g1(&self, base: &E::G1, secret: &E::ScalarField) -> E::G1 {
+        self.count(|c| c.mul_g1 += 1);
+        ScalarBackend::<E>::mul_g1(&InMemoryBackend, base, secret)
+    }
+
+    fn mul_g2(&self, base: &E::G2, secret: &E::ScalarField) -> E::G2 {
+        self.count(|c| c.mul_g2 += 1);
+        ScalarBackend::<E>::mul_g2(&InMemoryBackend, base, secret)
+    }
+}
@@ -0,0 +1,267 @@
+//! Signatures on bilateral messages, i.e. messages with elements in both G1 and G2.
+//!
+//! The secret key `(x0,x1,...,xl; w0,w1,...,wk)` has a public key with `Xi = p2^xi` in G2 and
+//! `Wj = p1^wj` in G1. A signature on `(M1,...,Ml; N1,...,Nk)` with randomness `y` has an
+//! anchor `v1 = p1^a`, `v2 = p2^a` for a random `a`, and
+//!
+//! - `z = (x0 v1 + x1 M1 + ... + xl Ml) * y` in G1
+//! - `z' = (w0 v2 + w1 N1 + ... + wk Nk) * y` in G2
+//! - `y1 = p1^(1/y)`, `y2 = p2^(1/y)`
+//!
+//! and the verification equations are
+//!
+//! - `e(y1, p2) == e(p1, y2)`
+//! - `e(v1, p2) == e(p1, v2)`
+//! - `e(z, y2) == e(v1, X0) * e(M1, X1) * ... * e(Ml, Xl)`
+//! - `e(y1, z') == e(W0, v2) * e(W1, N1) * ... * e(Wk, Nk)`
+//!
+//! Changing the representation with `u` scales both halves of the message and the anchor by
+//! `u`, and `z, z'` by `u f`, `y1, y2` by `1/f` for a fresh `f`.
+//!
+//! The anchor is signed in each half, so scaling one half by `u` requires scaling its part of
+//! the anchor by `u`, and the second equation then requires scaling the other half by the same
+//! `u`. The class is the one of both halves together.
+
+use alloc::vec::Vec;
+use core::ops::Mul;
+
+use ark_ec::pairing::Pairing;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{One, UniformRand};
+use rand_core::{CryptoRng, RngCore};
+
+use crate::{params::PublicParams, serialization::impl_bounded_deserialize};
+
+/// Public key for bilateral messages.
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize)]
+pub struct BilateralPublicKey<E: Pairing> {
+    // (p2^x1,...,p2^xl) for the G1 half
+    pub(crate) bx: Vec<E::G2>,
+    // (p1^w1,...,p1^wk) for the G2 half
+    pub(crate) bw: Vec<E::G1>,
+    // p2^x0 for the anchor in G1
+    pub(crate) bx0: E::G2,
+    // p1^w0 for the anchor in G2
+    pub(crate) bw0: E::G1,
+}
+
+impl_bounded_deserialize!(BilateralPublicKey {
+    bx: vec,
+    bw: vec,
+    bx0: value,
+    bw0: value,
+});
+
+/// Secret key for bilateral messages.
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize)]
+pub struct BilateralSecretKey<E: Pairing> {
+    pub(crate) x: Vec<E::ScalarField>,
+    pub(crate) w: Vec<E::ScalarField>,
+    pub(crate) x0: E::ScalarField,
+    pub(crate) w0: E::ScalarField,
+}
+
+impl_bounded_deserialize!(BilateralSecretKey {
+    x: vec,
+    w: vec,
+    x0: value,
+    w0: value,
+});
+
+/// Signature on a bilateral message.
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct BilateralSignature<E: Pairing> {
+    pub(crate) z: E::G1,
+    pub(crate) z2: E::G2,
+    pub(crate) y1: E::G1,
+    pub(crate) y2: E::G2,
+    // the anchor (p1^a, p2^a), scaled with the message
+    pub(crate) v1: E::G1,
+    pub(crate) v2: E::G2,
+}
+
+impl<E: Pairing> PublicParams<E> {
+    /// Generate a key pair for bilateral messages with `size_g1` elements in G1 and
+    /// `size_g2` elements in G2.
+    pub fn key_gen_bilateral<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        size_g1: u32,
+        size_g2: u32,
+    ) -> (BilateralPublicKey<E>, BilateralSecretKey<E>) {
+        let x = (0..size_g1)
+            .map(|_| E::ScalarField::rand(rng))
+            .collect::<Vec<E::ScalarField>>();
+        let w = (0..size_g2)
+            .map(|_| E::ScalarField::rand(rng))
+            .collect::<Vec<E::ScalarField>>();
+        let x0 = E::ScalarField::rand(rng);
+        let w0 = E::ScalarField::rand(rng);
+        let bx = x.iter().map(|xi| self.p2.mul(xi)).collect();
+        let bw = w.iter().map(|wi| self.p1.mul(wi)).collect();
+        let bx0 = self.p2.mul(x0);
+        let bw0 = self.p1.mul(w0);
+        (
+            BilateralPublicKey { bx, bw, bx0, bw0 },
+            BilateralSecretKey { x, w, x0, w0 },
+        )
+    }
+}
+
+impl<E: Pairing> BilateralPublicKey<E> {
+    /// Verify the signature on a bilateral message.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::{change_representation_bilateral, Fr, PublicParams, UniformRand, G1, G2};
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let (pk, sk) = pp.key_gen_bilateral(&mut rng, 5, 5);
+    /// let mut msg_g1 = (0..5).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    /// let mut msg_g2 = (0..5).map(|_| G2::rand(&mut rng)).collect::<Vec<G2>>();
+    /// let mut sig = sk.sign_bilateral(&mut rng, &pp, &msg_g1, &msg_g2);
+    ///
+    /// let u = Fr::rand(&mut rng);
+    /// change_representation_bilateral(&mut rng, &mut msg_g1, &mut msg_g2, &mut sig, u);
+    /// assert!(pk.verify(&pp, &msg_g1, &msg_g2, &sig));
+    /// ```
+    pub fn verify(
+        &self,
+        pp: &PublicParams<E>,
+        msg_g1: &[E::G1],
+        msg_g2: &[E::G2],
+        sig: &BilateralSignature<E>,
+    ) -> bool {
+        // check lengths
+        if self.bx.len() < msg_g1.len() || self.bw.len() < msg_g2.len() {
+            return false;
+        }
+
+        // e(y1, p2) == e(p1, y2)
+        if E::pairing(sig.y1, pp.p2) != E::pairing(pp.p1, sig.y2) {
+            return false;
+        }
+
+        // e(v1, p2) == e(p1, v2)
+        if E::pairing(sig.v1, pp.p2) != E::pairing(pp.p1, sig.v2) {
+            return false;
+        }
+
+        // e(z, y2) == e(v1, X0) * e(M1, X1) * ... * e(Ml, Xl)
+        let lhs = E::pairing(sig.z, sig.y2);
+        let rhs = E::multi_pairing(
+            core::iter::once(sig.v1).chain(msg_g1.iter().copied()),
+            core::iter::once(self.bx0).chain(self.bx.iter().take(msg_g1.len()).copied()),
+        );
+        if lhs != rhs {
+            return false;
+        }
+
+        // e(y1, z') == e(W0, v2) * e(W1, N1) * ... * e(Wk, Nk)
+        let lhs = E::pairing(sig.y1, sig.z2);
+        let rhs = E::multi_pairing(
+            core::iter::once(self.bw0).chain(self.bw.iter().take(msg_g2.len()).copied()),
+            core::iter::once(sig.v2).chain(msg_g2.iter().copied()),
+        );
+        lhs == rhs
+    }
+
+    /// Convert the public key.
+    /// The input scalar `p` must be the same as the one used in the conversion of the secret key and the signature.
+    pub fn convert(&mut self, p: E::ScalarField) {
+        self.bx.iter_mut().for_each(|bxi| *bxi *= p);
+        self.bw.iter_mut().for_each(|bwi| *bwi *= p);
+        self.bx0 *= p;
+        self.bw0 *= p;
+    }
+}
+
+impl<E: Pairing> BilateralSecretKey<E> {
+    /// Sign a bilateral message.
+    ///
+    /// ## Safety
+    /// This function panics if the lengths of the secret key are less than the lengths of the message.
+    pub fn sign_bilateral<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        pp: &PublicParams<E>,
+        msg_g1: &[E::G1],
+        msg_g2: &[E::G2],
+    ) -> BilateralSignature<E> {
+        if self.x.len() < msg_g1.len() || self.w.len() < msg_g2.len() {
+            panic!("The lengths of the secret key must be equal or greater than the lengths of the message.");
+        }
+
+        let y = E::ScalarField::rand(rng);
+        // v1 = p1^a, v2 = p2^a
+        let a = E::ScalarField::rand(rng);
+        let v1 = pp.p1.mul(a);
+        let v2 = pp.p2.mul(a);
+        // z = (x0 v1 + x1 M1 + ... + xl Ml) * y
+        let z = msg_g1
+            .iter()
+            .zip(self.x.iter())
+            .fold(v1.mul(y * self.x0), |acc, (m, xi)| acc + m.mul(y * xi));
+        // z' = (w0 v2 + w1 N1 + ... + wk Nk) * y
+        let z2 = msg_g2
+            .iter()
+            .zip(self.w.iter())
+            .fold(v2.mul(y * self.w0), |acc, (n, wi)| acc + n.mul(y * wi));
+        let y1 = pp.p1.mul(E::ScalarField::one() / y);
+        let y2 = pp.p2.mul(E::ScalarField::one() / y);
+        BilateralSignature {
+            z,
+            z2,
+            y1,
+            y2,
+            v1,
+            v2,
+        }
+    }
+
+    /// Convert the secret key.
+    /// The input scalar `p` must be the same as the one used in the conversion of the public key and the signature.
+    pub fn convert(&mut self, p: E::ScalarField) {
+        self.x.iter_mut().for_each(|xi| *xi *= p);
+        self.w.iter_mut().for_each(|wi| *wi *= p);
+        self.x0 *= p;
+        self.w0 *= p;
+    }
+}
+
+impl<E: Pairing> BilateralSignature<E> {
+    /// Convert the signature.
+    /// The input scalar `p` must be the same as the one used in the conversion of the public key and the secret key.
+    pub fn convert<R: RngCore + CryptoRng>(&mut self, rng: &mut R, p: E::ScalarField) {
+        let f = E::ScalarField::rand(rng);
+        self.convert_with_f(p, f);
+    }
+
+    /// Convert the signature with a scalar `f`.
+    pub(crate) fn convert_with_f(&mut self, p: E::ScalarField, f: E::ScalarField) {
+        self.z *= p * f;
+        self.z2 *= p * f;
+        self.y1 *= E::ScalarField::one() / f;
+        self.y2 *= E::ScalarField::one() / f;
+    }
+}
+
+/// Change the representation of both halves of the bilateral message and the signature. The
+/// anchor of the signature is scaled with the message.
+pub fn change_representation_bilateral<E: Pairing, R: RngCore + CryptoRng>(
+    rng: &mut R,
+    msg_g1: &mut [E::G1],
+    msg_g2: &mut [E::G2],
+    signature: &mut BilateralSignature<E>,
+    u: E::ScalarField,
+) {
+    let f = E::ScalarField::rand(rng);
+    signature.convert_with_f(u, f);
+    signature.v1 *= u;
+    signature.v2 *= u;
+
+    msg_g1.iter_mut().for_each(|mi| *mi *= u);
+    msg_g2.iter_mut().for_each(|ni| *ni *= u);
+}
@@ -0,0 +1,392 @@
+//! Command line tool for the parameters, keys and signatures, enabled by the feature `cli`.
+//!
+//! The parameters, keys and signatures are stored in PEM files of their compressed
+//! serialization, with the labels `MERCURIAL PUBLIC PARAMS`, `MERCURIAL PUBLIC KEY`,
+//! `MERCURIAL SECRET KEY` and `MERCURIAL SIGNATURE`. Messages and scalars are stored as hex of
+//! the compressed serialization of `Vec<G1>` and `Fr`.
+//!
+//! Exit codes: 0 on success, 1 if a signature does not verify, 2 for invalid arguments and 3
+//! for I/O errors or invalid files.
+
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use clap::{Parser, Subcommand};
+use mercurial_signature::{
+    change_representation, Fr, PublicKey, PublicParams, SecretKey, Signature, G1,
+};
+use rand_core::OsRng;
+use sha2::{Digest, Sha256};
+
+const PARAMS_LABEL: &str = "MERCURIAL PUBLIC PARAMS";
+const PUBLIC_KEY_LABEL: &str = "MERCURIAL PUBLIC KEY";
+const SECRET_KEY_LABEL: &str = "MERCURIAL SECRET KEY";
+const SIGNATURE_LABEL: &str = "MERCURIAL SIGNATURE";
+
+const EXIT_INVALID_SIGNATURE: u8 = 1;
+const EXIT_ERROR: u8 = 3;
+
+#[derive(Parser)]
+#[command(
+    name = "mercurial-sig",
+    version,
+    about = "Mercurial signature operations"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Generate or show public parameters.
+    #[command(subcommand)]
+    Params(ParamsCommand),
+    /// Generate a key pair.
+    Keygen {
+        #[arg(long)]
+        params: PathBuf,
+        /// Number of message elements.
+        #[arg(long)]
+        len: u32,
+        #[arg(long)]
+        pk_out: PathBuf,
+        #[arg(long)]
+        sk_out: PathBuf,
+    },
+    /// Sign a message.
+    Sign {
+        #[arg(long)]
+        params: PathBuf,
+        /// Secret key file.
+        #[arg(long)]
+        key: PathBuf,
+        /// Message file in hex.
+        #[arg(long)]
+        message: PathBuf,
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// Verify a signature. Exits with 1 if the signature does not verify.
+    Verify {
+        #[arg(long)]
+        params: PathBuf,
+        /// Public key file.
+        #[arg(long)]
+        key: PathBuf,
+        /// Message file in hex.
+        #[arg(long)]
+        message: PathBuf,
+        #[arg(long)]
+        signature: PathBuf,
+    },
+    /// Convert a public key, a secret key or a signature by a scalar.
+    Convert {
+        /// Scalar file in hex.
+        #[arg(long)]
+        scalar_file: PathBuf,
+        /// Key or signature file.
+        #[arg(long = "in")]
+        input: PathBuf,
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// Change the representation of a message and its signature by a scalar.
+    ChangeRep {
+        /// Scalar file in hex.
+        #[arg(long)]
+        scalar_file: PathBuf,
+        /// Message file in hex.
+        #[arg(long)]
+        message: PathBuf,
+        #[arg(long)]
+        signature: PathBuf,
+        #[arg(long)]
+        message_out: PathBuf,
+        #[arg(long)]
+        signature_out: PathBuf,
+    },
+    /// Print the type, length, size and fingerprint of a file.
+    Inspect { file: PathBuf },
+}
+
+#[derive(Subcommand)]
+enum ParamsCommand {
+    /// Generate public parameters.
+    New {
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// Print the size and fingerprint of public parameters.
+    Show { file: PathBuf },
+}
+
+/// Error of I/O or of an invalid file.
+struct Error(String);
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse().command) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => {
+            eprintln!("signature is invalid");
+            ExitCode::from(EXIT_INVALID_SIGNATURE)
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::from(EXIT_ERROR)
+        }
+    }
+}
+
+/// Run the command. Returns `false` if a signature does not verify.
+fn run(command: Command) -> Result<bool, Error> {
+    match command {
+        Command::Params(ParamsCommand::New { out }) => {
+            write_pem(&out, PARAMS_LABEL, &PublicParams::new(&mut OsRng))?;
+        }
+        Command::Params(ParamsCommand::Show { file }) => {
+            let pp: PublicParams = read_pem(&file, PARAMS_LABEL)?;
+            print_info(PARAMS_LABEL, None, &pp);
+        }
+        Command::Keygen {
+            params,
+            len,
+            pk_out,
+            sk_out,
+        } => {
+            let pp: PublicParams = read_pem(&params, PARAMS_LABEL)?;
+            let (pk, sk) = pp.key_gen(&mut OsRng, len);
+            write_pem(&pk_out, PUBLIC_KEY_LABEL, &pk)?;
+            write_pem(&sk_out, SECRET_KEY_LABEL, &sk)?;
+        }
+        Command::Sign {
+            params,
+            key,
+            message,
+            out,
+        } => {
+            let pp: PublicParams = read_pem(&params, PARAMS_LABEL)?;
+            let sk: SecretKey = read_pem(&key, SECRET_KEY_LABEL)?;
+            let message = read_message(&message)?;
+            if message.len() > sk.length() {
+                return Err(Error("the message is longer than the key".to_string()));
+            }
+            write_pem(&out, SIGNATURE_LABEL, &sk.sign(&mut OsRng, &pp, &message))?;
+        }
+        Command::Verify {
+            params,
+            key,
+            message,
+            signature,
+        } => {
+            let pp: PublicParams = read_pem(&params, PARAMS_LABEL)?;
+            let pk: PublicKey = read_pem(&key, PUBLIC_KEY_LABEL)?;
+            let message = read_message(&message)?;
+            let sig: Signature = read_pem(&signature, SIGNATURE_LABEL)?;
+            let valid = pk.verify(&pp, &message, &sig);
+            if valid {
+                println!("signature is valid");
+            }
+            return Ok(valid);
+        }
+        Command::Convert {
+            scalar_file,
+            input,
+            out,
+        } => {
+            let p: Fr = read_hex(&scalar_file)?;
+            let (label, bytes) = read_pem_any(&input)?;
+            match label.as_str() {
+                PUBLIC_KEY_LABEL => {
+                    let mut pk: PublicKey = deserialize(&bytes, &input)?;
+                    pk.convert(p);
+                    write_pem(&out, PUBLIC_KEY_LABEL, &pk)?;
+                }
+                SECRET_KEY_LABEL => {
+                    let mut sk: SecretKey = deserialize(&bytes, &input)?;
+                    sk.convert(p);
+                    write_pem(&out, SECRET_KEY_LABEL, &sk)?;
+                }
+                SIGNATURE_LABEL => {
+                    let mut sig: Signature = deserialize(&bytes, &input)?;
+                    sig.convert(&mut OsRng, p);
+                    write_pem(&out, SIGNATURE_LABEL, &sig)?;
+                }
+                _ => {
+                    return Err(Error(format!(
+                        "{}: cannot convert {}",
+                        input.display(),
+                        label
+                    )))
+                }
+            }
+        }
+        Command::ChangeRep {
+            scalar_file,
+            message,
+            signature,
+            message_out,
+            signature_out,
+        } => {
+            let u: Fr = read_hex(&scalar_file)?;
+            let mut message = read_message(&message)?;
+            let mut sig: Signature = read_pem(&signature, SIGNATURE_LABEL)?;
+            change_representation(&mut OsRng, &mut message, &mut sig, u);
+            write_hex(&message_out, &message)?;
+            write_pem(&signature_out, SIGNATURE_LABEL, &sig)?;
+        }
+        Command::Inspect { file } => {
+            let (label, bytes) = read_pem_any(&file)?;
+            match label.as_str() {
+                PARAMS_LABEL => {
+                    print_info(&label, None, &deserialize::<PublicParams>(&bytes, &file)?)
+                }
+                PUBLIC_KEY_LABEL => {
+                    let pk: PublicKey = deserialize(&bytes, &file)?;
+                    print_info(&label, Some(pk.length()), &pk)
+                }
+                SECRET_KEY_LABEL => {
+                    let sk: SecretKey = deserialize(&bytes, &file)?;
+                    print_info(&label, Some(sk.length()), &sk)
+                }
+                SIGNATURE_LABEL => {
+                    print_info(&label, None, &deserialize::<Signature>(&bytes, &file)?)
+                }
+                _ => return Err(Error(format!("{}: unknown type {}", file.display(), label))),
+            }
+        }
+    }
+    Ok(true)
+}
+
+/// Print the type, the length, the sizes and the fingerprint, i.e. the first 16 bytes of the
+/// SHA-256 of the compressed serialization.
+fn print_info<T: CanonicalSerialize>(label: &str, length: Option<usize>, value: &T) {
+    let bytes = serialize(value);
+    println!("type: {}", label);
+    if let Some(length) = length {
+        println!("length: {}", length);
+    }
+    println!("compressed size: {}", value.compressed_size());
+    println!("uncompressed size: {}", value.uncompressed_size());
+    println!("fingerprint: {}", to_hex(&Sha256::digest(&bytes)[..16]));
+}
+
+fn write_pem<T: CanonicalSerialize>(path: &Path, label: &str, value: &T) -> Result<(), Error> {
+    let encoded = STANDARD.encode(serialize(value));
+    let mut pem = format!("-----BEGIN {}-----\n", label);
+    for line in encoded.as_bytes().chunks(64) {
+        pem.push_str(std::str::from_utf8(line).unwrap());
+        pem.push('\n');
+    }
+    pem.push_str(&format!("-----END {}-----\n", label));
+    write_file(path, pem)
+}
+
+fn read_pem<T: CanonicalDeserialize>(path: &Path, label: &str) -> Result<T, Error> {
+    let (found, bytes) = read_pem_any(path)?;
+    if found != label {
+        return Err(Error(format!(
+            "{}: expected {}, found {}",
+            path.display(),
+            label,
+            found
+        )));
+    }
+    deserialize(&bytes, path)
+}
+
+/// Read a PEM file. Returns the label and the decoded bytes.
+fn read_pem_any(path: &Path) -> Result<(String, Vec<u8>), Error> {
+    let text = read_file(path)?;
+    let invalid = || Error(format!("{}: invalid PEM", path.display()));
+    let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+    let label = lines
+        .next()
+        .and_then(|line| line.strip_prefix("-----BEGIN "))
+        .and_then(|line| line.strip_suffix("-----"))
+        .ok_or_else(invalid)?
+        .to_string();
+    let end = format!("-----END {}-----", label);
+    let mut encoded = String::new();
+    for line in lines.by_ref() {
+        if line == end {
+            let bytes = STANDARD.decode(&encoded).map_err(|_| invalid())?;
+            return Ok((label, bytes));
+        }
+        encoded.push_str(line);
+    }
+    Err(invalid())
+}
+
+fn write_hex<T: CanonicalSerialize>(path: &Path, value: &T) -> Result<(), Error> {
+    write_file(path, to_hex(&serialize(value)) + "\n")
+}
+
+fn read_hex<T: CanonicalDeserialize>(path: &Path) -> Result<T, Error> {
+    let text = read_file(path)?;
+    let bytes =
+        from_hex(text.trim()).ok_or_else(|| Error(format!("{}: invalid hex", path.display())))?;
+    deserialize(&bytes, path)
+}
+
+/// Read a message, checking its length prefix against the file first so that a corrupted
+/// prefix cannot request a huge allocation.
+fn read_message(path: &Path) -> Result<Vec<G1>, Error> {
+    let text = read_file(path)?;
+    let bytes =
+        from_hex(text.trim()).ok_or_else(|| Error(format!("{}: invalid hex", path.display())))?;
+    let element_size = G1::default().compressed_size();
+    let length = bytes
+        .get(..8)
+        .map(|prefix| u64::from_le_bytes(prefix.try_into().unwrap()));
+    match length {
+        Some(length) if length.checked_mul(element_size as u64) == Some(bytes.len() as u64 - 8) => {
+            deserialize(&bytes, path)
+        }
+        _ => Err(Error(format!("{}: invalid message length", path.display()))),
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn read_file(path: &Path) -> Result<String, Error> {
+    fs::read_to_string(path).map_err(|e| Error(format!("{}: {}", path.display(), e)))
+}
+
+fn write_file(path: &Path, contents: String) -> Result<(), Error> {
+    fs::write(path, contents).map_err(|e| Error(format!("{}: {}", path.display(), e)))
+}
+
+fn serialize<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes).unwrap();
+    bytes
+}
+
+fn deserialize<T: CanonicalDeserialize>(bytes: &[u8], path: &Path) -> Result<T, Error> {
+    T::deserialize_compressed(bytes).map_err(|e| Error(format!("{}: {}", path.display(), e)))
+}
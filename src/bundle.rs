@@ -0,0 +1,403 @@
+//! Containers of many signatures or public keys, e.g. the credentials of a wallet, serialized
+//! as one frame and loaded or saved at once.
+//!
+//! The frame of a [SignatureBundle] or a [KeyBundle] is:
+//!
+//! 1. the magic bytes `MRCB`, the version `1` and the kind of the items, `0` for signatures
+//!    and `1` for public keys,
+//! 2. the 8 bytes identifying the curve, i.e. the first bytes of the SHA-256 of its compressed
+//!    generators of G1 and G2,
+//! 3. the number of items as a little endian `u64`,
+//! 4. the items in the compressed form: a signature is `z, y1, y2`, and a public key is its
+//!    length as a little endian `u64` followed by its elements,
+//! 5. the SHA-256 of all the bytes above.
+//!
+//! The header is written once for all the items, instead of a length prefix and a validation
+//! per item, and the checksum detects a corrupted or truncated frame.
+
+use alloc::vec::Vec;
+use ark_ec::{pairing::Pairing, AffineRepr};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Validate, Write,
+};
+use core::fmt;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    public_key::PublicKey,
+    serialization::{deserialize_vec, MAX_RESERVED},
+    signature::Signature,
+};
+
+const MAGIC: [u8; 4] = *b"MRCB";
+const VERSION: u8 = 1;
+const HEADER_LEN: usize = 22;
+const CHECKSUM_LEN: usize = 32;
+
+/// Error in reading a [SignatureBundle] or a [KeyBundle].
+#[derive(Debug)]
+pub enum BundleError {
+    /// The magic bytes, the version or the kind of the items is unknown.
+    InvalidHeader,
+    /// The bundle is of another curve.
+    CurveMismatch,
+    /// The number of items does not fit in the bytes of the bundle.
+    LengthMismatch,
+    /// The checksum does not match the bytes of the bundle.
+    ChecksumMismatch,
+    /// An item is malformed, or the reader fails.
+    Serialization(SerializationError),
+}
+
+impl fmt::Display for BundleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BundleError::InvalidHeader => f.write_str("invalid bundle header"),
+            BundleError::CurveMismatch => f.write_str("the bundle is of another curve"),
+            BundleError::LengthMismatch => {
+                f.write_str("the number of items does not fit in the bundle")
+            }
+            BundleError::ChecksumMismatch => f.write_str("the checksum of the bundle is wrong"),
+            BundleError::Serialization(e) => write!(f, "serialization: {}", e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BundleError {}
+
+impl From<SerializationError> for BundleError {
+    fn from(e: SerializationError) -> Self {
+        BundleError::Serialization(e)
+    }
+}
+
+/// Signatures in one frame, see the [module](self) documentation.
+///
+/// ## Example
+///
+/// ```rust
+/// use mercurial_signature::{PublicParams, SignatureBundle, UniformRand, G1};
+///
+/// let mut rng = rand::thread_rng();
+/// let pp = PublicParams::new(&mut rng);
+/// let (pk, sk) = pp.key_gen(&mut rng, 10);
+/// let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+///
+/// let mut bundle = SignatureBundle::new();
+/// bundle.push(sk.sign(&mut rng, &pp, &message));
+/// bundle.push(sk.sign(&mut rng, &pp, &message));
+///
+/// let bundle = SignatureBundle::from_bytes(&bundle.to_bytes()).unwrap();
+/// assert_eq!(bundle.length(), 2);
+/// assert!(bundle.iter().all(|sig| pk.verify(&pp, &message, sig)));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignatureBundle<E: Pairing> {
+    sigs: Vec<Signature<E>>,
+}
+
+/// Public keys in one frame, see the [module](self) documentation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyBundle<E: Pairing> {
+    keys: Vec<PublicKey<E>>,
+}
+
+impl<E: Pairing> Default for SignatureBundle<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Pairing> SignatureBundle<E> {
+    /// Create an empty bundle.
+    pub fn new() -> Self {
+        SignatureBundle { sigs: Vec::new() }
+    }
+
+    /// Append a signature.
+    pub fn push(&mut self, sig: Signature<E>) {
+        self.sigs.push(sig);
+    }
+
+    /// The signature at the index, or `None` if it is out of range.
+    pub fn get(&self, index: usize) -> Option<&Signature<E>> {
+        self.sigs.get(index)
+    }
+
+    /// Iterate over the signatures in the order of insertion.
+    pub fn iter(&self) -> core::slice::Iter<'_, Signature<E>> {
+        self.sigs.iter()
+    }
+
+    /// Number of signatures.
+    pub fn length(&self) -> usize {
+        self.sigs.len()
+    }
+
+    /// Write the frame of the bundle.
+    pub fn write<W: Write>(&self, writer: W) -> Result<(), SerializationError> {
+        write_frame::<E, _, _>(writer, KIND_SIGNATURE, &self.sigs, |writer, sig| {
+            sig.serialize_compressed(writer)
+        })
+    }
+
+    /// Read the frame of a bundle from a stream, validating every signature. The signatures
+    /// are read one by one, so a large number of items in the header reserves no more memory
+    /// than the signatures actually read.
+    pub fn read<R: Read>(reader: R) -> Result<Self, BundleError> {
+        let sigs = read_frame::<E, _, _>(reader, KIND_SIGNATURE, |reader| {
+            Signature::deserialize_compressed(reader)
+        })?;
+        Ok(SignatureBundle { sigs })
+    }
+
+    /// The bytes of the frame, see [SignatureBundle::write].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.write(&mut bytes).unwrap();
+        bytes
+    }
+
+    /// Read a bundle from its bytes, see [SignatureBundle::read]. The number of items is
+    /// checked against the number of bytes before reading them.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BundleError> {
+        let count = frame_count(bytes, KIND_SIGNATURE)?;
+        let size = signature_size::<E>();
+        if count.checked_mul(size) != Some(bytes.len() - HEADER_LEN - CHECKSUM_LEN) {
+            return Err(BundleError::LengthMismatch);
+        }
+        Self::read(bytes)
+    }
+}
+
+impl<E: Pairing> FromIterator<Signature<E>> for SignatureBundle<E> {
+    fn from_iter<I: IntoIterator<Item = Signature<E>>>(iter: I) -> Self {
+        SignatureBundle {
+            sigs: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<E: Pairing> Default for KeyBundle<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Pairing> KeyBundle<E> {
+    /// Create an empty bundle.
+    pub fn new() -> Self {
+        KeyBundle { keys: Vec::new() }
+    }
+
+    /// Append a public key.
+    pub fn push(&mut self, key: PublicKey<E>) {
+        self.keys.push(key);
+    }
+
+    /// The public key at the index, or `None` if it is out of range.
+    pub fn get(&self, index: usize) -> Option<&PublicKey<E>> {
+        self.keys.get(index)
+    }
+
+    /// Iterate over the public keys in the order of insertion.
+    pub fn iter(&self) -> core::slice::Iter<'_, PublicKey<E>> {
+        self.keys.iter()
+    }
+
+    /// Number of public keys.
+    pub fn length(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Write the frame of the bundle.
+    pub fn write<W: Write>(&self, writer: W) -> Result<(), SerializationError> {
+        write_frame::<E, _, _>(writer, KIND_PUBLIC_KEY, &self.keys, |writer, key| {
+            key.bx.serialize_compressed(writer)
+        })
+    }
+
+    /// Read the frame of a bundle from a stream, validating every public key, see
+    /// [SignatureBundle::read].
+    pub fn read<R: Read>(reader: R) -> Result<Self, BundleError> {
+        let keys = read_frame::<E, _, _>(reader, KIND_PUBLIC_KEY, |reader| {
+            Ok(PublicKey {
+                bx: deserialize_vec(reader, Compress::Yes, Validate::Yes)?,
+            })
+        })?;
+        Ok(KeyBundle { keys })
+    }
+
+    /// The bytes of the frame, see [KeyBundle::write].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.write(&mut bytes).unwrap();
+        bytes
+    }
+
+    /// Read a bundle from its bytes, see [KeyBundle::read]. The number of keys, and the length
+    /// of each key, are checked against the number of bytes before reading them.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BundleError> {
+        let count = frame_count(bytes, KIND_PUBLIC_KEY)?;
+        let g2_size = E::G2Affine::zero().compressed_size();
+        let mut rest = &bytes[HEADER_LEN..bytes.len() - CHECKSUM_LEN];
+        for _ in 0..count {
+            let len = rest
+                .get(..8)
+                .map(|len| u64::from_le_bytes(len.try_into().unwrap()))
+                .ok_or(BundleError::LengthMismatch)?;
+            let size = usize::try_from(len)
+                .ok()
+                .and_then(|len| len.checked_mul(g2_size))
+                .filter(|size| *size <= rest.len() - 8)
+                .ok_or(BundleError::LengthMismatch)?;
+            rest = &rest[8 + size..];
+        }
+        if !rest.is_empty() {
+            return Err(BundleError::LengthMismatch);
+        }
+        Self::read(bytes)
+    }
+}
+
+impl<E: Pairing> FromIterator<PublicKey<E>> for KeyBundle<E> {
+    fn from_iter<I: IntoIterator<Item = PublicKey<E>>>(iter: I) -> Self {
+        KeyBundle {
+            keys: iter.into_iter().collect(),
+        }
+    }
+}
+
+const KIND_SIGNATURE: u8 = 0;
+const KIND_PUBLIC_KEY: u8 = 1;
+
+/// The 8 bytes identifying the curve in the header.
+fn curve_id<E: Pairing>() -> [u8; 8] {
+    let mut bytes = Vec::new();
+    E::G1Affine::generator()
+        .serialize_compressed(&mut bytes)
+        .unwrap();
+    E::G2Affine::generator()
+        .serialize_compressed(&mut bytes)
+        .unwrap();
+    Sha256::digest(&bytes)[..8].try_into().unwrap()
+}
+
+fn signature_size<E: Pairing>() -> usize {
+    2 * E::G1Affine::zero().compressed_size() + E::G2Affine::zero().compressed_size()
+}
+
+fn header<E: Pairing>(kind: u8, count: usize) -> [u8; HEADER_LEN] {
+    let mut header = [0u8; HEADER_LEN];
+    header[..4].copy_from_slice(&MAGIC);
+    header[4] = VERSION;
+    header[5] = kind;
+    header[6..14].copy_from_slice(&curve_id::<E>());
+    header[14..].copy_from_slice(&(count as u64).to_le_bytes());
+    header
+}
+
+/// Check the header of the bytes of a frame, and return its number of items.
+fn frame_count(bytes: &[u8], kind: u8) -> Result<usize, BundleError> {
+    if bytes.len() < HEADER_LEN + CHECKSUM_LEN {
+        return Err(BundleError::LengthMismatch);
+    }
+    if bytes[..4] != MAGIC || bytes[4] != VERSION || bytes[5] != kind {
+        return Err(BundleError::InvalidHeader);
+    }
+    usize::try_from(u64::from_le_bytes(
+        bytes[14..HEADER_LEN].try_into().unwrap(),
+    ))
+    .map_err(|_| BundleError::LengthMismatch)
+}
+
+fn write_frame<E: Pairing, W: Write, T>(
+    writer: W,
+    kind: u8,
+    items: &[T],
+    mut write_item: impl FnMut(&mut HashingWriter<W>, &T) -> Result<(), SerializationError>,
+) -> Result<(), SerializationError> {
+    let mut writer = HashingWriter {
+        inner: writer,
+        hasher: Sha256::new(),
+    };
+    writer.write_all(&header::<E>(kind, items.len()))?;
+    for item in items {
+        write_item(&mut writer, item)?;
+    }
+    let checksum = writer.hasher.finalize();
+    writer.inner.write_all(&checksum)?;
+    Ok(())
+}
+
+fn read_frame<E: Pairing, R: Read, T>(
+    reader: R,
+    kind: u8,
+    mut read_item: impl FnMut(&mut HashingReader<R>) -> Result<T, SerializationError>,
+) -> Result<Vec<T>, BundleError> {
+    let mut reader = HashingReader {
+        inner: reader,
+        hasher: Sha256::new(),
+    };
+    let mut header = [0u8; HEADER_LEN];
+    reader
+        .read_exact(&mut header)
+        .map_err(SerializationError::from)?;
+    if header[..4] != MAGIC || header[4] != VERSION || header[5] != kind {
+        return Err(BundleError::InvalidHeader);
+    }
+    if header[6..14] != curve_id::<E>() {
+        return Err(BundleError::CurveMismatch);
+    }
+    let count = usize::try_from(u64::from_le_bytes(header[14..].try_into().unwrap()))
+        .map_err(|_| BundleError::LengthMismatch)?;
+
+    let mut items = Vec::with_capacity(count.min(MAX_RESERVED));
+    for _ in 0..count {
+        items.push(read_item(&mut reader)?);
+    }
+
+    let expected = reader.hasher.finalize();
+    let mut checksum = [0u8; CHECKSUM_LEN];
+    reader
+        .inner
+        .read_exact(&mut checksum)
+        .map_err(SerializationError::from)?;
+    if expected[..] != checksum {
+        return Err(BundleError::ChecksumMismatch);
+    }
+    Ok(items)
+}
+
+/// Writer which hashes the bytes written through it.
+struct HashingWriter<W> {
+    inner: W,
+    hasher: Sha256,
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> ark_std::io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> ark_std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Reader which hashes the bytes read through it.
+struct HashingReader<R> {
+    inner: R,
+    hasher: Sha256,
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> ark_std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+}
@@ -0,0 +1,347 @@
+//! Signed claims in the style of JWT, e.g. `iss`, `sub`, `exp` and custom claims.
+//!
+//! The claims are kept in the canonical order of their names, and each claim is hashed to an
+//! element of G1 to form the message, so a key of length `l` signs up to `l` claims. The
+//! [SignedClaims] are serialized in a compact token `<claims>.<signature>`, both parts in
+//! base64url without padding.
+//!
+//! The signature can be re-randomized with [SignedClaims::randomize], so that presenting the
+//! same claims twice does not link the presentations by the signature. The claims themselves
+//! are disclosed in full.
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::{format, string::String, string::ToString, vec::Vec};
+
+use ark_ec::pairing::Pairing;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{One, UniformRand};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use core::fmt;
+use rand_core::{CryptoRng, RngCore};
+
+use crate::{
+    hash::hash_to_g1, params::PublicParams, public_key::PublicKey, secret_key::SecretKey,
+    signature::Signature, signer::AsyncSigner,
+};
+
+/// The value of a claim.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ClaimValue {
+    String(String),
+    Number(i64),
+    Bool(bool),
+}
+
+/// A map of claims, ordered by the names.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Claims {
+    claims: BTreeMap<String, ClaimValue>,
+}
+
+/// Claims with a signature on them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignedClaims<E: Pairing> {
+    pub(crate) claims: Claims,
+    pub(crate) sig: Signature<E>,
+}
+
+/// The checks of [verify_claims] in addition to the signature.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Validation {
+    /// The current time, in the unit of `exp` and `nbf`, e.g. seconds since the Unix epoch.
+    pub now: i64,
+    /// The allowed difference of the clocks for checking `exp` and `nbf`.
+    pub leeway: i64,
+    /// The expected `iss`, if any.
+    pub issuer: Option<String>,
+    /// The allowed names of the claims. Other claims are rejected if it is set.
+    pub allowed_claims: Option<BTreeSet<String>>,
+}
+
+/// Error in verifying or decoding signed claims.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ClaimsError {
+    /// The compact token is malformed.
+    InvalidToken,
+    /// The signature does not verify the claims.
+    InvalidSignature,
+    /// The claims expired, i.e. `exp` is not after the current time.
+    Expired,
+    /// The claims are not valid yet, i.e. `nbf` is after the current time.
+    NotYetValid,
+    /// The `iss` is missing or not the expected issuer.
+    InvalidIssuer,
+    /// A registered claim has a value of the wrong type.
+    InvalidClaim(String),
+    /// The claim is not in the allowed claims.
+    UnknownClaim(String),
+}
+
+impl fmt::Display for ClaimsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClaimsError::InvalidToken => f.write_str("the compact token is malformed"),
+            ClaimsError::InvalidSignature => {
+                f.write_str("the signature does not verify the claims")
+            }
+            ClaimsError::Expired => f.write_str("the claims expired"),
+            ClaimsError::NotYetValid => f.write_str("the claims are not valid yet"),
+            ClaimsError::InvalidIssuer => f.write_str("the issuer is missing or not expected"),
+            ClaimsError::InvalidClaim(name) => {
+                write!(f, "the claim {} has a value of the wrong type", name)
+            }
+            ClaimsError::UnknownClaim(name) => write!(f, "the claim {} is not allowed", name),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ClaimsError {}
+
+impl Claims {
+    /// Create an empty map of claims.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the claim, replacing the previous value of the name if any.
+    pub fn insert(&mut self, name: &str, value: ClaimValue) {
+        self.claims.insert(name.to_string(), value);
+    }
+
+    /// The value of the claim.
+    pub fn get(&self, name: &str) -> Option<&ClaimValue> {
+        self.claims.get(name)
+    }
+
+    /// Number of claims.
+    pub fn length(&self) -> usize {
+        self.claims.len()
+    }
+
+    /// Encode the claims to the message, one element per claim in the canonical order.
+    pub fn to_message<E: Pairing>(&self) -> Vec<E::G1> {
+        self.claims
+            .iter()
+            .map(|(name, value)| {
+                let mut bytes = Vec::new();
+                encode_claim(&mut bytes, name, value);
+                hash_to_g1::<E>(b"mercurial-signature/claims", &bytes)
+            })
+            .collect()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = (self.claims.len() as u64).to_le_bytes().to_vec();
+        self.claims
+            .iter()
+            .for_each(|(name, value)| encode_claim(&mut bytes, name, value));
+        bytes
+    }
+
+    fn from_bytes(mut bytes: &[u8]) -> Option<Self> {
+        let count = read_u64(&mut bytes)?;
+        let mut claims = BTreeMap::new();
+        for _ in 0..count {
+            let name = read_string(&mut bytes)?;
+            let (&tag, rest) = bytes.split_first()?;
+            bytes = rest;
+            let value = match tag {
+                0 => ClaimValue::String(read_string(&mut bytes)?),
+                1 => ClaimValue::Number(read_u64(&mut bytes)? as i64),
+                2 => {
+                    let (&b, rest) = bytes.split_first()?;
+                    bytes = rest;
+                    match b {
+                        0 => ClaimValue::Bool(false),
+                        1 => ClaimValue::Bool(true),
+                        _ => return None,
+                    }
+                }
+                _ => return None,
+            };
+            // the names must be strictly increasing, so that the encoding is canonical
+            if claims.keys().next_back().is_some_and(|last| *last >= name) {
+                return None;
+            }
+            claims.insert(name, value);
+        }
+        bytes.is_empty().then_some(Claims { claims })
+    }
+}
+
+impl<E: Pairing> SignedClaims<E> {
+    /// The signed claims.
+    pub fn claims(&self) -> &Claims {
+        &self.claims
+    }
+
+    /// Re-randomize the signature, so that it is unlinkable to the previous one.
+    pub fn randomize<R: RngCore + CryptoRng>(&mut self, rng: &mut R) {
+        let f = E::ScalarField::rand(rng);
+        self.sig.convert_with_f(E::ScalarField::one(), f);
+    }
+
+    /// Serialize to the compact token `<claims>.<signature>`.
+    pub fn to_compact(&self) -> String {
+        let mut sig = Vec::new();
+        self.sig.serialize_compressed(&mut sig).unwrap();
+        format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(self.claims.to_bytes()),
+            URL_SAFE_NO_PAD.encode(sig)
+        )
+    }
+
+    /// Deserialize from the compact token. The signature is not verified, see [verify_claims].
+    pub fn from_compact(token: &str) -> Result<Self, ClaimsError> {
+        let (claims, sig) = token.split_once('.').ok_or(ClaimsError::InvalidToken)?;
+        let claims = URL_SAFE_NO_PAD
+            .decode(claims)
+            .ok()
+            .and_then(|bytes| Claims::from_bytes(&bytes))
+            .ok_or(ClaimsError::InvalidToken)?;
+        let sig = URL_SAFE_NO_PAD
+            .decode(sig)
+            .ok()
+            .and_then(|bytes| Signature::deserialize_compressed(bytes.as_slice()).ok())
+            .ok_or(ClaimsError::InvalidToken)?;
+        Ok(SignedClaims { claims, sig })
+    }
+}
+
+impl Validation {
+    /// Validate at the time `now` without leeway, issuer or restriction of the claims.
+    pub fn new(now: i64) -> Self {
+        Validation {
+            now,
+            leeway: 0,
+            issuer: None,
+            allowed_claims: None,
+        }
+    }
+}
+
+/// Sign the claims.
+///
+/// ## Safety
+/// This function panics if the length of the secret key is less than the number of claims.
+///
+/// ## Example
+///
+/// ```rust
+/// use mercurial_signature::{sign_claims, verify_claims, ClaimValue, Claims, PublicParams, Validation};
+///
+/// let mut rng = rand::thread_rng();
+/// let pp = PublicParams::new(&mut rng);
+/// let (pk, sk) = pp.key_gen(&mut rng, 5);
+///
+/// let mut claims = Claims::new();
+/// claims.insert("iss", ClaimValue::String("issuer".to_string()));
+/// claims.insert("exp", ClaimValue::Number(2000));
+/// let signed = sign_claims(&mut rng, &sk, &pp, &claims);
+///
+/// assert!(verify_claims(&pk, &pp, &signed, &Validation::new(1000)).is_ok());
+/// assert!(verify_claims(&pk, &pp, &signed, &Validation::new(3000)).is_err());
+/// ```
+pub fn sign_claims<E: Pairing, R: RngCore + CryptoRng>(
+    rng: &mut R,
+    sk: &SecretKey<E>,
+    pp: &PublicParams<E>,
+    claims: &Claims,
+) -> SignedClaims<E> {
+    SignedClaims {
+        claims: claims.clone(),
+        sig: sk.sign(rng, pp, &claims.to_message::<E>()),
+    }
+}
+
+/// Sign the claims with the signer, e.g. a remote service holding the secret key.
+pub async fn sign_claims_with_signer<E: Pairing, S: AsyncSigner<E>>(
+    signer: &S,
+    pp: &PublicParams<E>,
+    claims: &Claims,
+) -> Result<SignedClaims<E>, S::Error> {
+    let sig = signer.sign(&pp.digest(), &claims.to_message::<E>()).await?;
+    Ok(SignedClaims {
+        claims: claims.clone(),
+        sig,
+    })
+}
+
+/// Verify the signature on the claims, then check `exp`, `nbf`, `iss` and the allowed claims
+/// as set in the validation.
+pub fn verify_claims<E: Pairing>(
+    pk: &PublicKey<E>,
+    pp: &PublicParams<E>,
+    signed: &SignedClaims<E>,
+    validation: &Validation,
+) -> Result<(), ClaimsError> {
+    let claims = &signed.claims;
+    if claims.length() > pk.length() || !pk.verify(pp, &claims.to_message::<E>(), &signed.sig) {
+        return Err(ClaimsError::InvalidSignature);
+    }
+
+    let number = |name: &str| match claims.get(name) {
+        None => Ok(None),
+        Some(ClaimValue::Number(n)) => Ok(Some(*n)),
+        Some(_) => Err(ClaimsError::InvalidClaim(name.to_string())),
+    };
+    if let Some(exp) = number("exp")? {
+        if validation.now.saturating_sub(validation.leeway) >= exp {
+            return Err(ClaimsError::Expired);
+        }
+    }
+    if let Some(nbf) = number("nbf")? {
+        if validation.now.saturating_add(validation.leeway) < nbf {
+            return Err(ClaimsError::NotYetValid);
+        }
+    }
+    if let Some(issuer) = &validation.issuer {
+        if claims.get("iss") != Some(&ClaimValue::String(issuer.clone())) {
+            return Err(ClaimsError::InvalidIssuer);
+        }
+    }
+    if let Some(allowed) = &validation.allowed_claims {
+        if let Some(name) = claims.claims.keys().find(|name| !allowed.contains(*name)) {
+            return Err(ClaimsError::UnknownClaim(name.clone()));
+        }
+    }
+    Ok(())
+}
+
+fn encode_claim(bytes: &mut Vec<u8>, name: &str, value: &ClaimValue) {
+    bytes.extend_from_slice(&(name.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(name.as_bytes());
+    match value {
+        ClaimValue::String(s) => {
+            bytes.push(0);
+            bytes.extend_from_slice(&(s.len() as u64).to_le_bytes());
+            bytes.extend_from_slice(s.as_bytes());
+        }
+        ClaimValue::Number(n) => {
+            bytes.push(1);
+            bytes.extend_from_slice(&n.to_le_bytes());
+        }
+        ClaimValue::Bool(b) => {
+            bytes.push(2);
+            bytes.push(*b as u8);
+        }
+    }
+}
+
+fn read_u64(bytes: &mut &[u8]) -> Option<u64> {
+    let (value, rest) = bytes.split_first_chunk::<8>()?;
+    *bytes = rest;
+    Some(u64::from_le_bytes(*value))
+}
+
+fn read_string(bytes: &mut &[u8]) -> Option<String> {
+    let len = usize::try_from(read_u64(bytes)?).ok()?;
+    if bytes.len() < len {
+        return None;
+    }
+    let (s, rest) = bytes.split_at(len);
+    *bytes = rest;
+    String::from_utf8(s.to_vec()).ok()
+}
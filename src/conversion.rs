@@ -0,0 +1,141 @@
+//! Conversion with a scalar contributed by two parties, e.g. a user and a service jointly
+//! deriving a new pseudonym, so that neither of them knows the conversion scalar `p`.
+//!
+//! 1. Party A picks `pA` and party B picks `pB`. They exchange the commitments `p1^pA` and
+//!    `p1^pB` before any conversion, so that neither can choose its scalar adaptively.
+//! 2. Party A converts the public key and the signature with `pA` and sends them to party B
+//!    in a [ConversionPass]. Party B checks the pass and converts the result with `pB`.
+//!    Party A checks the second pass in turn.
+//!
+//! The result is converted by `p = pA pB`. A pass is checked by the pairing test that the
+//! converted public key is in the same class as the previous one for the committed scalar,
+//! i.e. `e(p1^p, bxi) == e(p1, bxi')`, and that the converted signature verifies the message
+//! under the converted public key.
+//!
+//! The secret key is not part of the passes: converting it requires knowing `p`, which the
+//! protocol prevents. The holder of the secret key keeps signing with the original key and
+//! the signatures are brought to the new pseudonym by running the protocol.
+
+use alloc::vec::Vec;
+use core::ops::Mul;
+
+use ark_ec::pairing::Pairing;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::UniformRand;
+use rand_core::{CryptoRng, RngCore};
+
+use crate::{ct::ct_eq_scalars, params::PublicParams, public_key::PublicKey, signature::Signature};
+
+/// The session of one party, holding its contributed scalar.
+#[derive(Clone)]
+pub struct ContributedConversion<E: Pairing> {
+    p: E::ScalarField,
+}
+
+impl<E: Pairing> PartialEq for ContributedConversion<E> {
+    fn eq(&self, other: &Self) -> bool {
+        ct_eq_scalars::<E>(&[self.p], &[other.p])
+    }
+}
+
+impl<E: Pairing> Eq for ContributedConversion<E> {}
+
+/// The commitment to the scalar of a party, sent before the conversion.
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ConversionCommitment<E: Pairing> {
+    // p1^p
+    pub(crate) c: E::G1,
+}
+
+/// The public key and signature converted by a party.
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ConversionPass<E: Pairing> {
+    pub(crate) pk: PublicKey<E>,
+    pub(crate) sig: Signature<E>,
+}
+
+impl<E: Pairing> ContributedConversion<E> {
+    /// Start a session by picking a random scalar. Returns the session and the commitment
+    /// to be sent to the other party.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::{ContributedConversion, PublicParams, UniformRand, G1};
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let (pk, sk) = pp.key_gen(&mut rng, 10);
+    /// let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    /// let sig = sk.sign(&mut rng, &pp, &message);
+    ///
+    /// let (session_a, commitment_a) = ContributedConversion::new(&mut rng, &pp);
+    /// let (session_b, commitment_b) = ContributedConversion::new(&mut rng, &pp);
+    ///
+    /// let pass_a = session_a.apply(&mut rng, &pk, &sig);
+    /// assert!(pass_a.verify(&pp, &commitment_a, &pk, &message));
+    /// let pass_b = session_b.apply(&mut rng, pass_a.public_key(), pass_a.signature());
+    /// assert!(pass_b.verify(&pp, &commitment_b, pass_a.public_key(), &message));
+    /// ```
+    pub fn new<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        pp: &PublicParams<E>,
+    ) -> (Self, ConversionCommitment<E>) {
+        let p = E::ScalarField::rand(rng);
+        let c = pp.p1.mul(p);
+        (ContributedConversion { p }, ConversionCommitment { c })
+    }
+
+    /// Convert the public key and the signature with the scalar of this party.
+    pub fn apply<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        pk: &PublicKey<E>,
+        sig: &Signature<E>,
+    ) -> ConversionPass<E> {
+        let mut pk = pk.clone();
+        let mut sig = sig.clone();
+        pk.convert(self.p);
+        sig.convert(rng, self.p);
+        ConversionPass { pk, sig }
+    }
+}
+
+impl<E: Pairing> ConversionPass<E> {
+    /// The converted public key.
+    pub fn public_key(&self) -> &PublicKey<E> {
+        &self.pk
+    }
+
+    /// The converted signature.
+    pub fn signature(&self) -> &Signature<E> {
+        &self.sig
+    }
+
+    /// Check the pass is converted from `pk` with the committed scalar, and the converted
+    /// signature verifies the message.
+    pub fn verify(
+        &self,
+        pp: &PublicParams<E>,
+        commitment: &ConversionCommitment<E>,
+        pk: &PublicKey<E>,
+        message: &[E::G1],
+    ) -> bool {
+        same_class(pp, commitment, pk, &self.pk) && self.pk.verify(pp, message, &self.sig)
+    }
+}
+
+/// Check `e(p1^p, bxi) == e(p1, bxi')` for all i.
+fn same_class<E: Pairing>(
+    pp: &PublicParams<E>,
+    commitment: &ConversionCommitment<E>,
+    pk: &PublicKey<E>,
+    converted: &PublicKey<E>,
+) -> bool {
+    pk.bx.len() == converted.bx.len()
+        && pk
+            .bx
+            .iter()
+            .zip(converted.bx.iter())
+            .all(|(bxi, bxi2)| E::pairing(commitment.c, *bxi) == E::pairing(pp.p1, *bxi2))
+}
@@ -0,0 +1,204 @@
+//! Co-signatures of multiple signers on the same message.
+//!
+//! True aggregation is not possible for this scheme: each signature carries its own
+//! randomness `y` in both `y1, y2` and `z`, and the verification equation `e(z, y2)` pairs
+//! two signature elements, so the equations of different signers cannot be merged into a
+//! single signature. Instead, [CoSignature] keeps one signature per signer and verifies all
+//! of them at once with random linear combinations:
+//!
+//! - `e(s1 y1_1 + ... + sk y1_k, p2) == e(p1, s1 y2_1 + ... + sk y2_k)`
+//! - `e(r1 z_1, y2_1) * ... * e(rk z_k, y2_k) == e(M1, r1 bx_1,1 + ... + rk bx_k,1) * ... * e(Ml, r1 bx_1,l + ... + rk bx_k,l)`
+//!
+//! where `r, s` are random scalars picked by the verifier. The second equation is a single
+//! multi-pairing with `k + l` pairs instead of `k (l + 1)` pairings.
+//!
+//! To protect against rogue keys, the signers are registered in [CoSigners] only with a
+//! [ProofOfPossession], i.e. a Schnorr proof of knowledge of the secret key.
+
+use alloc::vec::Vec;
+use core::ops::Mul;
+
+use ark_ec::pairing::Pairing;
+use ark_serialize::CanonicalSerialize;
+use ark_std::{UniformRand, Zero};
+use rand_core::{CryptoRng, RngCore};
+
+use crate::{
+    params::PublicParams, public_key::PublicKey, secret_key::SecretKey,
+    serialization::impl_bounded_deserialize, signature::Signature, transcript::Transcript,
+};
+
+/// A proof of knowledge of the secret key corresponding to a public key.
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize)]
+pub struct ProofOfPossession<E: Pairing> {
+    // commitments p2^ri
+    pub(crate) r: Vec<E::G2>,
+    // responses si = ri + c xi
+    pub(crate) s: Vec<E::ScalarField>,
+}
+
+impl_bounded_deserialize!(ProofOfPossession { r: vec, s: vec });
+
+/// The set of co-signers whose public keys come with a valid proof of possession.
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize)]
+pub struct CoSigners<E: Pairing> {
+    pub(crate) keys: Vec<PublicKey<E>>,
+}
+
+impl_bounded_deserialize!(CoSigners { keys: vec });
+
+/// The signatures of the co-signers on the same message, in the order of [CoSigners].
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize)]
+pub struct CoSignature<E: Pairing> {
+    pub(crate) sigs: Vec<Signature<E>>,
+}
+
+impl_bounded_deserialize!(CoSignature { sigs: vec });
+
+impl<E: Pairing> ProofOfPossession<E> {
+    /// Prove the knowledge of the secret key.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::{CoSignature, CoSigners, ProofOfPossession, PublicParams, UniformRand, G1};
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let (pk_a, sk_a) = pp.key_gen(&mut rng, 10);
+    /// let (pk_b, sk_b) = pp.key_gen(&mut rng, 10);
+    /// let pop_a = ProofOfPossession::prove(&mut rng, &pp, &sk_a);
+    /// let pop_b = ProofOfPossession::prove(&mut rng, &pp, &sk_b);
+    /// let signers = CoSigners::new(&pp, &[(pk_a, pop_a), (pk_b, pop_b)]).unwrap();
+    ///
+    /// let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    /// let sig_a = sk_a.sign(&mut rng, &pp, &message);
+    /// let sig_b = sk_b.sign(&mut rng, &pp, &message);
+    /// let cosig = CoSignature::combine(&[sig_a, sig_b]);
+    /// assert!(cosig.verify(&mut rng, &pp, &signers, &message));
+    /// ```
+    pub fn prove<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        pp: &PublicParams<E>,
+        sk: &SecretKey<E>,
+    ) -> Self {
+        let bx = sk.x.iter().map(|xi| pp.p2.mul(xi)).collect::<Vec<E::G2>>();
+        let ri = (0..sk.x.len())
+            .map(|_| E::ScalarField::rand(rng))
+            .collect::<Vec<E::ScalarField>>();
+        let r = ri.iter().map(|ri| pp.p2.mul(ri)).collect::<Vec<E::G2>>();
+        let c = challenge::<E>(pp, &bx, &r);
+        let s = ri
+            .iter()
+            .zip(sk.x.iter())
+            .map(|(ri, xi)| *ri + c * xi)
+            .collect();
+        ProofOfPossession { r, s }
+    }
+
+    /// Verify the proof against the public key.
+    pub fn verify(&self, pp: &PublicParams<E>, pk: &PublicKey<E>) -> bool {
+        if self.r.len() != pk.bx.len() || self.s.len() != pk.bx.len() {
+            return false;
+        }
+
+        // p2^si == p2^ri + c bxi
+        let c = challenge::<E>(pp, &pk.bx, &self.r);
+        self.r
+            .iter()
+            .zip(self.s.iter())
+            .zip(pk.bx.iter())
+            .all(|((ri, si), bxi)| pp.p2.mul(si) == *ri + bxi.mul(c))
+    }
+}
+
+impl<E: Pairing> CoSigners<E> {
+    /// Register the co-signers. Returns `None` if any proof of possession is invalid.
+    pub fn new(
+        pp: &PublicParams<E>,
+        keys: &[(PublicKey<E>, ProofOfPossession<E>)],
+    ) -> Option<Self> {
+        if !keys.iter().all(|(pk, pop)| pop.verify(pp, pk)) {
+            return None;
+        }
+        Some(CoSigners {
+            keys: keys.iter().map(|(pk, _)| pk.clone()).collect(),
+        })
+    }
+
+    /// Number of co-signers.
+    pub fn length(&self) -> usize {
+        self.keys.len()
+    }
+}
+
+impl<E: Pairing> CoSignature<E> {
+    /// Combine the signatures of the co-signers, in the order of [CoSigners].
+    pub fn combine(sigs: &[Signature<E>]) -> Self {
+        CoSignature {
+            sigs: sigs.to_vec(),
+        }
+    }
+
+    /// Verify the signatures of all co-signers on the message in a batch.
+    pub fn verify<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        pp: &PublicParams<E>,
+        signers: &CoSigners<E>,
+        message: &[E::G1],
+    ) -> bool {
+        // check the number of signatures and the length l
+        if self.sigs.len() != signers.keys.len()
+            || signers.keys.iter().any(|pk| pk.bx.len() < message.len())
+        {
+            return false;
+        }
+
+        let r = (0..self.sigs.len())
+            .map(|_| E::ScalarField::rand(rng))
+            .collect::<Vec<E::ScalarField>>();
+        let s = (0..self.sigs.len())
+            .map(|_| E::ScalarField::rand(rng))
+            .collect::<Vec<E::ScalarField>>();
+
+        // e(s1 y1_1 + ... + sk y1_k, p2) == e(p1, s1 y2_1 + ... + sk y2_k)
+        let (y1, y2) = self
+            .sigs
+            .iter()
+            .zip(s.iter())
+            .fold((E::G1::zero(), E::G2::zero()), |(y1, y2), (sig, si)| {
+                (y1 + sig.y1.mul(si), y2 + sig.y2.mul(si))
+            });
+        if E::pairing(y1, pp.p2) != E::pairing(pp.p1, y2) {
+            return false;
+        }
+
+        // e(r1 z_1, y2_1) * ... * e(rk z_k, y2_k) == e(M1, r1 bx_1,1 + ... + rk bx_k,1) * ... * e(Ml, r1 bx_1,l + ... + rk bx_k,l)
+        let lhs = E::multi_pairing(
+            self.sigs
+                .iter()
+                .zip(r.iter())
+                .map(|(sig, ri)| sig.z.mul(ri)),
+            self.sigs.iter().map(|sig| sig.y2),
+        );
+        let bx = (0..message.len()).map(|i| {
+            signers
+                .keys
+                .iter()
+                .zip(r.iter())
+                .fold(E::G2::zero(), |acc, (pk, ri)| acc + pk.bx[i].mul(ri))
+        });
+        let rhs = E::multi_pairing(message.iter().copied(), bx);
+        lhs == rhs
+    }
+}
+
+/// Fiat-Shamir challenge of the proof of possession.
+fn challenge<E: Pairing>(pp: &PublicParams<E>, bx: &[E::G2], r: &[E::G2]) -> E::ScalarField {
+    let mut transcript = Transcript::new(b"mercurial-signature/proof-of-possession");
+    transcript.append(b"pp", pp);
+    transcript.append(b"pk", bx);
+    transcript.append(b"commitments", r);
+    transcript.challenge_scalar::<E>(b"challenge")
+}
@@ -0,0 +1,53 @@
+//! Constant time comparison of scalars, for comparing secret scalars such as the elements of
+//! the secret key or the conversion scalar `p`.
+
+use alloc::vec::Vec;
+use ark_ec::pairing::Pairing;
+use ark_serialize::CanonicalSerialize;
+use subtle::{Choice, ConstantTimeEq};
+
+/// A scalar compared in constant time. The scalars are serialized to bytes, which are compared
+/// by [ConstantTimeEq].
+///
+/// ## Example
+///
+/// ```rust
+/// use mercurial_signature::{CtFr, Fr};
+/// use subtle::ConstantTimeEq;
+///
+/// let a = CtFr::new(Fr::from(7u64));
+/// let b = CtFr::new(Fr::from(7u64));
+/// assert!(bool::from(a.ct_eq(&b)));
+/// ```
+#[derive(Clone, Copy)]
+pub struct CtFr<E: Pairing>(pub E::ScalarField);
+
+impl<E: Pairing> CtFr<E> {
+    /// Wrap the scalar.
+    pub fn new(value: E::ScalarField) -> Self {
+        CtFr(value)
+    }
+}
+
+impl<E: Pairing> ConstantTimeEq for CtFr<E> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        let mut a = Vec::new();
+        let mut b = Vec::new();
+        self.0.serialize_compressed(&mut a).unwrap();
+        other.0.serialize_compressed(&mut b).unwrap();
+        a.ct_eq(&b)
+    }
+}
+
+/// Compare the vectors of scalars in constant time with respect to their elements. The lengths
+/// are public and compared in variable time.
+pub(crate) fn ct_eq_scalars<E: Pairing>(a: &[E::ScalarField], b: &[E::ScalarField]) -> bool {
+    a.len() == b.len()
+        && bool::from(
+            a.iter()
+                .zip(b.iter())
+                .fold(Choice::from(1), |acc, (ai, bi)| {
+                    acc & CtFr::<E>(*ai).ct_eq(&CtFr(*bi))
+                }),
+        )
+}
@@ -0,0 +1,102 @@
+//! Hierarchical derivation of key pairs from one master seed.
+//!
+//! A [MasterKey] derives the key pair of a path, e.g. `[tenant, epoch]`, so that an issuer of
+//! many keys stores one seed instead of the keys. The derivation is a chain of HKDF-SHA512:
+//! the seed is extracted into the chain key of the empty path, the chain key of a child is
+//! expanded from the chain key of its parent and the path component, and the elements of the
+//! secret key are expanded from the chain key of the path, domain separated by the curve and
+//! the length. The derivation is fixed: the same seed and path give the same key pair on every
+//! machine and in every version of this crate.
+//!
+//! The key pairs of different paths, and of the same path with different lengths, are
+//! independent to anyone without the seed, i.e. they cannot be linked to each other nor to the
+//! master. There is no public derivation: a child public key cannot be derived from a parent
+//! public key, since the public keys of this scheme have no such structure. Deriving any key
+//! needs the seed.
+
+use alloc::vec::Vec;
+use core::ops::Mul;
+
+use ark_ec::pairing::Pairing;
+use ark_ff::{BigInteger, PrimeField};
+use hkdf::Hkdf;
+use sha2::Sha512;
+use zeroize::Zeroizing;
+
+use crate::{params::PublicParams, public_key::PublicKey, secret_key::SecretKey};
+
+const SALT: &[u8] = b"mercurial-signature/hd-key";
+
+/// Master seed of derived key pairs, see the [module](self) documentation.
+///
+/// ## Example
+///
+/// ```rust
+/// use mercurial_signature::{MasterKey, PublicParams, UniformRand, G1};
+///
+/// let mut rng = rand::thread_rng();
+/// let pp = PublicParams::new(&mut rng);
+/// let master = MasterKey::new([7u8; 32]);
+/// let (pk, sk) = master.derive(&[42, 0], &pp, 5);
+/// assert!(master.derive(&[42, 0], &pp, 5).0 == pk);
+///
+/// let message = (0..5).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+/// let sig = sk.sign(&mut rng, &pp, &message);
+/// assert!(pk.verify(&pp, &message, &sig));
+/// ```
+pub struct MasterKey {
+    seed: Zeroizing<[u8; 32]>,
+}
+
+impl MasterKey {
+    /// Create the master key of a uniformly random seed.
+    pub fn new(seed: [u8; 32]) -> Self {
+        MasterKey {
+            seed: Zeroizing::new(seed),
+        }
+    }
+
+    /// Derive the key pair of `len` elements at the path.
+    pub fn derive<E: Pairing>(
+        &self,
+        path: &[u32],
+        pp: &PublicParams<E>,
+        len: u32,
+    ) -> (PublicKey<E>, SecretKey<E>) {
+        let (_, mut hkdf) = Hkdf::<Sha512>::extract(Some(SALT), self.seed.as_slice());
+        for index in path {
+            let mut chain_key = Zeroizing::new([0u8; 64]);
+            hkdf.expand_multi_info(&[b"child", &index.to_be_bytes()], chain_key.as_mut_slice())
+                .unwrap();
+            hkdf = Hkdf::from_prk(chain_key.as_slice()).unwrap();
+        }
+
+        // the curve is identified by the modulus of its scalar field
+        let curve = E::ScalarField::MODULUS.to_bytes_le();
+        let x = (0..len)
+            .map(|i| {
+                let mut okm = Zeroizing::new([0u8; 64]);
+                hkdf.expand_multi_info(
+                    &[
+                        b"secret-key",
+                        &(curve.len() as u32).to_be_bytes(),
+                        &curve,
+                        &len.to_be_bytes(),
+                        &i.to_be_bytes(),
+                    ],
+                    okm.as_mut_slice(),
+                )
+                .unwrap();
+                E::ScalarField::from_le_bytes_mod_order(okm.as_slice())
+            })
+            .collect::<Vec<E::ScalarField>>();
+        let bx = x.iter().map(|xi| pp.p2.mul(xi)).collect();
+        (PublicKey { bx }, SecretKey { x })
+    }
+}
+
+impl core::fmt::Debug for MasterKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "MasterKey([REDACTED])")
+    }
+}
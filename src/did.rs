@@ -0,0 +1,120 @@
+//! `did:key` encoding of the public keys on BLS12-381.
+//!
+//! The identifier is `did:key:z<base58btc(varint(codec) || key)>`, where `key` is the
+//! compressed serialization of the public key, i.e. the number of elements as a `u64` followed
+//! by the compressed elements of G2. There is no registered multicodec for the key vectors of
+//! mercurial signatures, so the code [MULTICODEC] is taken from the private use range.
+
+use alloc::{format, string::String, vec::Vec};
+use ark_bls12_381::Bls12_381;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use core::fmt;
+
+use crate::public_key::PublicKey;
+
+/// The multicodec of the public keys on BLS12-381, in the private use range `0x300000..0x3fffff`.
+pub const MULTICODEC: u64 = 0x300381;
+
+const DID_KEY_PREFIX: &str = "did:key:";
+
+/// Error in decoding a `did:key` identifier.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DidKeyError {
+    /// The identifier does not start with `did:key:z`.
+    InvalidPrefix,
+    /// The identifier is not valid base58btc.
+    InvalidMultibase,
+    /// The multicodec is not [MULTICODEC].
+    UnsupportedCodec(u64),
+    /// The key bytes are not a valid public key.
+    InvalidKey,
+}
+
+impl fmt::Display for DidKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DidKeyError::InvalidPrefix => {
+                f.write_str("the identifier does not start with did:key:z")
+            }
+            DidKeyError::InvalidMultibase => f.write_str("the identifier is not valid base58btc"),
+            DidKeyError::UnsupportedCodec(codec) => {
+                write!(f, "the multicodec {:#x} is not supported", codec)
+            }
+            DidKeyError::InvalidKey => f.write_str("the key bytes are not a valid public key"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DidKeyError {}
+
+impl PublicKey<Bls12_381> {
+    /// Encode the public key as a `did:key` identifier.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::{PublicKey, PublicParams};
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let (pk, _) = pp.key_gen(&mut rng, 5);
+    /// let did = pk.to_did_key();
+    /// assert!(did.starts_with("did:key:z"));
+    /// assert!(PublicKey::from_did_key(&did).unwrap() == pk);
+    /// ```
+    pub fn to_did_key(&self) -> String {
+        let mut bytes = Vec::new();
+        write_varint(&mut bytes, MULTICODEC);
+        self.serialize_compressed(&mut bytes).unwrap();
+        format!("{}z{}", DID_KEY_PREFIX, bs58::encode(bytes).into_string())
+    }
+
+    /// Decode a public key from a `did:key` identifier. The elements are checked to be in the
+    /// prime order subgroup of G2, and the key must not be empty.
+    pub fn from_did_key(did: &str) -> Result<Self, DidKeyError> {
+        let encoded = did
+            .strip_prefix(DID_KEY_PREFIX)
+            .and_then(|s| s.strip_prefix('z'))
+            .ok_or(DidKeyError::InvalidPrefix)?;
+        let bytes = bs58::decode(encoded)
+            .into_vec()
+            .map_err(|_| DidKeyError::InvalidMultibase)?;
+
+        let (codec, mut key) = read_varint(&bytes).ok_or(DidKeyError::InvalidMultibase)?;
+        if codec != MULTICODEC {
+            return Err(DidKeyError::UnsupportedCodec(codec));
+        }
+        let pk =
+            PublicKey::deserialize_compressed(&mut key).map_err(|_| DidKeyError::InvalidKey)?;
+        if !key.is_empty() || pk.bx.is_empty() {
+            return Err(DidKeyError::InvalidKey);
+        }
+        Ok(pk)
+    }
+}
+
+/// Write the unsigned varint of multiformats.
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+/// Read an unsigned varint of multiformats. Returns the value and the remaining bytes, or
+/// `None` if the varint is truncated, too long or not minimal.
+fn read_varint(bytes: &[u8]) -> Option<(u64, &[u8])> {
+    let mut value = 0u64;
+    for (i, byte) in bytes.iter().enumerate().take(9) {
+        value |= ((byte & 0x7f) as u64) << (7 * i);
+        if byte & 0x80 == 0 {
+            if i > 0 && *byte == 0 {
+                return None;
+            }
+            return Some((value, &bytes[i + 1..]));
+        }
+    }
+    None
+}
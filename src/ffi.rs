@@ -0,0 +1,487 @@
+//! C ABI, enabled by the feature `ffi`. The header is `include/mercurial_signature.h`. Build the
+//! shared library with `cargo rustc --lib --release --features ffi --crate-type cdylib`, or a
+//! static one with `--crate-type staticlib`.
+//!
+//! Ownership rules:
+//! - The parameters, keys and signatures are opaque pointers owned by the caller. Each of them
+//!   is created by a `msig_*_new*`, `msig_keygen`, `msig_sign` or `msig_*_from_bytes` function and
+//!   must be released exactly once by the corresponding `msig_*_free` function.
+//! - Byte buffers are always owned by the caller. The input buffers are only read during the
+//!   call. The output functions write to a buffer of capacity `out_cap` and set `*out_len` to
+//!   the required length; if the capacity is not enough, nothing is written and
+//!   [MsigStatus::BufferTooSmall] is returned, so that the caller can retry with `*out_len`.
+//! - The string of [msig_last_error_message] is owned by the library and valid until the next
+//!   call on the same thread.
+//!
+//! Messages are in the compressed serialization of `Vec<G1>` and scalars of `Fr`, as in
+//! [CanonicalSerialize].
+
+use std::{
+    cell::RefCell,
+    ffi::{c_char, CString},
+};
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
+use rand_core::OsRng;
+
+use crate::{
+    change_representation, serialization::deserialize_vec, Fr, PublicKey, PublicParams, SecretKey,
+    Signature, G1,
+};
+
+/// Public parameters.
+pub struct MsigParams(PublicParams);
+/// Public key.
+pub struct MsigPublicKey(PublicKey);
+/// Secret key.
+pub struct MsigSecretKey(SecretKey);
+/// Signature.
+pub struct MsigSignature(Signature);
+
+/// Status code returned by the functions. The reason of an error is available from
+/// [msig_last_error_message].
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MsigStatus {
+    /// Success.
+    Ok = 0,
+    /// A required pointer is null.
+    NullPointer = 1,
+    /// An input cannot be deserialized or is not valid for the operation.
+    InvalidInput = 2,
+    /// The output buffer is too small. The required length is written to `out_len`.
+    BufferTooSmall = 3,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<CString> = RefCell::new(CString::default());
+}
+
+/// The message of the last error on the current thread, or an empty string. The string is
+/// owned by the library and valid until the next call on the same thread.
+#[no_mangle]
+pub extern "C" fn msig_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ptr())
+}
+
+/// Derive the public parameters from a seed, see [PublicParams::derive_from_ceremony_transcript].
+///
+/// ## Safety
+/// `seed` must point to `seed_len` readable bytes and `out` to a writable pointer.
+#[no_mangle]
+pub unsafe extern "C" fn msig_params_new_from_seed(
+    seed: *const u8,
+    seed_len: usize,
+    out: *mut *mut MsigParams,
+) -> MsigStatus {
+    run(|| {
+        let seed = slice(seed, seed_len)?;
+        let pp = PublicParams::derive_from_ceremony_transcript(seed)
+            .map_err(|e| invalid(format!("invalid seed: {:?}", e)))?;
+        write_box(out, MsigParams(pp))
+    })
+}
+
+/// Generate a key pair for messages of `size` elements.
+///
+/// ## Safety
+/// `params` must be a valid pointer, and `out_pk` and `out_sk` writable pointers.
+#[no_mangle]
+pub unsafe extern "C" fn msig_keygen(
+    params: *const MsigParams,
+    size: u32,
+    out_pk: *mut *mut MsigPublicKey,
+    out_sk: *mut *mut MsigSecretKey,
+) -> MsigStatus {
+    run(|| {
+        let pp = reference(params)?;
+        if out_pk.is_null() || out_sk.is_null() {
+            return Err(null_pointer());
+        }
+        let (pk, sk) = pp.0.key_gen(&mut OsRng, size);
+        write_box(out_pk, MsigPublicKey(pk))?;
+        write_box(out_sk, MsigSecretKey(sk))
+    })
+}
+
+/// Sign the message.
+///
+/// ## Safety
+/// `params` and `sk` must be valid pointers, `message` must point to `message_len` readable
+/// bytes and `out` to a writable pointer.
+#[no_mangle]
+pub unsafe extern "C" fn msig_sign(
+    params: *const MsigParams,
+    sk: *const MsigSecretKey,
+    message: *const u8,
+    message_len: usize,
+    out: *mut *mut MsigSignature,
+) -> MsigStatus {
+    run(|| {
+        let (pp, sk) = (reference(params)?, reference(sk)?);
+        let message = deserialize_message(slice(message, message_len)?)?;
+        if message.len() > sk.0.length() {
+            return Err(invalid("the message is longer than the key".to_string()));
+        }
+        write_box(out, MsigSignature(sk.0.sign(&mut OsRng, &pp.0, &message)))
+    })
+}
+
+/// Verify the signature on the message. The result is written to `out_valid`.
+///
+/// ## Safety
+/// `params`, `pk` and `sig` must be valid pointers, `message` must point to `message_len`
+/// readable bytes and `out_valid` to a writable bool.
+#[no_mangle]
+pub unsafe extern "C" fn msig_verify(
+    params: *const MsigParams,
+    pk: *const MsigPublicKey,
+    message: *const u8,
+    message_len: usize,
+    sig: *const MsigSignature,
+    out_valid: *mut bool,
+) -> MsigStatus {
+    run(|| {
+        let (pp, pk, sig) = (reference(params)?, reference(pk)?, reference(sig)?);
+        let message = deserialize_message(slice(message, message_len)?)?;
+        if out_valid.is_null() {
+            return Err(null_pointer());
+        }
+        *out_valid = pk.0.verify(&pp.0, &message, &sig.0);
+        Ok(())
+    })
+}
+
+/// Convert the public key by the scalar `p`.
+///
+/// ## Safety
+/// `pk` must be a valid pointer and `p` must point to `p_len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn msig_convert_pk(
+    pk: *mut MsigPublicKey,
+    p: *const u8,
+    p_len: usize,
+) -> MsigStatus {
+    run(|| {
+        let p: Fr = deserialize(slice(p, p_len)?, "scalar")?;
+        mutable(pk)?.0.convert(p);
+        Ok(())
+    })
+}
+
+/// Convert the secret key by the scalar `p`.
+///
+/// ## Safety
+/// `sk` must be a valid pointer and `p` must point to `p_len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn msig_convert_sk(
+    sk: *mut MsigSecretKey,
+    p: *const u8,
+    p_len: usize,
+) -> MsigStatus {
+    run(|| {
+        let p: Fr = deserialize(slice(p, p_len)?, "scalar")?;
+        mutable(sk)?.0.convert(p);
+        Ok(())
+    })
+}
+
+/// Convert the signature by the scalar `p`.
+///
+/// ## Safety
+/// `sig` must be a valid pointer and `p` must point to `p_len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn msig_convert_sig(
+    sig: *mut MsigSignature,
+    p: *const u8,
+    p_len: usize,
+) -> MsigStatus {
+    run(|| {
+        let p: Fr = deserialize(slice(p, p_len)?, "scalar")?;
+        mutable(sig)?.0.convert(&mut OsRng, p);
+        Ok(())
+    })
+}
+
+/// Change the representation of the message and the signature by the scalar `u`. The new
+/// message is written to `out`, and the signature is changed in place only on success.
+///
+/// ## Safety
+/// `sig` must be a valid pointer, `message` and `u` must point to `message_len` and `u_len`
+/// readable bytes, `out` to `out_cap` writable bytes and `out_len` to a writable length.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn msig_change_representation(
+    message: *const u8,
+    message_len: usize,
+    sig: *mut MsigSignature,
+    u: *const u8,
+    u_len: usize,
+    out: *mut u8,
+    out_cap: usize,
+    out_len: *mut usize,
+) -> MsigStatus {
+    run(|| {
+        let mut message = deserialize_message(slice(message, message_len)?)?;
+        let u: Fr = deserialize(slice(u, u_len)?, "scalar")?;
+        let sig = mutable(sig)?;
+        let mut new_sig = sig.0.clone();
+        change_representation(&mut OsRng, &mut message, &mut new_sig, u);
+        write_bytes(&message, out, out_cap, out_len)?;
+        sig.0 = new_sig;
+        Ok(())
+    })
+}
+
+/// Serialize the public parameters.
+///
+/// ## Safety
+/// `params` must be a valid pointer, `out` must point to `out_cap` writable bytes and
+/// `out_len` to a writable length.
+#[no_mangle]
+pub unsafe extern "C" fn msig_params_to_bytes(
+    params: *const MsigParams,
+    out: *mut u8,
+    out_cap: usize,
+    out_len: *mut usize,
+) -> MsigStatus {
+    run(|| write_bytes(&reference(params)?.0, out, out_cap, out_len))
+}
+
+/// Deserialize the public parameters.
+///
+/// ## Safety
+/// `bytes` must point to `len` readable bytes and `out` to a writable pointer.
+#[no_mangle]
+pub unsafe extern "C" fn msig_params_from_bytes(
+    bytes: *const u8,
+    len: usize,
+    out: *mut *mut MsigParams,
+) -> MsigStatus {
+    run(|| write_box(out, MsigParams(deserialize(slice(bytes, len)?, "params")?)))
+}
+
+/// Serialize the public key.
+///
+/// ## Safety
+/// `pk` must be a valid pointer, `out` must point to `out_cap` writable bytes and `out_len` to a
+/// writable length.
+#[no_mangle]
+pub unsafe extern "C" fn msig_public_key_to_bytes(
+    pk: *const MsigPublicKey,
+    out: *mut u8,
+    out_cap: usize,
+    out_len: *mut usize,
+) -> MsigStatus {
+    run(|| write_bytes(&reference(pk)?.0, out, out_cap, out_len))
+}
+
+/// Deserialize the public key.
+///
+/// ## Safety
+/// `bytes` must point to `len` readable bytes and `out` to a writable pointer.
+#[no_mangle]
+pub unsafe extern "C" fn msig_public_key_from_bytes(
+    bytes: *const u8,
+    len: usize,
+    out: *mut *mut MsigPublicKey,
+) -> MsigStatus {
+    run(|| {
+        write_box(
+            out,
+            MsigPublicKey(deserialize(slice(bytes, len)?, "public key")?),
+        )
+    })
+}
+
+/// Serialize the secret key.
+///
+/// ## Safety
+/// `sk` must be a valid pointer, `out` must point to `out_cap` writable bytes and `out_len` to a
+/// writable length.
+#[no_mangle]
+pub unsafe extern "C" fn msig_secret_key_to_bytes(
+    sk: *const MsigSecretKey,
+    out: *mut u8,
+    out_cap: usize,
+    out_len: *mut usize,
+) -> MsigStatus {
+    run(|| write_bytes(&reference(sk)?.0, out, out_cap, out_len))
+}
+
+/// Deserialize the secret key.
+///
+/// ## Safety
+/// `bytes` must point to `len` readable bytes and `out` to a writable pointer.
+#[no_mangle]
+pub unsafe extern "C" fn msig_secret_key_from_bytes(
+    bytes: *const u8,
+    len: usize,
+    out: *mut *mut MsigSecretKey,
+) -> MsigStatus {
+    run(|| {
+        write_box(
+            out,
+            MsigSecretKey(deserialize(slice(bytes, len)?, "secret key")?),
+        )
+    })
+}
+
+/// Serialize the signature.
+///
+/// ## Safety
+/// `sig` must be a valid pointer, `out` must point to `out_cap` writable bytes and `out_len` to
+/// a writable length.
+#[no_mangle]
+pub unsafe extern "C" fn msig_signature_to_bytes(
+    sig: *const MsigSignature,
+    out: *mut u8,
+    out_cap: usize,
+    out_len: *mut usize,
+) -> MsigStatus {
+    run(|| write_bytes(&reference(sig)?.0, out, out_cap, out_len))
+}
+
+/// Deserialize the signature.
+///
+/// ## Safety
+/// `bytes` must point to `len` readable bytes and `out` to a writable pointer.
+#[no_mangle]
+pub unsafe extern "C" fn msig_signature_from_bytes(
+    bytes: *const u8,
+    len: usize,
+    out: *mut *mut MsigSignature,
+) -> MsigStatus {
+    run(|| {
+        write_box(
+            out,
+            MsigSignature(deserialize(slice(bytes, len)?, "signature")?),
+        )
+    })
+}
+
+/// Release the public parameters. A null pointer is ignored.
+///
+/// ## Safety
+/// `params` must be null or created by this library and not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn msig_params_free(params: *mut MsigParams) {
+    free(params)
+}
+
+/// Release the public key. A null pointer is ignored.
+///
+/// ## Safety
+/// `pk` must be null or created by this library and not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn msig_public_key_free(pk: *mut MsigPublicKey) {
+    free(pk)
+}
+
+/// Release the secret key. A null pointer is ignored.
+///
+/// ## Safety
+/// `sk` must be null or created by this library and not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn msig_secret_key_free(sk: *mut MsigSecretKey) {
+    free(sk)
+}
+
+/// Release the signature. A null pointer is ignored.
+///
+/// ## Safety
+/// `sig` must be null or created by this library and not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn msig_signature_free(sig: *mut MsigSignature) {
+    free(sig)
+}
+
+type FfiResult = Result<(), (MsigStatus, String)>;
+
+/// Run the function, recording the error message and catching panics.
+fn run(f: impl FnOnce() -> FfiResult) -> MsigStatus {
+    let (status, message) = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)) {
+        Ok(Ok(())) => (MsigStatus::Ok, String::new()),
+        Ok(Err(error)) => error,
+        Err(_) => (MsigStatus::InvalidInput, "unexpected panic".to_string()),
+    };
+    LAST_ERROR.with(|e| *e.borrow_mut() = CString::new(message).unwrap_or_default());
+    status
+}
+
+fn null_pointer() -> (MsigStatus, String) {
+    (MsigStatus::NullPointer, "null pointer".to_string())
+}
+
+fn invalid(message: String) -> (MsigStatus, String) {
+    (MsigStatus::InvalidInput, message)
+}
+
+unsafe fn slice<'a>(ptr: *const u8, len: usize) -> Result<&'a [u8], (MsigStatus, String)> {
+    match (ptr.is_null(), len) {
+        (_, 0) => Ok(&[]),
+        (true, _) => Err(null_pointer()),
+        (false, _) => Ok(std::slice::from_raw_parts(ptr, len)),
+    }
+}
+
+unsafe fn reference<'a, T>(ptr: *const T) -> Result<&'a T, (MsigStatus, String)> {
+    ptr.as_ref().ok_or_else(null_pointer)
+}
+
+unsafe fn mutable<'a, T>(ptr: *mut T) -> Result<&'a mut T, (MsigStatus, String)> {
+    ptr.as_mut().ok_or_else(null_pointer)
+}
+
+unsafe fn write_box<T>(out: *mut *mut T, value: T) -> FfiResult {
+    if out.is_null() {
+        return Err(null_pointer());
+    }
+    *out = Box::into_raw(Box::new(value));
+    Ok(())
+}
+
+unsafe fn write_bytes<T: CanonicalSerialize>(
+    value: &T,
+    out: *mut u8,
+    out_cap: usize,
+    out_len: *mut usize,
+) -> FfiResult {
+    if out_len.is_null() {
+        return Err(null_pointer());
+    }
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes).unwrap();
+    *out_len = bytes.len();
+    if bytes.len() > out_cap {
+        return Err((
+            MsigStatus::BufferTooSmall,
+            format!("the output needs {} bytes", bytes.len()),
+        ));
+    }
+    if out.is_null() {
+        return Err(null_pointer());
+    }
+    std::ptr::copy_nonoverlapping(bytes.as_ptr(), out, bytes.len());
+    Ok(())
+}
+
+unsafe fn free<T>(ptr: *mut T) {
+    if !ptr.is_null() {
+        drop(Box::from_raw(ptr));
+    }
+}
+
+fn deserialize<T: CanonicalDeserialize>(
+    bytes: &[u8],
+    name: &str,
+) -> Result<T, (MsigStatus, String)> {
+    T::deserialize_compressed(bytes).map_err(|e| invalid(format!("invalid {}: {}", name, e)))
+}
+
+/// Deserialize a message. The length prefix is from the caller, so the vector is read by
+/// [deserialize_vec], which does not reserve more than a bounded capacity up front.
+fn deserialize_message(bytes: &[u8]) -> Result<Vec<G1>, (MsigStatus, String)> {
+    deserialize_vec(&mut &*bytes, Compress::Yes, Validate::Yes)
+        .map_err(|e| invalid(format!("invalid message: {}", e)))
+}
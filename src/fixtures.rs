@@ -0,0 +1,161 @@
+//! Fixtures for the tests of this crate and of the crates depending on it, with the feature
+//! `test-utils`.
+//!
+//! This module is test tooling and is exempt from semantic versioning: it may change in any
+//! release. It must not be used outside of tests, as its randomness is derived from a seed.
+
+use alloc::vec::Vec;
+use rand_chacha::ChaCha20Rng;
+use rand_core::{RngCore, SeedableRng};
+
+use crate::{
+    change_representation, change_representation_g2, Fr, PublicKey, PublicKeyG1, PublicParams,
+    SecretKey, Signature, SignatureG2, UniformRand, G1, G2,
+};
+
+/// A component of a signature, see [Fixture::corrupt_signature].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignatureComponent {
+    Z,
+    Y1,
+    Y2,
+}
+
+/// Public parameters, a key pair, and signatures on random messages in G1 and in G2, all
+/// derived from a seed.
+///
+/// ## Example
+///
+/// ```rust
+/// use mercurial_signature::fixtures::{Fixture, SignatureComponent};
+///
+/// let mut f = Fixture::new(7);
+/// assert!(f.pk.verify(&f.pp, &f.message, &f.sig));
+/// assert!(f.pk_g1.verify(&f.pp, &f.message_g2, &f.sig_g2));
+///
+/// let p = f.random_scalar();
+/// let converted = f.converted_copy(p);
+/// assert!(converted.pk.verify(&f.pp, &f.message, &converted.sig));
+///
+/// let corrupted = f.corrupt_signature(SignatureComponent::Y1);
+/// assert!(!f.pk.verify(&f.pp, &f.message, &corrupted));
+/// ```
+#[derive(Clone)]
+pub struct Fixture {
+    /// The generator of the randomness of the fixture, for the tests to draw more.
+    pub rng: ChaCha20Rng,
+    pub pp: PublicParams,
+    pub pk: PublicKey,
+    pub sk: SecretKey,
+    pub message: Vec<G1>,
+    pub sig: Signature,
+    /// The public key in G1 of [sk](Fixture::sk), for the messages in G2.
+    pub pk_g1: PublicKeyG1,
+    pub message_g2: Vec<G2>,
+    pub sig_g2: SignatureG2,
+}
+
+impl Fixture {
+    /// Length of the keys and the messages of [Fixture::new].
+    pub const LENGTH: u32 = 10;
+
+    /// A fixture with keys and messages of [Fixture::LENGTH] elements.
+    pub fn new(seed: u64) -> Self {
+        Self::with_length(seed, Self::LENGTH)
+    }
+
+    /// A fixture with keys and messages of `length` elements.
+    pub fn with_length(seed: u64, length: u32) -> Self {
+        Self::from_rng(ChaCha20Rng::seed_from_u64(seed), length)
+    }
+
+    fn from_rng(mut rng: ChaCha20Rng, length: u32) -> Self {
+        let pp = PublicParams::new(&mut rng);
+        let (pk, sk) = pp.key_gen(&mut rng, length);
+        let message = (0..length).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+        let sig = sk.sign(&mut rng, &pp, &message);
+        let pk_g1 = sk.public_key_g1(&pp);
+        let message_g2 = (0..length).map(|_| G2::rand(&mut rng)).collect::<Vec<G2>>();
+        let sig_g2 = sk.sign_g2(&mut rng, &pp, &message_g2);
+        Fixture {
+            rng,
+            pp,
+            pk,
+            sk,
+            message,
+            sig,
+            pk_g1,
+            message_g2,
+            sig_g2,
+        }
+    }
+
+    /// A random scalar, e.g. for a conversion or a change of representation.
+    pub fn random_scalar(&mut self) -> Fr {
+        Fr::rand(&mut self.rng)
+    }
+
+    /// A random message in G1 of `length` elements.
+    pub fn random_message(&mut self, length: u32) -> Vec<G1> {
+        (0..length).map(|_| G1::rand(&mut self.rng)).collect()
+    }
+
+    /// A random message in G2 of `length` elements.
+    pub fn random_message_g2(&mut self, length: u32) -> Vec<G2> {
+        (0..length).map(|_| G2::rand(&mut self.rng)).collect()
+    }
+
+    /// A copy with the keys and the signatures converted by `p`. The messages are unchanged.
+    pub fn converted_copy(&mut self, p: Fr) -> Fixture {
+        let mut copy = self.fork();
+        copy.pk.convert(p);
+        copy.sk.convert(p);
+        copy.sig.convert(&mut copy.rng, p);
+        copy.pk_g1.convert(p);
+        copy.sig_g2.convert(&mut copy.rng, p);
+        copy
+    }
+
+    /// A copy with the messages and the signatures changed to the representation by `u`. The
+    /// keys are unchanged.
+    pub fn rerepresented_copy(&mut self, u: Fr) -> Fixture {
+        let mut copy = self.fork();
+        change_representation(&mut copy.rng, &mut copy.message, &mut copy.sig, u);
+        change_representation_g2(&mut copy.rng, &mut copy.message_g2, &mut copy.sig_g2, u);
+        copy
+    }
+
+    /// The signature on [message](Fixture::message) with a component shifted by the generator
+    /// of its group, which does not verify.
+    pub fn corrupt_signature(&self, which: SignatureComponent) -> Signature {
+        let mut sig = self.sig.clone();
+        match which {
+            SignatureComponent::Z => sig.z += self.pp.p1,
+            SignatureComponent::Y1 => sig.y1 += self.pp.p1,
+            SignatureComponent::Y2 => sig.y2 += self.pp.p2,
+        }
+        sig
+    }
+
+    /// The signature on [message_g2](Fixture::message_g2) with a component shifted by the
+    /// generator of its group, which does not verify.
+    pub fn corrupt_signature_g2(&self, which: SignatureComponent) -> SignatureG2 {
+        let mut sig = self.sig_g2.clone();
+        match which {
+            SignatureComponent::Z => sig.z += self.pp.p2,
+            SignatureComponent::Y1 => sig.y1 += self.pp.p2,
+            SignatureComponent::Y2 => sig.y2 += self.pp.p1,
+        }
+        sig
+    }
+
+    /// A clone whose generator is seeded from this one, so that the two draw different
+    /// randomness.
+    fn fork(&mut self) -> Fixture {
+        let mut seed = [0u8; 32];
+        self.rng.fill_bytes(&mut seed);
+        let mut copy = self.clone();
+        copy.rng = ChaCha20Rng::from_seed(seed);
+        copy
+    }
+}
@@ -0,0 +1,147 @@
+//! Secret key guarded against accidental exposure.
+//!
+//! A [GuardedSecretKey] is not `Clone`, redacts its `Debug` output, and has no serialization,
+//! so that the key is not copied, logged or written by mistake. The key is used only inside
+//! [GuardedSecretKey::expose], and its elements are zeroized when it is dropped.
+//!
+//! With the feature `secure-memory`, the elements are also locked in memory, i.e. `mlock` on
+//! Unix and `VirtualLock` on Windows, so that they are not swapped to disk. Locking is on
+//! whole pages and is not counted: dropping a key unlocks the pages it shares with another
+//! locked key. Locking is best effort, e.g. it fails beyond `RLIMIT_MEMLOCK`, see
+//! [GuardedSecretKey::is_locked].
+//!
+//! ```rust,compile_fail
+//! use mercurial_signature::{GuardedSecretKey, PublicParams};
+//!
+//! let mut rng = rand::thread_rng();
+//! let (_, sk) = PublicParams::new(&mut rng).key_gen(&mut rng, 5);
+//! let guarded = GuardedSecretKey::new(sk);
+//! let copy = guarded.clone();
+//! ```
+//!
+//! ```rust,compile_fail
+//! use ark_serialize::CanonicalSerialize;
+//! use mercurial_signature::{GuardedSecretKey, PublicParams};
+//!
+//! let mut rng = rand::thread_rng();
+//! let (_, sk) = PublicParams::new(&mut rng).key_gen(&mut rng, 5);
+//! let guarded = GuardedSecretKey::new(sk);
+//! let mut bytes = Vec::new();
+//! guarded.serialize_compressed(&mut bytes).unwrap();
+//! ```
+
+use ark_ec::pairing::Pairing;
+use zeroize::Zeroize;
+
+use crate::secret_key::SecretKey;
+
+/// Secret key guarded against accidental exposure, see the [module](self) documentation.
+///
+/// ## Example
+///
+/// ```rust
+/// use mercurial_signature::{GuardedSecretKey, PublicParams, UniformRand, G1};
+///
+/// let mut rng = rand::thread_rng();
+/// let pp = PublicParams::new(&mut rng);
+/// let (pk, sk) = pp.key_gen(&mut rng, 5);
+/// let sk = GuardedSecretKey::new(sk);
+/// assert_eq!(format!("{:?}", sk), "GuardedSecretKey([REDACTED; 5])");
+///
+/// let message = (0..5).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+/// let sig = sk.expose(|sk| sk.sign(&mut rng, &pp, &message));
+/// assert!(pk.verify(&pp, &message, &sig));
+/// ```
+pub struct GuardedSecretKey<E: Pairing> {
+    sk: SecretKey<E>,
+    locked: bool,
+}
+
+impl<E: Pairing> GuardedSecretKey<E> {
+    /// Guard the secret key. The elements are moved without copying them.
+    pub fn new(sk: SecretKey<E>) -> Self {
+        let locked = memory::lock(sk.x.as_ptr() as *const u8, buffer_len(&sk));
+        GuardedSecretKey { sk, locked }
+    }
+
+    /// Use the secret key, e.g. to sign. The key must not be cloned out of the closure.
+    pub fn expose<T>(&self, f: impl FnOnce(&SecretKey<E>) -> T) -> T {
+        f(&self.sk)
+    }
+
+    /// Length of the secret key.
+    pub fn length(&self) -> usize {
+        self.sk.length()
+    }
+
+    /// Whether the elements are locked in memory. It is always `false` without the feature
+    /// `secure-memory`.
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+}
+
+impl<E: Pairing> From<SecretKey<E>> for GuardedSecretKey<E> {
+    fn from(sk: SecretKey<E>) -> Self {
+        GuardedSecretKey::new(sk)
+    }
+}
+
+impl<E: Pairing> core::fmt::Debug for GuardedSecretKey<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "GuardedSecretKey([REDACTED; {}])", self.sk.length())
+    }
+}
+
+impl<E: Pairing> Drop for GuardedSecretKey<E> {
+    fn drop(&mut self) {
+        let (ptr, len) = (self.sk.x.as_ptr() as *const u8, buffer_len(&self.sk));
+        // zeroize the elements and the spare capacity, before the buffer is freed
+        self.sk.x.zeroize();
+        if self.locked {
+            memory::unlock(ptr, len);
+        }
+    }
+}
+
+/// Length in bytes of the buffer of the elements, including the spare capacity.
+fn buffer_len<E: Pairing>(sk: &SecretKey<E>) -> usize {
+    sk.x.capacity() * core::mem::size_of::<E::ScalarField>()
+}
+
+#[cfg(all(feature = "secure-memory", unix))]
+mod memory {
+    pub(super) fn lock(ptr: *const u8, len: usize) -> bool {
+        // SAFETY: the range is the allocated buffer of the elements
+        len > 0 && unsafe { libc::mlock(ptr as *const libc::c_void, len) } == 0
+    }
+
+    pub(super) fn unlock(ptr: *const u8, len: usize) {
+        // SAFETY: the range is the allocated buffer of the elements, locked by `lock`
+        unsafe { libc::munlock(ptr as *const libc::c_void, len) };
+    }
+}
+
+#[cfg(all(feature = "secure-memory", windows))]
+mod memory {
+    use windows_sys::Win32::System::Memory::{VirtualLock, VirtualUnlock};
+
+    pub(super) fn lock(ptr: *const u8, len: usize) -> bool {
+        // SAFETY: the range is the allocated buffer of the elements
+        len > 0 && unsafe { VirtualLock(ptr as *const core::ffi::c_void, len) } != 0
+    }
+
+    pub(super) fn unlock(ptr: *const u8, len: usize) {
+        // SAFETY: the range is the allocated buffer of the elements, locked by `lock`
+        unsafe { VirtualUnlock(ptr as *const core::ffi::c_void, len) };
+    }
+}
+
+#[cfg(not(all(feature = "secure-memory", any(unix, windows))))]
+mod memory {
+    pub(super) fn lock(_ptr: *const u8, _len: usize) -> bool {
+        false
+    }
+
+    pub(super) fn unlock(_ptr: *const u8, _len: usize) {}
+}
@@ -0,0 +1,52 @@
+use alloc::vec;
+use ark_ec::{pairing::Pairing, AffineRepr};
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+use sha2::{Digest, Sha512};
+use sha3::{digest::ExtendableOutput, Shake256};
+
+/// Hash the data to a scalar, with the domain separation tag `dst`.
+pub(crate) fn hash_to_scalar<E: Pairing>(dst: &[u8], data: &[u8]) -> E::ScalarField {
+    let mut hasher = Sha512::new();
+    hasher.update(dst);
+    hasher.update(data);
+    E::ScalarField::from_le_bytes_mod_order(&hasher.finalize())
+}
+
+/// Hash the data to an element of G1 whose discrete logarithm is unknown, with the domain
+/// separation tag `dst`. It uses try-and-increment, so it is not constant time and is meant
+/// for public data only.
+pub(crate) fn hash_to_g1<E: Pairing>(dst: &[u8], data: &[u8]) -> E::G1 {
+    let size = E::G1Affine::zero().compressed_size();
+    (0u64..)
+        .find_map(|counter| {
+            let mut hasher = Sha512::new();
+            hasher.update(dst);
+            hasher.update(data);
+            hasher.update(counter.to_le_bytes());
+            let bytes = hasher.finalize();
+            E::G1Affine::from_random_bytes(&bytes[..size.min(bytes.len())])
+                .map(|p| p.clear_cofactor())
+                .filter(|p| !p.is_zero())
+        })
+        .unwrap()
+        .into()
+}
+
+/// Hash the data to an element of G2 whose discrete logarithm is unknown, with the domain
+/// separation tag `dst`. Like [hash_to_g1], it uses try-and-increment, with SHAKE-256 to
+/// output as many bytes as a compressed element of G2.
+pub(crate) fn hash_to_g2<E: Pairing>(dst: &[u8], data: &[u8]) -> E::G2 {
+    let size = E::G2Affine::zero().compressed_size();
+    (0u64..)
+        .find_map(|counter| {
+            let input = [dst, data, &counter.to_le_bytes()].concat();
+            let mut bytes = vec![0u8; size];
+            Shake256::digest_xof(input, &mut bytes);
+            E::G2Affine::from_random_bytes(&bytes)
+                .map(|p| p.clear_cofactor())
+                .filter(|p| !p.is_zero())
+        })
+        .unwrap()
+        .into()
+}
@@ -0,0 +1,458 @@
+//! Directory-backed store of public parameters, key pairs and credentials, with the feature
+//! `keystore`.
+//!
+//! Each entry is a file `<label>.<kind>.pem` in the directory, e.g. `issuer.keypair.pem`. It
+//! holds PEM blocks of the compressed serialization, with the labels of the command line tool,
+//! followed by a block `MERCURIAL CHECKSUM` with the SHA-256 of the blocks before it, so that a
+//! corrupted file is detected.
+//!
+//! A secret key may be encrypted with a passphrase, in a block `MERCURIAL ENCRYPTED SECRET KEY`
+//! of `salt || nonce || ciphertext`. The ciphertext is the compressed secret key encrypted by
+//! ChaCha20-Poly1305, under the key derived from the passphrase and the salt by Argon2id, with
+//! the compressed public key as associated data.
+//!
+//! An entry is written to a temporary file in the directory and then renamed, so that a reader
+//! sees either the previous or the new entry, never a partial one. On Unix, the directory
+//! created by [Keystore::open] is accessible only by its owner, and the files are readable and
+//! writable only by their owner.
+
+use std::{
+    fmt, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use argon2::Argon2;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+use crate::{
+    pem, serialization::deserialize_vec, GuardedSecretKey, PublicKey, PublicParams, SecretKey,
+    Signature, G1,
+};
+
+const PARAMS_LABEL: &str = "MERCURIAL PUBLIC PARAMS";
+const PUBLIC_KEY_LABEL: &str = "MERCURIAL PUBLIC KEY";
+const SECRET_KEY_LABEL: &str = "MERCURIAL SECRET KEY";
+const ENCRYPTED_SECRET_KEY_LABEL: &str = "MERCURIAL ENCRYPTED SECRET KEY";
+const MESSAGE_LABEL: &str = "MERCURIAL MESSAGE";
+const SIGNATURE_LABEL: &str = "MERCURIAL SIGNATURE";
+const CHECKSUM_LABEL: &str = "MERCURIAL CHECKSUM";
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// Error in reading or writing a [Keystore].
+#[derive(Debug)]
+pub enum KeystoreError {
+    /// The label is empty, longer than 64 characters, or has characters other than ASCII
+    /// letters, digits, `-` and `_`.
+    InvalidLabel,
+    /// There is no entry of the label and the kind.
+    NotFound,
+    /// The file of the entry is malformed, or its checksum does not match.
+    Corrupted,
+    /// The secret key is encrypted, see [Keystore::get_keypair_with_passphrase].
+    PassphraseRequired,
+    /// The passphrase is wrong, or the encrypted secret key is corrupted.
+    WrongPassphrase,
+    /// An error of the file system.
+    Io(io::Error),
+}
+
+impl fmt::Display for KeystoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeystoreError::InvalidLabel => f.write_str("invalid label"),
+            KeystoreError::NotFound => f.write_str("entry not found"),
+            KeystoreError::Corrupted => f.write_str("entry is corrupted"),
+            KeystoreError::PassphraseRequired => f.write_str("passphrase required"),
+            KeystoreError::WrongPassphrase => f.write_str("wrong passphrase"),
+            KeystoreError::Io(e) => write!(f, "i/o: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for KeystoreError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            KeystoreError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for KeystoreError {
+    fn from(e: io::Error) -> Self {
+        KeystoreError::Io(e)
+    }
+}
+
+/// Kind of an entry of a [Keystore].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EntryKind {
+    Params,
+    Keypair,
+    Credential,
+}
+
+impl EntryKind {
+    const ALL: [EntryKind; 3] = [EntryKind::Params, EntryKind::Keypair, EntryKind::Credential];
+
+    fn extension(self) -> &'static str {
+        match self {
+            EntryKind::Params => ".params.pem",
+            EntryKind::Keypair => ".keypair.pem",
+            EntryKind::Credential => ".credential.pem",
+        }
+    }
+}
+
+/// An entry of a [Keystore], see [Keystore::list].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Entry {
+    pub label: String,
+    pub kind: EntryKind,
+}
+
+/// A key pair stored in a [Keystore]. Its secret key is guarded, see [GuardedSecretKey].
+#[derive(Debug)]
+pub struct Keypair {
+    pub pk: PublicKey,
+    pub sk: GuardedSecretKey,
+}
+
+/// Store of public parameters, key pairs and credentials in a directory.
+///
+/// ## Example
+///
+/// ```rust
+/// use mercurial_signature::{GuardedSecretKey, Keypair, Keystore, PublicParams};
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// let keystore = Keystore::open(dir.path()).unwrap();
+///
+/// let mut rng = rand::thread_rng();
+/// let pp = PublicParams::new(&mut rng);
+/// let (pk, sk) = pp.key_gen(&mut rng, 5);
+/// let keypair = Keypair { pk, sk: GuardedSecretKey::new(sk) };
+/// keystore.put_params("default", &pp).unwrap();
+/// keystore.put_keypair("issuer", &keypair).unwrap();
+///
+/// assert_eq!(keystore.get_params("default").unwrap(), pp);
+/// assert_eq!(keystore.get_keypair("issuer").unwrap().pk, keypair.pk);
+/// ```
+pub struct Keystore {
+    dir: PathBuf,
+}
+
+impl Keystore {
+    /// Open the store in the directory, creating the directory if it does not exist.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, KeystoreError> {
+        let dir = path.as_ref().to_path_buf();
+        if !dir.is_dir() {
+            create_private_dir(&dir)?;
+        }
+        Ok(Keystore { dir })
+    }
+
+    /// Store the public parameters, replacing any of the same label.
+    pub fn put_params(&self, label: &str, pp: &PublicParams) -> Result<(), KeystoreError> {
+        self.write(label, EntryKind::Params, &[(PARAMS_LABEL, &serialize(pp))])
+    }
+
+    /// Read the public parameters of the label.
+    pub fn get_params(&self, label: &str) -> Result<PublicParams, KeystoreError> {
+        match self.read(label, EntryKind::Params)?.as_slice() {
+            [(l, bytes)] if l == PARAMS_LABEL => deserialize(bytes),
+            _ => Err(KeystoreError::Corrupted),
+        }
+    }
+
+    /// Store the key pair unencrypted, replacing any of the same label.
+    pub fn put_keypair(&self, label: &str, keypair: &Keypair) -> Result<(), KeystoreError> {
+        self.write(
+            label,
+            EntryKind::Keypair,
+            &[
+                (PUBLIC_KEY_LABEL, &serialize(&keypair.pk)),
+                (
+                    SECRET_KEY_LABEL,
+                    &Zeroizing::new(keypair.sk.expose(serialize)),
+                ),
+            ],
+        )
+    }
+
+    /// Store the key pair with its secret key encrypted by the passphrase, replacing any of the
+    /// same label.
+    pub fn put_keypair_with_passphrase(
+        &self,
+        label: &str,
+        keypair: &Keypair,
+        passphrase: &str,
+    ) -> Result<(), KeystoreError> {
+        let pk = serialize(&keypair.pk);
+        let sk = Zeroizing::new(keypair.sk.expose(serialize));
+        let encrypted = encrypt(&sk, &pk, passphrase);
+        self.write(
+            label,
+            EntryKind::Keypair,
+            &[
+                (PUBLIC_KEY_LABEL, &pk),
+                (ENCRYPTED_SECRET_KEY_LABEL, &encrypted),
+            ],
+        )
+    }
+
+    /// Read the key pair of the label. Returns [KeystoreError::PassphraseRequired] if its secret
+    /// key is encrypted.
+    pub fn get_keypair(&self, label: &str) -> Result<Keypair, KeystoreError> {
+        self.read_keypair(label, None)
+    }
+
+    /// Read the key pair of the label, decrypting its secret key by the passphrase. An
+    /// unencrypted key pair is read without the passphrase.
+    pub fn get_keypair_with_passphrase(
+        &self,
+        label: &str,
+        passphrase: &str,
+    ) -> Result<Keypair, KeystoreError> {
+        self.read_keypair(label, Some(passphrase))
+    }
+
+    /// Store a credential, i.e. a message and its signature, replacing any of the same label.
+    pub fn put_credential(
+        &self,
+        label: &str,
+        message: &[G1],
+        sig: &Signature,
+    ) -> Result<(), KeystoreError> {
+        self.write(
+            label,
+            EntryKind::Credential,
+            &[
+                (MESSAGE_LABEL, &serialize(message)),
+                (SIGNATURE_LABEL, &serialize(sig)),
+            ],
+        )
+    }
+
+    /// Read the message and the signature of the credential of the label.
+    pub fn get_credential(&self, label: &str) -> Result<(Vec<G1>, Signature), KeystoreError> {
+        match self.read(label, EntryKind::Credential)?.as_slice() {
+            [(l1, message), (l2, sig)] if l1 == MESSAGE_LABEL && l2 == SIGNATURE_LABEL => {
+                let mut reader = message.as_slice();
+                let message = deserialize_vec(&mut reader, Compress::Yes, Validate::Yes)
+                    .map_err(|_| KeystoreError::Corrupted)?;
+                if !reader.is_empty() {
+                    return Err(KeystoreError::Corrupted);
+                }
+                Ok((message, deserialize(sig)?))
+            }
+            _ => Err(KeystoreError::Corrupted),
+        }
+    }
+
+    /// The entries in the store, sorted by label and kind. Other files in the directory are
+    /// ignored.
+    pub fn list(&self) -> Result<Vec<Entry>, KeystoreError> {
+        let mut entries = Vec::new();
+        for file in fs::read_dir(&self.dir)? {
+            let name = file?.file_name();
+            let Some(name) = name.to_str() else {
+                continue;
+            };
+            for kind in EntryKind::ALL {
+                if let Some(label) = name.strip_suffix(kind.extension()) {
+                    if is_valid_label(label) {
+                        entries.push(Entry {
+                            label: label.to_string(),
+                            kind,
+                        });
+                    }
+                }
+            }
+        }
+        entries.sort();
+        Ok(entries)
+    }
+
+    fn read_keypair(
+        &self,
+        label: &str,
+        passphrase: Option<&str>,
+    ) -> Result<Keypair, KeystoreError> {
+        let blocks = self.read(label, EntryKind::Keypair)?;
+        let [(l1, pk_bytes), (l2, sk_bytes)] = blocks.as_slice() else {
+            return Err(KeystoreError::Corrupted);
+        };
+        if l1 != PUBLIC_KEY_LABEL {
+            return Err(KeystoreError::Corrupted);
+        }
+        let pk: PublicKey = deserialize(pk_bytes)?;
+        let sk: SecretKey = match (l2.as_str(), passphrase) {
+            (SECRET_KEY_LABEL, _) => deserialize(sk_bytes)?,
+            (ENCRYPTED_SECRET_KEY_LABEL, None) => return Err(KeystoreError::PassphraseRequired),
+            (ENCRYPTED_SECRET_KEY_LABEL, Some(passphrase)) => {
+                deserialize(&Zeroizing::new(decrypt(sk_bytes, pk_bytes, passphrase)?))?
+            }
+            _ => return Err(KeystoreError::Corrupted),
+        };
+        if pk.length() != sk.length() {
+            return Err(KeystoreError::Corrupted);
+        }
+        Ok(Keypair {
+            pk,
+            sk: GuardedSecretKey::new(sk),
+        })
+    }
+
+    fn path(&self, label: &str, kind: EntryKind) -> Result<PathBuf, KeystoreError> {
+        if !is_valid_label(label) {
+            return Err(KeystoreError::InvalidLabel);
+        }
+        Ok(self.dir.join(format!("{}{}", label, kind.extension())))
+    }
+
+    /// Write the blocks and their checksum to a temporary file, and rename it to the entry.
+    fn write(
+        &self,
+        label: &str,
+        kind: EntryKind,
+        blocks: &[(&str, &[u8])],
+    ) -> Result<(), KeystoreError> {
+        let path = self.path(label, kind)?;
+        let checksum = checksum(blocks);
+        let mut blocks = blocks.to_vec();
+        blocks.push((CHECKSUM_LABEL, &checksum));
+
+        // the temporary file is created with the permissions 0600 on Unix
+        let mut file = tempfile::Builder::new()
+            .prefix(".tmp-")
+            .tempfile_in(&self.dir)?;
+        file.write_all(pem::encode(&blocks).as_bytes())?;
+        file.as_file().sync_all()?;
+        file.persist(&path).map_err(|e| e.error)?;
+        Ok(())
+    }
+
+    /// Read the blocks of the entry, without the checksum after checking it.
+    fn read(&self, label: &str, kind: EntryKind) -> Result<Vec<(String, Vec<u8>)>, KeystoreError> {
+        let bytes = match fs::read(self.path(label, kind)?) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Err(KeystoreError::NotFound),
+            result => result?,
+        };
+        let text = core::str::from_utf8(&bytes).map_err(|_| KeystoreError::Corrupted)?;
+        let mut blocks = pem::decode(text).ok_or(KeystoreError::Corrupted)?;
+        let (label, expected) = blocks.pop().ok_or(KeystoreError::Corrupted)?;
+        let refs = blocks
+            .iter()
+            .map(|(label, bytes)| (label.as_str(), bytes.as_slice()))
+            .collect::<Vec<_>>();
+        if label != CHECKSUM_LABEL || checksum(&refs) != expected {
+            return Err(KeystoreError::Corrupted);
+        }
+        Ok(blocks)
+    }
+}
+
+fn is_valid_label(label: &str) -> bool {
+    !label.is_empty()
+        && label.len() <= 64
+        && label
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+}
+
+#[cfg(unix)]
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    use std::os::unix::fs::DirBuilderExt;
+    fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)
+}
+
+#[cfg(not(unix))]
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir)
+}
+
+/// SHA-256 of the blocks, each framed by the lengths of its label and its bytes.
+fn checksum(blocks: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    for (label, bytes) in blocks {
+        hasher.update((label.len() as u64).to_le_bytes());
+        hasher.update(label.as_bytes());
+        hasher.update((bytes.len() as u64).to_le_bytes());
+        hasher.update(bytes);
+    }
+    hasher.finalize().to_vec()
+}
+
+fn encrypt(plaintext: &[u8], aad: &[u8], passphrase: &str) -> Vec<u8> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+    let ciphertext = cipher(passphrase, &salt)
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: plaintext,
+                aad,
+            },
+        )
+        .unwrap();
+    [&salt[..], &nonce, &ciphertext].concat()
+}
+
+fn decrypt(encrypted: &[u8], aad: &[u8], passphrase: &str) -> Result<Vec<u8>, KeystoreError> {
+    if encrypted.len() < SALT_LEN + NONCE_LEN {
+        return Err(KeystoreError::Corrupted);
+    }
+    let (salt, rest) = encrypted.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    cipher(passphrase, salt)
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad,
+            },
+        )
+        .map_err(|_| KeystoreError::WrongPassphrase)
+}
+
+/// The cipher under the key derived from the passphrase and the salt by Argon2id with the
+/// default parameters.
+fn cipher(passphrase: &str, salt: &[u8]) -> ChaCha20Poly1305 {
+    let mut key = Key::default();
+    // the salt and the output have valid lengths
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .unwrap();
+    ChaCha20Poly1305::new(&key)
+}
+
+fn serialize<T: CanonicalSerialize + ?Sized>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes).unwrap();
+    bytes
+}
+
+/// Deserialize all the bytes, with the points checked to be in their subgroups.
+fn deserialize<T: CanonicalDeserialize>(mut bytes: &[u8]) -> Result<T, KeystoreError> {
+    let value = T::deserialize_compressed(&mut bytes).map_err(|_| KeystoreError::Corrupted)?;
+    if !bytes.is_empty() {
+        return Err(KeystoreError::Corrupted);
+    }
+    Ok(value)
+}
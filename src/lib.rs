@@ -1,20 +1,215 @@
 #![doc = include_str!("../README.md")]
+#![cfg_attr(not(feature = "std"), no_std)]
 
+extern crate alloc;
+
+mod anonymous_tokens;
+pub use anonymous_tokens::issue_with_signer as issue_tokens_with_signer;
+#[cfg(feature = "bilateral")]
+mod bilateral;
+#[cfg(feature = "bilateral")]
+pub use bilateral::change_representation_bilateral;
+mod backend;
+#[cfg(feature = "test-utils")]
+pub use backend::{CountingBackend, OperationCounts};
+pub use backend::{InMemoryBackend, ScalarBackend};
+mod bundle;
+pub use bundle::BundleError;
+mod claims;
+pub use claims::{
+    sign_claims, sign_claims_with_signer, verify_claims, ClaimValue, Claims, ClaimsError,
+    Validation,
+};
+mod conversion;
+mod cosign;
+mod ct;
+mod derivation;
+pub use derivation::MasterKey;
+mod did;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub use did::{DidKeyError, MULTICODEC};
+#[cfg(feature = "test-utils")]
+pub mod fixtures;
+mod guarded;
+mod hash;
+#[cfg(feature = "merlin")]
+mod merlin_transcript;
+#[cfg(feature = "merlin")]
+pub use merlin_transcript::MercurialTranscript;
+#[cfg(feature = "keystore")]
+mod keystore;
+#[cfg(feature = "uniffi")]
+pub mod mobile;
+#[cfg(feature = "keystore")]
+pub use keystore::{Entry as KeystoreEntry, EntryKind, Keypair, Keystore, KeystoreError};
+#[cfg(feature = "mnemonic")]
+mod mnemonic;
+#[cfg(feature = "mnemonic")]
+pub use mnemonic::{key_gen_from_mnemonic, Mnemonic, MnemonicError};
+mod opening;
 mod params;
+#[cfg(feature = "keystore")]
+mod pem;
+pub use params::ParamsError;
+#[cfg(feature = "poseidon")]
+mod poseidon;
+#[cfg(feature = "poseidon")]
+pub use poseidon::poseidon_hash_to_fr;
+pub mod prelude;
 mod public_key;
+pub use public_key::{commit_public_key, KeyError};
+mod public_key_g1;
+#[cfg(feature = "python")]
+pub mod python;
 mod representation;
-pub use representation::change_representation;
+#[cfg(feature = "getrandom")]
+pub use representation::randomize_representation_default;
+pub use representation::{
+    change_representation, change_representation_affine, change_representation_g2,
+    change_representation_tagged, inverse_change_representation,
+};
+mod rng;
+#[cfg(feature = "test-utils")]
+pub use rng::InsecureTestRng;
+pub use rng::RngAdapter;
+mod same_message;
+mod serialization;
+pub use same_message::prove_same_message;
 mod secret_key;
+pub use secret_key::permute_message;
 mod signature;
+mod signature_g2;
+pub use signature::combine_partial_signatures;
+mod signer;
+#[cfg(feature = "snark")]
+mod snark;
+#[cfg(feature = "test-utils")]
+pub use signer::MockSignerError;
+pub use signer::{sign_many_with_signer, AsyncSigner, LocalSignerError};
+#[cfg(feature = "snark")]
+pub use snark::ConstraintField;
+mod split;
+#[cfg(feature = "proptest")]
+pub mod strategies;
+pub use split::{convert_shares, split_secret_key, SplitError};
+mod terms;
+mod threshold;
+pub use threshold::{deal_shares, ThresholdError};
+mod transcript;
+pub use transcript::Transcript;
+#[cfg(feature = "test-utils")]
+pub use transcript::{take_recorded_transcripts, RecordedTranscript, TranscriptEntry};
+pub mod utils;
+#[cfg(feature = "verification-cache")]
+mod verification_cache;
+#[cfg(feature = "verification-cache")]
+pub use verification_cache::{verify_cached, verify_cached_g2, VerificationCache};
+mod wallet;
+pub use wallet::WalletError;
+#[cfg(feature = "wasm")]
+mod wasm;
+#[cfg(feature = "wasm")]
+pub use wasm::{
+    random_message, random_scalar, WasmKeypair, WasmPublicKey, WasmPublicParams, WasmSignature,
+};
+mod witness;
+
+#[cfg(feature = "uniffi")]
+uniffi::setup_scaffolding!();
 
 // type alias for the curve Bls12_381
 pub type PublicParams = params::PublicParams<ark_bls12_381::Bls12_381>;
 pub type PublicKey = public_key::PublicKey<ark_bls12_381::Bls12_381>;
+pub type PublicKeyView<'a> = public_key::PublicKeyView<'a, ark_bls12_381::Bls12_381>;
 pub type SecretKey = secret_key::SecretKey<ark_bls12_381::Bls12_381>;
+pub type GuardedSecretKey = guarded::GuardedSecretKey<ark_bls12_381::Bls12_381>;
 pub type Signature = signature::Signature<ark_bls12_381::Bls12_381>;
+pub type SignatureBundle = bundle::SignatureBundle<ark_bls12_381::Bls12_381>;
+pub type KeyBundle = bundle::KeyBundle<ark_bls12_381::Bls12_381>;
+#[cfg(feature = "bilateral")]
+pub type BilateralPublicKey = bilateral::BilateralPublicKey<ark_bls12_381::Bls12_381>;
+#[cfg(feature = "bilateral")]
+pub type BilateralSecretKey = bilateral::BilateralSecretKey<ark_bls12_381::Bls12_381>;
+#[cfg(feature = "bilateral")]
+pub type BilateralSignature = bilateral::BilateralSignature<ark_bls12_381::Bls12_381>;
+pub type PublicKeyG1 = public_key_g1::PublicKeyG1<ark_bls12_381::Bls12_381>;
+pub type SignatureG2 = signature_g2::SignatureG2<ark_bls12_381::Bls12_381>;
+pub type RepresentationChangeProof =
+    representation::RepresentationChangeProof<ark_bls12_381::Bls12_381>;
+pub type SameMessageProof = same_message::SameMessageProof<ark_bls12_381::Bls12_381>;
+pub type TaggedSignature = signature::TaggedSignature<ark_bls12_381::Bls12_381>;
+pub type Wallet = wallet::Wallet<ark_bls12_381::Bls12_381>;
+pub type Credential = wallet::Credential<ark_bls12_381::Bls12_381>;
+pub type SignedClaims = claims::SignedClaims<ark_bls12_381::Bls12_381>;
+pub type AggregateSignature = signature::AggregateSignature<ark_bls12_381::Bls12_381>;
+pub type PartialSignature = signature::PartialSignature<ark_bls12_381::Bls12_381>;
+pub type ShareA = split::ShareA<ark_bls12_381::Bls12_381>;
+pub type ShareB = split::ShareB<ark_bls12_381::Bls12_381>;
+pub type SplitSigningState = split::SplitSigningState<ark_bls12_381::Bls12_381>;
+pub type SplitRound1 = split::SplitRound1<ark_bls12_381::Bls12_381>;
+pub type SplitRound2 = split::SplitRound2<ark_bls12_381::Bls12_381>;
+pub type ContributedConversion = conversion::ContributedConversion<ark_bls12_381::Bls12_381>;
+pub type ConversionCommitment = conversion::ConversionCommitment<ark_bls12_381::Bls12_381>;
+pub type ConversionPass = conversion::ConversionPass<ark_bls12_381::Bls12_381>;
+pub type CtFr = ct::CtFr<ark_bls12_381::Bls12_381>;
+pub type ProofOfPossession = cosign::ProofOfPossession<ark_bls12_381::Bls12_381>;
+pub type CoSigners = cosign::CoSigners<ark_bls12_381::Bls12_381>;
+pub type CoSignature = cosign::CoSignature<ark_bls12_381::Bls12_381>;
+pub type VerifyWitness = witness::VerifyWitness<ark_bls12_381::Bls12_381>;
+pub type VerificationTerms = terms::VerificationTerms<ark_bls12_381::Bls12_381>;
+pub type MultiPairingAccumulator = terms::MultiPairingAccumulator<ark_bls12_381::Bls12_381>;
+pub type TokenIssuer = anonymous_tokens::Issuer<ark_bls12_381::Bls12_381>;
+pub type TokenClient = anonymous_tokens::Client<ark_bls12_381::Bls12_381>;
+pub type TokenRedeemer = anonymous_tokens::Redeemer<ark_bls12_381::Bls12_381>;
+pub type TokenIssuanceRequest = anonymous_tokens::IssuanceRequest<ark_bls12_381::Bls12_381>;
+pub type TokenIssuanceResponse = anonymous_tokens::IssuanceResponse<ark_bls12_381::Bls12_381>;
+pub type TokenRedemption = anonymous_tokens::Redemption<ark_bls12_381::Bls12_381>;
+pub type OpeningAuthority = opening::OpeningAuthority<ark_bls12_381::Bls12_381>;
+pub type OpeningKey = opening::OpeningKey<ark_bls12_381::Bls12_381>;
+pub type RegistrationRecord = opening::RegistrationRecord<ark_bls12_381::Bls12_381>;
+pub type OpeningProof = opening::OpeningProof<ark_bls12_381::Bls12_381>;
+pub type LinkResult = opening::LinkResult<ark_bls12_381::Bls12_381>;
+pub type KeyShare = threshold::KeyShare<ark_bls12_381::Bls12_381>;
+pub type VerificationShare = threshold::VerificationShare<ark_bls12_381::Bls12_381>;
+pub type Round1Message = threshold::Round1Message<ark_bls12_381::Bls12_381>;
+pub type Round2Message = threshold::Round2Message<ark_bls12_381::Bls12_381>;
+pub type RandomizedMessage = threshold::RandomizedMessage<ark_bls12_381::Bls12_381>;
+pub type BackendKey<B> = backend::BackendKey<ark_bls12_381::Bls12_381, B>;
+pub type LocalSigner = signer::LocalSigner<ark_bls12_381::Bls12_381>;
+#[cfg(feature = "test-utils")]
+pub type MockRemoteSigner = signer::MockRemoteSigner<ark_bls12_381::Bls12_381>;
+/// cbindgen:ignore
+pub type FeldmanCommitment = threshold::FeldmanCommitment<ark_bls12_381::Bls12_381>;
+pub type ThresholdAggregator<'a> = threshold::Aggregator<'a, ark_bls12_381::Bls12_381>;
+
+// type alias for the curve BW6-761, whose G1 and G2 are over the same base field
+#[cfg(feature = "bw6_761")]
+pub type CurveBw6_761 = ark_bw6_761::BW6_761;
+#[cfg(feature = "bw6_761")]
+pub type PublicParamsBw6_761 = params::PublicParams<CurveBw6_761>;
+#[cfg(feature = "bw6_761")]
+pub type PublicKeyBw6_761 = public_key::PublicKey<CurveBw6_761>;
+#[cfg(feature = "bw6_761")]
+pub type SecretKeyBw6_761 = secret_key::SecretKey<CurveBw6_761>;
+#[cfg(feature = "bw6_761")]
+pub type SignatureBw6_761 = signature::Signature<CurveBw6_761>;
+#[cfg(feature = "bw6_761")]
+pub type G1Bw6_761 = ark_bw6_761::G1Projective;
+#[cfg(feature = "bw6_761")]
+pub type G2Bw6_761 = ark_bw6_761::G2Projective;
+#[cfg(feature = "bw6_761")]
+pub type FrBw6_761 = ark_bw6_761::Fr;
+
+// type alias for the pairing gadget of BLS12-381 in R1CS constraints over its base field
+#[cfg(feature = "snark")]
+pub type CurveBls12_381Var = ark_r1cs_std::pairing::bls12::PairingVar<ark_bls12_381::Config>;
+#[cfg(feature = "snark")]
+pub type VerifyCircuit = snark::VerifyCircuit<ark_bls12_381::Bls12_381, CurveBls12_381Var>;
 
 // re-export the curve types
 pub type G1 = ark_bls12_381::G1Projective;
+pub type G1Affine = ark_bls12_381::G1Affine;
 pub type G2 = ark_bls12_381::G2Projective;
 pub type Fr = ark_bls12_381::Fr;
 
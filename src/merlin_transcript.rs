@@ -0,0 +1,89 @@
+//! Fiat-Shamir transcript over [merlin], with the feature `merlin`.
+//!
+//! A [MercurialTranscript] is for the protocols built on this crate by the applications using
+//! merlin for their own proofs, e.g. a proof of knowledge bound to a signature. The values of
+//! the scheme are appended by their compressed serialization, each preceded by its kind, so
+//! that e.g. a public key is never taken for a message of the same bytes.
+
+use alloc::vec::Vec;
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+
+use crate::{params::PublicParams, public_key::PublicKey, signature::Signature};
+
+/// A Fiat-Shamir transcript wrapping [merlin::Transcript].
+///
+/// ## Example
+///
+/// ```rust
+/// use ark_bls12_381::Bls12_381;
+/// use mercurial_signature::{MercurialTranscript, PublicParams};
+///
+/// let mut rng = rand::thread_rng();
+/// let pp = PublicParams::new(&mut rng);
+/// let (pk, sk) = pp.key_gen(&mut rng, 2);
+///
+/// let mut transcript = MercurialTranscript::new(b"example");
+/// transcript.append_public_params(b"pp", &pp);
+/// transcript.append_public_key(b"pk", &pk);
+/// let c1 = transcript.challenge_scalar::<Bls12_381>(b"challenge");
+/// let c2 = transcript.challenge_scalar::<Bls12_381>(b"challenge");
+/// assert!(c1 != c2);
+/// ```
+#[derive(Clone)]
+pub struct MercurialTranscript(merlin::Transcript);
+
+impl MercurialTranscript {
+    /// Create a transcript for the domain of a protocol.
+    pub fn new(domain: &'static [u8]) -> Self {
+        let mut transcript = merlin::Transcript::new(b"mercurial-signature");
+        transcript.append_message(b"domain", domain);
+        MercurialTranscript(transcript)
+    }
+
+    /// Append the public parameters to the transcript.
+    pub fn append_public_params<E: Pairing>(&mut self, label: &'static [u8], pp: &PublicParams<E>) {
+        self.append(b"public-params", label, pp);
+    }
+
+    /// Append a public key to the transcript.
+    pub fn append_public_key<E: Pairing>(&mut self, label: &'static [u8], pk: &PublicKey<E>) {
+        self.append(b"public-key", label, pk);
+    }
+
+    /// Append a message to the transcript.
+    pub fn append_message<E: Pairing>(&mut self, label: &'static [u8], message: &[E::G1]) {
+        self.append(b"message", label, message);
+    }
+
+    /// Append a signature to the transcript.
+    pub fn append_signature<E: Pairing>(&mut self, label: &'static [u8], sig: &Signature<E>) {
+        self.append(b"signature", label, sig);
+    }
+
+    /// Derive a challenge from the transcript. The challenge is absorbed into the transcript,
+    /// so that the next challenge is different.
+    pub fn challenge_scalar<E: Pairing>(&mut self, label: &'static [u8]) -> E::ScalarField {
+        let mut output = [0u8; 64];
+        self.0.challenge_bytes(label, &mut output);
+        E::ScalarField::from_le_bytes_mod_order(&output)
+    }
+
+    /// The wrapped transcript, e.g. to append the commitments of a proof.
+    pub fn as_merlin(&mut self) -> &mut merlin::Transcript {
+        &mut self.0
+    }
+
+    fn append<T: CanonicalSerialize + ?Sized>(
+        &mut self,
+        kind: &'static [u8],
+        label: &'static [u8],
+        value: &T,
+    ) {
+        let mut bytes = Vec::new();
+        value.serialize_compressed(&mut bytes).unwrap();
+        self.0.append_message(b"kind", kind);
+        self.0.append_message(label, &bytes);
+    }
+}
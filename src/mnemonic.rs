@@ -0,0 +1,124 @@
+//! BIP39 mnemonic phrases of key seeds, with the feature `mnemonic`.
+//!
+//! A [Mnemonic] backs up the seed of a key pair as a phrase of English words, written down by
+//! an operator. The key pair is generated from the BIP39 seed of the phrase and a passphrase
+//! by [key_gen_from_mnemonic], i.e. [PublicParams::key_gen_deterministic], so that the same
+//! phrase and passphrase always restore the same key pair.
+
+use core::fmt;
+
+use ark_ec::pairing::Pairing;
+use rand_core::{CryptoRng, RngCore};
+use zeroize::Zeroizing;
+
+use crate::{params::PublicParams, public_key::PublicKey, secret_key::SecretKey};
+
+/// Error in parsing a mnemonic phrase.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MnemonicError {
+    /// The number of words is not 12, 15, 18, 21 or 24.
+    WordCount(usize),
+    /// The word at the index is not in the English wordlist.
+    UnknownWord(usize),
+    /// The checksum of the phrase is wrong, e.g. a word is mistyped as another word.
+    InvalidChecksum,
+}
+
+impl fmt::Display for MnemonicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MnemonicError::WordCount(count) => write!(
+                f,
+                "the mnemonic has {} words, instead of 12, 15, 18, 21 or 24",
+                count
+            ),
+            MnemonicError::UnknownWord(index) => {
+                write!(f, "the word {} of the mnemonic is unknown", index + 1)
+            }
+            MnemonicError::InvalidChecksum => write!(f, "the checksum of the mnemonic is wrong"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MnemonicError {}
+
+/// BIP39 mnemonic phrase in English, see the [module](self) documentation.
+///
+/// The `Display` output is the phrase. The `Debug` output is redacted.
+///
+/// ## Example
+///
+/// ```rust
+/// use mercurial_signature::{key_gen_from_mnemonic, Mnemonic, PublicParams};
+///
+/// let mut rng = rand::thread_rng();
+/// let pp = PublicParams::new(&mut rng);
+/// let mnemonic = Mnemonic::generate(&mut rng);
+/// let (pk, _) = key_gen_from_mnemonic(&pp, &mnemonic, "passphrase", 10);
+///
+/// // restore the key pair from the phrase written down
+/// let phrase = mnemonic.to_string();
+/// let restored = Mnemonic::parse(&phrase).unwrap();
+/// assert!(key_gen_from_mnemonic(&pp, &restored, "passphrase", 10).0 == pk);
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct Mnemonic(bip39::Mnemonic);
+
+impl Mnemonic {
+    /// Generate a phrase of 24 words, i.e. of 256 bits of entropy.
+    pub fn generate<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        let mut entropy = Zeroizing::new([0u8; 32]);
+        rng.fill_bytes(entropy.as_mut_slice());
+        Mnemonic(bip39::Mnemonic::from_entropy(entropy.as_slice()).unwrap())
+    }
+
+    /// Parse a phrase entered by a user. The words are separated by whitespace, and are
+    /// normalized to lowercase.
+    pub fn parse(phrase: &str) -> Result<Self, MnemonicError> {
+        let phrase = Zeroizing::new(phrase.to_lowercase());
+        bip39::Mnemonic::parse_in(bip39::Language::English, phrase.as_str())
+            .map(Mnemonic)
+            .map_err(|err| match err {
+                bip39::Error::BadWordCount(count) => MnemonicError::WordCount(count),
+                bip39::Error::UnknownWord(index) => MnemonicError::UnknownWord(index),
+                // the entropy of a parsed phrase is always of a valid length, and the language
+                // is given
+                bip39::Error::BadEntropyBitCount(_)
+                | bip39::Error::InvalidChecksum
+                | bip39::Error::AmbiguousLanguages(_) => MnemonicError::InvalidChecksum,
+            })
+    }
+
+    /// Number of words of the phrase.
+    pub fn word_count(&self) -> usize {
+        self.0.word_count()
+    }
+
+    /// The 64-byte BIP39 seed of the phrase and the passphrase, which may be empty.
+    pub fn to_seed(&self, passphrase: &str) -> Zeroizing<[u8; 64]> {
+        Zeroizing::new(self.0.to_seed(passphrase))
+    }
+}
+
+impl fmt::Display for Mnemonic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl fmt::Debug for Mnemonic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Mnemonic([REDACTED; {}])", self.word_count())
+    }
+}
+
+/// Generate the key pair of the phrase and the passphrase, see [Mnemonic].
+pub fn key_gen_from_mnemonic<E: Pairing>(
+    pp: &PublicParams<E>,
+    mnemonic: &Mnemonic,
+    passphrase: &str,
+    size: u32,
+) -> (PublicKey<E>, SecretKey<E>) {
+    pp.key_gen_deterministic(&mnemonic.to_seed(passphrase), size)
+}
@@ -0,0 +1,374 @@
+//! Bindings for Swift and Kotlin with UniFFI, enabled by the feature `uniffi`.
+//!
+//! The interface is declared with the UniFFI proc-macros, so there is no UDL file. Generate the
+//! bindings from the built library, e.g.
+//! `cargo rustc --lib --release --features uniffi --crate-type cdylib` and then
+//! `uniffi-bindgen generate --library target/release/libmercurial_signature.so --language kotlin --out-dir out`.
+//!
+//! The values cross the boundary as byte arrays in the compressed serialization, i.e. a message
+//! is a serialized `Vec<G1>` and a scalar is a serialized `Fr`. Invalid inputs are returned as
+//! [MobileError] instead of panics.
+//!
+//! The functions that need randomness take an optional [RandomSource]. Without it, the
+//! randomness is from the operating system. A host can supply its own source where the platform
+//! requires it, e.g. `SecRandomCopyBytes` on iOS or `SecureRandom` on Android.
+//!
+//! Threading: all the objects are immutable, and `Send` and `Sync`, so they can be shared
+//! between threads. The operations that change a value, e.g. [MobileSignature::convert], return
+//! a new object instead. A [RandomSource] is called synchronously on the calling thread, and
+//! must be `Send` and `Sync` too.
+
+use std::{fmt, sync::Arc};
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use rand_core::{CryptoRng, OsRng, RngCore};
+
+use crate::{change_representation, Fr, PublicKey, PublicParams, SecretKey, Signature, G1};
+
+/// Error returned to the host.
+#[derive(Debug, uniffi::Error)]
+pub enum MobileError {
+    /// An input cannot be deserialized or is not valid for the operation.
+    InvalidInput { reason: String },
+    /// The [RandomSource] did not return the requested number of bytes.
+    RandomSource { reason: String },
+}
+
+impl fmt::Display for MobileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MobileError::InvalidInput { reason } => write!(f, "invalid input: {}", reason),
+            MobileError::RandomSource { reason } => write!(f, "random source: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for MobileError {}
+
+/// Source of cryptographically secure randomness implemented by the host.
+#[uniffi::export(with_foreign)]
+pub trait RandomSource: Send + Sync {
+    /// Return `len` uniformly random bytes from a cryptographically secure generator.
+    fn fill_bytes(&self, len: u32) -> Vec<u8>;
+}
+
+/// Public parameters.
+#[derive(uniffi::Object)]
+pub struct MobilePublicParams {
+    inner: PublicParams,
+}
+
+/// Public key, e.g. received from an issuer.
+#[derive(uniffi::Object)]
+pub struct MobilePublicKey {
+    inner: PublicKey,
+}
+
+/// Key pair of a signer.
+#[derive(uniffi::Object)]
+pub struct MobileKeypair {
+    pk: PublicKey,
+    sk: SecretKey,
+}
+
+/// Signature.
+#[derive(uniffi::Object)]
+pub struct MobileSignature {
+    inner: Signature,
+}
+
+/// Result of [MobileSignature::change_representation].
+#[derive(uniffi::Record)]
+pub struct ChangedRepresentation {
+    /// The new message, serialized.
+    pub message: Vec<u8>,
+    /// The signature on the new message.
+    pub signature: Arc<MobileSignature>,
+}
+
+#[uniffi::export]
+impl MobilePublicParams {
+    /// Generate public parameters.
+    #[uniffi::constructor]
+    pub fn new(rng: Option<Arc<dyn RandomSource>>) -> Result<Arc<Self>, MobileError> {
+        let inner = with_rng(rng, PublicParams::new)?;
+        Ok(Arc::new(MobilePublicParams { inner }))
+    }
+
+    #[uniffi::constructor]
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Arc<Self>, MobileError> {
+        Ok(Arc::new(MobilePublicParams {
+            inner: deserialize(&bytes, "public parameters")?,
+        }))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        serialize(&self.inner)
+    }
+}
+
+#[uniffi::export]
+impl MobilePublicKey {
+    #[uniffi::constructor]
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Arc<Self>, MobileError> {
+        Ok(Arc::new(MobilePublicKey {
+            inner: deserialize(&bytes, "public key")?,
+        }))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        serialize(&self.inner)
+    }
+
+    /// Verify the signature on the message.
+    pub fn verify(
+        &self,
+        pp: Arc<MobilePublicParams>,
+        message: Vec<u8>,
+        sig: Arc<MobileSignature>,
+    ) -> Result<bool, MobileError> {
+        let message = deserialize_message(&message)?;
+        Ok(self.inner.verify(&pp.inner, &message, &sig.inner))
+    }
+
+    /// The public key converted by the scalar `p`.
+    pub fn convert(&self, p: Vec<u8>) -> Result<Arc<MobilePublicKey>, MobileError> {
+        let mut inner = self.inner.clone();
+        inner.convert(deserialize(&p, "scalar")?);
+        Ok(Arc::new(MobilePublicKey { inner }))
+    }
+}
+
+#[uniffi::export]
+impl MobileKeypair {
+    /// Generate a key pair for messages of `size` elements.
+    #[uniffi::constructor]
+    pub fn new(
+        pp: Arc<MobilePublicParams>,
+        size: u32,
+        rng: Option<Arc<dyn RandomSource>>,
+    ) -> Result<Arc<Self>, MobileError> {
+        let (pk, sk) = with_rng(rng, |rng| pp.inner.key_gen(rng, size))?;
+        Ok(Arc::new(MobileKeypair { pk, sk }))
+    }
+
+    #[uniffi::constructor]
+    pub fn from_bytes(public_key: Vec<u8>, secret_key: Vec<u8>) -> Result<Arc<Self>, MobileError> {
+        let pk: PublicKey = deserialize(&public_key, "public key")?;
+        let sk: SecretKey = deserialize(&secret_key, "secret key")?;
+        if pk.length() != sk.length() {
+            return Err(invalid(
+                "the public key and the secret key have different lengths",
+            ));
+        }
+        Ok(Arc::new(MobileKeypair { pk, sk }))
+    }
+
+    pub fn public_key(&self) -> Arc<MobilePublicKey> {
+        Arc::new(MobilePublicKey {
+            inner: self.pk.clone(),
+        })
+    }
+
+    pub fn secret_key_bytes(&self) -> Vec<u8> {
+        serialize(&self.sk)
+    }
+
+    /// Sign the message.
+    pub fn sign(
+        &self,
+        pp: Arc<MobilePublicParams>,
+        message: Vec<u8>,
+        rng: Option<Arc<dyn RandomSource>>,
+    ) -> Result<Arc<MobileSignature>, MobileError> {
+        let message = deserialize_message(&message)?;
+        if message.len() > self.sk.length() {
+            return Err(invalid("the message is longer than the key"));
+        }
+        let inner = with_rng(rng, |rng| self.sk.sign(rng, &pp.inner, &message))?;
+        Ok(Arc::new(MobileSignature { inner }))
+    }
+
+    /// Verify the signature on the message.
+    pub fn verify(
+        &self,
+        pp: Arc<MobilePublicParams>,
+        message: Vec<u8>,
+        sig: Arc<MobileSignature>,
+    ) -> Result<bool, MobileError> {
+        let message = deserialize_message(&message)?;
+        Ok(self.pk.verify(&pp.inner, &message, &sig.inner))
+    }
+
+    /// The key pair with both keys converted by the scalar `p`.
+    pub fn convert(&self, p: Vec<u8>) -> Result<Arc<MobileKeypair>, MobileError> {
+        let p: Fr = deserialize(&p, "scalar")?;
+        let (mut pk, mut sk) = (self.pk.clone(), self.sk.clone());
+        pk.convert(p);
+        sk.convert(p);
+        Ok(Arc::new(MobileKeypair { pk, sk }))
+    }
+}
+
+#[uniffi::export]
+impl MobileSignature {
+    #[uniffi::constructor]
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Arc<Self>, MobileError> {
+        Ok(Arc::new(MobileSignature {
+            inner: deserialize(&bytes, "signature")?,
+        }))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        serialize(&self.inner)
+    }
+
+    /// The signature converted by the scalar `p`.
+    pub fn convert(
+        &self,
+        p: Vec<u8>,
+        rng: Option<Arc<dyn RandomSource>>,
+    ) -> Result<Arc<MobileSignature>, MobileError> {
+        let p: Fr = deserialize(&p, "scalar")?;
+        let mut inner = self.inner.clone();
+        with_rng(rng, |rng| inner.convert(rng, p))?;
+        Ok(Arc::new(MobileSignature { inner }))
+    }
+
+    /// Change the representation of the message and the signature by the scalar `u`.
+    pub fn change_representation(
+        &self,
+        message: Vec<u8>,
+        u: Vec<u8>,
+        rng: Option<Arc<dyn RandomSource>>,
+    ) -> Result<ChangedRepresentation, MobileError> {
+        let mut message = deserialize_message(&message)?;
+        let u: Fr = deserialize(&u, "scalar")?;
+        let mut inner = self.inner.clone();
+        with_rng(rng, |rng| {
+            change_representation(rng, &mut message, &mut inner, u)
+        })?;
+        Ok(ChangedRepresentation {
+            message: serialize(&message),
+            signature: Arc::new(MobileSignature { inner }),
+        })
+    }
+}
+
+/// A random scalar, e.g. for [MobileSignature::convert] or
+/// [MobileSignature::change_representation].
+#[uniffi::export]
+pub fn mobile_random_scalar(rng: Option<Arc<dyn RandomSource>>) -> Result<Vec<u8>, MobileError> {
+    let p = with_rng(rng, <Fr as ark_std::UniformRand>::rand)?;
+    Ok(serialize(&p))
+}
+
+/// A random message of `size` elements.
+#[uniffi::export]
+pub fn mobile_random_message(
+    size: u32,
+    rng: Option<Arc<dyn RandomSource>>,
+) -> Result<Vec<u8>, MobileError> {
+    let message = with_rng(rng, |rng| {
+        (0..size)
+            .map(|_| <G1 as ark_std::UniformRand>::rand(rng))
+            .collect::<Vec<G1>>()
+    })?;
+    Ok(serialize(&message))
+}
+
+/// Randomness of a [RandomSource], or of the operating system. A short answer from the host
+/// is recorded and the missing bytes are zeros, so that the result of the operation is
+/// discarded by [with_rng].
+struct HostRng {
+    source: Option<Arc<dyn RandomSource>>,
+    failure: Option<String>,
+}
+
+impl RngCore for HostRng {
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0u8; 4];
+        self.fill_bytes(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0u8; 8];
+        self.fill_bytes(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        let Some(source) = &self.source else {
+            return OsRng.fill_bytes(dest);
+        };
+        let bytes = source.fill_bytes(dest.len() as u32);
+        if bytes.len() == dest.len() {
+            dest.copy_from_slice(&bytes);
+        } else {
+            dest.fill(0);
+            self.failure = Some(format!(
+                "requested {} bytes, got {}",
+                dest.len(),
+                bytes.len()
+            ));
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+// The host is required to supply cryptographically secure randomness, see [RandomSource].
+impl CryptoRng for HostRng {}
+
+/// Run `f` with the randomness of `source`, or of the operating system if it is `None`.
+fn with_rng<T>(
+    source: Option<Arc<dyn RandomSource>>,
+    f: impl FnOnce(&mut HostRng) -> T,
+) -> Result<T, MobileError> {
+    let mut rng = HostRng {
+        source,
+        failure: None,
+    };
+    let result = f(&mut rng);
+    match rng.failure {
+        None => Ok(result),
+        Some(reason) => Err(MobileError::RandomSource { reason }),
+    }
+}
+
+fn invalid(reason: &str) -> MobileError {
+    MobileError::InvalidInput {
+        reason: reason.to_string(),
+    }
+}
+
+fn serialize<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes).unwrap();
+    bytes
+}
+
+fn deserialize<T: CanonicalDeserialize>(bytes: &[u8], name: &str) -> Result<T, MobileError> {
+    T::deserialize_compressed(bytes).map_err(|e| MobileError::InvalidInput {
+        reason: format!("invalid {}: {}", name, e),
+    })
+}
+
+/// Deserialize a message, checking its length prefix against the input first so that a
+/// corrupted prefix cannot request a huge allocation.
+fn deserialize_message(bytes: &[u8]) -> Result<Vec<G1>, MobileError> {
+    let element_size = G1::default().compressed_size();
+    let length = bytes
+        .get(..8)
+        .map(|prefix| u64::from_le_bytes(prefix.try_into().unwrap()));
+    match length {
+        Some(length) if length.checked_mul(element_size as u64) == Some(bytes.len() as u64 - 8) => {
+            deserialize(bytes, "message")
+        }
+        _ => Err(invalid("invalid message: wrong length")),
+    }
+}
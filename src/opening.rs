@@ -0,0 +1,350 @@
+//! Opening authority that can link a pseudonym back to a registered public key.
+//!
+//! A pseudonym is a converted public key `p * pk`. Normal verifiers cannot tell whether two
+//! public keys are in the same class, but anyone holding `Di = (xi / x1) p1` for `i = 2,...,l`
+//! can, by the pairing test `e(Di, bx1') == e(p1, bxi')`. The `Di` are the same for all the
+//! pseudonyms of the key.
+//!
+//! 1. Registration: the user encrypts `D2,...,Dl` to the [OpeningKey] of the authority with
+//!    ElGamal in G1, and proves with a sigma protocol that the ciphertexts encrypt `Di` with
+//!    `bxi = (xi / x1) bx1`. The issuer accepts the [RegistrationRecord] only if the proof
+//!    verifies, e.g. before signing the credential of the user.
+//! 2. Opening: the [OpeningAuthority] decrypts the `Di` of a record and checks the pseudonym
+//!    against them. If they match, it outputs an [OpeningProof] containing the `Di` and a
+//!    Chaum-Pedersen proof of the correct decryption, which anyone can check.
+//!
+//! Note that the `Di` in an opening proof link all pseudonyms of the key, past and future. A
+//! user whose pseudonym has been opened should register a new key.
+
+use alloc::vec::Vec;
+use core::ops::Mul;
+
+use ark_ec::pairing::Pairing;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{One, UniformRand};
+use rand_core::{CryptoRng, RngCore};
+
+use crate::{
+    params::PublicParams, public_key::PublicKey, secret_key::SecretKey,
+    serialization::impl_bounded_deserialize, transcript::Transcript,
+};
+
+/// The opening authority, holding the decryption key.
+#[derive(Clone)]
+pub struct OpeningAuthority<E: Pairing> {
+    sk: E::ScalarField,
+    pk: OpeningKey<E>,
+}
+
+/// The public key of the opening authority.
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct OpeningKey<E: Pairing> {
+    // p1^sk
+    pub(crate) a: E::G1,
+}
+
+/// The registration of a public key, with the linking information encrypted to the authority.
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize)]
+pub struct RegistrationRecord<E: Pairing> {
+    pub(crate) pk: PublicKey<E>,
+    // ElGamal ciphertexts (ri p1, Di + ri A) for i = 2,...,l
+    pub(crate) c1: Vec<E::G1>,
+    pub(crate) c2: Vec<E::G1>,
+    // commitments of the proof of the encryption
+    pub(crate) t1: Vec<E::G1>,
+    pub(crate) t2: Vec<E::G1>,
+    pub(crate) t3: Vec<E::G2>,
+    // responses for di = xi / x1 and ri
+    pub(crate) zd: Vec<E::ScalarField>,
+    pub(crate) zr: Vec<E::ScalarField>,
+}
+
+impl_bounded_deserialize!(RegistrationRecord {
+    pk: value,
+    c1: vec,
+    c2: vec,
+    t1: vec,
+    t2: vec,
+    t3: vec,
+    zd: vec,
+    zr: vec
+});
+
+/// The proof that a pseudonym is linked to a registration record.
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize)]
+pub struct OpeningProof<E: Pairing> {
+    // decrypted D2,...,Dl
+    pub(crate) d: Vec<E::G1>,
+    // commitments k p1, k c1_i
+    pub(crate) u: E::G1,
+    pub(crate) v: Vec<E::G1>,
+    // response k + c sk
+    pub(crate) w: E::ScalarField,
+}
+
+impl_bounded_deserialize!(OpeningProof {
+    d: vec,
+    u: value,
+    v: vec,
+    w: value
+});
+
+/// The result of opening a pseudonym against a registration record.
+#[derive(Clone, PartialEq, Eq)]
+pub enum LinkResult<E: Pairing> {
+    /// The pseudonym is a conversion of the registered public key.
+    Linked(OpeningProof<E>),
+    /// The pseudonym is not related to the record. Nothing is revealed.
+    NotLinked,
+}
+
+impl<E: Pairing> OpeningAuthority<E> {
+    /// Create an authority with a fresh key.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::{Fr, LinkResult, OpeningAuthority, PublicParams, RegistrationRecord, UniformRand};
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let authority = OpeningAuthority::new(&mut rng, &pp);
+    ///
+    /// let (pk, sk) = pp.key_gen(&mut rng, 5);
+    /// let record = RegistrationRecord::new(&mut rng, &pp, authority.public_key(), &pk, &sk);
+    /// assert!(record.verify(&pp, authority.public_key()));
+    ///
+    /// let mut pseudonym = pk.clone();
+    /// pseudonym.convert(Fr::rand(&mut rng));
+    /// match authority.open(&mut rng, &pp, &pseudonym, &record) {
+    ///     LinkResult::Linked(proof) => {
+    ///         assert!(proof.verify(&pp, authority.public_key(), &pseudonym, &record))
+    ///     }
+    ///     LinkResult::NotLinked => panic!("the pseudonym is linked"),
+    /// }
+    /// ```
+    pub fn new<R: RngCore + CryptoRng>(rng: &mut R, pp: &PublicParams<E>) -> Self {
+        let sk = E::ScalarField::rand(rng);
+        OpeningAuthority {
+            sk,
+            pk: OpeningKey { a: pp.p1.mul(sk) },
+        }
+    }
+
+    /// The public key of the authority.
+    pub fn public_key(&self) -> &OpeningKey<E> {
+        &self.pk
+    }
+
+    /// Open the pseudonym against the record. Returns [LinkResult::NotLinked] if the record is
+    /// invalid or the pseudonym is not a conversion of its public key.
+    pub fn open<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        pp: &PublicParams<E>,
+        pseudonym: &PublicKey<E>,
+        record: &RegistrationRecord<E>,
+    ) -> LinkResult<E> {
+        if !record.verify(pp, &self.pk) {
+            return LinkResult::NotLinked;
+        }
+
+        // Di = c2_i - sk c1_i
+        let d = record
+            .c1
+            .iter()
+            .zip(record.c2.iter())
+            .map(|(c1, c2)| *c2 - c1.mul(self.sk))
+            .collect::<Vec<E::G1>>();
+        if !is_linked(pp, &d, pseudonym) {
+            return LinkResult::NotLinked;
+        }
+
+        let k = E::ScalarField::rand(rng);
+        let u = pp.p1.mul(k);
+        let v = record.c1.iter().map(|c1| c1.mul(k)).collect::<Vec<E::G1>>();
+        let c = opening_challenge(pp, &self.pk, pseudonym, record, &d, u, &v);
+        LinkResult::Linked(OpeningProof {
+            d,
+            u,
+            v,
+            w: k + c * self.sk,
+        })
+    }
+}
+
+impl<E: Pairing> RegistrationRecord<E> {
+    /// Register the public key by encrypting its linking information to the authority.
+    ///
+    /// ## Safety
+    /// This function panics if the secret key is shorter than 2, as there is then no linking
+    /// information, or does not correspond to the public key.
+    pub fn new<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        pp: &PublicParams<E>,
+        authority: &OpeningKey<E>,
+        pk: &PublicKey<E>,
+        sk: &SecretKey<E>,
+    ) -> Self {
+        if sk.x.len() < 2 || sk.x.len() != pk.bx.len() {
+            panic!(
+                "The secret key must have at least 2 elements and correspond to the public key."
+            );
+        }
+
+        // di = xi / x1
+        let x1_inv = E::ScalarField::one() / sk.x[0];
+        let d = sk.x[1..].iter().map(|xi| *xi * x1_inv).collect::<Vec<_>>();
+        let r = (0..d.len())
+            .map(|_| E::ScalarField::rand(rng))
+            .collect::<Vec<E::ScalarField>>();
+        let c1 = r.iter().map(|ri| pp.p1.mul(ri)).collect::<Vec<E::G1>>();
+        let c2 = d
+            .iter()
+            .zip(r.iter())
+            .map(|(di, ri)| pp.p1.mul(di) + authority.a.mul(ri))
+            .collect::<Vec<E::G1>>();
+
+        let a = (0..d.len())
+            .map(|_| E::ScalarField::rand(rng))
+            .collect::<Vec<E::ScalarField>>();
+        let b = (0..d.len())
+            .map(|_| E::ScalarField::rand(rng))
+            .collect::<Vec<E::ScalarField>>();
+        let t1 = b.iter().map(|bi| pp.p1.mul(bi)).collect::<Vec<E::G1>>();
+        let t2 = a
+            .iter()
+            .zip(b.iter())
+            .map(|(ai, bi)| pp.p1.mul(ai) + authority.a.mul(bi))
+            .collect::<Vec<E::G1>>();
+        let t3 = a.iter().map(|ai| pk.bx[0].mul(ai)).collect::<Vec<E::G2>>();
+
+        let mut record = RegistrationRecord {
+            pk: pk.clone(),
+            c1,
+            c2,
+            t1,
+            t2,
+            t3,
+            zd: Vec::new(),
+            zr: Vec::new(),
+        };
+        let c = registration_challenge(pp, authority, &record);
+        record.zd = a
+            .iter()
+            .zip(d.iter())
+            .map(|(ai, di)| *ai + c * di)
+            .collect();
+        record.zr = b
+            .iter()
+            .zip(r.iter())
+            .map(|(bi, ri)| *bi + c * ri)
+            .collect();
+        record
+    }
+
+    /// The registered public key.
+    pub fn public_key(&self) -> &PublicKey<E> {
+        &self.pk
+    }
+
+    /// Verify the proof that the record encrypts the linking information of its public key.
+    /// Returns `false` if the public key is shorter than 2, as every pseudonym of that length
+    /// would be linked to it.
+    pub fn verify(&self, pp: &PublicParams<E>, authority: &OpeningKey<E>) -> bool {
+        let n = self.pk.bx.len().saturating_sub(1);
+        if self.pk.bx.len() < 2
+            || [&self.c1, &self.c2, &self.t1, &self.t2]
+                .iter()
+                .any(|v| v.len() != n)
+            || self.t3.len() != n
+            || self.zd.len() != n
+            || self.zr.len() != n
+        {
+            return false;
+        }
+
+        let c = registration_challenge(pp, authority, self);
+        (0..n).all(|i| {
+            // zr p1 == t1 + c c1
+            pp.p1.mul(self.zr[i]) == self.t1[i] + self.c1[i].mul(c)
+                // zd p1 + zr A == t2 + c c2
+                && pp.p1.mul(self.zd[i]) + authority.a.mul(self.zr[i])
+                    == self.t2[i] + self.c2[i].mul(c)
+                // zd bx1 == t3 + c bxi
+                && self.pk.bx[0].mul(self.zd[i]) == self.t3[i] + self.pk.bx[i + 1].mul(c)
+        })
+    }
+}
+
+impl<E: Pairing> OpeningProof<E> {
+    /// Verify the pseudonym is linked to the record, with the decryption by the authority.
+    pub fn verify(
+        &self,
+        pp: &PublicParams<E>,
+        authority: &OpeningKey<E>,
+        pseudonym: &PublicKey<E>,
+        record: &RegistrationRecord<E>,
+    ) -> bool {
+        if !record.verify(pp, authority)
+            || self.d.len() != record.c1.len()
+            || self.v.len() != record.c1.len()
+            || !is_linked(pp, &self.d, pseudonym)
+        {
+            return false;
+        }
+
+        // w p1 == u + c A and w c1_i == v_i + c (c2_i - Di)
+        let c = opening_challenge(pp, authority, pseudonym, record, &self.d, self.u, &self.v);
+        pp.p1.mul(self.w) == self.u + authority.a.mul(c)
+            && (0..self.d.len())
+                .all(|i| record.c1[i].mul(self.w) == self.v[i] + (record.c2[i] - self.d[i]).mul(c))
+    }
+}
+
+/// Check `e(Di, bx1') == e(p1, bxi')` for i = 2,...,l.
+fn is_linked<E: Pairing>(pp: &PublicParams<E>, d: &[E::G1], pseudonym: &PublicKey<E>) -> bool {
+    pseudonym.bx.len() == d.len() + 1
+        && d.iter()
+            .zip(pseudonym.bx[1..].iter())
+            .all(|(di, bxi)| E::pairing(*di, pseudonym.bx[0]) == E::pairing(pp.p1, *bxi))
+}
+
+/// Fiat-Shamir challenge of the proof of the encryption in the record.
+fn registration_challenge<E: Pairing>(
+    pp: &PublicParams<E>,
+    authority: &OpeningKey<E>,
+    record: &RegistrationRecord<E>,
+) -> E::ScalarField {
+    let mut transcript = Transcript::new(b"mercurial-signature/opening/registration");
+    transcript.append(b"pp", pp);
+    transcript.append(b"authority", authority);
+    transcript.append(b"pk", &record.pk);
+    transcript.append(b"c1", &record.c1);
+    transcript.append(b"c2", &record.c2);
+    transcript.append(b"t1", &record.t1);
+    transcript.append(b"t2", &record.t2);
+    transcript.append(b"t3", &record.t3);
+    transcript.challenge_scalar::<E>(b"challenge")
+}
+
+/// Fiat-Shamir challenge of the proof of the decryption.
+fn opening_challenge<E: Pairing>(
+    pp: &PublicParams<E>,
+    authority: &OpeningKey<E>,
+    pseudonym: &PublicKey<E>,
+    record: &RegistrationRecord<E>,
+    d: &[E::G1],
+    u: E::G1,
+    v: &[E::G1],
+) -> E::ScalarField {
+    let mut transcript = Transcript::new(b"mercurial-signature/opening/decryption");
+    transcript.append(b"pp", pp);
+    transcript.append(b"authority", authority);
+    transcript.append(b"pseudonym", pseudonym);
+    transcript.append(b"record", record);
+    transcript.append(b"decrypted", d);
+    transcript.append_point_g1::<E>(b"commitment_p1", &u);
+    transcript.append(b"commitment_c1", v);
+    transcript.challenge_scalar::<E>(b"challenge")
+}
@@ -1,33 +1,303 @@
-use std::ops::Mul;
+use alloc::{vec, vec::Vec};
+use core::{fmt, ops::Mul};
 
-use ark_ec::pairing::Pairing;
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_ec::{pairing::Pairing, AffineRepr};
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Valid};
 use ark_std::UniformRand;
-use rand_core::RngCore;
+use hkdf::Hkdf;
+#[cfg(feature = "getrandom")]
+use rand_core::OsRng;
+use rand_core::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256, Sha512};
+use sha3::{
+    digest::{ExtendableOutput, Update, XofReader},
+    Shake256,
+};
 
-use crate::{public_key::PublicKey, secret_key::SecretKey};
+use crate::{
+    hash::hash_to_g1,
+    public_key::{KeyError, PublicKey},
+    public_key_g1::PublicKeyG1,
+    secret_key::SecretKey,
+};
 
-#[derive(Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct PublicParams<E: Pairing> {
     // generators
     pub p1: E::G1,
     pub p2: E::G2,
 }
 
+/// Error in deriving the public parameters.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParamsError {
+    /// The ceremony transcript is empty.
+    EmptyTranscript,
+}
+
+impl fmt::Display for ParamsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamsError::EmptyTranscript => f.write_str("the ceremony transcript is empty"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParamsError {}
+
 impl<E: Pairing> PublicParams<E> {
     /// Generate public parameters.
-    pub fn new<R: RngCore>(rng: &mut R) -> Self {
+    pub fn new<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
         let p1 = E::G1::rand(rng);
         let p2 = E::G2::rand(rng);
         PublicParams { p1, p2 }
     }
 
+    /// Generate public parameters with the randomness of the operating system.
+    #[cfg(feature = "getrandom")]
+    pub fn new_default() -> Self {
+        Self::new(&mut OsRng)
+    }
+
+    /// Number of bytes of the uncompressed serialization, i.e.
+    /// [uncompressed_size](CanonicalSerialize::uncompressed_size).
+    pub fn serialized_size(&self) -> usize {
+        self.uncompressed_size()
+    }
+
+    /// Number of bytes of the compressed serialization, i.e.
+    /// [compressed_size](CanonicalSerialize::compressed_size).
+    pub fn compressed_serialized_size(&self) -> usize {
+        self.compressed_size()
+    }
+
+    /// SHA-256 of the compressed serialization, identifying the parameters to a remote signer,
+    /// see [AsyncSigner](crate::AsyncSigner).
+    pub fn digest(&self) -> [u8; 32] {
+        let mut bytes = Vec::with_capacity(self.compressed_size());
+        self.serialize_compressed(&mut bytes).unwrap();
+        Sha256::digest(&bytes).into()
+    }
+
+    /// Hash the data to an element of G1 whose discrete logarithm is unknown, with the domain
+    /// separation tag bound to these parameters by their [digest](PublicParams::digest), so
+    /// that the same data hashes to unrelated elements under different parameters. It uses
+    /// try-and-increment, so it is not constant time and is meant for public data only.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::PublicParams;
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let pp2 = PublicParams::new(&mut rng);
+    /// assert!(pp.with_hash_to_g1(b"data") == pp.with_hash_to_g1(b"data"));
+    /// assert!(pp.with_hash_to_g1(b"data") != pp2.with_hash_to_g1(b"data"));
+    /// ```
+    pub fn with_hash_to_g1(&self, data: &[u8]) -> E::G1 {
+        let dst = [
+            b"mercurial-signature/params-hash-to-g1/".as_slice(),
+            &self.digest(),
+        ]
+        .concat();
+        hash_to_g1::<E>(&dst, data)
+    }
+
+    /// Derive the public parameters deterministically from the output of a setup ceremony.
+    /// The transcript is used as the input keying material of HKDF-SHA512, whose output is
+    /// mapped to the generators by try-and-increment, so that nobody knows their discrete
+    /// logarithms as long as the transcript was not chosen adversarially.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::PublicParams;
+    ///
+    /// let transcript = b"output of the ceremony";
+    /// let pp1 = PublicParams::derive_from_ceremony_transcript(transcript).unwrap();
+    /// let pp2 = PublicParams::derive_from_ceremony_transcript(transcript).unwrap();
+    /// assert!(pp1 == pp2);
+    /// ```
+    pub fn derive_from_ceremony_transcript(transcript_bytes: &[u8]) -> Result<Self, ParamsError> {
+        if transcript_bytes.is_empty() {
+            return Err(ParamsError::EmptyTranscript);
+        }
+
+        let hkdf = Hkdf::<Sha512>::new(Some(b"mercurial-signature/ceremony"), transcript_bytes);
+        let expand = |label: &[u8], counter: u64, size: usize| {
+            let mut okm = vec![0u8; size];
+            hkdf.expand(&[label, &counter.to_le_bytes()].concat(), &mut okm)
+                .unwrap();
+            okm
+        };
+
+        let g1_size = E::G1Affine::zero().compressed_size();
+        let p1 = (0u64..)
+            .find_map(|counter| {
+                E::G1Affine::from_random_bytes(&expand(b"p1", counter, g1_size))
+                    .map(|p| p.clear_cofactor())
+                    .filter(|p| !p.is_zero())
+            })
+            .unwrap()
+            .into();
+        let g2_size = E::G2Affine::zero().compressed_size();
+        let p2 = (0u64..)
+            .find_map(|counter| {
+                E::G2Affine::from_random_bytes(&expand(b"p2", counter, g2_size))
+                    .map(|p| p.clear_cofactor())
+                    .filter(|p| !p.is_zero())
+            })
+            .unwrap()
+            .into();
+        Ok(PublicParams { p1, p2 })
+    }
+
     /// Generate a key pair.
-    pub fn key_gen<R: RngCore>(&self, rng: &mut R, size: u32) -> (PublicKey<E>, SecretKey<E>) {
+    pub fn key_gen<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        size: u32,
+    ) -> (PublicKey<E>, SecretKey<E>) {
         let x = (0..size)
             .map(|_| E::ScalarField::rand(rng))
             .collect::<Vec<E::ScalarField>>();
         let bx: Vec<E::G2> = x.iter().map(|xi| self.p2.mul(xi)).collect();
         (PublicKey { bx }, SecretKey { x })
     }
+
+    /// Extend a key pair to `new_len` elements, appending fresh elements to both keys. The
+    /// existing elements are kept, so the signatures by the key pair keep verifying under the
+    /// extended public key. Returns [KeyError::LengthMismatch] if the keys have different
+    /// lengths, or [KeyError::ShorterLength] if `new_len` is less than their length.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::{PublicParams, UniformRand, G1};
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let (mut pk, mut sk) = pp.key_gen(&mut rng, 10);
+    /// let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    /// let sig = sk.sign(&mut rng, &pp, &message);
+    ///
+    /// pp.extend_key(&mut rng, &mut pk, &mut sk, 15).unwrap();
+    /// assert!(pk.verify(&pp, &message, &sig));
+    /// ```
+    pub fn extend_key<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        pk: &mut PublicKey<E>,
+        sk: &mut SecretKey<E>,
+        new_len: u32,
+    ) -> Result<(), KeyError> {
+        if pk.bx.len() != sk.x.len() {
+            return Err(KeyError::LengthMismatch);
+        }
+        let new_len = new_len as usize;
+        if new_len < sk.x.len() {
+            return Err(KeyError::ShorterLength);
+        }
+        while sk.x.len() < new_len {
+            let xi = E::ScalarField::rand(rng);
+            pk.bx.push(self.p2.mul(xi));
+            sk.x.push(xi);
+        }
+        Ok(())
+    }
+
+    /// Generate a key pair with the randomness of the operating system.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::{PublicParams, UniformRand, G1};
+    ///
+    /// let pp = PublicParams::new_default();
+    /// let (pk, sk) = pp.key_gen_default(10);
+    /// let message = (0..10).map(|_| G1::rand(&mut rand::thread_rng())).collect::<Vec<G1>>();
+    /// let sig = sk.sign_default(&pp, &message);
+    /// assert!(pk.verify(&pp, &message, &sig));
+    /// ```
+    #[cfg(feature = "getrandom")]
+    pub fn key_gen_default(&self, size: u32) -> (PublicKey<E>, SecretKey<E>) {
+        self.key_gen(&mut OsRng, size)
+    }
+
+    /// Generate a key pair with the public key in G1, for signing messages in G2.
+    pub fn key_gen_g1<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        size: u32,
+    ) -> (PublicKeyG1<E>, SecretKey<E>) {
+        let x = (0..size)
+            .map(|_| E::ScalarField::rand(rng))
+            .collect::<Vec<E::ScalarField>>();
+        let bx: Vec<E::G1> = x.iter().map(|xi| self.p1.mul(xi)).collect();
+        (PublicKeyG1 { bx }, SecretKey { x })
+    }
+
+    /// Generate a key pair deterministically from a seed. The seed is expanded with SHAKE-256
+    /// to the scalars of the secret key, so the same seed always results in the same key pair.
+    ///
+    /// The seed must be uniformly random and kept secret, as it is equivalent to the secret key.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::PublicParams;
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let seed = [7u8; 64];
+    /// let (pk1, sk1) = pp.key_gen_deterministic(&seed, 10);
+    /// let (pk2, sk2) = pp.key_gen_deterministic(&seed, 10);
+    /// assert!(pk1 == pk2 && sk1 == sk2);
+    /// ```
+    pub fn key_gen_deterministic(
+        &self,
+        seed: &[u8; 64],
+        size: u32,
+    ) -> (PublicKey<E>, SecretKey<E>) {
+        let mut hasher = Shake256::default();
+        hasher.update(b"mercurial-signature/key-gen");
+        hasher.update(seed);
+        let mut reader = hasher.finalize_xof();
+
+        let x = (0..size)
+            .map(|_| {
+                // 64 bytes per scalar so that the modular reduction is close to uniform
+                let mut bytes = [0u8; 64];
+                reader.read(&mut bytes);
+                E::ScalarField::from_le_bytes_mod_order(&bytes)
+            })
+            .collect::<Vec<E::ScalarField>>();
+        let bx: Vec<E::G2> = x.iter().map(|xi| self.p2.mul(xi)).collect();
+        (PublicKey { bx }, SecretKey { x })
+    }
+
+    /// Generate the powers of `p1` by `tau`, i.e. `[p1, tau p1, ..., tau^n p1]`, as a
+    /// structured reference string of KZG commitments up to degree `n`, with the feature
+    /// `test-utils`.
+    ///
+    /// This is for tests only: whoever knows `tau` can open a commitment to any value. A
+    /// production reference string is generated by a ceremony that discards `tau`.
+    #[cfg(any(test, feature = "test-utils"))]
+    pub fn generate_srs(&self, n: usize, tau: E::ScalarField) -> Vec<E::G1> {
+        core::iter::successors(Some(self.p1), |p| Some(p.mul(tau)))
+            .take(n + 1)
+            .collect()
+    }
+
+    /// Check the structured reference string is non-empty, starts with `p1`, and its
+    /// elements are in the prime order subgroup of G1.
+    ///
+    /// This is a placeholder of the verification: it does not check that the elements are the
+    /// successive powers of the same `tau`.
+    pub fn verify_srs(&self, srs: &[E::G1]) -> bool {
+        srs.first() == Some(&self.p1) && E::G1::batch_check(srs.iter()).is_ok()
+    }
 }
@@ -0,0 +1,39 @@
+//! PEM encoding of the compressed serialization, in the format of the command line tool: a
+//! block per value, with the base64 of its bytes in lines of 64 characters.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+/// Encode the blocks of `(label, bytes)` one after another.
+pub(crate) fn encode(blocks: &[(&str, &[u8])]) -> String {
+    let mut pem = String::new();
+    for (label, bytes) in blocks {
+        pem.push_str(&format!("-----BEGIN {}-----\n", label));
+        for line in STANDARD.encode(bytes).as_bytes().chunks(64) {
+            pem.push_str(core::str::from_utf8(line).unwrap());
+            pem.push('\n');
+        }
+        pem.push_str(&format!("-----END {}-----\n", label));
+    }
+    pem
+}
+
+/// Decode the blocks of `(label, bytes)`. Returns `None` if the text is not a sequence of
+/// complete blocks.
+pub(crate) fn decode(text: &str) -> Option<Vec<(String, Vec<u8>)>> {
+    let mut blocks = Vec::new();
+    let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+    while let Some(line) = lines.next() {
+        let label = line.strip_prefix("-----BEGIN ")?.strip_suffix("-----")?;
+        let end = format!("-----END {}-----", label);
+        let mut encoded = String::new();
+        loop {
+            let line = lines.next()?;
+            if line == end {
+                break;
+            }
+            encoded.push_str(line);
+        }
+        blocks.push((label.to_string(), STANDARD.decode(&encoded).ok()?));
+    }
+    Some(blocks)
+}
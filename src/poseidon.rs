@@ -0,0 +1,56 @@
+//! Poseidon hashing of scalars, for attributes that are opened inside a SNARK where SHA-2 is
+//! expensive.
+//!
+//! The parameters are fixed for a state of width 3 over a field of about 255 bits, e.g. the
+//! scalar field of BLS12-381, following the Poseidon paper for 128-bit security:
+//!
+//! - rate 2, capacity 1,
+//! - S-box `x^5`,
+//! - 8 full rounds and 57 partial rounds,
+//! - round constants and MDS matrix generated by the Grain LFSR of the reference
+//!   implementation, see `find_poseidon_ark_and_mds` in `ark-crypto-primitives`.
+//!
+//! The number of inputs is absorbed before the inputs, so that inputs of different lengths
+//! (e.g. with trailing zeros) hash differently.
+
+use ark_crypto_primitives::sponge::{
+    poseidon::{find_poseidon_ark_and_mds, PoseidonConfig, PoseidonSponge},
+    Absorb, CryptographicSponge, FieldBasedCryptographicSponge,
+};
+use ark_ff::PrimeField;
+
+const RATE: usize = 2;
+const CAPACITY: usize = 1;
+const ALPHA: u64 = 5;
+const FULL_ROUNDS: usize = 8;
+const PARTIAL_ROUNDS: usize = 57;
+
+/// Hash the scalars to a scalar with Poseidon, with the parameters described in the module documentation.
+///
+/// ## Example
+///
+/// ```rust
+/// use mercurial_signature::{poseidon_hash_to_fr, Fr};
+///
+/// let attributes = [Fr::from(1u64), Fr::from(2u64)];
+/// assert!(poseidon_hash_to_fr(&attributes) == poseidon_hash_to_fr(&attributes));
+/// assert!(poseidon_hash_to_fr(&attributes) != poseidon_hash_to_fr(&attributes[..1]));
+/// ```
+pub fn poseidon_hash_to_fr<F: PrimeField + Absorb>(inputs: &[F]) -> F {
+    let mut sponge = PoseidonSponge::new(&poseidon_config::<F>());
+    sponge.absorb(&F::from(inputs.len() as u64));
+    sponge.absorb(&inputs);
+    sponge.squeeze_native_field_elements(1)[0]
+}
+
+/// The Poseidon parameters for the field `F`.
+fn poseidon_config<F: PrimeField>() -> PoseidonConfig<F> {
+    let (ark, mds) = find_poseidon_ark_and_mds::<F>(
+        F::MODULUS_BIT_SIZE as u64,
+        RATE,
+        FULL_ROUNDS as u64,
+        PARTIAL_ROUNDS as u64,
+        0,
+    );
+    PoseidonConfig::new(FULL_ROUNDS, PARTIAL_ROUNDS, ALPHA, mds, ark, RATE, CAPACITY)
+}
@@ -0,0 +1,19 @@
+//! Convenient re-exports of the curve types and the signature scheme types.
+//!
+//! ```rust
+//! use mercurial_signature::prelude::*;
+//!
+//! let mut rng = rand::thread_rng();
+//! let pp = PublicParams::new(&mut rng);
+//! let (pk, sk) = pp.key_gen(&mut rng, 10);
+//! let mut message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+//! let mut sig = sk.sign(&mut rng, &pp, &message);
+//!
+//! let u = Fr::rand(&mut rng);
+//! change_representation(&mut rng, &mut message, &mut sig, u);
+//! assert!(pk.verify(&pp, &message, &sig));
+//! ```
+
+pub use crate::{
+    change_representation, Fr, PublicKey, PublicParams, SecretKey, Signature, UniformRand, G1, G2,
+};
@@ -1,21 +1,220 @@
-use ark_ec::pairing::Pairing;
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use ark_std::Zero;
+use alloc::vec::Vec;
+use ark_ec::{pairing::Pairing, AdditiveGroup, CurveGroup};
+use ark_ff::{BigInteger, PrimeField};
+use ark_serialize::CanonicalSerialize;
+use ark_std::{One, Zero};
+use core::{borrow::Borrow, fmt, ops::Mul};
 
-use crate::{params::PublicParams, signature::Signature};
+use crate::{
+    hash::{hash_to_g2, hash_to_scalar},
+    params::PublicParams,
+    secret_key::SecretKey,
+    serialization::impl_bounded_deserialize,
+    signature::{
+        derive_tagged_key, hash_context, hash_multi_message, hash_tag, with_context_element,
+        Signature, TaggedSignature,
+    },
+    terms::VerificationTerms,
+};
 
-#[derive(Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+/// Error in combining or extending keys.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum KeyError {
+    /// The keys have different lengths.
+    LengthMismatch,
+    /// The new length is less than the length of the keys.
+    ShorterLength,
+}
+
+impl fmt::Display for KeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyError::LengthMismatch => f.write_str("the keys have different lengths"),
+            KeyError::ShorterLength => {
+                f.write_str("the new length is less than the length of the keys")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for KeyError {}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize)]
 pub struct PublicKey<E: Pairing> {
     // pk = (p2^x1,...,p2^xl) where (x1,...,xl) is the secret key
     pub(crate) bx: Vec<E::G2>,
 }
 
+impl_bounded_deserialize!(PublicKey { bx: vec });
+
 impl<E: Pairing> PublicKey<E> {
     /// Length of the public key.
     pub fn length(&self) -> usize {
         self.bx.len()
     }
 
+    /// Number of bytes of the uncompressed serialization, i.e.
+    /// [uncompressed_size](CanonicalSerialize::uncompressed_size).
+    pub fn serialized_size(&self) -> usize {
+        self.uncompressed_size()
+    }
+
+    /// Number of bytes of the compressed serialization, i.e.
+    /// [compressed_size](CanonicalSerialize::compressed_size).
+    pub fn compressed_serialized_size(&self) -> usize {
+        self.compressed_size()
+    }
+
+    /// Verify a signature on a message with a public tag, see [SecretKey::sign_tagged](crate::SecretKey).
+    /// Returns `false` if the message is longer than half the key.
+    pub fn verify_tagged(
+        &self,
+        pp: &PublicParams<E>,
+        tag: &[u8],
+        message: &[E::G1],
+        sig: &TaggedSignature<E>,
+    ) -> bool {
+        let bx = derive_tagged_key(&self.bx, hash_tag::<E>(tag));
+        let sig = Signature {
+            z: sig.z,
+            y1: sig.y1,
+            y2: sig.y2,
+        };
+        verify_with_elements(&bx, pp, message, &sig)
+    }
+
+    /// Verify a signature on a message bound to a context string, see
+    /// [SecretKey::sign_with_context](crate::SecretKey). Returns `false` if the message is not
+    /// shorter than the key.
+    pub fn verify_with_context(
+        &self,
+        pp: &PublicParams<E>,
+        context: &[u8],
+        message: &[E::G1],
+        sig: &Signature<E>,
+    ) -> bool {
+        self.verify_with_context_representation(pp, context, E::ScalarField::one(), message, sig)
+    }
+
+    /// Verify a signature bound to a context string after changing the representation of the
+    /// message `(C, M1,...,Ml)` by `u`, where `C` is the hash of the context. The `message` is
+    /// the changed `(u M1,...,u Ml)` without its first element, which is recomputed as `u C`.
+    /// With `u = 1` it is [PublicKey::verify_with_context].
+    ///
+    /// The first element of a changed representation is some multiple of any element of G1,
+    /// so the context is checked only with the disclosed `u`. Without it, a signature for one
+    /// context would verify a message whose first element is a multiple of another context.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::{change_representation, Fr, PublicParams, UniformRand, G1};
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let (pk, sk) = pp.key_gen(&mut rng, 11);
+    /// let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    /// let sig = sk.sign_with_context(&mut rng, &pp, b"protocol-a", &message);
+    ///
+    /// // the holder changes the representation of the context element with the message
+    /// let mut full = pk.context_message(b"protocol-a", &message);
+    /// let mut sig2 = sig.clone();
+    /// let u = Fr::rand(&mut rng);
+    /// change_representation(&mut rng, &mut full, &mut sig2, u);
+    /// assert!(pk.verify_with_context_representation(&pp, b"protocol-a", u, &full[1..], &sig2));
+    /// assert!(!pk.verify_with_context_representation(&pp, b"protocol-b", u, &full[1..], &sig2));
+    /// ```
+    pub fn verify_with_context_representation(
+        &self,
+        pp: &PublicParams<E>,
+        context: &[u8],
+        u: E::ScalarField,
+        message: &[E::G1],
+        sig: &Signature<E>,
+    ) -> bool {
+        if self.bx.len() <= message.len() {
+            return false;
+        }
+
+        let message = with_context_element::<E>(hash_context::<E>(context).mul(u), message);
+        self.verify(pp, &message, sig)
+    }
+
+    /// The message `(C, M1,...,Ml)` signed by [SecretKey::sign_with_context](crate::SecretKey),
+    /// where `C` is the hash of the context, e.g. to change its representation.
+    pub fn context_message(&self, context: &[u8], message: &[E::G1]) -> Vec<E::G1> {
+        with_context_element::<E>(hash_context::<E>(context), message)
+    }
+
+    /// Verify a signature on multiple messages, see [SecretKey::sign_multi_message]. Returns
+    /// `false` if the messages are empty or have different lengths.
+    pub fn verify_multi_message(
+        &self,
+        pp: &PublicParams<E>,
+        messages: &[&[E::G1]],
+        sig: &Signature<E>,
+    ) -> bool {
+        hash_multi_message::<E>(messages).is_some_and(|message| self.verify(pp, &message, sig))
+    }
+
+    /// Verify the signature together with the opening of a commitment to the public key, see
+    /// [commit_public_key]. Returns `true` if the commitment opens to this public key with
+    /// the `blinding` and the signature verifies the message under it.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::{commit_public_key, Fr, PublicParams, UniformRand, G1};
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let (pk, sk) = pp.key_gen(&mut rng, 10);
+    /// let blinding = Fr::rand(&mut rng);
+    /// let commitment = commit_public_key(&pk, blinding, &pp);
+    ///
+    /// let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    /// let sig = sk.sign(&mut rng, &pp, &message);
+    /// assert!(pk.verify_commitment_opening(&pp, commitment, blinding, &message, &sig));
+    /// ```
+    pub fn verify_commitment_opening(
+        &self,
+        pp: &PublicParams<E>,
+        commitment: E::G2,
+        blinding: E::ScalarField,
+        message: &[E::G1],
+        sig: &Signature<E>,
+    ) -> bool {
+        commit_public_key(self, blinding, pp) == commitment && self.verify(pp, message, sig)
+    }
+
+    /// Verify a signature on the empty message.
+    ///
+    /// For the empty message, the right hand side of `e(z, y2) == e(M1, bx1) * ... * e(Ml, bxl)`
+    /// is the identity, so the signature verifies if and only if `e(y1, p2) == e(p1, y2)` holds and `z`
+    /// is the identity of G1. This is what [SecretKey::sign](crate::SecretKey) outputs for the empty
+    /// message, for any key. Hence such a signature says nothing about the signer.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::PublicParams;
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let (pk, sk) = pp.key_gen(&mut rng, 10);
+    /// let sig = sk.sign(&mut rng, &pp, &[]);
+    /// assert!(pk.verify_zero_message(&pp, &sig));
+    /// ```
+    pub fn verify_zero_message(&self, pp: &PublicParams<E>, sig: &Signature<E>) -> bool {
+        self.verify(pp, &[], sig)
+    }
+
+    /// Elements of the public key, e.g. to be signed as a message in G2 by another key.
+    pub fn elements(&self) -> &[E::G2] {
+        &self.bx
+    }
+
     /// Convert the public key.
     /// This function converts the public key to a new public key that is equivalent to the original public key.
     /// The input scalar `p` must be the same as the one used in the conversion of the secret key and the signature.
@@ -38,6 +237,18 @@ impl<E: Pairing> PublicKey<E> {
     /// assert!(pk.verify(&pp, &message, &sig));
     /// ```
     pub fn verify(&self, pp: &PublicParams<E>, message: &[E::G1], sig: &Signature<E>) -> bool {
+        verify_with_elements(&self.bx, pp, message, sig)
+    }
+
+    /// Verify a signature on a message of affine points, e.g. a `Vec<G1Affine>`, an array or an
+    /// iterator, see [SecretKey::sign_affine](crate::SecretKey). The pairings take the affine
+    /// points as they are, whereas [PublicKey::verify] converts each projective point to affine.
+    pub fn verify_affine<I>(&self, pp: &PublicParams<E>, message: I, sig: &Signature<E>) -> bool
+    where
+        I: IntoIterator,
+        I::Item: Borrow<E::G1Affine>,
+    {
+        let message = message.into_iter().map(|m| *m.borrow()).collect::<Vec<_>>();
         // check length l
         if self.bx.len() < message.len() {
             return false;
@@ -52,15 +263,134 @@ impl<E: Pairing> PublicKey<E> {
 
         // e(z, y2) == e(m1, bx1) * ... * e(ml, bxl)
         let lhs = E::pairing(sig.z, sig.y2);
-        let rhs = message
-            .iter()
-            .zip(self.bx.iter())
-            .fold(E::pairing(E::G1::zero(), E::G2::zero()), |acc, (m, bxi)| {
-                acc + E::pairing(*m, *bxi)
-            });
+        let rhs = E::multi_pairing(&message, &self.bx[..message.len()]);
         lhs == rhs
     }
 
+    /// The pairing products of [PublicKey::verify] without evaluating them, e.g. to be folded
+    /// with other pairing checks into one multi-pairing, see [VerificationTerms].
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::{Fr, MultiPairingAccumulator, PublicParams, UniformRand, G1};
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let (pk, sk) = pp.key_gen(&mut rng, 10);
+    /// let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    /// let sig = sk.sign(&mut rng, &pp, &message);
+    ///
+    /// let terms = pk.verification_terms(&pp, &message, &sig);
+    /// assert!(terms.check());
+    ///
+    /// let mut acc = MultiPairingAccumulator::new();
+    /// terms.fold_into(&mut acc, Fr::rand(&mut rng));
+    /// assert!(acc.verify());
+    /// ```
+    pub fn verification_terms(
+        &self,
+        pp: &PublicParams<E>,
+        message: &[E::G1],
+        sig: &Signature<E>,
+    ) -> VerificationTerms<E> {
+        VerificationTerms::new(pp, self, message, sig)
+    }
+
+    /// The elements of the public key prepared for pairings, to be cached by the caller and
+    /// passed to [PublicKey::verify_with_prepared_g2].
+    pub fn prepared_elements(&self) -> Vec<E::G2Prepared> {
+        self.bx
+            .iter()
+            .map(|bxi| E::G2Prepared::from(*bxi))
+            .collect()
+    }
+
+    /// Verify a signature like [PublicKey::verify], with the elements of this public key
+    /// prepared by [PublicKey::prepared_elements]. Preparing the elements is a large part of a
+    /// pairing, so a verifier of many signatures under the same key prepares them once. The
+    /// result is meaningless if `prepared_bx` is not prepared from this public key.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::{PublicParams, UniformRand, G1};
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let (pk, sk) = pp.key_gen(&mut rng, 10);
+    /// let prepared_bx = pk.prepared_elements();
+    ///
+    /// let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    /// let sig = sk.sign(&mut rng, &pp, &message);
+    /// assert!(pk.verify_with_prepared_g2(&pp, &message, &sig, &prepared_bx));
+    /// ```
+    pub fn verify_with_prepared_g2(
+        &self,
+        pp: &PublicParams<E>,
+        message: &[E::G1],
+        sig: &Signature<E>,
+        prepared_bx: &[E::G2Prepared],
+    ) -> bool {
+        // check length l
+        if prepared_bx.len() < message.len() {
+            return false;
+        }
+
+        // e(y1, p2) == e(p1, y2)
+        let lhs = E::pairing(sig.y1, pp.p2);
+        let rhs = E::pairing(pp.p1, sig.y2);
+        if lhs != rhs {
+            return false;
+        }
+
+        // e(m1, bx1) * ... * e(ml, bxl) * e(-z, y2) == 1
+        let g1s = message.iter().copied().chain([-sig.z]);
+        let g2s = prepared_bx[..message.len()]
+            .iter()
+            .cloned()
+            .chain([E::G2Prepared::from(sig.y2)]);
+        E::final_exponentiation(E::multi_miller_loop(g1s, g2s))
+            .is_some_and(|output| output.is_zero())
+    }
+
+    /// The public key of the first `len` elements, for a verifier of messages of at most `len`
+    /// elements, see [PublicKeyView] for the soundness.
+    ///
+    /// ## Safety
+    /// This function panics if `len` is greater than the length of the public key.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::{PublicParams, UniformRand, G1};
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let (pk, sk) = pp.key_gen(&mut rng, 100);
+    /// let message = (0..5).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    /// let sig = sk.sign(&mut rng, &pp, &message);
+    ///
+    /// let pk = pk.truncated(5);
+    /// assert_eq!(pk.length(), 5);
+    /// assert!(pk.verify(&pp, &message, &sig));
+    /// ```
+    pub fn truncated(&self, len: usize) -> PublicKey<E> {
+        PublicKey {
+            bx: self.bx[..len].to_vec(),
+        }
+    }
+
+    /// The view of the first `len` elements, like [PublicKey::truncated] without cloning.
+    ///
+    /// ## Safety
+    /// This function panics if `len` is greater than the length of the public key.
+    pub fn view(&self, len: usize) -> PublicKeyView<'_, E> {
+        PublicKeyView {
+            bx: &self.bx[..len],
+        }
+    }
+
     /// Convert the public key.
     /// This function converts the public key to a new public key that is equivalent to the original public key.
     /// The input scalar `p` must be the same as the one used in the conversion of the secret key and the signature.
@@ -85,4 +415,172 @@ impl<E: Pairing> PublicKey<E> {
     pub fn convert(&mut self, p: E::ScalarField) {
         self.bx.iter_mut().for_each(|bxi| *bxi *= p);
     }
+
+    /// Convert the public keys with the same scalar `p`. It is equivalent to calling
+    /// [PublicKey::convert] on each key, but faster for many keys: the wNAF of `p` is computed
+    /// once, and the tables of all the elements are normalized together so that the
+    /// multiplications use mixed additions.
+    pub fn batch_convert(keys: &mut [PublicKey<E>], p: E::ScalarField) {
+        // odd multiples (P, 3P, ..., (2^(w-1)-1)P) of each element for the wNAF of window w
+        const WINDOW: usize = 4;
+        const TABLE_SIZE: usize = 1 << (WINDOW - 2);
+
+        let naf = p.into_bigint().find_wnaf(WINDOW).unwrap();
+        let mut tables =
+            Vec::with_capacity(keys.iter().map(|key| key.bx.len()).sum::<usize>() * TABLE_SIZE);
+        keys.iter().flat_map(|key| key.bx.iter()).for_each(|bxi| {
+            let double = bxi.double();
+            let mut multiple = *bxi;
+            for _ in 0..TABLE_SIZE {
+                tables.push(multiple);
+                multiple += double;
+            }
+        });
+        let tables = E::G2::normalize_batch(&tables);
+
+        keys.iter_mut()
+            .flat_map(|key| key.bx.iter_mut())
+            .zip(tables.chunks(TABLE_SIZE))
+            .for_each(|(bxi, table)| {
+                let mut result = E::G2::zero();
+                for digit in naf.iter().rev() {
+                    result.double_in_place();
+                    if *digit > 0 {
+                        result += table[(*digit as usize) / 2];
+                    } else if *digit < 0 {
+                        result -= table[(-*digit as usize) / 2];
+                    }
+                }
+                *bxi = result;
+            });
+    }
+
+    /// Add the public keys element-wise, i.e. the public key of the sum of the secret keys, see
+    /// [SecretKey::homomorphic_add]. In an additive threshold scheme, it is the group public key
+    /// of the parties. Returns [KeyError::LengthMismatch] if the keys have different lengths.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::{PublicParams, UniformRand, G1};
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let (pk1, sk1) = pp.key_gen(&mut rng, 10);
+    /// let (pk2, sk2) = pp.key_gen(&mut rng, 10);
+    /// let pk = pk1.homomorphic_add(&pk2).unwrap();
+    /// let sk = sk1.homomorphic_add(&sk2).unwrap();
+    ///
+    /// let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    /// let sig = sk.sign(&mut rng, &pp, &message);
+    /// assert!(pk.verify(&pp, &message, &sig));
+    /// ```
+    pub fn homomorphic_add(&self, other: &PublicKey<E>) -> Result<PublicKey<E>, KeyError> {
+        if self.bx.len() != other.bx.len() {
+            return Err(KeyError::LengthMismatch);
+        }
+        Ok(PublicKey {
+            bx: self.bx.iter().zip(&other.bx).map(|(a, b)| *a + b).collect(),
+        })
+    }
+
+    /// The public key corresponding to a secret key with noise added by
+    /// [SecretKey::add_noise]. It replaces the original public key and must be published
+    /// before verifying the signatures by the noisy key.
+    pub fn from_noisy_sk(pp: &PublicParams<E>, noisy_sk: &SecretKey<E>) -> Self {
+        PublicKey {
+            bx: noisy_sk.x.iter().map(|xi| pp.p2.mul(xi)).collect(),
+        }
+    }
+}
+
+/// Borrowed prefix of a public key, see [PublicKey::view].
+///
+/// Verifying under a prefix is sound because [PublicKey::verify] already accepts a message
+/// shorter than the key, using only the elements of the key at the positions of the message.
+/// A signature binds all the positions it signed, so it verifies under the prefix if and only
+/// if it verifies under the full key. A message longer than the prefix is rejected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PublicKeyView<'a, E: Pairing> {
+    bx: &'a [E::G2],
+}
+
+impl<E: Pairing> PublicKeyView<'_, E> {
+    /// Length of the view.
+    pub fn length(&self) -> usize {
+        self.bx.len()
+    }
+
+    /// Elements of the view.
+    pub fn elements(&self) -> &[E::G2] {
+        self.bx
+    }
+
+    /// Verify a signature on a message of at most [PublicKeyView::length] elements, see
+    /// [PublicKey::verify].
+    pub fn verify(&self, pp: &PublicParams<E>, message: &[E::G1], sig: &Signature<E>) -> bool {
+        verify_with_elements(self.bx, pp, message, sig)
+    }
+
+    /// The owned public key of the view, i.e. [PublicKey::truncated].
+    pub fn to_public_key(self) -> PublicKey<E> {
+        PublicKey {
+            bx: self.bx.to_vec(),
+        }
+    }
+}
+
+fn verify_with_elements<E: Pairing>(
+    bx: &[E::G2],
+    pp: &PublicParams<E>,
+    message: &[E::G1],
+    sig: &Signature<E>,
+) -> bool {
+    // check length l
+    if bx.len() < message.len() {
+        return false;
+    }
+
+    // e(y1, p2) == e(p1, y2)
+    let lhs = E::pairing(sig.y1, pp.p2);
+    let rhs = E::pairing(pp.p1, sig.y2);
+    if lhs != rhs {
+        return false;
+    }
+
+    // e(z, y2) == e(m1, bx1) * ... * e(ml, bxl)
+    let lhs = E::pairing(sig.z, sig.y2);
+    let rhs = message
+        .iter()
+        .zip(bx.iter())
+        .fold(E::pairing(E::G1::zero(), E::G2::zero()), |acc, (m, bxi)| {
+            acc + E::pairing(*m, *bxi)
+        });
+    lhs == rhs
+}
+
+/// Commit to the public key in G2 as `r1 bx1 + ... + rl bxl + blinding h`, where `ri` are
+/// hashed from the public key and the index, and `h` is hashed from the public parameters
+/// so that its discrete logarithm is unknown. The commitment hides the public key with a
+/// random `blinding`, and is opened by the public key and the `blinding`, see
+/// [PublicKey::verify_commitment_opening].
+pub fn commit_public_key<E: Pairing>(
+    pk: &PublicKey<E>,
+    blinding: E::ScalarField,
+    pp: &PublicParams<E>,
+) -> E::G2 {
+    let mut bytes = Vec::new();
+    pp.serialize_compressed(&mut bytes).unwrap();
+    let h = hash_to_g2::<E>(b"mercurial-signature/public-key-commitment", &bytes);
+
+    let mut bytes = Vec::new();
+    pk.serialize_compressed(&mut bytes).unwrap();
+    pk.bx
+        .iter()
+        .enumerate()
+        .fold(h.mul(blinding), |acc, (i, bxi)| {
+            let data = [(i as u64).to_le_bytes().as_slice(), &bytes].concat();
+            let ri = hash_to_scalar::<E>(b"mercurial-signature/public-key-commitment", &data);
+            acc + bxi.mul(ri)
+        })
 }
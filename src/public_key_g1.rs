@@ -0,0 +1,72 @@
+use alloc::vec::Vec;
+use ark_ec::pairing::Pairing;
+use ark_serialize::CanonicalSerialize;
+
+use crate::{
+    params::PublicParams, serialization::impl_bounded_deserialize, signature_g2::SignatureG2,
+};
+
+/// Public key with elements in G1, for signing messages in G2. It mirrors [PublicKey](crate::PublicKey)
+/// with the roles of the groups swapped.
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize)]
+pub struct PublicKeyG1<E: Pairing> {
+    // pk = (p1^x1,...,p1^xl) where (x1,...,xl) is the secret key
+    pub(crate) bx: Vec<E::G1>,
+}
+
+impl_bounded_deserialize!(PublicKeyG1 { bx: vec });
+
+impl<E: Pairing> PublicKeyG1<E> {
+    /// Length of the public key.
+    pub fn length(&self) -> usize {
+        self.bx.len()
+    }
+
+    /// Elements of the public key, e.g. to be signed as a message in G1 by another key.
+    pub fn elements(&self) -> &[E::G1] {
+        &self.bx
+    }
+
+    /// Verify the signature on a message in G2.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::{PublicParams, UniformRand, G2};
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let (pk, sk) = pp.key_gen_g1(&mut rng, 10);
+    /// let message = (0..10).map(|_| G2::rand(&mut rng)).collect::<Vec<G2>>();
+    /// let sig = sk.sign_g2(&mut rng, &pp, &message);
+    /// assert!(pk.verify(&pp, &message, &sig));
+    /// ```
+    pub fn verify(&self, pp: &PublicParams<E>, message: &[E::G2], sig: &SignatureG2<E>) -> bool {
+        // check length l
+        if self.bx.len() < message.len() {
+            return false;
+        }
+
+        // e(p1, y1) == e(y2, p2)
+        let lhs = E::pairing(pp.p1, sig.y1);
+        let rhs = E::pairing(sig.y2, pp.p2);
+        if lhs != rhs {
+            return false;
+        }
+
+        // e(y2, z) == e(bx1, m1) * ... * e(bxl, ml)
+        let lhs = E::pairing(sig.y2, sig.z);
+        let rhs = E::multi_pairing(
+            self.bx.iter().take(message.len()).copied(),
+            message.iter().copied(),
+        );
+        lhs == rhs
+    }
+
+    /// Convert the public key.
+    /// This function converts the public key to a new public key that is equivalent to the original public key.
+    /// The input scalar `p` must be the same as the one used in the conversion of the secret key and the signature.
+    pub fn convert(&mut self, p: E::ScalarField) {
+        self.bx.iter_mut().for_each(|bxi| *bxi *= p);
+    }
+}
@@ -0,0 +1,341 @@
+//! Python module with PyO3, enabled by the feature `python`.
+//!
+//! Build and install the module with `maturin develop`, which enables the features in
+//! `pyproject.toml`. The module is imported as `mercurial_signature`.
+//!
+//! The values cross the boundary as `bytes` in the compressed serialization, i.e. a message is
+//! a serialized `Vec<G1>` and a scalar is a serialized `Fr`. Invalid inputs raise
+//! `MercurialSignatureError`, a subclass of `ValueError`.
+//!
+//! The randomness is from the operating system. The functions that need randomness take an
+//! optional `seed` to make the result reproducible, e.g. in experiments. A seeded result is
+//! predictable by anyone who knows the seed, so it must not be used for real keys.
+//!
+//! The objects are immutable. The operations that change a value, e.g. `Signature.convert`,
+//! return a new object instead.
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::{rngs::StdRng, SeedableRng};
+use pyo3::{create_exception, exceptions::PyValueError, prelude::*, types::PyBytes};
+use rand_core::OsRng;
+
+use crate::{change_representation, Fr, PublicKey, PublicParams, SecretKey, Signature, G1};
+
+create_exception!(
+    mercurial_signature,
+    MercurialSignatureError,
+    PyValueError,
+    "Invalid input to the mercurial signature scheme."
+);
+
+/// Public parameters.
+#[pyclass(name = "PublicParams", module = "mercurial_signature", frozen)]
+pub struct PyPublicParams {
+    inner: PublicParams,
+}
+
+/// Public key, e.g. received from an issuer.
+#[pyclass(name = "PublicKey", module = "mercurial_signature", frozen)]
+pub struct PyPublicKey {
+    inner: PublicKey,
+}
+
+/// Key pair of a signer.
+#[pyclass(name = "Keypair", module = "mercurial_signature", frozen)]
+pub struct PyKeypair {
+    pk: PublicKey,
+    sk: SecretKey,
+}
+
+/// Signature.
+#[pyclass(name = "Signature", module = "mercurial_signature", frozen)]
+pub struct PySignature {
+    inner: Signature,
+}
+
+#[pymethods]
+impl PyPublicParams {
+    /// Generate public parameters.
+    #[new]
+    #[pyo3(signature = (seed=None))]
+    fn new(seed: Option<u64>) -> Self {
+        PyPublicParams {
+            inner: PublicParams::new(&mut rng(seed)),
+        }
+    }
+
+    #[staticmethod]
+    fn from_bytes(bytes: &[u8]) -> PyResult<Self> {
+        Ok(PyPublicParams {
+            inner: deserialize(bytes, "public parameters")?,
+        })
+    }
+
+    fn to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &serialize(&self.inner))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("PublicParams({})", short_hex(&serialize(&self.inner)))
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+#[pymethods]
+impl PyPublicKey {
+    #[staticmethod]
+    fn from_bytes(bytes: &[u8]) -> PyResult<Self> {
+        Ok(PyPublicKey {
+            inner: deserialize(bytes, "public key")?,
+        })
+    }
+
+    fn to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &serialize(&self.inner))
+    }
+
+    /// Number of message elements the key verifies.
+    fn __len__(&self) -> usize {
+        self.inner.length()
+    }
+
+    /// Verify the signature on the message.
+    fn verify(&self, pp: &PyPublicParams, message: &[u8], sig: &PySignature) -> PyResult<bool> {
+        let message = deserialize_message(message)?;
+        Ok(self.inner.verify(&pp.inner, &message, &sig.inner))
+    }
+
+    /// The public key converted by the scalar `p`.
+    fn convert(&self, p: &[u8]) -> PyResult<PyPublicKey> {
+        let mut inner = self.inner.clone();
+        inner.convert(deserialize(p, "scalar")?);
+        Ok(PyPublicKey { inner })
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "PublicKey(length={}, {})",
+            self.inner.length(),
+            short_hex(&serialize(&self.inner))
+        )
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+#[pymethods]
+impl PyKeypair {
+    /// Generate a key pair for messages of `size` elements.
+    #[new]
+    #[pyo3(signature = (pp, size, seed=None))]
+    fn new(pp: &PyPublicParams, size: u32, seed: Option<u64>) -> Self {
+        let (pk, sk) = pp.inner.key_gen(&mut rng(seed), size);
+        PyKeypair { pk, sk }
+    }
+
+    #[staticmethod]
+    fn from_bytes(public_key: &[u8], secret_key: &[u8]) -> PyResult<Self> {
+        let pk: PublicKey = deserialize(public_key, "public key")?;
+        let sk: SecretKey = deserialize(secret_key, "secret key")?;
+        if pk.length() != sk.length() {
+            return Err(MercurialSignatureError::new_err(
+                "the public key and the secret key have different lengths",
+            ));
+        }
+        Ok(PyKeypair { pk, sk })
+    }
+
+    #[getter]
+    fn public_key(&self) -> PyPublicKey {
+        PyPublicKey {
+            inner: self.pk.clone(),
+        }
+    }
+
+    fn secret_key_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &serialize(&self.sk))
+    }
+
+    /// Sign the message.
+    #[pyo3(signature = (pp, message, seed=None))]
+    fn sign(
+        &self,
+        pp: &PyPublicParams,
+        message: &[u8],
+        seed: Option<u64>,
+    ) -> PyResult<PySignature> {
+        let message = deserialize_message(message)?;
+        if message.len() > self.sk.length() {
+            return Err(MercurialSignatureError::new_err(
+                "the message is longer than the key",
+            ));
+        }
+        Ok(PySignature {
+            inner: self.sk.sign(&mut rng(seed), &pp.inner, &message),
+        })
+    }
+
+    /// Verify the signature on the message.
+    fn verify(&self, pp: &PyPublicParams, message: &[u8], sig: &PySignature) -> PyResult<bool> {
+        let message = deserialize_message(message)?;
+        Ok(self.pk.verify(&pp.inner, &message, &sig.inner))
+    }
+
+    /// The key pair with both keys converted by the scalar `p`.
+    fn convert(&self, p: &[u8]) -> PyResult<PyKeypair> {
+        let p: Fr = deserialize(p, "scalar")?;
+        let (mut pk, mut sk) = (self.pk.clone(), self.sk.clone());
+        pk.convert(p);
+        sk.convert(p);
+        Ok(PyKeypair { pk, sk })
+    }
+
+    // the secret key is not shown
+    fn __repr__(&self) -> String {
+        format!(
+            "Keypair(length={}, public_key={})",
+            self.pk.length(),
+            short_hex(&serialize(&self.pk))
+        )
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self.pk == other.pk && self.sk == other.sk
+    }
+}
+
+#[pymethods]
+impl PySignature {
+    #[staticmethod]
+    fn from_bytes(bytes: &[u8]) -> PyResult<Self> {
+        Ok(PySignature {
+            inner: deserialize(bytes, "signature")?,
+        })
+    }
+
+    fn to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &serialize(&self.inner))
+    }
+
+    /// The signature converted by the scalar `p`.
+    #[pyo3(signature = (p, seed=None))]
+    fn convert(&self, p: &[u8], seed: Option<u64>) -> PyResult<PySignature> {
+        let p: Fr = deserialize(p, "scalar")?;
+        let mut inner = self.inner.clone();
+        inner.convert(&mut rng(seed), p);
+        Ok(PySignature { inner })
+    }
+
+    /// Change the representation of the message and the signature by the scalar `u`. Returns
+    /// the new message and the new signature.
+    #[pyo3(signature = (message, u, seed=None))]
+    fn change_representation<'py>(
+        &self,
+        py: Python<'py>,
+        message: &[u8],
+        u: &[u8],
+        seed: Option<u64>,
+    ) -> PyResult<(Bound<'py, PyBytes>, PySignature)> {
+        let mut message = deserialize_message(message)?;
+        let u: Fr = deserialize(u, "scalar")?;
+        let mut inner = self.inner.clone();
+        change_representation(&mut rng(seed), &mut message, &mut inner, u);
+        Ok((
+            PyBytes::new(py, &serialize(&message)),
+            PySignature { inner },
+        ))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Signature({})", short_hex(&serialize(&self.inner)))
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+/// A random scalar, e.g. for `Signature.convert` or `Signature.change_representation`.
+#[pyfunction]
+#[pyo3(signature = (seed=None))]
+fn random_scalar(py: Python<'_>, seed: Option<u64>) -> Bound<'_, PyBytes> {
+    let p = <Fr as ark_std::UniformRand>::rand(&mut rng(seed));
+    PyBytes::new(py, &serialize(&p))
+}
+
+/// A random message of `size` elements.
+#[pyfunction]
+#[pyo3(signature = (size, seed=None))]
+fn random_message(py: Python<'_>, size: u32, seed: Option<u64>) -> Bound<'_, PyBytes> {
+    let mut rng = rng(seed);
+    let message = (0..size)
+        .map(|_| <G1 as ark_std::UniformRand>::rand(&mut rng))
+        .collect::<Vec<G1>>();
+    PyBytes::new(py, &serialize(&message))
+}
+
+#[pymodule]
+pub fn mercurial_signature(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyPublicParams>()?;
+    m.add_class::<PyPublicKey>()?;
+    m.add_class::<PyKeypair>()?;
+    m.add_class::<PySignature>()?;
+    m.add_function(wrap_pyfunction!(random_scalar, m)?)?;
+    m.add_function(wrap_pyfunction!(random_message, m)?)?;
+    m.add(
+        "MercurialSignatureError",
+        m.py().get_type::<MercurialSignatureError>(),
+    )?;
+    Ok(())
+}
+
+/// The generator seeded by `seed`, or by the operating system if it is `None`.
+fn rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_rng(OsRng).unwrap(),
+    }
+}
+
+/// The first bytes in hex, to tell the objects apart in `repr`.
+fn short_hex(bytes: &[u8]) -> String {
+    let hex = bytes
+        .iter()
+        .take(8)
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>();
+    format!("{}...", hex)
+}
+
+fn serialize<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes).unwrap();
+    bytes
+}
+
+fn deserialize<T: CanonicalDeserialize>(bytes: &[u8], name: &str) -> PyResult<T> {
+    T::deserialize_compressed(bytes)
+        .map_err(|e| MercurialSignatureError::new_err(format!("invalid {}: {}", name, e)))
+}
+
+/// Deserialize a message, checking its length prefix against the input first so that a
+/// corrupted prefix cannot request a huge allocation.
+fn deserialize_message(bytes: &[u8]) -> PyResult<Vec<G1>> {
+    let element_size = G1::default().compressed_size();
+    let length = bytes
+        .get(..8)
+        .map(|prefix| u64::from_le_bytes(prefix.try_into().unwrap()));
+    match length {
+        Some(length) if length.checked_mul(element_size as u64) == Some(bytes.len() as u64 - 8) => {
+            deserialize(bytes, "message")
+        }
+        _ => Err(MercurialSignatureError::new_err(
+            "invalid message: wrong length",
+        )),
+    }
+}
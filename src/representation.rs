@@ -1,7 +1,17 @@
-use crate::signature::Signature;
-use ark_ec::pairing::Pairing;
-use ark_std::UniformRand;
-use rand_core::RngCore;
+use crate::{
+    hash::hash_to_scalar,
+    signature::{Signature, TaggedSignature},
+    signature_g2::SignatureG2,
+    transcript::Transcript,
+};
+use alloc::vec::Vec;
+use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{One, UniformRand, Zero};
+use core::ops::Mul;
+#[cfg(feature = "getrandom")]
+use rand_core::OsRng;
+use rand_core::{CryptoRng, RngCore};
 
 /// Change the representation of the message and the signature.
 ///
@@ -21,7 +31,7 @@ use rand_core::RngCore;
 /// change_representation(&mut rng, &mut message, &mut sig, u);
 /// assert!(pk.verify(&pp, &message, &sig));
 /// ```
-pub fn change_representation<E: Pairing, R: RngCore>(
+pub fn change_representation<E: Pairing, R: RngCore + CryptoRng>(
     rng: &mut R,
     message: &mut [E::G1],
     signature: &mut Signature<E>,
@@ -32,3 +42,231 @@ pub fn change_representation<E: Pairing, R: RngCore>(
 
     message.iter_mut().for_each(|mi| *mi *= u);
 }
+
+/// Change the representation of the message of affine points and the signature, like
+/// [change_representation]. The scaled points are converted back to affine in one batch.
+///
+/// ## Example
+///
+/// ```rust
+/// use ark_ec::CurveGroup;
+/// use mercurial_signature::{change_representation_affine, Fr, PublicParams, UniformRand, G1};
+///
+/// let mut rng = rand::thread_rng();
+/// let pp = PublicParams::new(&mut rng);
+/// let (pk, sk) = pp.key_gen(&mut rng, 10);
+/// let mut message = G1::normalize_batch(&(0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>());
+/// let mut sig = sk.sign_affine(&mut rng, &pp, &message);
+///
+/// let u = Fr::rand(&mut rng);
+/// change_representation_affine(&mut rng, &mut message, &mut sig, u);
+/// assert!(pk.verify_affine(&pp, &message, &sig));
+/// ```
+pub fn change_representation_affine<E: Pairing, R: RngCore + CryptoRng>(
+    rng: &mut R,
+    message: &mut [E::G1Affine],
+    signature: &mut Signature<E>,
+    u: E::ScalarField,
+) {
+    let f = E::ScalarField::rand(rng);
+    signature.convert_with_f(u, f);
+
+    let scaled = message.iter().map(|mi| mi.mul(u)).collect::<Vec<_>>();
+    message.copy_from_slice(&E::G1::normalize_batch(&scaled));
+}
+
+/// Change the representation of the message and the signature by a random `u`, with the
+/// randomness of the operating system.
+///
+/// ## Example
+///
+/// ```rust
+/// use mercurial_signature::{randomize_representation_default, PublicParams, UniformRand, G1};
+///
+/// let pp = PublicParams::new_default();
+/// let (pk, sk) = pp.key_gen_default(10);
+/// let mut message = (0..10).map(|_| G1::rand(&mut rand::thread_rng())).collect::<Vec<G1>>();
+/// let mut sig = sk.sign_default(&pp, &message);
+///
+/// randomize_representation_default(&mut message, &mut sig);
+/// assert!(pk.verify(&pp, &message, &sig));
+/// ```
+#[cfg(feature = "getrandom")]
+pub fn randomize_representation_default<E: Pairing>(
+    message: &mut [E::G1],
+    signature: &mut Signature<E>,
+) {
+    let u = E::ScalarField::rand(&mut OsRng);
+    change_representation(&mut OsRng, message, signature, u);
+}
+
+/// Undo [change_representation] with the same scalar `u`, i.e. scale the message by `1/u`.
+///
+/// The message is recovered exactly. The signature is a valid signature on the recovered message,
+/// but it keeps the randomness applied by [change_representation], so it is not identical to the
+/// original signature.
+///
+/// ## Example
+///
+/// ```rust
+/// use mercurial_signature::{change_representation, inverse_change_representation, Fr, PublicParams, UniformRand, G1};
+///
+/// let mut rng = rand::thread_rng();
+/// let pp = PublicParams::new(&mut rng);
+/// let (pk, sk) = pp.key_gen(&mut rng, 10);
+/// let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+/// let sig = sk.sign(&mut rng, &pp, &message);
+///
+/// let u = Fr::rand(&mut rng);
+/// let mut message2 = message.clone();
+/// let mut sig2 = sig.clone();
+/// change_representation(&mut rng, &mut message2, &mut sig2, u);
+/// inverse_change_representation(&mut message2, &mut sig2, u);
+/// assert!(message2 == message);
+/// assert!(pk.verify(&pp, &message, &sig2));
+/// ```
+pub fn inverse_change_representation<E: Pairing>(
+    message: &mut [E::G1],
+    signature: &mut Signature<E>,
+    u: E::ScalarField,
+) {
+    let u_inv = E::ScalarField::one() / u;
+    signature.convert_with_f(u_inv, E::ScalarField::one());
+
+    message.iter_mut().for_each(|mi| *mi *= u_inv);
+}
+
+/// Change the representation of the message in G2 and the signature.
+///
+/// ## Example
+///
+/// ```rust
+/// use mercurial_signature::{change_representation_g2, Fr, PublicParams, UniformRand, G2};
+///
+/// let mut rng = rand::thread_rng();
+/// let pp = PublicParams::new(&mut rng);
+/// let (pk, sk) = pp.key_gen_g1(&mut rng, 10);
+/// let mut message = (0..10).map(|_| G2::rand(&mut rng)).collect::<Vec<G2>>();
+/// let mut sig = sk.sign_g2(&mut rng, &pp, &message);
+///
+/// let u = Fr::rand(&mut rng);
+/// change_representation_g2(&mut rng, &mut message, &mut sig, u);
+/// assert!(pk.verify(&pp, &message, &sig));
+/// ```
+pub fn change_representation_g2<E: Pairing, R: RngCore + CryptoRng>(
+    rng: &mut R,
+    message: &mut [E::G2],
+    signature: &mut SignatureG2<E>,
+    u: E::ScalarField,
+) {
+    let f = E::ScalarField::rand(rng);
+    signature.convert_with_f(u, f);
+
+    message.iter_mut().for_each(|mi| *mi *= u);
+}
+
+/// Change the representation of the message and the tagged signature. The tag stays fixed.
+pub fn change_representation_tagged<E: Pairing, R: RngCore + CryptoRng>(
+    rng: &mut R,
+    message: &mut [E::G1],
+    signature: &mut TaggedSignature<E>,
+    u: E::ScalarField,
+) {
+    let f = E::ScalarField::rand(rng);
+    signature.convert_with_f(u, f);
+
+    message.iter_mut().for_each(|mi| *mi *= u);
+}
+
+/// A zero-knowledge proof that a message is the representation of another message changed by
+/// a secret scalar `u`, i.e. `new_message = u * old_message`, without revealing `u`.
+///
+/// It is a Fiat-Shamir transformed Chaum-Pedersen proof on the random linear combinations
+/// `C = r1 M1 + ... + rl Ml` and `C' = r1 M1' + ... + rl Ml'`, where `r1,...,rl` are derived from
+/// both messages.
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct RepresentationChangeProof<E: Pairing> {
+    // commitment t C
+    pub(crate) a: E::G1,
+    // response s = t + c u
+    pub(crate) s: E::ScalarField,
+}
+
+impl<E: Pairing> RepresentationChangeProof<E> {
+    /// Prove `new_message = u * old_message`.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::{change_representation, Fr, PublicParams, RepresentationChangeProof, UniformRand, G1};
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let (pk, sk) = pp.key_gen(&mut rng, 10);
+    /// let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    /// let mut sig = sk.sign(&mut rng, &pp, &message);
+    ///
+    /// let u = Fr::rand(&mut rng);
+    /// let mut new_message = message.clone();
+    /// change_representation(&mut rng, &mut new_message, &mut sig, u);
+    ///
+    /// let proof = RepresentationChangeProof::prove(&mut rng, &message, &new_message, u);
+    /// assert!(proof.verify(&message, &new_message));
+    /// ```
+    pub fn prove<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        old_message: &[E::G1],
+        new_message: &[E::G1],
+        u: E::ScalarField,
+    ) -> Self {
+        let (c_old, c_new) = combine_messages::<E>(old_message, new_message);
+        let t = E::ScalarField::rand(rng);
+        let a = c_old.mul(t);
+        let c = challenge::<E>(c_old, c_new, a);
+        RepresentationChangeProof { a, s: t + c * u }
+    }
+
+    /// Verify the proof of `new_message = u * old_message` for a secret `u`.
+    pub fn verify(&self, old_message: &[E::G1], new_message: &[E::G1]) -> bool {
+        if old_message.len() != new_message.len() {
+            return false;
+        }
+
+        // s C == A + c C'
+        let (c_old, c_new) = combine_messages::<E>(old_message, new_message);
+        let c = challenge::<E>(c_old, c_new, self.a);
+        c_old.mul(self.s) == self.a + c_new.mul(c)
+    }
+}
+
+/// Random linear combinations of the messages, with coefficients derived from both messages.
+pub(crate) fn combine_messages<E: Pairing>(
+    old_message: &[E::G1],
+    new_message: &[E::G1],
+) -> (E::G1, E::G1) {
+    let mut bytes = Vec::new();
+    old_message.serialize_compressed(&mut bytes).unwrap();
+    new_message.serialize_compressed(&mut bytes).unwrap();
+
+    old_message.iter().zip(new_message.iter()).enumerate().fold(
+        (E::G1::zero(), E::G1::zero()),
+        |(c_old, c_new), (i, (m_old, m_new))| {
+            let mut data = (i as u64).to_le_bytes().to_vec();
+            data.extend_from_slice(&bytes);
+            let ri = hash_to_scalar::<E>(
+                b"mercurial-signature/representation-change/coefficient",
+                &data,
+            );
+            (c_old + m_old.mul(ri), c_new + m_new.mul(ri))
+        },
+    )
+}
+
+/// Fiat-Shamir challenge of the proof of the representation change.
+fn challenge<E: Pairing>(c_old: E::G1, c_new: E::G1, a: E::G1) -> E::ScalarField {
+    let mut transcript = Transcript::new(b"mercurial-signature/representation-change");
+    transcript.append_point_g1::<E>(b"combined_old", &c_old);
+    transcript.append_point_g1::<E>(b"combined_new", &c_new);
+    transcript.append_point_g1::<E>(b"commitment", &a);
+    transcript.challenge_scalar::<E>(b"challenge")
+}
@@ -0,0 +1,128 @@
+//! Random number generators accepted by this crate.
+//!
+//! The functions that need randomness take a generator implementing [RngCore] and [CryptoRng]
+//! of `rand_core` 0.6, the generation used by the arkworks crates. A generator of `rand_core`
+//! 0.9 is accepted through [RngAdapter], with the feature `rand_core_0_9`.
+//!
+//! A generator that is not cryptographically secure does not compile:
+//!
+//! ```rust,compile_fail
+//! use mercurial_signature::PublicParams;
+//!
+//! let mut rng = rand::rngs::mock::StepRng::new(0, 1);
+//! let pp = PublicParams::new(&mut rng);
+//! ```
+//!
+//! A deterministic generator for the tests is accepted through `InsecureTestRng`, with the
+//! feature `test-utils`.
+
+use rand_core::{CryptoRng, CryptoRngCore, RngCore};
+
+/// A generator of either `rand_core` 0.6 or 0.9, usable wherever this crate needs randomness.
+///
+/// ## Example
+///
+/// ```rust
+/// use mercurial_signature::{PublicParams, RngAdapter};
+///
+/// let mut thread_rng = rand::thread_rng();
+/// let mut rng = RngAdapter::from_rand_core_0_6(&mut thread_rng);
+/// let pp = PublicParams::new(&mut rng);
+/// let (pk, sk) = pp.key_gen(&mut rng, 10);
+/// ```
+pub struct RngAdapter<'a>(Inner<'a>);
+
+enum Inner<'a> {
+    V0_6(&'a mut dyn CryptoRngCore),
+    #[cfg(feature = "rand_core_0_9")]
+    V0_9(&'a mut dyn rand_core_0_9::CryptoRng),
+}
+
+impl<'a> RngAdapter<'a> {
+    /// Adapt a generator of `rand_core` 0.6.
+    pub fn from_rand_core_0_6<R: RngCore + CryptoRng>(rng: &'a mut R) -> Self {
+        RngAdapter(Inner::V0_6(rng))
+    }
+
+    /// Adapt a generator of `rand_core` 0.9. A fallible generator, e.g. `OsRng`, is adapted
+    /// after `unwrap_err`, which panics on its errors.
+    #[cfg(feature = "rand_core_0_9")]
+    pub fn from_rand_core_0_9<R: rand_core_0_9::CryptoRng>(rng: &'a mut R) -> Self {
+        RngAdapter(Inner::V0_9(rng))
+    }
+}
+
+impl RngCore for RngAdapter<'_> {
+    fn next_u32(&mut self) -> u32 {
+        match &mut self.0 {
+            Inner::V0_6(rng) => rng.next_u32(),
+            #[cfg(feature = "rand_core_0_9")]
+            Inner::V0_9(rng) => rng.next_u32(),
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        match &mut self.0 {
+            Inner::V0_6(rng) => rng.next_u64(),
+            #[cfg(feature = "rand_core_0_9")]
+            Inner::V0_9(rng) => rng.next_u64(),
+        }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        match &mut self.0 {
+            Inner::V0_6(rng) => rng.fill_bytes(dest),
+            #[cfg(feature = "rand_core_0_9")]
+            Inner::V0_9(rng) => rng.fill_bytes(dest),
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        match &mut self.0 {
+            Inner::V0_6(rng) => rng.try_fill_bytes(dest),
+            #[cfg(feature = "rand_core_0_9")]
+            Inner::V0_9(rng) => {
+                rng.fill_bytes(dest);
+                Ok(())
+            }
+        }
+    }
+}
+
+impl CryptoRng for RngAdapter<'_> {}
+
+/// A generator marked as cryptographically secure without being so, e.g. the deterministic
+/// `rand::rngs::mock::StepRng`, for reproducible tests. It must not be used outside of tests.
+///
+/// ## Example
+///
+/// ```rust
+/// use mercurial_signature::{InsecureTestRng, PublicParams};
+///
+/// let mut rng = InsecureTestRng(rand::rngs::mock::StepRng::new(0, 1));
+/// let pp = PublicParams::new(&mut rng);
+/// ```
+#[cfg(feature = "test-utils")]
+pub struct InsecureTestRng<R: RngCore>(pub R);
+
+#[cfg(feature = "test-utils")]
+impl<R: RngCore> RngCore for InsecureTestRng<R> {
+    fn next_u32(&mut self) -> u32 {
+        self.0.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.0.try_fill_bytes(dest)
+    }
+}
+
+#[cfg(feature = "test-utils")]
+impl<R: RngCore> CryptoRng for InsecureTestRng<R> {}
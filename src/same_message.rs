@@ -0,0 +1,167 @@
+//! Proof that two signatures under different keys sign messages of the same class, without
+//! revealing the messages.
+//!
+//! The prover holds `(pk_a, sig_a, Ma)` and `(pk_b, sig_b, Mb)` with `Mb = k Ma`. It changes the
+//! representations to fresh `Ma' = ua Ma` and `Mb' = ub Mb` with random `ua, ub`, so that
+//! `Mb' = t Ma'` for `t = ub k / ua`, and proves the knowledge of `t` by a Fiat-Shamir transformed
+//! Chaum-Pedersen proof on random linear combinations of `Ma'` and `Mb'`, as in
+//! [RepresentationChangeProof](crate::RepresentationChangeProof). The challenge is bound to a
+//! nonce of the verifier, so that a proof cannot be replayed to another verifier.
+//!
+//! The verifier learns the fresh representatives and their signatures, which are unlinkable to
+//! the messages held by the prover.
+
+use alloc::vec::Vec;
+use core::ops::Mul;
+
+use ark_ec::pairing::Pairing;
+use ark_serialize::CanonicalSerialize;
+use ark_std::UniformRand;
+use rand_core::{CryptoRng, RngCore};
+
+use crate::{
+    params::PublicParams,
+    public_key::PublicKey,
+    representation::{change_representation, combine_messages},
+    serialization::impl_bounded_deserialize,
+    signature::Signature,
+    transcript::Transcript,
+};
+
+/// The proof that two signatures sign messages of the same class.
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize)]
+pub struct SameMessageProof<E: Pairing> {
+    // fresh representatives and their signatures
+    pub(crate) msg_a: Vec<E::G1>,
+    pub(crate) sig_a: Signature<E>,
+    pub(crate) msg_b: Vec<E::G1>,
+    pub(crate) sig_b: Signature<E>,
+    // commitment r C_a
+    pub(crate) a: E::G1,
+    // response s = r + c t
+    pub(crate) s: E::ScalarField,
+}
+
+impl_bounded_deserialize!(SameMessageProof {
+    msg_a: vec,
+    sig_a: value,
+    msg_b: vec,
+    sig_b: value,
+    a: value,
+    s: value
+});
+
+/// Prove the messages `msg_a` and `msg_b`, signed by `sig_a` under `pk_a` and by `sig_b` under
+/// `pk_b`, are of the same class. The scalar `k` links the messages by `msg_b = k * msg_a`, i.e.
+/// it is one if the messages are equal.
+///
+/// ## Example
+///
+/// ```rust
+/// use mercurial_signature::{prove_same_message, Fr, PublicParams, UniformRand, G1};
+///
+/// let mut rng = rand::thread_rng();
+/// let pp = PublicParams::new(&mut rng);
+/// let (pk_a, sk_a) = pp.key_gen(&mut rng, 10);
+/// let (pk_b, sk_b) = pp.key_gen(&mut rng, 10);
+/// let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+/// let sig_a = sk_a.sign(&mut rng, &pp, &message);
+/// let sig_b = sk_b.sign(&mut rng, &pp, &message);
+///
+/// let nonce = b"verifier nonce";
+/// let proof = prove_same_message(
+///     &mut rng,
+///     &pp,
+///     (&pk_a, &sig_a, &message),
+///     (&pk_b, &sig_b, &message),
+///     Fr::from(1u64),
+///     nonce,
+/// );
+/// assert!(proof.verify(&pp, &pk_a, &pk_b, nonce));
+/// ```
+pub fn prove_same_message<E: Pairing, R: RngCore + CryptoRng>(
+    rng: &mut R,
+    pp: &PublicParams<E>,
+    (pk_a, sig_a, msg_a): (&PublicKey<E>, &Signature<E>, &[E::G1]),
+    (pk_b, sig_b, msg_b): (&PublicKey<E>, &Signature<E>, &[E::G1]),
+    k: E::ScalarField,
+    nonce: &[u8],
+) -> SameMessageProof<E> {
+    let ua = E::ScalarField::rand(rng);
+    let ub = E::ScalarField::rand(rng);
+    let (msg_a, sig_a) = fresh_representation(rng, msg_a, sig_a, ua);
+    let (msg_b, sig_b) = fresh_representation(rng, msg_b, sig_b, ub);
+    // Mb' = t Ma'
+    let t = ub * k / ua;
+
+    let (c_a, c_b) = combine_messages::<E>(&msg_a, &msg_b);
+    let r = E::ScalarField::rand(rng);
+    let a = c_a.mul(r);
+    let c = challenge::<E>(pp, pk_a, pk_b, c_a, c_b, a, nonce);
+    SameMessageProof {
+        msg_a,
+        sig_a,
+        msg_b,
+        sig_b,
+        a,
+        s: r + c * t,
+    }
+}
+
+impl<E: Pairing> SameMessageProof<E> {
+    /// Verify the signatures on the fresh representatives and the proof that they are of the
+    /// same class, for the nonce of the verifier.
+    pub fn verify(
+        &self,
+        pp: &PublicParams<E>,
+        pk_a: &PublicKey<E>,
+        pk_b: &PublicKey<E>,
+        nonce: &[u8],
+    ) -> bool {
+        if self.msg_a.len() != self.msg_b.len()
+            || !pk_a.verify(pp, &self.msg_a, &self.sig_a)
+            || !pk_b.verify(pp, &self.msg_b, &self.sig_b)
+        {
+            return false;
+        }
+
+        // s C_a == A + c C_b
+        let (c_a, c_b) = combine_messages::<E>(&self.msg_a, &self.msg_b);
+        let c = challenge::<E>(pp, pk_a, pk_b, c_a, c_b, self.a, nonce);
+        c_a.mul(self.s) == self.a + c_b.mul(c)
+    }
+}
+
+/// Change the representation of a copy of the message and the signature.
+fn fresh_representation<E: Pairing, R: RngCore + CryptoRng>(
+    rng: &mut R,
+    message: &[E::G1],
+    sig: &Signature<E>,
+    u: E::ScalarField,
+) -> (Vec<E::G1>, Signature<E>) {
+    let mut message = message.to_vec();
+    let mut sig = sig.clone();
+    change_representation(rng, &mut message, &mut sig, u);
+    (message, sig)
+}
+
+/// Fiat-Shamir challenge of the proof, bound to the public keys and the nonce.
+fn challenge<E: Pairing>(
+    pp: &PublicParams<E>,
+    pk_a: &PublicKey<E>,
+    pk_b: &PublicKey<E>,
+    c_a: E::G1,
+    c_b: E::G1,
+    a: E::G1,
+    nonce: &[u8],
+) -> E::ScalarField {
+    let mut transcript = Transcript::new(b"mercurial-signature/same-message");
+    transcript.append(b"pp", pp);
+    transcript.append(b"pk_a", pk_a);
+    transcript.append(b"pk_b", pk_b);
+    transcript.append_point_g1::<E>(b"combined_a", &c_a);
+    transcript.append_point_g1::<E>(b"combined_b", &c_b);
+    transcript.append_point_g1::<E>(b"commitment", &a);
+    transcript.append_bytes(b"nonce", nonce);
+    transcript.challenge_scalar::<E>(b"challenge")
+}
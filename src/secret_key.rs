@@ -1,23 +1,161 @@
+use alloc::{vec, vec::Vec};
 use ark_ec::pairing::Pairing;
+use ark_ff::{BigInteger, PrimeField};
 use ark_std::{One, UniformRand, Zero};
-use std::ops::Mul;
+use core::{borrow::Borrow, ops::Mul};
 
-use crate::{params::PublicParams, signature::Signature};
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use rand_core::RngCore;
+use crate::{
+    backend::{convert_with_backend, sign_with_backend, InMemoryBackend},
+    ct::ct_eq_scalars,
+    params::PublicParams,
+    public_key::KeyError,
+    public_key_g1::PublicKeyG1,
+    serialization::impl_bounded_deserialize,
+    signature::{
+        derive_tagged_key, hash_context, hash_multi_message, hash_tag, with_context_element,
+        PartialSignature, Signature, TaggedSignature,
+    },
+    signature_g2::SignatureG2,
+};
+use ark_serialize::CanonicalSerialize;
+#[cfg(feature = "getrandom")]
+use rand_core::OsRng;
+use rand_core::{CryptoRng, RngCore};
 
-#[derive(Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+/// Secret key. Outside of the tests of this crate, its `Debug` output redacts the elements
+/// of the key, e.g. `SecretKey { x: [REDACTED; 10] }`, so that it is not leaked in logs.
+#[derive(Clone, CanonicalSerialize)]
+#[cfg_attr(test, derive(Debug))]
 pub struct SecretKey<E: Pairing> {
     // sk = (x1,...,xl)
     pub(crate) x: Vec<E::ScalarField>,
 }
 
+impl_bounded_deserialize!(SecretKey { x: vec });
+
+impl<E: Pairing> PartialEq for SecretKey<E> {
+    fn eq(&self, other: &Self) -> bool {
+        ct_eq_scalars::<E>(&self.x, &other.x)
+    }
+}
+
+impl<E: Pairing> Eq for SecretKey<E> {}
+
+#[cfg(not(test))]
+impl<E: Pairing> core::fmt::Debug for SecretKey<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SecretKey")
+            .field("x", &format_args!("[REDACTED; {}]", self.x.len()))
+            .finish()
+    }
+}
+
+/// Reorder the elements of the message by the permutation, so that the `i`-th element of the
+/// result is `message[permutation[i]]`.
+///
+/// ## Safety
+/// This function panics if `permutation` is not a permutation of the indices of the message.
+pub fn permute_message<T: Clone>(message: &[T], permutation: &[usize]) -> Vec<T> {
+    let mut seen = vec![false; message.len()];
+    if permutation.len() != message.len()
+        || !permutation
+            .iter()
+            .all(|&i| i < seen.len() && !core::mem::replace(&mut seen[i], true))
+    {
+        panic!("The permutation must reorder all the indices of the message.");
+    }
+
+    permutation.iter().map(|&i| message[i].clone()).collect()
+}
+
 impl<E: Pairing> SecretKey<E> {
     /// Length of the secret key.
     pub fn length(&self) -> usize {
         self.x.len()
     }
 
+    /// Number of bytes of the uncompressed serialization, i.e.
+    /// [uncompressed_size](CanonicalSerialize::uncompressed_size).
+    pub fn serialized_size(&self) -> usize {
+        self.uncompressed_size()
+    }
+
+    /// Number of bytes of the compressed serialization, i.e.
+    /// [compressed_size](CanonicalSerialize::compressed_size).
+    pub fn compressed_serialized_size(&self) -> usize {
+        self.compressed_size()
+    }
+
+    /// Write the compressed serialization of the secret key to the file, atomically: the bytes
+    /// are written to a temporary file in the same directory, which is then renamed to `path`,
+    /// so that a reader sees either the previous or the new key, never a partial one. On Unix,
+    /// the file is readable and writable only by its owner.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::{PublicParams, SecretKey};
+    ///
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let path = dir.path().join("issuer.key");
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let (_, sk) = pp.key_gen(&mut rng, 5);
+    /// sk.write_to_file(&path).unwrap();
+    /// assert!(SecretKey::read_from_file(&path).unwrap() == sk);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn write_to_file(&self, path: &std::path::Path) -> std::io::Result<()> {
+        use std::io::Write;
+
+        let mut bytes = Vec::with_capacity(self.compressed_size());
+        self.serialize_compressed(&mut bytes)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+
+        let file_name = path.file_name().ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "the path has no file name",
+            )
+        })?;
+        let mut tmp_name = std::ffi::OsString::from(".tmp-");
+        tmp_name.push(file_name);
+        let tmp_path = path.with_file_name(tmp_name);
+
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let result = options.open(&tmp_path).and_then(|mut file| {
+            file.write_all(&bytes)?;
+            file.sync_all()?;
+            std::fs::rename(&tmp_path, path)
+        });
+        if result.is_err() {
+            let _ = std::fs::remove_file(&tmp_path);
+        }
+        result
+    }
+
+    /// Read the secret key written by [SecretKey::write_to_file]. Returns an error of the kind
+    /// [InvalidData](std::io::ErrorKind::InvalidData) if the file is not a valid secret key,
+    /// e.g. truncated.
+    #[cfg(feature = "std")]
+    pub fn read_from_file(path: &std::path::Path) -> std::io::Result<Self> {
+        let bytes = std::fs::read(path)?;
+        let mut reader = bytes.as_slice();
+        let sk = <Self as ark_serialize::CanonicalDeserialize>::deserialize_compressed(&mut reader)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        if !reader.is_empty() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "trailing bytes after the secret key",
+            ));
+        }
+        Ok(sk)
+    }
+
     /// Sign a message.
     ///
     /// ## Safety
@@ -35,12 +173,343 @@ impl<E: Pairing> SecretKey<E> {
     /// let sig = sk.sign(&mut rng, &pp, &message);
     /// assert!(pk.verify(&pp, &message, &sig));
     /// ```
-    pub fn sign<R: RngCore>(
+    pub fn sign<R: RngCore + CryptoRng>(
         &self,
         rng: &mut R,
         pp: &PublicParams<E>,
         message: &[E::G1],
     ) -> Signature<E> {
+        let y = E::ScalarField::rand(rng);
+        self.sign_with_y(pp, message, y)
+    }
+
+    /// Sign a message of affine points, e.g. a `Vec<G1Affine>`, an array or an iterator, without
+    /// collecting it into projective points first. The signature is the same as [SecretKey::sign]
+    /// on the projective message.
+    ///
+    /// ## Safety
+    /// This function panics if the length of the secret key is less than the length of the message.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use ark_ec::CurveGroup;
+    /// use mercurial_signature::{PublicParams, UniformRand, G1};
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let (pk, sk) = pp.key_gen(&mut rng, 10);
+    /// let message = G1::normalize_batch(&(0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>());
+    /// let sig = sk.sign_affine(&mut rng, &pp, &message);
+    /// assert!(pk.verify_affine(&pp, &message, &sig));
+    /// ```
+    pub fn sign_affine<R, I>(&self, rng: &mut R, pp: &PublicParams<E>, message: I) -> Signature<E>
+    where
+        R: RngCore + CryptoRng,
+        I: IntoIterator,
+        I::Item: Borrow<E::G1Affine>,
+    {
+        let y = E::ScalarField::rand(rng);
+        // z = (x1 M1 + ... + xl Ml) * y
+        let mut message = message.into_iter();
+        let z = self
+            .x
+            .iter()
+            .zip(message.by_ref())
+            .fold(E::G1::zero(), |acc, (xi, m)| acc + m.borrow().mul(y * xi));
+        if message.next().is_some() {
+            panic!(
+                "The length of the secret key must be equal or greater than the length of the message."
+            );
+        }
+
+        // y1 = p1^(1/y), y2 = p2^(1/y)
+        let y_inv = E::ScalarField::one() / y;
+        Signature {
+            z,
+            y1: pp.p1.mul(y_inv),
+            y2: pp.p2.mul(y_inv),
+        }
+    }
+
+    /// Sign a message with the randomness of the operating system.
+    ///
+    /// ## Safety
+    /// This function panics if the length of the secret key is less than the length of the message.
+    #[cfg(feature = "getrandom")]
+    pub fn sign_default(&self, pp: &PublicParams<E>, message: &[E::G1]) -> Signature<E> {
+        self.sign(&mut OsRng, pp, message)
+    }
+
+    /// Sign a message with a public tag, e.g. an epoch or a context identifier. The tag stays fixed
+    /// when changing the representation, and the signature verifies only with the same tag.
+    ///
+    /// The signature is a plain signature under the key `(x1 + t x(h+1),...,xh + t x(2h))`
+    /// derived from the hash `t` of the tag, where `h` is half the length of the key, so the
+    /// message is at most half as long as the key. Taking the tag off, or moving the signature
+    /// to another tag, requires converting by a scalar that depends on the secret key.
+    ///
+    /// ## Security
+    /// A plain signature on `(M, t M)` is a tagged signature on `M`. A key that signs with tags
+    /// must not sign plain messages of the full length of the key chosen by others.
+    ///
+    /// ## Safety
+    /// This function panics if half the length of the secret key is less than the length of the
+    /// message.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::{change_representation_tagged, Fr, PublicParams, UniformRand, G1};
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let (pk, sk) = pp.key_gen(&mut rng, 20);
+    /// let mut message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    /// let mut sig = sk.sign_tagged(&mut rng, &pp, b"epoch-1", &message);
+    ///
+    /// let u = Fr::rand(&mut rng);
+    /// change_representation_tagged(&mut rng, &mut message, &mut sig, u);
+    /// assert!(pk.verify_tagged(&pp, b"epoch-1", &message, &sig));
+    /// assert!(!pk.verify_tagged(&pp, b"epoch-2", &message, &sig));
+    /// ```
+    pub fn sign_tagged<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        pp: &PublicParams<E>,
+        tag: &[u8],
+        message: &[E::G1],
+    ) -> TaggedSignature<E> {
+        let x = derive_tagged_key(&self.x, hash_tag::<E>(tag));
+        let y = E::ScalarField::rand(rng);
+        let Signature { z, y1, y2 } = sign_with_backend(&InMemoryBackend, &x, pp, message, &y);
+        TaggedSignature { z, y1, y2 }
+    }
+
+    /// Sign a message bound to a context string, e.g. the name of a protocol, so that a
+    /// signature issued for one context never verifies for another one, see
+    /// [PublicKey::verify_with_context](crate::PublicKey). The first slot of the key signs the
+    /// hash of the context, prepended to the message, so the message is at most one element
+    /// shorter than the key. The signature is a plain signature on `(C, M1,...,Ml)` where `C`
+    /// is the hash of the context.
+    ///
+    /// Changing the representation by `u` scales `C` with the rest of the message. To keep the
+    /// check of the context sound, the verifier of a changed representation is given `u` and
+    /// checks the first element is `u C`, see
+    /// [PublicKey::verify_with_context_representation](crate::PublicKey). Disclosing `u` links
+    /// the representation to the original message, so [SecretKey::sign_tagged] is the choice
+    /// if the context must survive unlinkable changes of representation.
+    ///
+    /// ## Safety
+    /// This function panics if the length of the secret key is not greater than the length of
+    /// the message.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::{PublicParams, UniformRand, G1};
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let (pk, sk) = pp.key_gen(&mut rng, 11);
+    /// let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    /// let sig = sk.sign_with_context(&mut rng, &pp, b"protocol-a", &message);
+    /// assert!(pk.verify_with_context(&pp, b"protocol-a", &message, &sig));
+    /// assert!(!pk.verify_with_context(&pp, b"protocol-b", &message, &sig));
+    /// ```
+    pub fn sign_with_context<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        pp: &PublicParams<E>,
+        context: &[u8],
+        message: &[E::G1],
+    ) -> Signature<E> {
+        if self.x.len() <= message.len() {
+            panic!("The length of the secret key must be greater than the length of the message.");
+        }
+
+        let message = with_context_element::<E>(hash_context::<E>(context), message);
+        self.sign(rng, pp, &message)
+    }
+
+    /// Sign multiple messages with the same randomness `y`, only for aggregating the signatures
+    /// by [Signature::aggregate_same_key]. Use [sign](SecretKey::sign) for each message otherwise.
+    ///
+    /// ## Security
+    /// The signatures are not independent signatures. Since they share `y`, any linear
+    /// combination `a z1 + b z2` of them with the shared `y1, y2` is a valid signature on the
+    /// message `a M1 + b M2`, so anyone holding them forges signatures on messages that were
+    /// never signed. Only sign messages of which every linear combination may be signed, and
+    /// never hand the individual signatures to parties who must not obtain such signatures.
+    /// The signatures are also linkable to each other by the shared `y1, y2`.
+    ///
+    /// ## Safety
+    /// This function panics if the length of the secret key is less than the length of any message.
+    pub fn sign_for_aggregation<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        pp: &PublicParams<E>,
+        messages: &[&[E::G1]],
+    ) -> Vec<Signature<E>> {
+        let y = E::ScalarField::rand(rng);
+        messages
+            .iter()
+            .map(|message| self.sign_with_y(pp, message, y))
+            .collect()
+    }
+
+    /// Sign multiple messages with one signature. The messages are combined element-wise by
+    /// hashing into a single message, so the signature covers all of them in order, see
+    /// [PublicKey::verify_multi_message](crate::PublicKey).
+    ///
+    /// ## Safety
+    /// This function panics if there is no message, the messages have different lengths, or
+    /// the length of the secret key is less than the length of the messages.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::{PublicParams, UniformRand, G1};
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let (pk, sk) = pp.key_gen(&mut rng, 10);
+    /// let header = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    /// let body = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    /// let sig = sk.sign_multi_message(&mut rng, &pp, &[&header, &body]);
+    /// assert!(pk.verify_multi_message(&pp, &[&header, &body], &sig));
+    /// assert!(!pk.verify_multi_message(&pp, &[&body, &header], &sig));
+    /// ```
+    pub fn sign_multi_message<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        pp: &PublicParams<E>,
+        messages: &[&[E::G1]],
+    ) -> Signature<E> {
+        let Some(message) = hash_multi_message::<E>(messages) else {
+            panic!("The messages must be non-empty and have the same length.");
+        };
+        self.sign(rng, pp, &message)
+    }
+
+    /// Sign the message with its elements reordered by the permutation, i.e. the message
+    /// `(M[permutation[0]],...,M[permutation[l-1]])`, see [permute_message](crate::permute_message).
+    /// The signature verifies the permuted message.
+    ///
+    /// ## Safety
+    /// This function panics if `permutation` is not a permutation of the indices of the message,
+    /// or the length of the secret key is less than the length of the message.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::{permute_message, PublicParams, UniformRand, G1};
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let (pk, sk) = pp.key_gen(&mut rng, 5);
+    /// let message = (0..5).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    /// let sig = sk.sign_permuted(&mut rng, &pp, &message, &[4, 3, 2, 1, 0]);
+    /// assert!(pk.verify(&pp, &permute_message(&message, &[4, 3, 2, 1, 0]), &sig));
+    /// ```
+    pub fn sign_permuted<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        pp: &PublicParams<E>,
+        message: &[E::G1],
+        permutation: &[usize],
+    ) -> Signature<E> {
+        self.sign(rng, pp, &permute_message(message, permutation))
+    }
+
+    /// Randomise a message by a random scalar `w` and sign it. Returns the randomised message
+    /// `(w M1,...,w Ml)`, which is in the same equivalence class as the message, and its signature.
+    /// The input message is unchanged.
+    ///
+    /// ## Safety
+    /// This function panics if the length of the secret key is less than the length of the message.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::{PublicParams, UniformRand, G1};
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let (pk, sk) = pp.key_gen(&mut rng, 10);
+    /// let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    /// let (randomised, sig) = sk.randomise_and_sign(&mut rng, &pp, &message);
+    /// assert!(pk.verify(&pp, &randomised, &sig));
+    /// ```
+    pub fn randomise_and_sign<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        pp: &PublicParams<E>,
+        message: &[E::G1],
+    ) -> (Vec<E::G1>, Signature<E>) {
+        let w = E::ScalarField::rand(rng);
+        let message = message.iter().map(|m| m.mul(w)).collect::<Vec<E::G1>>();
+        let sig = self.sign(rng, pp, &message);
+        (message, sig)
+    }
+
+    /// Sign a message with the given randomness `y` instead of sampling it, e.g. for
+    /// deterministic tests or for a protocol that chooses `y` itself. The same `y` gives the
+    /// same signature.
+    ///
+    /// ## Security
+    /// In production, `y` must be uniformly random and never reused. Signatures with the same
+    /// `y` share `y1, y2`, so any linear combination `a z1 + b z2` of them is a valid signature
+    /// on the message `a M1 + b M2`: anyone holding them forges signatures on messages that
+    /// were never signed. They are also linkable after changing their representations.
+    ///
+    /// ## Safety
+    /// This function panics if the length of the secret key is less than the length of the
+    /// message, or if `y` is zero.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::{Fr, PublicParams, UniformRand, G1};
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let (pk, sk) = pp.key_gen(&mut rng, 10);
+    /// let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    /// let y = Fr::rand(&mut rng);
+    /// let sig = sk.sign_with_randomness(&pp, &message, y);
+    /// assert!(sig == sk.sign_with_randomness(&pp, &message, y));
+    /// assert!(pk.verify(&pp, &message, &sig));
+    /// ```
+    pub fn sign_with_randomness(
+        &self,
+        pp: &PublicParams<E>,
+        message: &[E::G1],
+        y: E::ScalarField,
+    ) -> Signature<E> {
+        self.sign_with_y(pp, message, y)
+    }
+
+    /// Sign a message with the randomness `y`.
+    pub(crate) fn sign_with_y(
+        &self,
+        pp: &PublicParams<E>,
+        message: &[E::G1],
+        y: E::ScalarField,
+    ) -> Signature<E> {
+        sign_with_backend(&InMemoryBackend, &self.x, pp, message, &y)
+    }
+
+    /// Sign a message in G2. The signature is verified by the public key in G1, see [PublicKeyG1::verify].
+    ///
+    /// ## Safety
+    /// This function panics if the length of the secret key is less than the length of the message.
+    pub fn sign_g2<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        pp: &PublicParams<E>,
+        message: &[E::G2],
+    ) -> SignatureG2<E> {
         if self.x.len() < message.len() {
             panic!("The length of the secret key must be equal or greater than the length of the message.");
         }
@@ -50,12 +519,58 @@ impl<E: Pairing> SecretKey<E> {
         let z = message
             .iter()
             .zip(self.x.iter())
-            .fold(E::G1::zero(), |acc, (m, xi)| acc + m.mul(y * xi));
-        // y1 = p1^(1/y)
-        let y1 = pp.p1.mul(E::ScalarField::one() / y);
-        // y2 = p2^(1/y)
-        let y2 = pp.p2.mul(E::ScalarField::one() / y);
-        Signature { z, y1, y2 }
+            .fold(E::G2::zero(), |acc, (m, xi)| acc + m.mul(y * xi));
+        // y1 = p2^(1/y)
+        let y1 = pp.p2.mul(E::ScalarField::one() / y);
+        // y2 = p1^(1/y)
+        let y2 = pp.p1.mul(E::ScalarField::one() / y);
+        SignatureG2 { z, y1, y2 }
+    }
+
+    /// The public key in G1 corresponding to the secret key.
+    pub fn public_key_g1(&self, pp: &PublicParams<E>) -> PublicKeyG1<E> {
+        PublicKeyG1 {
+            bx: self.x.iter().map(|xi| pp.p1.mul(xi)).collect(),
+        }
+    }
+
+    /// Compute a partial signature using only the key components at `indices`.
+    /// Partial signatures on disjoint index sets are combined by [combine_partial_signatures](crate::combine_partial_signatures).
+    ///
+    /// ## Safety
+    /// This function panics if an index is out of the range of the secret key or the message.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::{combine_partial_signatures, PublicParams, UniformRand, G1};
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let (pk, sk) = pp.key_gen(&mut rng, 10);
+    /// let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    ///
+    /// let partial1 = sk.partial_sign(&message, &[0, 2, 4, 6, 8]);
+    /// let partial2 = sk.partial_sign(&message, &[1, 3, 5, 7, 9]);
+    /// let sig = combine_partial_signatures(&mut rng, &pp, &[partial1, partial2]);
+    /// assert!(pk.verify(&pp, &message, &sig));
+    /// ```
+    pub fn partial_sign(&self, message: &[E::G1], indices: &[usize]) -> PartialSignature<E> {
+        if indices
+            .iter()
+            .any(|&i| i >= self.x.len() || i >= message.len())
+        {
+            panic!("The indices must be within the length of the secret key and the message.");
+        }
+
+        // z = sum of xi Mi for i in indices
+        let z = indices
+            .iter()
+            .fold(E::G1::zero(), |acc, &i| acc + message[i].mul(self.x[i]));
+        PartialSignature {
+            z,
+            indices: indices.to_vec(),
+        }
     }
 
     /// Convert the secret key.
@@ -80,6 +595,66 @@ impl<E: Pairing> SecretKey<E> {
     /// assert!(pk.verify(&pp, &message, &sig));
     /// ```
     pub fn convert(&mut self, p: E::ScalarField) {
-        self.x.iter_mut().for_each(|xi| *xi *= p);
+        convert_with_backend::<E, _>(&InMemoryBackend, &mut self.x, p);
+    }
+
+    /// Convert the secret keys with the same scalar `p`, in a single pass over all the elements.
+    /// It is equivalent to calling [SecretKey::convert] on each key.
+    pub fn batch_convert(keys: &mut [SecretKey<E>], p: E::ScalarField) {
+        keys.iter_mut()
+            .flat_map(|key| key.x.iter_mut())
+            .for_each(|xi| *xi *= p);
+    }
+
+    /// Add the secret keys element-wise. The sum signs for the sum of the public keys, see
+    /// [PublicKey::homomorphic_add](crate::PublicKey). Returns [KeyError::LengthMismatch] if the
+    /// keys have different lengths.
+    pub fn homomorphic_add(&self, other: &SecretKey<E>) -> Result<SecretKey<E>, KeyError> {
+        if self.x.len() != other.x.len() {
+            return Err(KeyError::LengthMismatch);
+        }
+        Ok(SecretKey {
+            x: self.x.iter().zip(&other.x).map(|(a, b)| *a + b).collect(),
+        })
+    }
+
+    /// Add a random noise in `[0, epsilon]` to each element of the secret key. The noisy key is
+    /// a different key: its signatures verify only under the public key from
+    /// [PublicKey::from_noisy_sk](crate::PublicKey), which must be published.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::{Fr, PublicKey, PublicParams, UniformRand, G1};
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let (_, mut sk) = pp.key_gen(&mut rng, 10);
+    /// sk.add_noise(&mut rng, Fr::from(1000u64));
+    /// let noisy_pk = PublicKey::from_noisy_sk(&pp, &sk);
+    ///
+    /// let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    /// let sig = sk.sign(&mut rng, &pp, &message);
+    /// assert!(noisy_pk.verify(&pp, &message, &sig));
+    /// ```
+    pub fn add_noise<R: RngCore + CryptoRng>(&mut self, rng: &mut R, epsilon: E::ScalarField) {
+        let bound = epsilon.into_bigint();
+        let bits = bound.num_bits() as usize;
+        self.x.iter_mut().for_each(|xi| {
+            // rejection sampling of a number with the bit length of epsilon, accepted with
+            // probability at least 1/2
+            let noise = loop {
+                let mut bytes = vec![0u8; bits.div_ceil(8)];
+                rng.fill_bytes(&mut bytes);
+                if let Some(last) = bytes.last_mut() {
+                    *last &= 0xff >> ((8 - bits % 8) % 8);
+                }
+                let noise = E::ScalarField::from_le_bytes_mod_order(&bytes);
+                if noise.into_bigint() <= bound {
+                    break noise;
+                }
+            };
+            *xi += noise;
+        });
     }
 }
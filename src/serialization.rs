@@ -0,0 +1,94 @@
+//! Deserialization of the types holding vectors.
+//!
+//! The derived [CanonicalDeserialize] of arkworks reserves the capacity of a vector from its
+//! length prefix before reading any element, so that a few untrusted bytes with a large length
+//! abort the process. The types holding vectors implement it by [impl_bounded_deserialize]
+//! instead, which grows the vectors with the elements actually read.
+
+use alloc::vec::Vec;
+use ark_serialize::{CanonicalDeserialize, Compress, Read, SerializationError, Valid, Validate};
+
+/// Capacity reserved before reading the elements of a vector. The length prefix is untrusted,
+/// so a longer vector grows as its elements are read.
+pub(crate) const MAX_RESERVED: usize = 1024;
+
+/// Deserialize a vector in the format of arkworks, i.e. the length as a `u64` followed by the
+/// elements.
+pub(crate) fn deserialize_vec<T: CanonicalDeserialize, R: Read>(
+    reader: &mut R,
+    compress: Compress,
+    validate: Validate,
+) -> Result<Vec<T>, SerializationError> {
+    deserialize_vec_with(reader, compress, validate, |reader| {
+        T::deserialize_with_mode(reader, compress, Validate::No)
+    })
+}
+
+/// Deserialize a vector of vectors, see [deserialize_vec].
+pub(crate) fn deserialize_nested_vec<T: CanonicalDeserialize, R: Read>(
+    reader: &mut R,
+    compress: Compress,
+    validate: Validate,
+) -> Result<Vec<Vec<T>>, SerializationError> {
+    deserialize_vec_with(reader, compress, validate, |reader| {
+        deserialize_vec(reader, compress, Validate::No)
+    })
+}
+
+fn deserialize_vec_with<T: Valid, R: Read>(
+    reader: &mut R,
+    compress: Compress,
+    validate: Validate,
+    mut element: impl FnMut(&mut R) -> Result<T, SerializationError>,
+) -> Result<Vec<T>, SerializationError> {
+    let len: usize = u64::deserialize_with_mode(&mut *reader, compress, validate)?
+        .try_into()
+        .map_err(|_| SerializationError::NotEnoughSpace)?;
+    let mut values = Vec::with_capacity(len.min(MAX_RESERVED));
+    for _ in 0..len {
+        values.push(element(reader)?);
+    }
+    if let Validate::Yes = validate {
+        T::batch_check(values.iter())?;
+    }
+    Ok(values)
+}
+
+/// Implement [Valid] and [CanonicalDeserialize] for a struct generic over `E: Pairing`, in the
+/// format of the derived implementations. Each field is listed in the order of declaration with
+/// its kind: `vec` for a vector, `nested_vec` for a vector of vectors, or `value` otherwise.
+macro_rules! impl_bounded_deserialize {
+    ($name:ident { $($field:ident: $kind:ident),* $(,)? }) => {
+        impl<E: ark_ec::pairing::Pairing> ark_serialize::Valid for $name<E> {
+            fn check(&self) -> Result<(), ark_serialize::SerializationError> {
+                $(ark_serialize::Valid::check(&self.$field)?;)*
+                Ok(())
+            }
+        }
+
+        impl<E: ark_ec::pairing::Pairing> ark_serialize::CanonicalDeserialize for $name<E> {
+            fn deserialize_with_mode<R: ark_serialize::Read>(
+                mut reader: R,
+                compress: ark_serialize::Compress,
+                validate: ark_serialize::Validate,
+            ) -> Result<Self, ark_serialize::SerializationError> {
+                Ok($name {
+                    $($field: impl_bounded_deserialize!(
+                        @field $kind, &mut reader, compress, validate
+                    ),)*
+                })
+            }
+        }
+    };
+    (@field vec, $reader:expr, $compress:expr, $validate:expr) => {
+        $crate::serialization::deserialize_vec($reader, $compress, $validate)?
+    };
+    (@field nested_vec, $reader:expr, $compress:expr, $validate:expr) => {
+        $crate::serialization::deserialize_nested_vec($reader, $compress, $validate)?
+    };
+    (@field value, $reader:expr, $compress:expr, $validate:expr) => {
+        ark_serialize::CanonicalDeserialize::deserialize_with_mode($reader, $compress, $validate)?
+    };
+}
+
+pub(crate) use impl_bounded_deserialize;
@@ -1,9 +1,20 @@
+use alloc::vec::Vec;
 use ark_ec::pairing::Pairing;
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use ark_std::{One, UniformRand};
-use rand_core::RngCore;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use ark_std::{One, UniformRand, Zero};
+use core::ops::{Add, Mul};
+#[cfg(feature = "getrandom")]
+use rand_core::OsRng;
+use rand_core::{CryptoRng, RngCore};
 
-#[derive(Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+use crate::{
+    hash::{hash_to_g1, hash_to_scalar},
+    params::PublicParams,
+    public_key::PublicKey,
+    serialization::impl_bounded_deserialize,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct Signature<E: Pairing> {
     pub(crate) z: E::G1,
     pub(crate) y1: E::G1,
@@ -32,11 +43,194 @@ impl<E: Pairing> Signature<E> {
     /// sig.convert(&mut rng, p);
     /// assert!(pk.verify(&pp, &message, &sig));
     /// ```
-    pub fn convert<R: RngCore>(&mut self, rng: &mut R, p: E::ScalarField) {
+    pub fn convert<R: RngCore + CryptoRng>(&mut self, rng: &mut R, p: E::ScalarField) {
         let f = E::ScalarField::rand(rng);
         self.convert_with_f(p, f);
     }
 
+    /// Convert the signature with the randomness of the operating system, see
+    /// [convert](Signature::convert).
+    #[cfg(feature = "getrandom")]
+    pub fn convert_default(&mut self, p: E::ScalarField) {
+        self.convert(&mut OsRng, p);
+    }
+
+    /// Aggregate signatures by the same key that share the randomness, i.e. the same `y1, y2`,
+    /// for compact storage. Returns `None` if the signatures do not share `y1, y2`.
+    ///
+    /// The aggregate is only meant for signatures by the same key from
+    /// [SecretKey::sign_for_aggregation](crate::SecretKey::sign_for_aggregation), which are
+    /// forgeable by linear combinations if handed out individually, see its security notes.
+    /// It is not safe against rogue keys.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::{PublicParams, Signature, UniformRand, G1};
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let (pk, sk) = pp.key_gen(&mut rng, 10);
+    /// let messages = (0..5)
+    ///     .map(|_| (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>())
+    ///     .collect::<Vec<_>>();
+    /// let messages = messages.iter().map(|m| m.as_slice()).collect::<Vec<_>>();
+    /// // only for aggregation, not a general way of signing many messages
+    /// let sigs = sk.sign_for_aggregation(&mut rng, &pp, &messages);
+    ///
+    /// let aggregate = Signature::aggregate_same_key(&sigs.iter().collect::<Vec<_>>()).unwrap();
+    /// assert!(aggregate.verify_individual(&pk, &pp, &messages));
+    /// ```
+    pub fn aggregate_same_key(sigs: &[&Signature<E>]) -> Option<AggregateSignature<E>> {
+        let first = sigs.first()?;
+        if sigs
+            .iter()
+            .any(|sig| sig.y1 != first.y1 || sig.y2 != first.y2)
+        {
+            return None;
+        }
+
+        Some(AggregateSignature {
+            zs: sigs.iter().map(|sig| sig.z).collect(),
+            y1: first.y1,
+            y2: first.y2,
+        })
+    }
+
+    /// The element `y2` of the signature, e.g. to be authenticated before
+    /// [partial_verify_z](Signature::partial_verify_z).
+    pub fn y2(&self) -> &E::G2 {
+        &self.y2
+    }
+
+    /// Verify only `e(z, y2) == e(m1, bx1) * ... * e(ml, bxl)` with the given `y2`, skipping the
+    /// check `e(y1, p2) == e(p1, y2)` of [PublicKey::verify]. This saves one pairing in protocols
+    /// which verify `y1, y2` in a separate step.
+    ///
+    /// ## Security
+    /// The caller must have authenticated `y2`, e.g. by the full verification of another
+    /// signature sharing it. The check alone is not a signature verification: with `y2 = bx1`
+    /// and `z = m1`, anyone forges a signature on a message of one element.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::{PublicParams, UniformRand, G1};
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let (pk, sk) = pp.key_gen(&mut rng, 10);
+    /// let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    /// let sig = sk.sign(&mut rng, &pp, &message);
+    ///
+    /// // y2 was authenticated in an earlier step
+    /// let y2 = *sig.y2();
+    /// assert!(sig.partial_verify_z(&pk, &message, &y2));
+    /// ```
+    pub fn partial_verify_z(&self, pk: &PublicKey<E>, message: &[E::G1], y2: &E::G2) -> bool {
+        // check length l
+        if pk.bx.len() < message.len() {
+            return false;
+        }
+
+        // e(z, y2) == e(m1, bx1) * ... * e(ml, bxl)
+        let lhs = E::pairing(self.z, *y2);
+        let rhs = message
+            .iter()
+            .zip(pk.bx.iter())
+            .fold(E::pairing(E::G1::zero(), E::G2::zero()), |acc, (m, bxi)| {
+                acc + E::pairing(*m, *bxi)
+            });
+        lhs == rhs
+    }
+
+    /// Verify the signature against a commitment to the public key in G2 instead of the public
+    /// key, for schemes which publish the commitment only. The commitment stands in for the
+    /// product `e(m1, bx1) * ... * e(ml, bxl)` of [PublicKey::verify], i.e. it checks
+    /// `e(y1, p2) == e(p1, y2)` and `e(z, y2) == e(p1, key_commitment)`.
+    ///
+    /// ## Preconditions
+    /// The check is meaningful only if the message is `(r1 p1,...,rl p1)` for scalars `ri`,
+    /// and `key_commitment` is exactly the weighted sum `r1 bx1 + ... + rl bxl` of the key, so
+    /// that `e(p1, key_commitment)` equals the product above. The message is not an input: it
+    /// is bound only through the commitment, so the protocol must authenticate that the
+    /// commitment was computed from the signer's key and the intended `ri`, e.g. by a proof of
+    /// the weighted sum. An unauthenticated commitment verifies nothing: for `z = a p1`, anyone
+    /// passes the check with `key_commitment = a y p2` where `y2 = p2^(1/y)`.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::{Fr, PublicParams, UniformRand, G1, G2};
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let (pk, sk) = pp.key_gen(&mut rng, 10);
+    /// let r = (0..10).map(|_| Fr::rand(&mut rng)).collect::<Vec<Fr>>();
+    /// let message = r.iter().map(|ri| pp.p1 * ri).collect::<Vec<G1>>();
+    /// let sig = sk.sign(&mut rng, &pp, &message);
+    ///
+    /// let key_commitment = r
+    ///     .iter()
+    ///     .zip(pk.elements())
+    ///     .map(|(ri, bxi)| *bxi * ri)
+    ///     .sum::<G2>();
+    /// assert!(sig.verify_without_public_key(&pp, key_commitment));
+    /// ```
+    pub fn verify_without_public_key(&self, pp: &PublicParams<E>, key_commitment: E::G2) -> bool {
+        // e(y1, p2) == e(p1, y2)
+        let lhs = E::pairing(self.y1, pp.p2);
+        let rhs = E::pairing(pp.p1, self.y2);
+        if lhs != rhs {
+            return false;
+        }
+
+        // e(z, y2) == e(p1, r1 bx1 + ... + rl bxl)
+        E::pairing(self.z, self.y2) == E::pairing(pp.p1, key_commitment)
+    }
+
+    /// Number of bytes of the uncompressed serialization, i.e.
+    /// [uncompressed_size](CanonicalSerialize::uncompressed_size).
+    pub fn serialized_size(&self) -> usize {
+        self.uncompressed_size()
+    }
+
+    /// Number of bytes of the compressed serialization, i.e.
+    /// [compressed_size](CanonicalSerialize::compressed_size).
+    pub fn compressed_serialized_size(&self) -> usize {
+        self.compressed_size()
+    }
+
+    /// Serialize the signature in the compressed form into an array of `N` bytes, without
+    /// allocation. The bytes after [compressed_size](CanonicalSerialize::compressed_size) are
+    /// zero, and the array can be read back with `Signature::deserialize_compressed`. Returns
+    /// an error if `N` is less than the compressed size.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use ark_serialize::CanonicalDeserialize;
+    /// use mercurial_signature::{PublicParams, Signature, UniformRand, G1};
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let (pk, sk) = pp.key_gen(&mut rng, 10);
+    /// let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    /// let sig = sk.sign(&mut rng, &pp, &message);
+    ///
+    /// let bytes = sig.to_bytes_fixed::<192>().unwrap();
+    /// let sig = Signature::deserialize_compressed(&bytes[..]).unwrap();
+    /// assert!(pk.verify(&pp, &message, &sig));
+    /// ```
+    pub fn to_bytes_fixed<const N: usize>(&self) -> Result<[u8; N], SerializationError> {
+        if N < self.compressed_size() {
+            return Err(SerializationError::NotEnoughSpace);
+        }
+        let mut bytes = [0u8; N];
+        self.serialize_compressed(&mut bytes[..])?;
+        Ok(bytes)
+    }
+
     /// Convert the signature with a scalar `f`.
     pub(crate) fn convert_with_f(&mut self, p: E::ScalarField, f: E::ScalarField) {
         self.z *= p * f;
@@ -44,3 +238,177 @@ impl<E: Pairing> Signature<E> {
         self.y2 *= E::ScalarField::one() / f;
     }
 }
+
+/// Signature on a message with a public tag, see [SecretKey::sign_tagged](crate::SecretKey).
+/// Changing the representation keeps the tag fixed.
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct TaggedSignature<E: Pairing> {
+    // signature under the key (x1 + t x(h+1),...,xh + t x(2h)) derived from the tag
+    pub(crate) z: E::G1,
+    pub(crate) y1: E::G1,
+    pub(crate) y2: E::G2,
+}
+
+impl<E: Pairing> TaggedSignature<E> {
+    /// Convert the signature.
+    /// The input scalar `p` must be the same as the one used in the conversion of the public key and the secret key.
+    pub fn convert<R: RngCore + CryptoRng>(&mut self, rng: &mut R, p: E::ScalarField) {
+        let f = E::ScalarField::rand(rng);
+        self.convert_with_f(p, f);
+    }
+
+    /// Convert the signature with a scalar `f`.
+    pub(crate) fn convert_with_f(&mut self, p: E::ScalarField, f: E::ScalarField) {
+        self.z *= p * f;
+        self.y1 *= E::ScalarField::one() / f;
+        self.y2 *= E::ScalarField::one() / f;
+    }
+}
+
+/// Hash the tag to the scalar `t` of the key derived from the tag.
+pub(crate) fn hash_tag<E: Pairing>(tag: &[u8]) -> E::ScalarField {
+    hash_to_scalar::<E>(b"mercurial-signature/tag", tag)
+}
+
+/// The key `(k1 + t k(h+1),...,kh + t k(2h))` derived from the key `(k1,...,kl)` and the hash
+/// `t` of the tag, where `h = l / 2`.
+pub(crate) fn derive_tagged_key<T: Copy + Add<Output = T> + Mul<S, Output = T>, S: Copy>(
+    key: &[T],
+    t: S,
+) -> Vec<T> {
+    let (first, second) = key.split_at(key.len() / 2);
+    first
+        .iter()
+        .zip(second.iter())
+        .map(|(&ki, &kj)| ki + kj * t)
+        .collect()
+}
+
+/// Hash the context string to the element of G1 prepended to the message, see
+/// [SecretKey::sign_with_context](crate::SecretKey).
+pub(crate) fn hash_context<E: Pairing>(context: &[u8]) -> E::G1 {
+    hash_to_g1::<E>(b"mercurial-signature/context", context)
+}
+
+/// The message with the element `c` of the context prepended, i.e. `(c, M1,...,Ml)`.
+pub(crate) fn with_context_element<E: Pairing>(c: E::G1, message: &[E::G1]) -> Vec<E::G1> {
+    core::iter::once(c).chain(message.iter().copied()).collect()
+}
+
+/// Combine multiple messages of the same length into one message, where the `i`-th element is
+/// the hash of the `i`-th elements of all the messages in order. Returns `None` if the messages
+/// are empty or their lengths are different.
+pub(crate) fn hash_multi_message<E: Pairing>(messages: &[&[E::G1]]) -> Option<Vec<E::G1>> {
+    let length = messages.first()?.len();
+    if messages.iter().any(|message| message.len() != length) {
+        return None;
+    }
+
+    Some(
+        (0..length)
+            .map(|i| {
+                let elements = messages
+                    .iter()
+                    .map(|message| message[i])
+                    .collect::<Vec<_>>();
+                let mut data = (i as u64).to_le_bytes().to_vec();
+                elements.serialize_compressed(&mut data).unwrap();
+                hash_to_g1::<E>(b"mercurial-signature/multi-message", &data)
+            })
+            .collect(),
+    )
+}
+
+/// Signatures by the same key sharing `y1, y2`, see [Signature::aggregate_same_key].
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize)]
+pub struct AggregateSignature<E: Pairing> {
+    pub(crate) zs: Vec<E::G1>,
+    pub(crate) y1: E::G1,
+    pub(crate) y2: E::G2,
+}
+
+impl_bounded_deserialize!(AggregateSignature {
+    zs: vec,
+    y1: value,
+    y2: value
+});
+
+impl<E: Pairing> AggregateSignature<E> {
+    /// Number of aggregated signatures.
+    pub fn length(&self) -> usize {
+        self.zs.len()
+    }
+
+    /// Verify each message against its signature in the aggregate, in the order of aggregation.
+    pub fn verify_individual(
+        &self,
+        pk: &PublicKey<E>,
+        pp: &PublicParams<E>,
+        messages: &[&[E::G1]],
+    ) -> bool {
+        if self.zs.len() != messages.len() {
+            return false;
+        }
+
+        self.zs.iter().zip(messages.iter()).all(|(z, message)| {
+            let sig = Signature {
+                z: *z,
+                y1: self.y1,
+                y2: self.y2,
+            };
+            pk.verify(pp, message, &sig)
+        })
+    }
+}
+
+/// A partial signature computed from a subset of the secret key components.
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize)]
+pub struct PartialSignature<E: Pairing> {
+    // sum of xi Mi for i in indices
+    pub(crate) z: E::G1,
+    pub(crate) indices: Vec<usize>,
+}
+
+impl_bounded_deserialize!(PartialSignature {
+    z: value,
+    indices: vec
+});
+
+impl<E: Pairing> PartialSignature<E> {
+    /// Indices of the key components used in the partial signature.
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+}
+
+/// Combine partial signatures into a signature with a fresh randomness `y`.
+///
+/// ## Safety
+/// This function panics if the index sets of the partial signatures overlap.
+pub fn combine_partial_signatures<E: Pairing, R: RngCore + CryptoRng>(
+    rng: &mut R,
+    pp: &PublicParams<E>,
+    partials: &[PartialSignature<E>],
+) -> Signature<E> {
+    let mut indices = partials
+        .iter()
+        .flat_map(|partial| partial.indices.iter())
+        .collect::<Vec<_>>();
+    let len = indices.len();
+    indices.sort_unstable();
+    indices.dedup();
+    if indices.len() != len {
+        panic!("The index sets of the partial signatures must be disjoint.");
+    }
+
+    let y = E::ScalarField::rand(rng);
+    // z = (x1 M1 + ... + xl Ml) * y
+    let z = partials
+        .iter()
+        .fold(E::G1::zero(), |acc, partial| acc + partial.z)
+        .mul(y);
+    // y1 = p1^(1/y), y2 = p2^(1/y)
+    let y1 = pp.p1.mul(E::ScalarField::one() / y);
+    let y2 = pp.p2.mul(E::ScalarField::one() / y);
+    Signature { z, y1, y2 }
+}
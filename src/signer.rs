@@ -0,0 +1,364 @@
+//! Signing through an asynchronous signer, e.g. a KMS or an HSM holding the secret key.
+//!
+//! The higher-level protocols take an [AsyncSigner] in the `*_with_signer` functions, so that
+//! the secret key never has to be loaded into memory. [LocalSigner] implements the trait with
+//! a [SecretKey] in memory. A signer is runtime agnostic: the futures are driven by whatever
+//! executor the caller uses.
+
+use alloc::{boxed::Box, vec::Vec};
+use core::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+    task::{Context, Poll},
+};
+#[cfg(feature = "test-utils")]
+use std::{
+    sync::{Arc, Mutex},
+    task::Waker,
+    thread,
+    time::Duration,
+};
+
+use ark_ec::pairing::Pairing;
+use ark_serialize::CanonicalSerialize;
+use rand_core::{CryptoRng, RngCore};
+
+use crate::{
+    hash::hash_to_scalar, params::PublicParams, public_key::PublicKey, secret_key::SecretKey,
+    signature::Signature,
+};
+
+/// A signer which signs messages asynchronously, e.g. by a request to a remote service.
+pub trait AsyncSigner<E: Pairing> {
+    type Error;
+
+    /// The public key verifying the signatures of the signer.
+    fn public_key(&self) -> &PublicKey<E>;
+
+    /// Sign the message under the public parameters identified by `pp_digest`, see
+    /// [PublicParams::digest].
+    fn sign(
+        &self,
+        pp_digest: &[u8; 32],
+        message: &[E::G1],
+    ) -> impl Future<Output = Result<Signature<E>, Self::Error>> + Send;
+}
+
+/// Error of [LocalSigner].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LocalSignerError {
+    /// The digest is not of the public parameters of the signer.
+    UnknownParams,
+    /// The message is longer than the key.
+    MessageTooLong,
+}
+
+impl fmt::Display for LocalSignerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LocalSignerError::UnknownParams => {
+                f.write_str("the digest is not of the public parameters of the signer")
+            }
+            LocalSignerError::MessageTooLong => f.write_str("the message is longer than the key"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LocalSignerError {}
+
+/// A signer with the secret key in memory.
+pub struct LocalSigner<E: Pairing> {
+    pp: PublicParams<E>,
+    pp_digest: [u8; 32],
+    pk: PublicKey<E>,
+    sk: SecretKey<E>,
+    // the randomness y is derived from the seed, a counter and the message
+    seed: [u8; 32],
+    counter: AtomicUsize,
+}
+
+impl<E: Pairing> LocalSigner<E> {
+    /// Create a signer for the key pair under the public parameters.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::{AsyncSigner, LocalSigner, PublicParams, UniformRand, G1};
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let (pk, sk) = pp.key_gen(&mut rng, 3);
+    /// let signer = LocalSigner::new(&mut rng, &pp, pk, sk);
+    ///
+    /// let message = (0..3).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    /// let sig = signer.sign_now(&pp.digest(), &message).unwrap();
+    /// assert!(signer.public_key().verify(&pp, &message, &sig));
+    /// ```
+    pub fn new<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        pp: &PublicParams<E>,
+        pk: PublicKey<E>,
+        sk: SecretKey<E>,
+    ) -> Self {
+        let mut seed = [0u8; 32];
+        rng.fill_bytes(&mut seed);
+        LocalSigner {
+            pp: pp.clone(),
+            pp_digest: pp.digest(),
+            pk,
+            sk,
+            seed,
+            counter: AtomicUsize::new(0),
+        }
+    }
+
+    /// Sign the message now, without going through a future.
+    pub fn sign_now(
+        &self,
+        pp_digest: &[u8; 32],
+        message: &[E::G1],
+    ) -> Result<Signature<E>, LocalSignerError> {
+        if pp_digest != &self.pp_digest {
+            return Err(LocalSignerError::UnknownParams);
+        }
+        if message.len() > self.sk.length() {
+            return Err(LocalSignerError::MessageTooLong);
+        }
+        let counter = self.counter.fetch_add(1, Ordering::Relaxed) as u64;
+        let mut data = Vec::new();
+        data.extend_from_slice(&self.seed);
+        data.extend_from_slice(&counter.to_le_bytes());
+        message.serialize_compressed(&mut data).unwrap();
+        let y = hash_to_scalar::<E>(b"mercurial-signature/local-signer", &data);
+        Ok(self.sk.sign_with_y(&self.pp, message, y))
+    }
+}
+
+impl<E: Pairing> AsyncSigner<E> for LocalSigner<E> {
+    type Error = LocalSignerError;
+
+    fn public_key(&self) -> &PublicKey<E> {
+        &self.pk
+    }
+
+    async fn sign(
+        &self,
+        pp_digest: &[u8; 32],
+        message: &[E::G1],
+    ) -> Result<Signature<E>, LocalSignerError> {
+        self.sign_now(pp_digest, message)
+    }
+}
+
+/// Sign multiple messages with the signer. The requests are issued at once and awaited
+/// together, so that the latencies of a remote signer overlap. Unlike
+/// [SecretKey::sign_for_aggregation], the signatures do not share the randomness `y`.
+///
+/// It returns the first error in the order of the messages if any request fails.
+pub async fn sign_many_with_signer<E: Pairing, S: AsyncSigner<E>>(
+    signer: &S,
+    pp: &PublicParams<E>,
+    messages: &[&[E::G1]],
+) -> Result<Vec<Signature<E>>, S::Error> {
+    let pp_digest = pp.digest();
+    let requests = messages
+        .iter()
+        .map(|message| signer.sign(&pp_digest, message))
+        .collect();
+    JoinAll::new(requests).await.into_iter().collect()
+}
+
+/// Await all the futures, polling each of them until it is ready.
+struct JoinAll<F: Future> {
+    futures: Vec<Pin<Box<F>>>,
+    outputs: Vec<Option<F::Output>>,
+}
+
+impl<F: Future> JoinAll<F> {
+    fn new(futures: Vec<F>) -> Self {
+        JoinAll {
+            outputs: futures.iter().map(|_| None).collect(),
+            futures: futures.into_iter().map(Box::pin).collect(),
+        }
+    }
+}
+
+// the futures are pinned in their boxes and the outputs are never pinned
+impl<F: Future> Unpin for JoinAll<F> {}
+
+impl<F: Future> Future for JoinAll<F> {
+    type Output = Vec<F::Output>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        for (future, output) in this.futures.iter_mut().zip(this.outputs.iter_mut()) {
+            if output.is_none() {
+                if let Poll::Ready(value) = future.as_mut().poll(cx) {
+                    *output = Some(value);
+                }
+            }
+        }
+        if this.outputs.iter().all(Option::is_some) {
+            Poll::Ready(this.outputs.drain(..).map(Option::unwrap).collect())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// A remote signer for tests, wrapping a [LocalSigner] with injected latency and failures.
+#[cfg(feature = "test-utils")]
+pub struct MockRemoteSigner<E: Pairing> {
+    local: LocalSigner<E>,
+    latency: Duration,
+    fail_every: Option<usize>,
+    requests: AtomicUsize,
+    in_flight: AtomicUsize,
+    max_in_flight: AtomicUsize,
+}
+
+/// Error of [MockRemoteSigner].
+#[cfg(feature = "test-utils")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MockSignerError {
+    /// An injected failure, as if the remote service were unavailable.
+    Unavailable,
+    /// The request was rejected by the wrapped signer.
+    Rejected(LocalSignerError),
+}
+
+#[cfg(feature = "test-utils")]
+impl fmt::Display for MockSignerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MockSignerError::Unavailable => f.write_str("the remote signer is unavailable"),
+            MockSignerError::Rejected(e) => write!(f, "the request was rejected: {}", e),
+        }
+    }
+}
+
+#[cfg(feature = "test-utils")]
+impl std::error::Error for MockSignerError {}
+
+#[cfg(feature = "test-utils")]
+impl<E: Pairing> MockRemoteSigner<E> {
+    /// Wrap the signer without latency or failures.
+    pub fn new(local: LocalSigner<E>) -> Self {
+        MockRemoteSigner {
+            local,
+            latency: Duration::ZERO,
+            fail_every: None,
+            requests: AtomicUsize::new(0),
+            in_flight: AtomicUsize::new(0),
+            max_in_flight: AtomicUsize::new(0),
+        }
+    }
+
+    /// Delay each response by `latency`, on a thread so that it does not block the executor.
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Fail every `n`-th request, counting from one.
+    ///
+    /// ## Safety
+    /// This function panics if `n` is zero.
+    pub fn with_failure_every(mut self, n: usize) -> Self {
+        assert!(n > 0, "n must be positive");
+        self.fail_every = Some(n);
+        self
+    }
+
+    /// Number of requests received.
+    pub fn requests(&self) -> usize {
+        self.requests.load(Ordering::SeqCst)
+    }
+
+    /// Largest number of requests in flight at the same time.
+    pub fn max_in_flight(&self) -> usize {
+        self.max_in_flight.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(feature = "test-utils")]
+impl<E: Pairing> AsyncSigner<E> for MockRemoteSigner<E> {
+    type Error = MockSignerError;
+
+    fn public_key(&self) -> &PublicKey<E> {
+        self.local.public_key()
+    }
+
+    async fn sign(
+        &self,
+        pp_digest: &[u8; 32],
+        message: &[E::G1],
+    ) -> Result<Signature<E>, MockSignerError> {
+        let request = self.requests.fetch_add(1, Ordering::SeqCst) + 1;
+        let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+        Delay::new(self.latency).await;
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+        if self.fail_every.is_some_and(|n| request.is_multiple_of(n)) {
+            return Err(MockSignerError::Unavailable);
+        }
+        self.local
+            .sign_now(pp_digest, message)
+            .map_err(MockSignerError::Rejected)
+    }
+}
+
+/// A timer future, sleeping on a thread started at the first poll.
+#[cfg(feature = "test-utils")]
+struct Delay {
+    duration: Duration,
+    state: Option<Arc<Mutex<(bool, Waker)>>>,
+}
+
+#[cfg(feature = "test-utils")]
+impl Delay {
+    fn new(duration: Duration) -> Self {
+        Delay {
+            duration,
+            state: None,
+        }
+    }
+}
+
+#[cfg(feature = "test-utils")]
+impl Future for Delay {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.duration.is_zero() {
+            return Poll::Ready(());
+        }
+        match &self.state {
+            Some(state) => {
+                let mut state = state.lock().unwrap();
+                if state.0 {
+                    return Poll::Ready(());
+                }
+                state.1 = cx.waker().clone();
+                Poll::Pending
+            }
+            None => {
+                let state = Arc::new(Mutex::new((false, cx.waker().clone())));
+                let duration = self.duration;
+                let shared = state.clone();
+                thread::spawn(move || {
+                    thread::sleep(duration);
+                    let mut state = shared.lock().unwrap();
+                    state.0 = true;
+                    state.1.wake_by_ref();
+                });
+                self.state = Some(state);
+                Poll::Pending
+            }
+        }
+    }
+}
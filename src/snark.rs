@@ -0,0 +1,122 @@
+//! Verification of signatures in R1CS constraints, with the feature `snark`.
+//!
+//! A [VerifyCircuit] expresses [PublicKey::verify] as constraints of `ark-relations`, so that a
+//! SNARK, e.g. Groth16, proves that a signature verifies without revealing it. The circuit
+//! is over the base field of the curve, where the pairing gadget of `ark-r1cs-std`, e.g.
+//! [CurveBls12_381Var](crate::CurveBls12_381Var), computes the pairings natively. The public
+//! parameters, the public key and the message are public inputs, and the signature is the
+//! witness. The two pairing equations of the verification are enforced as constraints, so the
+//! constraint system is satisfied if and only if the signature verifies.
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use ark_ec::pairing::Pairing;
+use ark_ff::Field;
+use ark_r1cs_std::{
+    alloc::AllocVar, eq::EqGadget, fields::FieldVar, groups::CurveVar, pairing::PairingVar,
+};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+
+use crate::{params::PublicParams, public_key::PublicKey, signature::Signature};
+
+/// The field of the constraints of the circuits on the curve `E`.
+pub type ConstraintField<E> = <<E as Pairing>::BaseField as Field>::BasePrimeField;
+
+/// Circuit of the verification of a signature, see the [module](self) documentation.
+///
+/// ## Example
+///
+/// ```rust
+/// use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+/// use mercurial_signature::{PublicParams, UniformRand, VerifyCircuit, G1};
+///
+/// let mut rng = rand::thread_rng();
+/// let pp = PublicParams::new(&mut rng);
+/// let (pk, sk) = pp.key_gen(&mut rng, 1);
+/// let message = vec![G1::rand(&mut rng)];
+/// let sig = sk.sign(&mut rng, &pp, &message);
+///
+/// let cs = ConstraintSystem::new_ref();
+/// VerifyCircuit::new(pp, pk, message, sig)
+///     .generate_constraints(cs.clone())
+///     .unwrap();
+/// assert!(cs.is_satisfied().unwrap());
+/// ```
+pub struct VerifyCircuit<E: Pairing, P: PairingVar<E>> {
+    pp: PublicParams<E>,
+    pk: PublicKey<E>,
+    message: Vec<E::G1>,
+    sig: Signature<E>,
+    _pairing: PhantomData<P>,
+}
+
+impl<E: Pairing, P: PairingVar<E>> VerifyCircuit<E, P> {
+    /// Create the circuit of verifying the signature on the message under the public key.
+    pub fn new(
+        pp: PublicParams<E>,
+        pk: PublicKey<E>,
+        message: Vec<E::G1>,
+        sig: Signature<E>,
+    ) -> Self {
+        VerifyCircuit {
+            pp,
+            pk,
+            message,
+            sig,
+            _pairing: PhantomData,
+        }
+    }
+}
+
+impl<E: Pairing, P: PairingVar<E>> ConstraintSynthesizer<ConstraintField<E>>
+    for VerifyCircuit<E, P>
+{
+    /// Enforce the equations of [PublicKey::verify]. Returns
+    /// [SynthesisError::Unsatisfiable] if the message is longer than the public key.
+    fn generate_constraints(
+        self,
+        cs: ConstraintSystemRef<ConstraintField<E>>,
+    ) -> Result<(), SynthesisError> {
+        // check length l
+        if self.pk.bx.len() < self.message.len() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+
+        let p1 = P::G1Var::new_input(cs.clone(), || Ok(self.pp.p1))?;
+        let p2 = P::G2Var::new_input(cs.clone(), || Ok(self.pp.p2))?;
+        let bx = self.pk.bx[..self.message.len()]
+            .iter()
+            .map(|bxi| P::G2Var::new_input(cs.clone(), || Ok(*bxi)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let message = self
+            .message
+            .iter()
+            .map(|m| P::G1Var::new_input(cs.clone(), || Ok(*m)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let z = P::G1Var::new_witness(cs.clone(), || Ok(self.sig.z))?;
+        let y1 = P::G1Var::new_witness(cs.clone(), || Ok(self.sig.y1))?;
+        let y2 = P::G2Var::new_witness(cs.clone(), || Ok(self.sig.y2))?;
+
+        // e(y1, p2) * e(-p1, y2) == 1
+        let y2 = P::prepare_g2(&y2)?;
+        let lhs = P::product_of_pairings(
+            &[P::prepare_g1(&y1)?, P::prepare_g1(&p1.negate()?)?],
+            &[P::prepare_g2(&p2)?, y2.clone()],
+        )?;
+        lhs.enforce_equal(&P::GTVar::one())?;
+
+        // e(m1, bx1) * ... * e(ml, bxl) * e(-z, y2) == 1
+        let g1s = message
+            .iter()
+            .chain([&z.negate()?])
+            .map(P::prepare_g1)
+            .collect::<Result<Vec<_>, _>>()?;
+        let g2s = bx
+            .iter()
+            .map(P::prepare_g2)
+            .chain([Ok(y2)])
+            .collect::<Result<Vec<_>, _>>()?;
+        P::product_of_pairings(&g1s, &g2s)?.enforce_equal(&P::GTVar::one())
+    }
+}
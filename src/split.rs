@@ -0,0 +1,270 @@
+//! Two-party split signing, e.g. between an application server and an HSM-like co-signer.
+//!
+//! The secret key is split additively `x = a + b` into [ShareA] and [ShareB], so neither
+//! share alone can sign. Signing takes two messages:
+//!
+//! 1. Party A picks a nonce share `ya` and sends the blinded message `ya M`.
+//! 2. Party B picks a nonce share `yb`, blinds the message again to `ya yb M`, and sends it
+//!    back along with its contribution `b1 (ya yb M1) + ... + bl (ya yb Ml)` and
+//!    `(p1^(1/yb), p2^(1/yb))`.
+//!
+//! Party A then adds its own contribution and removes `ya` from `y1, y2`, resulting in a
+//! standard signature with nonce `y = ya yb`. Party A checks the nonce of party B and the
+//! final signature under the joint public key, so a faulty or malicious co-signer is
+//! detected as a [SplitError] instead of yielding an invalid signature.
+
+use alloc::vec::Vec;
+use core::{fmt, ops::Mul};
+
+use ark_ec::pairing::Pairing;
+use ark_serialize::CanonicalSerialize;
+use ark_std::{One, UniformRand, Zero};
+use rand_core::{CryptoRng, RngCore};
+
+use crate::{
+    ct::ct_eq_scalars, params::PublicParams, public_key::PublicKey, secret_key::SecretKey,
+    serialization::impl_bounded_deserialize, signature::Signature,
+};
+
+/// The share of the secret key held by party A, who starts and finalizes the signing.
+#[derive(Clone, CanonicalSerialize)]
+pub struct ShareA<E: Pairing> {
+    // (a1,...,al) where xi = ai + bi
+    pub(crate) a: Vec<E::ScalarField>,
+}
+
+impl_bounded_deserialize!(ShareA { a: vec });
+
+impl<E: Pairing> PartialEq for ShareA<E> {
+    fn eq(&self, other: &Self) -> bool {
+        ct_eq_scalars::<E>(&self.a, &other.a)
+    }
+}
+
+impl<E: Pairing> Eq for ShareA<E> {}
+
+/// The share of the secret key held by party B, the co-signer.
+#[derive(Clone, CanonicalSerialize)]
+pub struct ShareB<E: Pairing> {
+    // (b1,...,bl) where xi = ai + bi
+    pub(crate) b: Vec<E::ScalarField>,
+}
+
+impl_bounded_deserialize!(ShareB { b: vec });
+
+impl<E: Pairing> PartialEq for ShareB<E> {
+    fn eq(&self, other: &Self) -> bool {
+        ct_eq_scalars::<E>(&self.b, &other.b)
+    }
+}
+
+impl<E: Pairing> Eq for ShareB<E> {}
+
+/// The local state kept by party A between the first message and the finalization.
+/// It must not be sent to party B.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SplitSigningState<E: Pairing> {
+    ya: E::ScalarField,
+    message: Vec<E::G1>,
+}
+
+/// The message sent from party A to party B.
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize)]
+pub struct SplitRound1<E: Pairing> {
+    // ya M
+    pub(crate) m: Vec<E::G1>,
+}
+
+impl_bounded_deserialize!(SplitRound1 { m: vec });
+
+/// The message sent from party B to party A.
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize)]
+pub struct SplitRound2<E: Pairing> {
+    // ya yb M
+    pub(crate) m: Vec<E::G1>,
+    // b1 (ya yb M1) + ... + bl (ya yb Ml)
+    pub(crate) z: E::G1,
+    // p1^(1/yb)
+    pub(crate) y1: E::G1,
+    // p2^(1/yb)
+    pub(crate) y2: E::G2,
+}
+
+impl_bounded_deserialize!(SplitRound2 {
+    m: vec,
+    z: value,
+    y1: value,
+    y2: value
+});
+
+/// Error in finalizing the signature from the message of party B, who is to blame.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SplitError {
+    /// The nonce `y1, y2` of party B is zero or inconsistent, i.e. `e(y1, p2) != e(p1, y2)`.
+    InvalidNonce,
+    /// The final signature does not verify under the joint public key.
+    InvalidSignature,
+}
+
+impl fmt::Display for SplitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SplitError::InvalidNonce => f.write_str("the nonce of the co-signer is invalid"),
+            SplitError::InvalidSignature => {
+                f.write_str("the signature does not verify under the joint public key")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SplitError {}
+
+/// Split the secret key into two additive shares.
+///
+/// ## Example
+///
+/// ```rust
+/// use mercurial_signature::{split_secret_key, Fr, PublicParams, UniformRand, G1};
+///
+/// let mut rng = rand::thread_rng();
+/// let pp = PublicParams::new(&mut rng);
+/// let (pk, sk) = pp.key_gen(&mut rng, 10);
+/// let (share_a, share_b) = split_secret_key(&mut rng, &sk);
+/// let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+///
+/// let (state, round1) = share_a.sign_round1(&mut rng, &message);
+/// let round2 = share_b.sign_round2(&mut rng, &pp, &round1);
+/// let sig = share_a.finalize(&pp, &pk, state, &round2).unwrap();
+/// assert!(pk.verify(&pp, &message, &sig));
+/// ```
+pub fn split_secret_key<E: Pairing, R: RngCore + CryptoRng>(
+    rng: &mut R,
+    sk: &SecretKey<E>,
+) -> (ShareA<E>, ShareB<E>) {
+    let b = (0..sk.x.len())
+        .map(|_| E::ScalarField::rand(rng))
+        .collect::<Vec<E::ScalarField>>();
+    let a = sk.x.iter().zip(b.iter()).map(|(xi, bi)| *xi - bi).collect();
+    (ShareA { a }, ShareB { b })
+}
+
+impl<E: Pairing> ShareA<E> {
+    /// Length of the share.
+    pub fn length(&self) -> usize {
+        self.a.len()
+    }
+
+    /// Start signing a message. Returns the local state and the message to party B.
+    ///
+    /// ## Safety
+    /// This function panics if the length of the share is less than the length of the message.
+    pub fn sign_round1<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        message: &[E::G1],
+    ) -> (SplitSigningState<E>, SplitRound1<E>) {
+        if self.a.len() < message.len() {
+            panic!(
+                "The length of the share must be equal or greater than the length of the message."
+            );
+        }
+
+        let ya = E::ScalarField::rand(rng);
+        let m = message.iter().map(|mi| mi.mul(ya)).collect();
+        let state = SplitSigningState {
+            ya,
+            message: message.to_vec(),
+        };
+        (state, SplitRound1 { m })
+    }
+
+    /// Finalize the signature from the message of party B. The nonce of party B is checked by
+    /// `e(y1, p2) == e(p1, y2)`, and the final signature is verified under the joint public
+    /// key `pk`, i.e. the public key of the split secret key.
+    pub fn finalize(
+        &self,
+        pp: &PublicParams<E>,
+        pk: &PublicKey<E>,
+        state: SplitSigningState<E>,
+        round2: &SplitRound2<E>,
+    ) -> Result<Signature<E>, SplitError> {
+        if round2.y1.is_zero() || E::pairing(round2.y1, pp.p2) != E::pairing(pp.p1, round2.y2) {
+            return Err(SplitError::InvalidNonce);
+        }
+
+        // z = (b1 + a1) (ya yb M1) + ... + (bl + al) (ya yb Ml)
+        let z = round2
+            .m
+            .iter()
+            .zip(self.a.iter())
+            .fold(round2.z, |acc, (m, ai)| acc + m.mul(ai));
+        // y1 = p1^(1/(ya yb)), y2 = p2^(1/(ya yb))
+        let ya_inv = E::ScalarField::one() / state.ya;
+        let sig = Signature {
+            z,
+            y1: round2.y1.mul(ya_inv),
+            y2: round2.y2.mul(ya_inv),
+        };
+        if round2.m.len() != state.message.len() || !pk.verify(pp, &state.message, &sig) {
+            return Err(SplitError::InvalidSignature);
+        }
+        Ok(sig)
+    }
+
+    /// Convert the share. The input scalar `p` must be the same as the one used in the
+    /// conversion of the share of party B and the public key.
+    pub fn convert(&mut self, p: E::ScalarField) {
+        self.a.iter_mut().for_each(|ai| *ai *= p);
+    }
+}
+
+impl<E: Pairing> ShareB<E> {
+    /// Length of the share.
+    pub fn length(&self) -> usize {
+        self.b.len()
+    }
+
+    /// Respond to the message of party A.
+    ///
+    /// ## Safety
+    /// This function panics if the length of the share is less than the length of the message.
+    pub fn sign_round2<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        pp: &PublicParams<E>,
+        round1: &SplitRound1<E>,
+    ) -> SplitRound2<E> {
+        if self.b.len() < round1.m.len() {
+            panic!(
+                "The length of the share must be equal or greater than the length of the message."
+            );
+        }
+
+        let yb = E::ScalarField::rand(rng);
+        let m = round1.m.iter().map(|mi| mi.mul(yb)).collect::<Vec<E::G1>>();
+        let z = m
+            .iter()
+            .zip(self.b.iter())
+            .fold(E::G1::zero(), |acc, (m, bi)| acc + m.mul(bi));
+        let yb_inv = E::ScalarField::one() / yb;
+        SplitRound2 {
+            m,
+            z,
+            y1: pp.p1.mul(yb_inv),
+            y2: pp.p2.mul(yb_inv),
+        }
+    }
+
+    /// Convert the share. The input scalar `p` must be the same as the one used in the
+    /// conversion of the share of party A and the public key.
+    pub fn convert(&mut self, p: E::ScalarField) {
+        self.b.iter_mut().for_each(|bi| *bi *= p);
+    }
+}
+
+/// Convert both shares with the same scalar `p`, equivalent to converting the secret key.
+pub fn convert_shares<E: Pairing>(a: &mut ShareA<E>, b: &mut ShareB<E>, p: E::ScalarField) {
+    a.convert(p);
+    b.convert(p);
+}
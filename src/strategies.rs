@@ -0,0 +1,72 @@
+//! Strategies of [proptest] for property tests, with the feature `proptest`.
+//!
+//! The values are derived from bytes and scalars drawn by proptest, so that a failing case
+//! shrinks to a minimal counterexample: short keys and messages, and small scalars. Like
+//! the `fixtures` module, this module is test tooling exempt from semantic versioning.
+
+use alloc::vec::Vec;
+use ark_ec::PrimeGroup;
+use ark_ff::PrimeField;
+use ark_std::Zero;
+use proptest::prelude::*;
+use rand_chacha::ChaCha20Rng;
+use rand_core::SeedableRng;
+
+use crate::{Fr, PublicKey, PublicParams, SecretKey, G1, G2};
+
+/// A scalar, shrinking towards zero.
+pub fn scalar() -> impl Strategy<Value = Fr> {
+    any::<[u8; 32]>().prop_map(|bytes| Fr::from_le_bytes_mod_order(&bytes))
+}
+
+/// A non-zero scalar, e.g. for a conversion or a change of representation.
+pub fn nonzero_scalar() -> impl Strategy<Value = Fr> {
+    scalar().prop_filter("non-zero scalar", |s| !s.is_zero())
+}
+
+/// A generator of randomness, e.g. for signing.
+pub fn rng() -> impl Strategy<Value = ChaCha20Rng> {
+    any::<[u8; 32]>().prop_map(ChaCha20Rng::from_seed)
+}
+
+/// A message of a length in `length`.
+pub fn message(length: core::ops::RangeInclusive<usize>) -> impl Strategy<Value = Vec<G1>> {
+    prop::collection::vec(scalar(), length)
+        .prop_map(|s| s.into_iter().map(|si| G1::generator() * si).collect())
+}
+
+/// Public parameters with random generators.
+pub fn public_params() -> impl Strategy<Value = PublicParams> {
+    (nonzero_scalar(), nonzero_scalar()).prop_map(|(a, b)| PublicParams {
+        p1: G1::generator() * a,
+        p2: G2::generator() * b,
+    })
+}
+
+/// A key pair under `pp` of a length in `length`.
+pub fn key_pair(
+    pp: PublicParams,
+    length: core::ops::RangeInclusive<usize>,
+) -> impl Strategy<Value = (PublicKey, SecretKey)> {
+    prop::collection::vec(nonzero_scalar(), length).prop_map(move |x| {
+        let sk = SecretKey { x };
+        (PublicKey::from_noisy_sk(&pp, &sk), sk)
+    })
+}
+
+/// Public parameters, a key pair of a length up to `max_length`, and a message no longer than
+/// the key.
+///
+/// ## Safety
+/// This function panics if `max_length` is zero.
+pub fn key_pair_and_message(
+    max_length: usize,
+) -> impl Strategy<Value = (PublicParams, PublicKey, SecretKey, Vec<G1>)> {
+    assert!(max_length > 0);
+    public_params()
+        .prop_flat_map(move |pp| (Just(pp.clone()), key_pair(pp, 1..=max_length)))
+        .prop_flat_map(|(pp, (pk, sk))| {
+            let length = sk.length();
+            (Just(pp), Just(pk), Just(sk), message(1..=length))
+        })
+}
@@ -0,0 +1,150 @@
+//! Pairing products of the verification, for composing it with other pairing checks.
+//!
+//! [PublicKey::verify] checks two equations, each of which is a product of pairings equal to
+//! the identity of the target group:
+//!
+//! 1. `e(y1, p2) * e(-p1, y2) == 1`,
+//! 2. `e(M1, bx1) * ... * e(Ml, bxl) * e(-z, y2) == 1`.
+//!
+//! [VerificationTerms] holds the pairs of both equations without evaluating them. A protocol
+//! with its own pairing checks folds them into a [MultiPairingAccumulator] with a random
+//! challenge, and runs one multi-pairing for all of them.
+
+use alloc::vec::Vec;
+use ark_ec::pairing::Pairing;
+use ark_std::Zero;
+use core::ops::Mul;
+
+use crate::{params::PublicParams, public_key::PublicKey, signature::Signature};
+
+/// The pairing products of the verification of a signature, see the [module](self)
+/// documentation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerificationTerms<E: Pairing> {
+    // the pairs of e(y1, p2) * e(-p1, y2) == 1
+    pub(crate) randomness_pairs: Vec<(E::G1, E::G2)>,
+    // the pairs of e(M1, bx1) * ... * e(Ml, bxl) * e(-z, y2) == 1
+    pub(crate) message_pairs: Vec<(E::G1, E::G2)>,
+    // false if the public key is shorter than the message
+    pub(crate) well_formed: bool,
+}
+
+impl<E: Pairing> VerificationTerms<E> {
+    /// Collect the pairs of the equations of [PublicKey::verify]. If the public key is shorter
+    /// than the message, the terms are not well formed and always reject.
+    pub fn new(
+        pp: &PublicParams<E>,
+        pk: &PublicKey<E>,
+        message: &[E::G1],
+        sig: &Signature<E>,
+    ) -> Self {
+        let well_formed = pk.bx.len() >= message.len();
+        let message_pairs = if well_formed {
+            message
+                .iter()
+                .copied()
+                .zip(pk.bx.iter().copied())
+                .chain([(-sig.z, sig.y2)])
+                .collect()
+        } else {
+            Vec::new()
+        };
+        VerificationTerms {
+            randomness_pairs: alloc::vec![(sig.y1, pp.p2), (-pp.p1, sig.y2)],
+            message_pairs,
+            well_formed,
+        }
+    }
+
+    /// The pairs whose product of pairings is the identity if `y1, y2` are consistent.
+    pub fn randomness_pairs(&self) -> &[(E::G1, E::G2)] {
+        &self.randomness_pairs
+    }
+
+    /// The pairs whose product of pairings is the identity if `z` signs the message. It is
+    /// empty if the terms are not well formed.
+    pub fn message_pairs(&self) -> &[(E::G1, E::G2)] {
+        &self.message_pairs
+    }
+
+    /// Whether the public key is at least as long as the message.
+    pub fn is_well_formed(&self) -> bool {
+        self.well_formed
+    }
+
+    /// Evaluate both equations, with the same decision as [PublicKey::verify].
+    pub fn check(&self) -> bool {
+        self.well_formed
+            && is_identity::<E>(&self.randomness_pairs)
+            && is_identity::<E>(&self.message_pairs)
+    }
+
+    /// Add both equations to the accumulator, weighted by `challenge` and `challenge^2`, so
+    /// that they cannot cancel each other or the other terms of the accumulator.
+    ///
+    /// ## Security
+    /// The `challenge` must be uniformly random and independent for each call, e.g. sampled
+    /// by the verifier or derived by Fiat-Shamir from all the inputs of the accumulator. With a
+    /// predictable challenge, an invalid signature can be crafted to offset another term.
+    pub fn fold_into(&self, acc: &mut MultiPairingAccumulator<E>, challenge: E::ScalarField) {
+        if !self.well_formed {
+            acc.rejected = true;
+            return;
+        }
+
+        let challenge_squared = challenge * challenge;
+        self.randomness_pairs
+            .iter()
+            .for_each(|(g1, g2)| acc.add(g1.mul(challenge), *g2));
+        self.message_pairs
+            .iter()
+            .for_each(|(g1, g2)| acc.add(g1.mul(challenge_squared), *g2));
+    }
+}
+
+/// Pairs of a product of pairings which is checked to be the identity by a single
+/// multi-pairing, see [VerificationTerms::fold_into].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultiPairingAccumulator<E: Pairing> {
+    g1s: Vec<E::G1>,
+    g2s: Vec<E::G2>,
+    rejected: bool,
+}
+
+impl<E: Pairing> Default for MultiPairingAccumulator<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Pairing> MultiPairingAccumulator<E> {
+    /// Create an empty accumulator, which accepts.
+    pub fn new() -> Self {
+        MultiPairingAccumulator {
+            g1s: Vec::new(),
+            g2s: Vec::new(),
+            rejected: false,
+        }
+    }
+
+    /// Multiply `e(g1, g2)` into the product, e.g. for a pairing check of the caller's protocol.
+    pub fn add(&mut self, g1: E::G1, g2: E::G2) {
+        self.g1s.push(g1);
+        self.g2s.push(g2);
+    }
+
+    /// Number of pairings in the product.
+    pub fn length(&self) -> usize {
+        self.g1s.len()
+    }
+
+    /// Check the product of the pairings is the identity, and no folded terms were malformed.
+    pub fn verify(&self) -> bool {
+        !self.rejected && E::multi_pairing(&self.g1s, &self.g2s).is_zero()
+    }
+}
+
+fn is_identity<E: Pairing>(pairs: &[(E::G1, E::G2)]) -> bool {
+    let (g1s, g2s): (Vec<_>, Vec<_>) = pairs.iter().copied().unzip();
+    E::multi_pairing(g1s, g2s).is_zero()
+}
@@ -0,0 +1,526 @@
+//! Threshold signing producing standard signatures.
+//!
+//! The secret key is shared with Shamir's scheme so that any `t` of the `n` parties can
+//! jointly sign a message. The nonce is shared multiplicatively, `y = y_1 * ... * y_t`, and
+//! the signing protocol has two rounds:
+//!
+//! 1. The signers randomize the message and the nonce one after another. Signer `j` picks
+//!    `y_j`, multiplies the running message by `y_j` and `y1, y2` by `1/y_j`. Starting from
+//!    `(M, p1, p2)`, the result is `(y M, p1/y, p2/y)` and no single party knows `y`. The
+//!    aggregator checks each step with pairings before forwarding the running value to the
+//!    next signer.
+//! 2. Each signer computes its Lagrange-weighted contribution to `x1 M1' + ... + xl Ml'` for
+//!    the randomized message `M' = y M`. The aggregator checks each contribution against the
+//!    signer's verification share, and their sum is `z` of the signature `(z, p1/y, p2/y)`
+//!    on `M`.
+//!
+//! The aggregator holds no signature before the second round: `z` needs the contributions of
+//! the signers on the randomized message, whose nonce none of them knows.
+//!
+//! A signer that does not respond or responds with an inconsistent message is identified
+//! by its index in the returned [ThresholdError].
+
+use alloc::{vec, vec::Vec};
+use core::{fmt, ops::Mul};
+
+use ark_ec::pairing::Pairing;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{One, UniformRand, Zero};
+use rand_core::{CryptoRng, RngCore};
+
+use crate::{
+    ct::ct_eq_scalars, params::PublicParams, secret_key::SecretKey,
+    serialization::impl_bounded_deserialize, signature::Signature, transcript::Transcript,
+};
+
+/// A Shamir share of the secret key held by the party with index `index` (starting from 1).
+#[derive(Clone, CanonicalSerialize)]
+pub struct KeyShare<E: Pairing> {
+    pub(crate) index: u32,
+    // (f1(index),...,fl(index)) where fi is the sharing polynomial of xi
+    pub(crate) x: Vec<E::ScalarField>,
+}
+
+impl_bounded_deserialize!(KeyShare {
+    index: value,
+    x: vec
+});
+
+impl<E: Pairing> PartialEq for KeyShare<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index && ct_eq_scalars::<E>(&self.x, &other.x)
+    }
+}
+
+impl<E: Pairing> Eq for KeyShare<E> {}
+
+/// The public counterpart of a [KeyShare], used to check the messages of the signer.
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize)]
+pub struct VerificationShare<E: Pairing> {
+    pub(crate) index: u32,
+    // (p2^f1(index),...,p2^fl(index))
+    pub(crate) bx: Vec<E::G2>,
+}
+
+impl_bounded_deserialize!(VerificationShare {
+    index: value,
+    bx: vec
+});
+
+/// The message and the nonce randomized by the nonce shares of the signers so far, passed
+/// from signer to signer in the first round.
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize)]
+pub struct RandomizedMessage<E: Pairing> {
+    // y M where y is the product of the applied nonce shares
+    pub(crate) message: Vec<E::G1>,
+    // p1^(1/y), p2^(1/y)
+    pub(crate) y1: E::G1,
+    pub(crate) y2: E::G2,
+}
+
+impl_bounded_deserialize!(RandomizedMessage {
+    message: vec,
+    y1: value,
+    y2: value
+});
+
+/// The message sent by a signer to the aggregator in the first round. It carries the running
+/// value after the signer applied its share of the nonce.
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize)]
+pub struct Round1Message<E: Pairing> {
+    pub(crate) index: u32,
+    pub(crate) randomized: RandomizedMessage<E>,
+}
+
+impl_bounded_deserialize!(Round1Message {
+    index: value,
+    randomized: value
+});
+
+/// The message sent by a signer to the aggregator in the second round.
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Round2Message<E: Pairing> {
+    pub(crate) index: u32,
+    // lambda_j * (f1(j) M1' + ... + fl(j) Ml')
+    pub(crate) z: E::G1,
+}
+
+/// A Feldman commitment `p2^a` to a coefficient `a` of a sharing polynomial.
+pub type FeldmanCommitment<E> = <E as Pairing>::G2;
+
+/// The key shares and the verification shares dealt by [deal_shares], in the order of the
+/// indices of the parties.
+type DealtShares<E> = (Vec<KeyShare<E>>, Vec<VerificationShare<E>>);
+
+/// The key shares and the Feldman commitments of [SecretKey::to_shares_with_proof].
+type CommittedShares<E> = (Vec<KeyShare<E>>, Vec<FeldmanCommitment<E>>);
+
+/// Errors of the threshold signing protocol. The index identifies the party to blame.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ThresholdError {
+    /// The threshold is zero or greater than the number of parties.
+    InvalidThreshold,
+    /// The number of signers is less than the threshold.
+    NotEnoughSigners,
+    /// The party is not a known signer of this session.
+    UnknownSigner(u32),
+    /// The party did not send its message.
+    MissingMessage(u32),
+    /// The message of the party does not pass the check against its verification share.
+    InvalidMessage(u32),
+}
+
+impl fmt::Display for ThresholdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThresholdError::InvalidThreshold => {
+                f.write_str("the threshold must be positive and not greater than the parties")
+            }
+            ThresholdError::NotEnoughSigners => {
+                f.write_str("the number of signers is less than the threshold")
+            }
+            ThresholdError::UnknownSigner(i) => write!(f, "the party {} is not a known signer", i),
+            ThresholdError::MissingMessage(i) => {
+                write!(f, "the party {} did not send its message", i)
+            }
+            ThresholdError::InvalidMessage(i) => {
+                write!(f, "the message of the party {} is invalid", i)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ThresholdError {}
+
+/// Split the secret key into `n` shares such that any `t` of them can sign. Returns
+/// [ThresholdError::InvalidThreshold] if `t` is zero or greater than `n`.
+///
+/// ## Example
+///
+/// ```rust
+/// use mercurial_signature::{deal_shares, PublicParams, ThresholdAggregator, G1};
+/// use mercurial_signature::UniformRand;
+///
+/// let mut rng = rand::thread_rng();
+/// let pp = PublicParams::new(&mut rng);
+/// let (pk, sk) = pp.key_gen(&mut rng, 10);
+/// let (shares, vshares) = deal_shares(&mut rng, &pp, &sk, 3, 5).unwrap();
+/// let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+///
+/// let signers = [1, 3, 5];
+/// let mut aggregator = ThresholdAggregator::new(&pp, &vshares, &message, &signers, 3).unwrap();
+///
+/// let mut running = aggregator.running().clone();
+/// for &j in signers.iter() {
+///     let msg = shares[j as usize - 1].sign_round1(&mut rng, &running);
+///     running = aggregator.receive_round1(&msg).unwrap();
+/// }
+/// let round2 = signers
+///     .iter()
+///     .map(|&j| shares[j as usize - 1].sign_round2(&running, &signers))
+///     .collect::<Vec<_>>();
+/// let sig = aggregator.finalize(&round2).unwrap();
+/// assert!(pk.verify(&pp, &message, &sig));
+/// ```
+pub fn deal_shares<E: Pairing, R: RngCore + CryptoRng>(
+    rng: &mut R,
+    pp: &PublicParams<E>,
+    sk: &SecretKey<E>,
+    t: u32,
+    n: u32,
+) -> Result<DealtShares<E>, ThresholdError> {
+    if t == 0 || t > n {
+        return Err(ThresholdError::InvalidThreshold);
+    }
+
+    let polys = sharing_polynomials(rng, sk, t);
+    Ok((1..=n)
+        .map(|index| {
+            let x = evaluate_polynomials::<E>(&polys, index);
+            let bx = x.iter().map(|xi| pp.p2.mul(xi)).collect();
+            (KeyShare { index, x }, VerificationShare { index, bx })
+        })
+        .unzip())
+}
+
+impl<E: Pairing> SecretKey<E> {
+    /// Split the secret key into `n` shares such that any `t` of them can sign, as
+    /// [deal_shares] does, with Feldman commitments to the sharing polynomials so that each
+    /// party can check its share by [KeyShare::verify_against_commitments] without trusting
+    /// the dealer.
+    ///
+    /// The commitments are `p2^a_ik` for the coefficients `a_i0 = xi, a_i1, ..., a_i(t-1)` of
+    /// the polynomial of `xi`, ordered by `i` and then by `k`. The commitments `p2^a_i0` are
+    /// the elements of the public key. Returns [ThresholdError::InvalidThreshold] if `t` is
+    /// zero or greater than `n`.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::PublicParams;
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let (_, sk) = pp.key_gen(&mut rng, 10);
+    /// let (shares, commitments) = sk.to_shares_with_proof(&mut rng, &pp, 3, 5).unwrap();
+    /// assert!(shares
+    ///     .iter()
+    ///     .all(|share| share.verify_against_commitments(&commitments, &pp)));
+    /// ```
+    pub fn to_shares_with_proof<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        pp: &PublicParams<E>,
+        t: u32,
+        n: u32,
+    ) -> Result<CommittedShares<E>, ThresholdError> {
+        if t == 0 || t > n {
+            return Err(ThresholdError::InvalidThreshold);
+        }
+
+        let polys = sharing_polynomials(rng, self, t);
+        let commitments = polys
+            .iter()
+            .flat_map(|coeffs| coeffs.iter().map(|a| pp.p2.mul(a)))
+            .collect();
+        let shares = (1..=n)
+            .map(|index| KeyShare {
+                index,
+                x: evaluate_polynomials::<E>(&polys, index),
+            })
+            .collect();
+        Ok((shares, commitments))
+    }
+}
+
+impl<E: Pairing> KeyShare<E> {
+    /// Index of the party holding this share.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Length of the key share.
+    pub fn length(&self) -> usize {
+        self.x.len()
+    }
+
+    /// Check the share against the Feldman commitments of the dealer, see
+    /// [SecretKey::to_shares_with_proof], i.e. `p2^fi(index) == C_i0 + index C_i1 + ... +
+    /// index^(t-1) C_i(t-1)` for each element of the share.
+    pub fn verify_against_commitments(
+        &self,
+        commitments: &[FeldmanCommitment<E>],
+        pp: &PublicParams<E>,
+    ) -> bool {
+        if self.x.is_empty()
+            || commitments.is_empty()
+            || !commitments.len().is_multiple_of(self.x.len())
+        {
+            return false;
+        }
+
+        let t = commitments.len() / self.x.len();
+        let point = E::ScalarField::from(self.index as u64);
+        self.x.iter().zip(commitments.chunks(t)).all(|(xi, ci)| {
+            let expected = ci
+                .iter()
+                .rev()
+                .fold(E::G2::zero(), |acc, c| acc.mul(point) + c);
+            pp.p2.mul(xi) == expected
+        })
+    }
+
+    /// Compute the first round message by applying a fresh nonce share `y_j` to the running
+    /// value sent by the aggregator, i.e. multiplying the message by `y_j` and `y1, y2` by
+    /// `1/y_j`.
+    pub fn sign_round1<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        running: &RandomizedMessage<E>,
+    ) -> Round1Message<E> {
+        let yj = E::ScalarField::rand(rng);
+        let yj_inv = E::ScalarField::one() / yj;
+        Round1Message {
+            index: self.index,
+            randomized: RandomizedMessage {
+                message: running.message.iter().map(|m| m.mul(yj)).collect(),
+                y1: running.y1.mul(yj_inv),
+                y2: running.y2.mul(yj_inv),
+            },
+        }
+    }
+
+    /// Compute the second round message, i.e. the contribution of this share to the combined
+    /// value `x1 M1' + ... + xl Ml'` of the randomized message `M'` of the first round. The
+    /// `signers` must be the same for all parties in the session.
+    ///
+    /// ## Safety
+    /// This function panics if the length of the key share is less than the length of the message.
+    pub fn sign_round2(
+        &self,
+        randomized: &RandomizedMessage<E>,
+        signers: &[u32],
+    ) -> Round2Message<E> {
+        if self.x.len() < randomized.message.len() {
+            panic!("The length of the key share must be equal or greater than the length of the message.");
+        }
+
+        let lambda = lagrange_coefficient::<E>(self.index, signers);
+        let z = randomized
+            .message
+            .iter()
+            .zip(self.x.iter())
+            .fold(E::G1::zero(), |acc, (m, xi)| acc + m.mul(lambda * xi));
+        Round2Message {
+            index: self.index,
+            z,
+        }
+    }
+}
+
+impl<E: Pairing> VerificationShare<E> {
+    /// Index of the party holding the corresponding key share.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+}
+
+/// The aggregator of a threshold signing session. It checks the messages of the signers
+/// and assembles the final signature.
+pub struct Aggregator<'a, E: Pairing> {
+    pp: &'a PublicParams<E>,
+    vshares: &'a [VerificationShare<E>],
+    signers: Vec<u32>,
+    // the running value of the first round, starting from (M, p1, p2)
+    running: RandomizedMessage<E>,
+    // the signers who have applied their nonce shares in the first round
+    randomized: Vec<u32>,
+}
+
+impl<'a, E: Pairing> Aggregator<'a, E> {
+    /// Create an aggregator for signing `message` by the parties in `signers`.
+    pub fn new(
+        pp: &'a PublicParams<E>,
+        vshares: &'a [VerificationShare<E>],
+        message: &[E::G1],
+        signers: &[u32],
+        t: u32,
+    ) -> Result<Self, ThresholdError> {
+        let mut signers = signers.to_vec();
+        signers.sort_unstable();
+        signers.dedup();
+        if signers.is_empty() || signers.len() < t as usize {
+            return Err(ThresholdError::NotEnoughSigners);
+        }
+        if let Some(&j) = signers
+            .iter()
+            .find(|&&j| !vshares.iter().any(|vs| vs.index == j))
+        {
+            return Err(ThresholdError::UnknownSigner(j));
+        }
+
+        Ok(Aggregator {
+            pp,
+            vshares,
+            signers,
+            running: RandomizedMessage {
+                message: message.to_vec(),
+                y1: pp.p1,
+                y2: pp.p2,
+            },
+            randomized: Vec::new(),
+        })
+    }
+
+    /// The running value of the first round, to be sent to the next signer. After every
+    /// signer has applied its nonce share, it is the randomized message of the second round.
+    pub fn running(&self) -> &RandomizedMessage<E> {
+        &self.running
+    }
+
+    /// Check a first round message against the running value. Returns the updated running
+    /// value to be sent to the next signer.
+    pub fn receive_round1(
+        &mut self,
+        msg: &Round1Message<E>,
+    ) -> Result<RandomizedMessage<E>, ThresholdError> {
+        if !self.signers.contains(&msg.index) || self.randomized.contains(&msg.index) {
+            return Err(ThresholdError::UnknownSigner(msg.index));
+        }
+
+        // y1' != 0, e(y1', p2) == e(p1, y2') and e(Mi', y2') == e(Mi, y2) for each i, checked
+        // together with the weights c^i derived from both values
+        let old = &self.running;
+        let new = &msg.randomized;
+        if new.message.len() != old.message.len()
+            || new.y1.is_zero()
+            || E::pairing(new.y1, self.pp.p2) != E::pairing(self.pp.p1, new.y2)
+        {
+            return Err(ThresholdError::InvalidMessage(msg.index));
+        }
+        let mut transcript = Transcript::new(b"mercurial-signature/threshold-nonce");
+        transcript.append(b"running", old);
+        transcript.append(b"randomized", new);
+        let c = transcript.challenge_scalar::<E>(b"challenge");
+        let (weighted_new, weighted_old, _) = new.message.iter().zip(old.message.iter()).fold(
+            (E::G1::zero(), E::G1::zero(), E::ScalarField::one()),
+            |(acc_new, acc_old, ci), (m_new, m_old)| {
+                (acc_new + m_new.mul(ci), acc_old + m_old.mul(ci), ci * c)
+            },
+        );
+        if !E::multi_pairing([weighted_new, -weighted_old], [new.y2, old.y2]).is_zero() {
+            return Err(ThresholdError::InvalidMessage(msg.index));
+        }
+
+        self.running = new.clone();
+        self.randomized.push(msg.index);
+        Ok(new.clone())
+    }
+
+    /// Check and combine the second round messages into the final signature. Every signer
+    /// must have applied its nonce share in the first round.
+    pub fn finalize(self, msgs: &[Round2Message<E>]) -> Result<Signature<E>, ThresholdError> {
+        if let Some(&j) = self.signers.iter().find(|j| !self.randomized.contains(j)) {
+            return Err(ThresholdError::MissingMessage(j));
+        }
+        if let Some(msg) = msgs.iter().find(|msg| !self.signers.contains(&msg.index)) {
+            return Err(ThresholdError::UnknownSigner(msg.index));
+        }
+
+        let message = &self.running.message;
+        let mut z = E::G1::zero();
+        for &j in self.signers.iter() {
+            let msg = msgs
+                .iter()
+                .find(|msg| msg.index == j)
+                .ok_or(ThresholdError::MissingMessage(j))?;
+            let vshare = self.vshares.iter().find(|vs| vs.index == j).unwrap();
+
+            // e(zj, p2) == e(lambda_j M1', p2^f1(j)) * ... * e(lambda_j Ml', p2^fl(j))
+            let lambda = lagrange_coefficient::<E>(j, &self.signers);
+            if vshare.bx.len() < message.len() {
+                return Err(ThresholdError::InvalidMessage(j));
+            }
+            let lhs = E::pairing(msg.z, self.pp.p2);
+            let rhs = E::multi_pairing(
+                message.iter().map(|m| m.mul(lambda)),
+                vshare.bx.iter().take(message.len()).copied(),
+            );
+            if lhs != rhs {
+                return Err(ThresholdError::InvalidMessage(j));
+            }
+            z += msg.z;
+        }
+
+        Ok(Signature {
+            z,
+            y1: self.running.y1,
+            y2: self.running.y2,
+        })
+    }
+}
+
+/// Polynomial `fi(X) = xi + a_i1 X + ... + a_i(t-1) X^(t-1)` for each `xi`, as coefficients.
+fn sharing_polynomials<E: Pairing, R: RngCore + CryptoRng>(
+    rng: &mut R,
+    sk: &SecretKey<E>,
+    t: u32,
+) -> Vec<Vec<E::ScalarField>> {
+    sk.x.iter()
+        .map(|xi| {
+            let mut coeffs = vec![*xi];
+            coeffs.extend((1..t).map(|_| E::ScalarField::rand(rng)));
+            coeffs
+        })
+        .collect()
+}
+
+/// Evaluate the polynomials at the index of a party.
+fn evaluate_polynomials<E: Pairing>(
+    polys: &[Vec<E::ScalarField>],
+    index: u32,
+) -> Vec<E::ScalarField> {
+    let point = E::ScalarField::from(index as u64);
+    polys
+        .iter()
+        .map(|coeffs| {
+            coeffs
+                .iter()
+                .rev()
+                .fold(E::ScalarField::zero(), |acc, c| acc * point + c)
+        })
+        .collect()
+}
+
+/// Lagrange coefficient of party `j` for interpolating at zero over the `signers`.
+fn lagrange_coefficient<E: Pairing>(j: u32, signers: &[u32]) -> E::ScalarField {
+    let xj = E::ScalarField::from(j as u64);
+    signers
+        .iter()
+        .filter(|&&k| k != j)
+        .fold(E::ScalarField::one(), |acc, &k| {
+            let xk = E::ScalarField::from(k as u64);
+            acc * xk / (xk - xj)
+        })
+}
@@ -0,0 +1,190 @@
+//! Fiat-Shamir transcript shared by the proofs of this crate.
+//!
+//! A [Transcript] is a running SHA-512 hash over the statement and the commitments of a proof.
+//! Every message is framed by its label and its length, so that different sequences of
+//! messages never hash the same. A challenge is derived from the messages so far and is itself
+//! absorbed, so that the later challenges depend on the earlier ones.
+//!
+//! Label conventions:
+//! - The domain of a transcript is `mercurial-signature/<protocol>`, unique for each proof.
+//! - The labels of the messages and the challenges are short lowercase ASCII names of the
+//!   values, e.g. `pp`, `pk`, `commitment`, `challenge`, without the domain prefix.
+//!
+//! With the feature `test-utils`, the transcripts are recorded when their challenges are
+//! derived, see [take_recorded_transcripts], so that the tests can replay them.
+
+use alloc::vec::Vec;
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+use sha2::{Digest, Sha512};
+
+/// A Fiat-Shamir transcript.
+///
+/// ## Safety
+/// The functions appending to the transcript panic if the label is empty or not lowercase
+/// ASCII, i.e. `a-z`, `0-9`, `-`, `_` or `/`.
+///
+/// ## Example
+///
+/// ```rust
+/// use ark_bls12_381::Bls12_381;
+/// use mercurial_signature::{Transcript, UniformRand, G1};
+///
+/// let mut rng = rand::thread_rng();
+/// let point = G1::rand(&mut rng);
+///
+/// let mut transcript = Transcript::new(b"mercurial-signature/example");
+/// transcript.append_point_g1::<Bls12_381>(b"point", &point);
+/// let c1 = transcript.clone().challenge_scalar::<Bls12_381>(b"challenge");
+///
+/// let mut transcript = Transcript::new(b"mercurial-signature/another-example");
+/// transcript.append_point_g1::<Bls12_381>(b"point", &point);
+/// let c2 = transcript.challenge_scalar::<Bls12_381>(b"challenge");
+/// assert!(c1 != c2);
+/// ```
+#[derive(Clone)]
+pub struct Transcript {
+    hasher: Sha512,
+    #[cfg(feature = "test-utils")]
+    record: RecordedTranscript,
+}
+
+impl Transcript {
+    /// Create a transcript for the domain of a proof, e.g. `mercurial-signature/same-message`.
+    pub fn new(domain: &[u8]) -> Self {
+        check_label(domain);
+        let mut hasher = Sha512::new();
+        hasher.update((domain.len() as u64).to_le_bytes());
+        hasher.update(domain);
+        Transcript {
+            hasher,
+            #[cfg(feature = "test-utils")]
+            record: RecordedTranscript {
+                domain: domain.to_vec(),
+                entries: Vec::new(),
+            },
+        }
+    }
+
+    /// Append bytes to the transcript.
+    pub fn append_bytes(&mut self, label: &[u8], bytes: &[u8]) {
+        self.frame(b"bytes", label, bytes);
+        #[cfg(feature = "test-utils")]
+        self.record.entries.push(TranscriptEntry::Message {
+            label: label.to_vec(),
+            bytes: bytes.to_vec(),
+        });
+    }
+
+    /// Append an element of G1 to the transcript.
+    pub fn append_point_g1<E: Pairing>(&mut self, label: &[u8], point: &E::G1) {
+        self.append(label, point);
+    }
+
+    /// Append an element of G2 to the transcript.
+    pub fn append_point_g2<E: Pairing>(&mut self, label: &[u8], point: &E::G2) {
+        self.append(label, point);
+    }
+
+    /// Append a scalar to the transcript.
+    pub fn append_scalar<E: Pairing>(&mut self, label: &[u8], scalar: &E::ScalarField) {
+        self.append(label, scalar);
+    }
+
+    /// Derive a challenge from the transcript. The challenge is absorbed into the transcript,
+    /// so that the next challenge is different.
+    pub fn challenge_scalar<E: Pairing>(&mut self, label: &[u8]) -> E::ScalarField {
+        self.frame(b"challenge", label, &[]);
+        let output = self.hasher.clone().finalize();
+        self.hasher.update(output);
+
+        #[cfg(feature = "test-utils")]
+        {
+            self.record.entries.push(TranscriptEntry::Challenge {
+                label: label.to_vec(),
+                output: output.to_vec(),
+            });
+            RECORDED.with(|recorded| recorded.borrow_mut().push(self.record.clone()));
+        }
+
+        E::ScalarField::from_le_bytes_mod_order(&output)
+    }
+
+    /// Append the compressed serialization of the value, e.g. the public parameters or a vector
+    /// of points.
+    pub(crate) fn append<T: CanonicalSerialize + ?Sized>(&mut self, label: &[u8], value: &T) {
+        let mut bytes = Vec::new();
+        value.serialize_compressed(&mut bytes).unwrap();
+        self.append_bytes(label, &bytes);
+    }
+
+    fn frame(&mut self, kind: &[u8], label: &[u8], bytes: &[u8]) {
+        check_label(label);
+        self.hasher.update(kind);
+        self.hasher.update((label.len() as u64).to_le_bytes());
+        self.hasher.update(label);
+        self.hasher.update((bytes.len() as u64).to_le_bytes());
+        self.hasher.update(bytes);
+    }
+}
+
+fn check_label(label: &[u8]) {
+    if label.is_empty()
+        || !label
+            .iter()
+            .all(|b| matches!(b, b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'/'))
+    {
+        panic!("The label must be non-empty lowercase ASCII.");
+    }
+}
+
+/// An operation on a recorded transcript.
+#[cfg(feature = "test-utils")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TranscriptEntry {
+    /// A message appended to the transcript.
+    Message { label: Vec<u8>, bytes: Vec<u8> },
+    /// A challenge derived from the transcript, with the hash output before the reduction.
+    Challenge { label: Vec<u8>, output: Vec<u8> },
+}
+
+/// A transcript recorded up to one of its challenges.
+#[cfg(feature = "test-utils")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecordedTranscript {
+    pub domain: Vec<u8>,
+    pub entries: Vec<TranscriptEntry>,
+}
+
+#[cfg(feature = "test-utils")]
+impl RecordedTranscript {
+    /// Replay the transcript in the domain. Returns `true` if all the recorded challenges are
+    /// derived again.
+    pub fn replay(&self, domain: &[u8]) -> bool {
+        let mut transcript = Transcript::new(domain);
+        self.entries.iter().all(|entry| match entry {
+            TranscriptEntry::Message { label, bytes } => {
+                transcript.frame(b"bytes", label, bytes);
+                true
+            }
+            TranscriptEntry::Challenge { label, output } => {
+                transcript.frame(b"challenge", label, &[]);
+                let derived = transcript.hasher.clone().finalize();
+                transcript.hasher.update(derived);
+                derived.as_slice() == output.as_slice()
+            }
+        })
+    }
+}
+
+#[cfg(feature = "test-utils")]
+thread_local! {
+    static RECORDED: std::cell::RefCell<Vec<RecordedTranscript>> = const { std::cell::RefCell::new(Vec::new()) };
+}
+
+/// Take the transcripts recorded by the current thread, in the order of their challenges.
+#[cfg(feature = "test-utils")]
+pub fn take_recorded_transcripts() -> Vec<RecordedTranscript> {
+    RECORDED.with(|recorded| std::mem::take(&mut *recorded.borrow_mut()))
+}
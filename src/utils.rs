@@ -0,0 +1,45 @@
+//! Utilities on the scalars, shared by the operations of this crate.
+
+use alloc::{vec, vec::Vec};
+use ark_ff::Field;
+
+/// Invert the scalars by Montgomery's trick, i.e. with a single inversion and `3(n-1)`
+/// multiplications for `n` non-zero scalars. A zero scalar has no inverse and is mapped to
+/// zero, like [Field::inverse] maps it to `None`; it does not affect the other inverses.
+///
+/// ## Example
+///
+/// ```rust
+/// use mercurial_signature::{utils::batch_inverse, Fr};
+/// use ark_std::{One, Zero};
+///
+/// let scalars = [Fr::from(2u64), Fr::zero(), Fr::from(5u64)];
+/// let inverses = batch_inverse(&scalars);
+/// assert_eq!(inverses[0] * scalars[0], Fr::one());
+/// assert_eq!(inverses[1], Fr::zero());
+/// assert_eq!(inverses[2] * scalars[2], Fr::one());
+/// ```
+pub fn batch_inverse<F: Field>(scalars: &[F]) -> Vec<F> {
+    // prefix[i] = product of the non-zero scalars before i
+    let mut prefix = Vec::with_capacity(scalars.len());
+    let mut product = F::one();
+    for s in scalars {
+        prefix.push(product);
+        if !s.is_zero() {
+            product *= s;
+        }
+    }
+
+    // the product of non-zero scalars is non-zero
+    let mut inverse = product.inverse().unwrap();
+    let mut inverses = vec![F::zero(); scalars.len()];
+    for (i, s) in scalars.iter().enumerate().rev() {
+        if s.is_zero() {
+            continue;
+        }
+        // inverse = 1 / (product of the non-zero scalars up to i)
+        inverses[i] = inverse * prefix[i];
+        inverse *= s;
+    }
+    inverses
+}
@@ -0,0 +1,246 @@
+//! Cache of verification results, with the feature `verification-cache`.
+//!
+//! A server verifying the same signatures many times, e.g. on the retries of its clients,
+//! looks the results up in a [VerificationCache] by [verify_cached] and [verify_cached_g2].
+//! Both valid and invalid results are cached. The key of a result is the SHA-256 of the full
+//! compressed encodings of the public parameters, the public key, the message and the
+//! signature, so that a different signature, e.g. a tampered one, never hits the result of
+//! another.
+//!
+//! The cache is bounded: the least recently used result is evicted when it is full. It is
+//! split into up to 16 shards of at least 256 results, each behind its own lock, so that
+//! concurrent verifications rarely wait for each other. A cache of fewer than 512 results has
+//! a single shard. The eviction is least recently used within a shard.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+use ark_ec::pairing::Pairing;
+use ark_serialize::CanonicalSerialize;
+use parking_lot::Mutex;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    params::PublicParams, public_key::PublicKey, public_key_g1::PublicKeyG1, signature::Signature,
+    signature_g2::SignatureG2,
+};
+
+const MAX_SHARDS: usize = 16;
+const MIN_SHARD_CAPACITY: usize = 256;
+
+type Key = [u8; 32];
+
+/// Bounded cache of verification results, see the [module](self) documentation.
+///
+/// ## Example
+///
+/// ```rust
+/// use mercurial_signature::{verify_cached, PublicParams, UniformRand, VerificationCache, G1};
+///
+/// let mut rng = rand::thread_rng();
+/// let pp = PublicParams::new(&mut rng);
+/// let (pk, sk) = pp.key_gen(&mut rng, 5);
+/// let message = (0..5).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+/// let sig = sk.sign(&mut rng, &pp, &message);
+///
+/// let cache = VerificationCache::new(1000);
+/// assert!(verify_cached(&cache, &pp, &pk, &message, &sig));
+/// assert!(verify_cached(&cache, &pp, &pk, &message, &sig));
+/// assert_eq!((cache.hits(), cache.misses()), (1, 1));
+/// ```
+pub struct VerificationCache {
+    shards: Vec<Mutex<Shard>>,
+    ttl: Option<Duration>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl VerificationCache {
+    /// Create a cache of up to `capacity` results, kept until they are evicted.
+    ///
+    /// ## Safety
+    /// This function panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "The capacity must be positive.");
+        let shards = (capacity / MIN_SHARD_CAPACITY).clamp(1, MAX_SHARDS);
+        VerificationCache {
+            shards: (0..shards)
+                .map(|i| {
+                    // the capacities of the shards sum up to `capacity`
+                    let capacity = capacity / shards + usize::from(i < capacity % shards);
+                    Mutex::new(Shard::new(capacity))
+                })
+                .collect(),
+            ttl: None,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Create a cache of up to `capacity` results, each kept for at most `ttl`.
+    ///
+    /// ## Safety
+    /// This function panics if `capacity` is zero.
+    pub fn with_ttl(capacity: usize, ttl: Duration) -> Self {
+        VerificationCache {
+            ttl: Some(ttl),
+            ..VerificationCache::new(capacity)
+        }
+    }
+
+    /// Number of the verifications answered from the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Number of the verifications computed and then cached.
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    /// Number of the cached results, including the expired ones not evicted yet.
+    pub fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.lock().entries.len())
+            .sum()
+    }
+
+    /// Whether there is no cached result.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove all the cached results. The hits and the misses are kept.
+    pub fn clear(&self) {
+        for shard in &self.shards {
+            let mut shard = shard.lock();
+            shard.entries.clear();
+            shard.order.clear();
+        }
+    }
+
+    /// Look up the result of the key, or compute it by `verify` and cache it. The lock is not
+    /// held while verifying.
+    fn get_or_verify(&self, key: Key, verify: impl FnOnce() -> bool) -> bool {
+        let shard = &self.shards[key[0] as usize % self.shards.len()];
+        if let Some(valid) = shard.lock().get(&key, self.ttl) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return valid;
+        }
+        let valid = verify();
+        shard.lock().insert(key, valid);
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        valid
+    }
+}
+
+/// Verify the signature on a message in G1, see [PublicKey::verify], with the result cached.
+pub fn verify_cached<E: Pairing>(
+    cache: &VerificationCache,
+    pp: &PublicParams<E>,
+    pk: &PublicKey<E>,
+    message: &[E::G1],
+    sig: &Signature<E>,
+) -> bool {
+    let key = cache_key::<E>(
+        b"g1",
+        &[&encode(pp), &encode(pk), &encode(message), &encode(sig)],
+    );
+    cache.get_or_verify(key, || pk.verify(pp, message, sig))
+}
+
+/// Verify the signature on a message in G2, see [PublicKeyG1::verify], with the result cached.
+pub fn verify_cached_g2<E: Pairing>(
+    cache: &VerificationCache,
+    pp: &PublicParams<E>,
+    pk: &PublicKeyG1<E>,
+    message: &[E::G2],
+    sig: &SignatureG2<E>,
+) -> bool {
+    let key = cache_key::<E>(
+        b"g2",
+        &[&encode(pp), &encode(pk), &encode(message), &encode(sig)],
+    );
+    cache.get_or_verify(key, || pk.verify(pp, message, sig))
+}
+
+/// SHA-256 of the curve, the scheme and the encodings, each framed by its length.
+fn cache_key<E: Pairing>(scheme: &[u8], encodings: &[&[u8]]) -> Key {
+    let mut hasher = Sha256::new();
+    let curve = core::any::type_name::<E>().as_bytes();
+    for bytes in [curve, scheme].iter().chain(encodings) {
+        hasher.update((bytes.len() as u64).to_le_bytes());
+        hasher.update(bytes);
+    }
+    hasher.finalize().into()
+}
+
+fn encode<T: CanonicalSerialize + ?Sized>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes).unwrap();
+    bytes
+}
+
+/// Results in the order of use, least recent first.
+struct Shard {
+    capacity: usize,
+    entries: HashMap<Key, Entry>,
+    order: BTreeMap<u64, Key>,
+    tick: u64,
+}
+
+struct Entry {
+    valid: bool,
+    inserted: Instant,
+    tick: u64,
+}
+
+impl Shard {
+    fn new(capacity: usize) -> Self {
+        Shard {
+            capacity,
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            tick: 0,
+        }
+    }
+
+    /// The result of the key if it is cached and not expired, marked as the most recent.
+    fn get(&mut self, key: &Key, ttl: Option<Duration>) -> Option<bool> {
+        let entry = self.entries.get_mut(key)?;
+        self.order.remove(&entry.tick);
+        if ttl.is_some_and(|ttl| entry.inserted.elapsed() > ttl) {
+            self.entries.remove(key);
+            return None;
+        }
+        self.tick += 1;
+        entry.tick = self.tick;
+        self.order.insert(self.tick, *key);
+        Some(entry.valid)
+    }
+
+    /// Cache the result of the key as the most recent, evicting the least recent if full.
+    fn insert(&mut self, key: Key, valid: bool) {
+        if let Some(entry) = self.entries.get(&key) {
+            self.order.remove(&entry.tick);
+        } else if self.entries.len() == self.capacity {
+            if let Some((_, evicted)) = self.order.pop_first() {
+                self.entries.remove(&evicted);
+            }
+        }
+        self.tick += 1;
+        self.order.insert(self.tick, key);
+        self.entries.insert(
+            key,
+            Entry {
+                valid,
+                inserted: Instant::now(),
+                tick: self.tick,
+            },
+        );
+    }
+}
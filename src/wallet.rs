@@ -0,0 +1,344 @@
+//! Wallet of the credentials of a holder, with lookup by the equivalence class of the issuer.
+//!
+//! A [Wallet] stores each credential, i.e. a message, its signature and the public key of its
+//! issuer, under the class of the issuer. The public keys of an issuer in all representations
+//! form a class, and [Wallet::find_by_issuer_class] answers whether the wallet holds a
+//! credential by the issuer of a given representation.
+//!
+//! Two public keys in G2 cannot be compared by pairings, and their classes are
+//! indistinguishable by design. The wallet therefore identifies an issuer by its key in G1
+//! from [SecretKey::public_key_g1](crate::SecretKey), given with its first credential. A key
+//! `(b1,...,bl)` in G2 is in the class of the key `(A1,...,Al)` in G1 if and only if
+//! `e(A1, bi) == e(Ai, b1)` for all `i`. The wallet checks the random linear combination
+//! `e(A1, r1 b1 + ... + rl bl) == e(r1 A1 + ... + rl Al, b1)` instead, with weights `ri`
+//! hashed from a secret seed of the wallet. The fingerprint `r1 A1 + ... + rl Al` is cached
+//! per class, so that a lookup costs one multi-scalar multiplication in G2 and two pairings
+//! per class of the same length, independently of the number of credentials. A key outside
+//! the class passes with negligible probability, as long as the seed is unknown to whoever
+//! crafted the key.
+//!
+//! Every key of length 1 is in the same class, so the lookup is meaningful from length 2.
+
+use alloc::vec::Vec;
+use ark_ec::pairing::Pairing;
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
+use ark_std::{UniformRand, Zero};
+use core::{fmt, ops::Mul};
+use rand_core::{CryptoRng, RngCore};
+
+use crate::{
+    hash::hash_to_scalar,
+    params::PublicParams,
+    public_key::PublicKey,
+    public_key_g1::PublicKeyG1,
+    representation::change_representation,
+    serialization::{deserialize_vec, impl_bounded_deserialize},
+    signature::Signature,
+};
+
+/// Error in inserting a credential into a [Wallet].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WalletError {
+    /// The signature does not verify the message under the public key of the issuer.
+    InvalidCredential,
+    /// The public key of the issuer in G1 does not match its public key in G2.
+    KeyMismatch,
+}
+
+impl fmt::Display for WalletError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WalletError::InvalidCredential => f.write_str("the credential does not verify"),
+            WalletError::KeyMismatch => {
+                f.write_str("the public keys of the issuer in G1 and G2 do not match")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for WalletError {}
+
+/// A message, its signature and the public key of its issuer.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize)]
+pub struct Credential<E: Pairing> {
+    pub(crate) message: Vec<E::G1>,
+    pub(crate) sig: Signature<E>,
+    pub(crate) issuer: PublicKey<E>,
+}
+
+impl_bounded_deserialize!(Credential {
+    message: vec,
+    sig: value,
+    issuer: value
+});
+
+impl<E: Pairing> Credential<E> {
+    /// The message in its current representation.
+    pub fn message(&self) -> &[E::G1] {
+        &self.message
+    }
+
+    /// The signature in its current representation.
+    pub fn signature(&self) -> &Signature<E> {
+        &self.sig
+    }
+
+    /// The public key of the issuer, in the representation given at insertion.
+    pub fn issuer(&self) -> &PublicKey<E> {
+        &self.issuer
+    }
+
+    /// Verify the signature on the message under the public key of the issuer.
+    pub fn verify(&self, pp: &PublicParams<E>) -> bool {
+        self.issuer.verify(pp, &self.message, &self.sig)
+    }
+}
+
+/// The class of an issuer, identified by its public key in G1.
+#[derive(Clone, PartialEq, Eq)]
+struct IssuerClass<E: Pairing> {
+    key: PublicKeyG1<E>,
+    // r1 A1 + ... + rl Al
+    fingerprint: E::G1,
+}
+
+/// Credentials stored by the class of their issuer, see the [module](self) documentation.
+///
+/// ## Example
+///
+/// ```rust
+/// use mercurial_signature::{Fr, PublicParams, UniformRand, Wallet, G1};
+///
+/// let mut rng = rand::thread_rng();
+/// let pp = PublicParams::new(&mut rng);
+/// let (pk, sk) = pp.key_gen(&mut rng, 5);
+/// let message = (0..5).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+/// let sig = sk.sign(&mut rng, &pp, &message);
+///
+/// let mut wallet = Wallet::new(&mut rng);
+/// wallet
+///     .insert(&pp, message, sig, pk.clone(), sk.public_key_g1(&pp))
+///     .unwrap();
+///
+/// // the issuer presents another representation of its key
+/// let mut pk2 = pk.clone();
+/// pk2.convert(Fr::rand(&mut rng));
+/// assert_eq!(wallet.find_by_issuer_class(&pk2).count(), 1);
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct Wallet<E: Pairing> {
+    seed: [u8; 32],
+    classes: Vec<IssuerClass<E>>,
+    // the index of the class of each credential
+    entries: Vec<(usize, Credential<E>)>,
+}
+
+impl<E: Pairing> Wallet<E> {
+    /// Create an empty wallet with a random seed of the weights of the lookup.
+    pub fn new<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        let mut seed = [0u8; 32];
+        rng.fill_bytes(&mut seed);
+        Wallet {
+            seed,
+            classes: Vec::new(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Number of credentials.
+    pub fn length(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Number of classes of issuers.
+    pub fn issuer_classes(&self) -> usize {
+        self.classes.len()
+    }
+
+    /// Iterate over the credentials in the order of insertion.
+    pub fn iter(&self) -> impl Iterator<Item = &Credential<E>> {
+        self.entries.iter().map(|(_, credential)| credential)
+    }
+
+    /// Insert a credential by the issuer of the public keys `issuer` in G2 and `issuer_g1` in
+    /// G1, in the same representation. The credential is stored under the existing class of
+    /// the issuer, or under a new class identified by `issuer_g1`. Returns the index of the
+    /// credential.
+    ///
+    /// Returns [WalletError::InvalidCredential] if the signature does not verify, or
+    /// [WalletError::KeyMismatch] if the keys are not of the same secret key.
+    pub fn insert(
+        &mut self,
+        pp: &PublicParams<E>,
+        message: Vec<E::G1>,
+        sig: Signature<E>,
+        issuer: PublicKey<E>,
+        issuer_g1: PublicKeyG1<E>,
+    ) -> Result<usize, WalletError> {
+        if !issuer.verify(pp, &message, &sig) {
+            return Err(WalletError::InvalidCredential);
+        }
+        // e(Ai, p2) == e(p1, bi)
+        if issuer.bx.len() != issuer_g1.bx.len()
+            || !issuer_g1
+                .bx
+                .iter()
+                .zip(issuer.bx.iter())
+                .all(|(ai, bi)| E::pairing(*ai, pp.p2) == E::pairing(pp.p1, *bi))
+        {
+            return Err(WalletError::KeyMismatch);
+        }
+
+        let class = match self.find_class(&issuer) {
+            Some(class) => class,
+            None => {
+                let fingerprint = self.fingerprint(&issuer_g1);
+                self.classes.push(IssuerClass {
+                    key: issuer_g1,
+                    fingerprint,
+                });
+                self.classes.len() - 1
+            }
+        };
+        self.entries.push((
+            class,
+            Credential {
+                message,
+                sig,
+                issuer,
+            },
+        ));
+        Ok(self.entries.len() - 1)
+    }
+
+    /// The credentials by the issuer of the public key, in any representation.
+    pub fn find_by_issuer_class(&self, pk: &PublicKey<E>) -> impl Iterator<Item = &Credential<E>> {
+        let class = self.find_class(pk);
+        self.entries
+            .iter()
+            .filter(move |(c, _)| Some(*c) == class)
+            .map(|(_, credential)| credential)
+    }
+
+    /// Change the representation of every credential by a fresh random scalar, so that the
+    /// credentials are unlinkable to their earlier presentations. The public keys of the
+    /// issuers are unchanged.
+    pub fn randomize_all<R: RngCore + CryptoRng>(&mut self, rng: &mut R) {
+        self.entries.iter_mut().for_each(|(_, credential)| {
+            let u = E::ScalarField::rand(rng);
+            change_representation(rng, &mut credential.message, &mut credential.sig, u);
+        });
+    }
+
+    /// The index of the class of the public key.
+    fn find_class(&self, pk: &PublicKey<E>) -> Option<usize> {
+        let b1 = *pk.bx.first()?;
+        // r1 b1 + ... + rl bl
+        let q = self
+            .weights(pk.bx.len())
+            .zip(pk.bx.iter())
+            .fold(E::G2::zero(), |acc, (ri, bi)| acc + bi.mul(ri));
+        self.classes.iter().position(|class| {
+            class.key.bx.len() == pk.bx.len()
+                && E::multi_pairing([class.key.bx[0], -class.fingerprint], [q, b1]).is_zero()
+        })
+    }
+
+    /// r1 A1 + ... + rl Al
+    fn fingerprint(&self, key: &PublicKeyG1<E>) -> E::G1 {
+        self.weights(key.bx.len())
+            .zip(key.bx.iter())
+            .fold(E::G1::zero(), |acc, (ri, ai)| acc + ai.mul(ri))
+    }
+
+    /// The weights `r1,...,rl` of the lookup.
+    fn weights(&self, length: usize) -> impl Iterator<Item = E::ScalarField> + '_ {
+        (0..length as u64).map(|i| {
+            let data = [self.seed.as_slice(), &i.to_le_bytes()].concat();
+            hash_to_scalar::<E>(b"mercurial-signature/wallet", &data)
+        })
+    }
+}
+
+/// The wallet is serialized as its seed, the public keys in G1 of the classes, and the
+/// credentials with the indices of their classes. The fingerprints are recomputed.
+impl<E: Pairing> CanonicalSerialize for Wallet<E> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.seed.serialize_with_mode(&mut writer, compress)?;
+        (self.classes.len() as u64).serialize_with_mode(&mut writer, compress)?;
+        for class in self.classes.iter() {
+            class.key.serialize_with_mode(&mut writer, compress)?;
+        }
+        (self.entries.len() as u64).serialize_with_mode(&mut writer, compress)?;
+        for (class, credential) in self.entries.iter() {
+            (*class as u64).serialize_with_mode(&mut writer, compress)?;
+            credential.serialize_with_mode(&mut writer, compress)?;
+        }
+        Ok(())
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.seed.serialized_size(compress)
+            + 8
+            + self
+                .classes
+                .iter()
+                .map(|class| class.key.serialized_size(compress))
+                .sum::<usize>()
+            + 8
+            + self
+                .entries
+                .iter()
+                .map(|(_, credential)| 8 + credential.serialized_size(compress))
+                .sum::<usize>()
+    }
+}
+
+impl<E: Pairing> Valid for Wallet<E> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.classes
+            .iter()
+            .try_for_each(|class| class.key.check())?;
+        self.entries
+            .iter()
+            .try_for_each(|(_, credential)| credential.check())
+    }
+}
+
+impl<E: Pairing> CanonicalDeserialize for Wallet<E> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let seed = <[u8; 32]>::deserialize_with_mode(&mut reader, compress, validate)?;
+        let keys: Vec<PublicKeyG1<E>> = deserialize_vec(&mut reader, compress, validate)?;
+        let entries: Vec<(u64, Credential<E>)> = deserialize_vec(&mut reader, compress, validate)?;
+
+        let mut wallet = Wallet {
+            seed,
+            classes: Vec::with_capacity(keys.len()),
+            entries: Vec::with_capacity(entries.len()),
+        };
+        for key in keys {
+            let fingerprint = wallet.fingerprint(&key);
+            wallet.classes.push(IssuerClass { key, fingerprint });
+        }
+        for (class, credential) in entries {
+            let class = usize::try_from(class)
+                .ok()
+                .filter(|class| *class < wallet.classes.len())
+                .ok_or(SerializationError::InvalidData)?;
+            wallet.entries.push((class, credential));
+        }
+        Ok(wallet)
+    }
+}
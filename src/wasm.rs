@@ -0,0 +1,238 @@
+//! Bindings for JavaScript with `wasm-bindgen`, enabled by the feature `wasm`.
+//!
+//! The values cross the boundary as byte arrays in the compressed serialization, i.e. a message
+//! is a serialized `Vec<G1>` and a scalar is a serialized `Fr`. The randomness is from the
+//! `crypto.getRandomValues` of the JavaScript environment. Invalid inputs throw an `Error` with
+//! the reason instead of aborting the module.
+//!
+//! The library is an `rlib` only, so that the crates depending on it do not build a `cdylib`.
+//! Build the module as a `cdylib` with `cargo rustc`, with the profile `wasm-release` for a
+//! small module, e.g.
+//! `cargo rustc --lib --target wasm32-unknown-unknown --features wasm --profile wasm-release --crate-type cdylib`,
+//! and run `wasm-bindgen` on the output.
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
+use rand_core::OsRng;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    change_representation, serialization::deserialize_vec, Fr, PublicKey, PublicParams, SecretKey,
+    Signature, G1,
+};
+
+/// Public parameters.
+#[wasm_bindgen]
+pub struct WasmPublicParams {
+    inner: PublicParams,
+}
+
+/// Public key, e.g. received from an issuer.
+#[wasm_bindgen]
+pub struct WasmPublicKey {
+    inner: PublicKey,
+}
+
+/// Key pair of a signer.
+#[wasm_bindgen]
+pub struct WasmKeypair {
+    pk: PublicKey,
+    sk: SecretKey,
+}
+
+/// Signature.
+#[wasm_bindgen]
+pub struct WasmSignature {
+    inner: Signature,
+}
+
+#[wasm_bindgen]
+impl WasmPublicParams {
+    /// Generate public parameters.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> WasmPublicParams {
+        WasmPublicParams {
+            inner: PublicParams::new(&mut OsRng),
+        }
+    }
+
+    #[wasm_bindgen(js_name = fromBytes)]
+    pub fn from_bytes(bytes: &[u8]) -> Result<WasmPublicParams, JsError> {
+        Ok(WasmPublicParams {
+            inner: deserialize(bytes, "public parameters")?,
+        })
+    }
+
+    #[wasm_bindgen(js_name = toBytes)]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        serialize(&self.inner)
+    }
+}
+
+impl Default for WasmPublicParams {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[wasm_bindgen]
+impl WasmPublicKey {
+    #[wasm_bindgen(js_name = fromBytes)]
+    pub fn from_bytes(bytes: &[u8]) -> Result<WasmPublicKey, JsError> {
+        Ok(WasmPublicKey {
+            inner: deserialize(bytes, "public key")?,
+        })
+    }
+
+    #[wasm_bindgen(js_name = toBytes)]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        serialize(&self.inner)
+    }
+
+    /// Verify the signature on the message.
+    pub fn verify(
+        &self,
+        pp: &WasmPublicParams,
+        message: &[u8],
+        sig: &WasmSignature,
+    ) -> Result<bool, JsError> {
+        let message = deserialize_message(message)?;
+        Ok(self.inner.verify(&pp.inner, &message, &sig.inner))
+    }
+
+    /// Convert the public key by the scalar `p`.
+    pub fn convert(&mut self, p: &[u8]) -> Result<(), JsError> {
+        self.inner.convert(deserialize(p, "scalar")?);
+        Ok(())
+    }
+}
+
+#[wasm_bindgen]
+impl WasmKeypair {
+    /// Generate a key pair for messages of `size` elements.
+    #[wasm_bindgen(constructor)]
+    pub fn new(pp: &WasmPublicParams, size: u32) -> WasmKeypair {
+        let (pk, sk) = pp.inner.key_gen(&mut OsRng, size);
+        WasmKeypair { pk, sk }
+    }
+
+    #[wasm_bindgen(js_name = fromBytes)]
+    pub fn from_bytes(public_key: &[u8], secret_key: &[u8]) -> Result<WasmKeypair, JsError> {
+        let pk: PublicKey = deserialize(public_key, "public key")?;
+        let sk: SecretKey = deserialize(secret_key, "secret key")?;
+        if pk.length() != sk.length() {
+            return Err(JsError::new(
+                "the public key and the secret key have different lengths",
+            ));
+        }
+        Ok(WasmKeypair { pk, sk })
+    }
+
+    #[wasm_bindgen(js_name = publicKey)]
+    pub fn public_key(&self) -> WasmPublicKey {
+        WasmPublicKey {
+            inner: self.pk.clone(),
+        }
+    }
+
+    #[wasm_bindgen(js_name = secretKeyBytes)]
+    pub fn secret_key_bytes(&self) -> Vec<u8> {
+        serialize(&self.sk)
+    }
+
+    /// Sign the message.
+    pub fn sign(&self, pp: &WasmPublicParams, message: &[u8]) -> Result<WasmSignature, JsError> {
+        let message = deserialize_message(message)?;
+        if message.len() > self.sk.length() {
+            return Err(JsError::new("the message is longer than the key"));
+        }
+        Ok(WasmSignature {
+            inner: self.sk.sign(&mut OsRng, &pp.inner, &message),
+        })
+    }
+
+    /// Verify the signature on the message.
+    pub fn verify(
+        &self,
+        pp: &WasmPublicParams,
+        message: &[u8],
+        sig: &WasmSignature,
+    ) -> Result<bool, JsError> {
+        let message = deserialize_message(message)?;
+        Ok(self.pk.verify(&pp.inner, &message, &sig.inner))
+    }
+
+    /// Convert both keys by the scalar `p`.
+    pub fn convert(&mut self, p: &[u8]) -> Result<(), JsError> {
+        let p: Fr = deserialize(p, "scalar")?;
+        self.pk.convert(p);
+        self.sk.convert(p);
+        Ok(())
+    }
+}
+
+#[wasm_bindgen]
+impl WasmSignature {
+    #[wasm_bindgen(js_name = fromBytes)]
+    pub fn from_bytes(bytes: &[u8]) -> Result<WasmSignature, JsError> {
+        Ok(WasmSignature {
+            inner: deserialize(bytes, "signature")?,
+        })
+    }
+
+    #[wasm_bindgen(js_name = toBytes)]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        serialize(&self.inner)
+    }
+
+    /// Convert the signature by the scalar `p`.
+    pub fn convert(&mut self, p: &[u8]) -> Result<(), JsError> {
+        self.inner.convert(&mut OsRng, deserialize(p, "scalar")?);
+        Ok(())
+    }
+
+    /// Change the representation of the message and the signature by the scalar `u`. Returns
+    /// the new message.
+    #[wasm_bindgen(js_name = changeRepresentation)]
+    pub fn change_representation(&mut self, message: &[u8], u: &[u8]) -> Result<Vec<u8>, JsError> {
+        let mut message = deserialize_message(message)?;
+        change_representation(
+            &mut OsRng,
+            &mut message,
+            &mut self.inner,
+            deserialize(u, "scalar")?,
+        );
+        Ok(serialize(&message))
+    }
+}
+
+/// A random scalar, e.g. for [WasmSignature::convert] or [WasmSignature::change_representation].
+#[wasm_bindgen(js_name = randomScalar)]
+pub fn random_scalar() -> Vec<u8> {
+    serialize(&<Fr as ark_std::UniformRand>::rand(&mut OsRng))
+}
+
+/// A random message of `size` elements.
+#[wasm_bindgen(js_name = randomMessage)]
+pub fn random_message(size: u32) -> Vec<u8> {
+    let message = (0..size)
+        .map(|_| <G1 as ark_std::UniformRand>::rand(&mut OsRng))
+        .collect::<Vec<G1>>();
+    serialize(&message)
+}
+
+fn serialize<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes).unwrap();
+    bytes
+}
+
+fn deserialize<T: CanonicalDeserialize>(bytes: &[u8], name: &str) -> Result<T, JsError> {
+    T::deserialize_compressed(bytes).map_err(|e| JsError::new(&format!("invalid {}: {}", name, e)))
+}
+
+/// Deserialize a message. The length prefix is from JavaScript, so the vector is read by
+/// [deserialize_vec], which does not reserve more than a bounded capacity up front.
+fn deserialize_message(bytes: &[u8]) -> Result<Vec<G1>, JsError> {
+    deserialize_vec(&mut &*bytes, Compress::Yes, Validate::Yes)
+        .map_err(|e| JsError::new(&format!("invalid message: {}", e)))
+}
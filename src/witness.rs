@@ -0,0 +1,167 @@
+//! Witnesses of the verification for proving statements about signatures in external circuits.
+//!
+//! [VerifyWitness] records every input and output of the pairings in [PublicKey::verify], in the
+//! following order, which is also the order of [VerifyWitness::to_field_elements]:
+//!
+//! 1. the generators `p1`, `p2`,
+//! 2. the signature `z`, `y1`, `y2`,
+//! 3. the message `M1,...,Ml` and the public key elements `bx1,...,bxl` used against it,
+//! 4. the pairing outputs `e(y1, p2)`, `e(p1, y2)`, `e(z, y2)`, `e(M1, bx1),...,e(Ml, bxl)` and
+//!    their product `e(M1, bx1) * ... * e(Ml, bxl)`.
+//!
+//! Group elements are in affine coordinates `(x, y, infinity)` over the base field, the pairing
+//! outputs are the coefficients of the target field over the base field.
+
+use alloc::vec::Vec;
+use ark_ec::pairing::{Pairing, PairingOutput};
+use ark_ff::ToConstraintField;
+use ark_serialize::CanonicalSerialize;
+
+use crate::{
+    params::PublicParams, public_key::PublicKey, serialization::impl_bounded_deserialize,
+    signature::Signature,
+};
+
+/// The inputs and outputs of the pairings in the verification of a signature.
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize)]
+pub struct VerifyWitness<E: Pairing> {
+    pub p1: E::G1,
+    pub p2: E::G2,
+    pub z: E::G1,
+    pub y1: E::G1,
+    pub y2: E::G2,
+    pub message: Vec<E::G1>,
+    pub bx: Vec<E::G2>,
+    /// e(y1, p2)
+    pub e_y1_p2: PairingOutput<E>,
+    /// e(p1, y2)
+    pub e_p1_y2: PairingOutput<E>,
+    /// e(z, y2)
+    pub e_z_y2: PairingOutput<E>,
+    /// e(Mi, bxi) for i = 1,...,l
+    pub e_m_bx: Vec<PairingOutput<E>>,
+    /// e(M1, bx1) * ... * e(Ml, bxl)
+    pub e_m_bx_product: PairingOutput<E>,
+}
+
+impl_bounded_deserialize!(VerifyWitness {
+    p1: value,
+    p2: value,
+    z: value,
+    y1: value,
+    y2: value,
+    message: vec,
+    bx: vec,
+    e_y1_p2: value,
+    e_p1_y2: value,
+    e_z_y2: value,
+    e_m_bx: vec,
+    e_m_bx_product: value
+});
+
+impl<E: Pairing> VerifyWitness<E> {
+    /// Compute the witness of the verification. Returns `None` if the public key is shorter
+    /// than the message, in which case the verification rejects without any pairing.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mercurial_signature::{PublicParams, UniformRand, VerifyWitness, G1};
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pp = PublicParams::new(&mut rng);
+    /// let (pk, sk) = pp.key_gen(&mut rng, 10);
+    /// let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    /// let sig = sk.sign(&mut rng, &pp, &message);
+    ///
+    /// let witness = VerifyWitness::compute(&pp, &pk, &message, &sig).unwrap();
+    /// assert!(witness.accepts());
+    /// ```
+    pub fn compute(
+        pp: &PublicParams<E>,
+        pk: &PublicKey<E>,
+        message: &[E::G1],
+        sig: &Signature<E>,
+    ) -> Option<Self> {
+        if pk.bx.len() < message.len() {
+            return None;
+        }
+
+        let bx = pk.bx[..message.len()].to_vec();
+        let e_m_bx = message
+            .iter()
+            .zip(bx.iter())
+            .map(|(m, bxi)| E::pairing(*m, *bxi))
+            .collect::<Vec<_>>();
+        let e_m_bx_product = e_m_bx.iter().sum();
+
+        Some(VerifyWitness {
+            p1: pp.p1,
+            p2: pp.p2,
+            z: sig.z,
+            y1: sig.y1,
+            y2: sig.y2,
+            message: message.to_vec(),
+            bx,
+            e_y1_p2: E::pairing(sig.y1, pp.p2),
+            e_p1_y2: E::pairing(pp.p1, sig.y2),
+            e_z_y2: E::pairing(sig.z, sig.y2),
+            e_m_bx,
+            e_m_bx_product,
+        })
+    }
+
+    /// Recompute the decision of the verification from the pairing outputs in the witness.
+    pub fn accepts(&self) -> bool {
+        self.e_m_bx.iter().sum::<PairingOutput<E>>() == self.e_m_bx_product
+            && self.e_y1_p2 == self.e_p1_y2
+            && self.e_z_y2 == self.e_m_bx_product
+    }
+
+    /// Check the pairing outputs in the witness are the pairings of its inputs.
+    pub fn is_consistent(&self) -> bool {
+        self.message.len() == self.bx.len()
+            && self.e_m_bx.len() == self.bx.len()
+            && self.e_y1_p2 == E::pairing(self.y1, self.p2)
+            && self.e_p1_y2 == E::pairing(self.p1, self.y2)
+            && self.e_z_y2 == E::pairing(self.z, self.y2)
+            && self
+                .message
+                .iter()
+                .zip(self.bx.iter())
+                .zip(self.e_m_bx.iter())
+                .all(|((m, bxi), e)| E::pairing(*m, *bxi) == *e)
+    }
+}
+
+impl<E: Pairing> VerifyWitness<E>
+where
+    E::G1: ToConstraintField<E::BaseField>,
+    E::G2: ToConstraintField<E::BaseField>,
+    E::TargetField: ToConstraintField<E::BaseField>,
+{
+    /// Flatten the witness to base field elements in the order described in the module documentation.
+    /// Returns `None` if an element cannot be represented over the base field.
+    pub fn to_field_elements(&self) -> Option<Vec<E::BaseField>> {
+        let mut elements = Vec::new();
+        elements.extend(self.p1.to_field_elements()?);
+        elements.extend(self.p2.to_field_elements()?);
+        elements.extend(self.z.to_field_elements()?);
+        elements.extend(self.y1.to_field_elements()?);
+        elements.extend(self.y2.to_field_elements()?);
+        for m in self.message.iter() {
+            elements.extend(m.to_field_elements()?);
+        }
+        for bxi in self.bx.iter() {
+            elements.extend(bxi.to_field_elements()?);
+        }
+        for e in [self.e_y1_p2, self.e_p1_y2, self.e_z_y2]
+            .iter()
+            .chain(self.e_m_bx.iter())
+            .chain([self.e_m_bx_product].iter())
+        {
+            elements.extend(e.0.to_field_elements()?);
+        }
+        Some(elements)
+    }
+}
@@ -0,0 +1,60 @@
+use ark_ec::CurveGroup;
+use mercurial_signature::fixtures::Fixture;
+use mercurial_signature::{change_representation_affine, G1Affine, G1};
+
+/// Test signing and verifying affine messages from vectors, arrays and iterators, agreeing with
+/// the projective message.
+#[test]
+fn verify_ok_with_affine_messages() {
+    let mut f = Fixture::with_length(1, 3);
+    let message = G1::normalize_batch(&f.message);
+
+    // vector
+    let sig = f.sk.sign_affine(&mut f.rng, &f.pp, &message);
+    assert!(f.pk.verify_affine(&f.pp, &message, &sig));
+    assert!(f.pk.verify(&f.pp, &f.message, &sig));
+    assert!(f.pk.verify_affine(&f.pp, message.clone(), &f.sig));
+
+    // array
+    let array: [G1Affine; 3] = [message[0], message[1], message[2]];
+    let sig = f.sk.sign_affine(&mut f.rng, &f.pp, array);
+    assert!(f.pk.verify_affine(&f.pp, array, &sig));
+
+    // map iterator
+    let sig =
+        f.sk.sign_affine(&mut f.rng, &f.pp, f.message.iter().map(|m| m.into_affine()));
+    assert!(f
+        .pk
+        .verify_affine(&f.pp, f.message.iter().map(|m| m.into_affine()), &sig));
+
+    // a different message
+    assert!(!f.pk.verify_affine(&f.pp, [message[1], message[0]], &sig));
+    let longer = G1::normalize_batch(&f.random_message(4));
+    assert!(!f.pk.verify_affine(&f.pp, &longer, &sig));
+}
+
+/// Test changing the representation of an affine message.
+#[test]
+fn verify_ok_after_changing_affine_representation() {
+    let mut f = Fixture::with_length(2, 3);
+    let mut message = G1::normalize_batch(&f.message);
+    let mut sig = f.sig.clone();
+    let u = f.random_scalar();
+    change_representation_affine(&mut f.rng, &mut message, &mut sig, u);
+    assert!(f.pk.verify_affine(&f.pp, &message, &sig));
+    assert!(!f
+        .pk
+        .verify_affine(&f.pp, G1::normalize_batch(&f.message), &sig));
+
+    let scaled = f.message.iter().map(|m| *m * u).collect::<Vec<G1>>();
+    assert_eq!(message, G1::normalize_batch(&scaled));
+}
+
+/// Test signing an affine message longer than the key panics.
+#[test]
+#[should_panic]
+fn sign_affine_panics_with_long_message() {
+    let mut f = Fixture::with_length(3, 3);
+    let message = G1::normalize_batch(&f.random_message(4));
+    f.sk.sign_affine(&mut f.rng, &f.pp, &message);
+}
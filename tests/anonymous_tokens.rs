@@ -0,0 +1,131 @@
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use mercurial_signature::{
+    PublicParams, Signature, TokenClient, TokenIssuanceRequest, TokenIssuer, TokenRedeemer,
+    TokenRedemption, UniformRand, G1,
+};
+
+/// Test issuing 10 tokens, redeeming 3 of them, rejecting a double redemption, and the
+/// redemptions share no bytes with the issuance transcript.
+#[test]
+fn issue_and_redeem_tokens() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let issuer = TokenIssuer::new(&mut rng, &pp);
+    let mut redeemer = TokenRedeemer::new(issuer.public_key());
+
+    let mut client = TokenClient::new();
+    let request = client.request(&mut rng, &pp, 10);
+    let response = issuer.issue(&mut rng, &pp, &request);
+    assert!(client.receive(&pp, issuer.public_key(), &response));
+    assert_eq!(client.length(), 10);
+
+    // the protocol messages are serializable
+    let mut transcript = Vec::new();
+    request.serialize_compressed(&mut transcript).unwrap();
+    assert!(TokenIssuanceRequest::deserialize_compressed(&transcript[..]).unwrap() == request);
+    response.serialize_compressed(&mut transcript).unwrap();
+
+    // a copy of the client redeems the same tokens again
+    let mut copied_client = client.clone();
+
+    for _ in 0..3 {
+        let redemption = client.redeem(&mut rng, &pp).unwrap();
+        let mut bytes = Vec::new();
+        redemption.serialize_compressed(&mut bytes).unwrap();
+        let redemption = TokenRedemption::deserialize_compressed(&bytes[..]).unwrap();
+        assert!(redeemer.redeem(&pp, &redemption));
+
+        // no 32 bytes of the redemption appear in the issuance transcript
+        assert!(bytes
+            .windows(32)
+            .all(|w| !transcript.windows(32).any(|t| t == w)));
+    }
+    assert_eq!(client.length(), 7);
+
+    // double redemption with a fresh representation is rejected
+    let redemption = copied_client.redeem(&mut rng, &pp).unwrap();
+    assert!(!redeemer.redeem(&pp, &redemption));
+}
+
+/// Test tokens of another issuer and tampered redemptions are rejected.
+#[test]
+fn redeem_fail_for_invalid_tokens() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let issuer = TokenIssuer::new(&mut rng, &pp);
+    let other_issuer = TokenIssuer::new(&mut rng, &pp);
+    let mut redeemer = TokenRedeemer::new(issuer.public_key());
+
+    let mut client = TokenClient::new();
+    let request = client.request(&mut rng, &pp, 2);
+    let response = other_issuer.issue(&mut rng, &pp, &request);
+    assert!(!client.receive(&pp, issuer.public_key(), &response));
+    assert!(client.receive(&pp, other_issuer.public_key(), &response));
+
+    let redemption = client.redeem(&mut rng, &pp).unwrap();
+    assert!(!redeemer.redeem(&pp, &redemption));
+
+    // the tag of another token does not match the proof
+    let mut client = TokenClient::new();
+    let request = client.request(&mut rng, &pp, 2);
+    let response = issuer.issue(&mut rng, &pp, &request);
+    assert!(client.receive(&pp, issuer.public_key(), &response));
+    let redemption1 = client.redeem(&mut rng, &pp).unwrap();
+    let redemption2 = client.redeem(&mut rng, &pp).unwrap();
+    let mut bytes1 = Vec::new();
+    redemption1.serialize_compressed(&mut bytes1).unwrap();
+    let mut bytes2 = Vec::new();
+    redemption2.serialize_compressed(&mut bytes2).unwrap();
+    // swap the tags, which follow the message and the signature
+    let offset = bytes1.len() - 3 * 48 - 32;
+    bytes1[offset..offset + 48].copy_from_slice(&bytes2[offset..offset + 48]);
+    let tampered = TokenRedemption::deserialize_compressed(&bytes1[..]).unwrap();
+    assert!(!redeemer.redeem(&pp, &tampered));
+    assert!(redeemer.redeem(&pp, &redemption1));
+    assert!(client.redeem(&mut rng, &pp).is_none());
+}
+
+/// Test the signatures of a batch do not share `y1`, so that the sum of two of them does not
+/// verify on the sum of their messages, which would be a token forged by the client.
+#[test]
+fn issue_fail_to_combine_signatures_of_batch() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let issuer = TokenIssuer::new(&mut rng, &pp);
+
+    let messages = (0..2)
+        .map(|_| vec![G1::rand(&mut rng), G1::rand(&mut rng)])
+        .collect::<Vec<_>>();
+    let mut bytes = Vec::new();
+    messages.serialize_compressed(&mut bytes).unwrap();
+    let request = TokenIssuanceRequest::deserialize_compressed(&bytes[..]).unwrap();
+
+    let response = issuer.issue(&mut rng, &pp, &request);
+    let mut bytes = Vec::new();
+    response.serialize_compressed(&mut bytes).unwrap();
+    let sigs = Vec::<Signature>::deserialize_compressed(&bytes[..]).unwrap();
+    for (message, sig) in messages.iter().zip(sigs.iter()) {
+        assert!(issuer.public_key().verify(&pp, message, sig));
+    }
+
+    // z, y1 and y2 in order
+    let mut bytes1 = Vec::new();
+    sigs[0].serialize_compressed(&mut bytes1).unwrap();
+    let mut bytes2 = Vec::new();
+    sigs[1].serialize_compressed(&mut bytes2).unwrap();
+    assert!(bytes1[48..96] != bytes2[48..96]);
+
+    let z1 = G1::deserialize_compressed(&bytes1[..48]).unwrap();
+    let z2 = G1::deserialize_compressed(&bytes2[..48]).unwrap();
+    let mut summed = Vec::new();
+    (z1 + z2).serialize_compressed(&mut summed).unwrap();
+    summed.extend_from_slice(&bytes1[48..]);
+    let summed_sig = Signature::deserialize_compressed(&summed[..]).unwrap();
+    let summed_message = vec![
+        messages[0][0] + messages[1][0],
+        messages[0][1] + messages[1][1],
+    ];
+    assert!(!issuer
+        .public_key()
+        .verify(&pp, &summed_message, &summed_sig));
+}
@@ -0,0 +1,139 @@
+use std::cell::RefCell;
+
+use mercurial_signature::{
+    BackendKey, Fr, InMemoryBackend, PublicParams, ScalarBackend, UniformRand, G1, G2,
+};
+#[cfg(feature = "test-utils")]
+use mercurial_signature::{CountingBackend, OperationCounts};
+use rand::{rngs::StdRng, CryptoRng, RngCore, SeedableRng};
+
+/// A backend holding the secrets in a vault, referenced by their indices.
+#[derive(Default)]
+struct Vault {
+    secrets: RefCell<Vec<Fr>>,
+}
+
+impl Vault {
+    fn store(&self, s: Fr) -> usize {
+        let mut secrets = self.secrets.borrow_mut();
+        secrets.push(s);
+        secrets.len() - 1
+    }
+
+    fn get(&self, handle: usize) -> Fr {
+        self.secrets.borrow()[handle]
+    }
+}
+
+impl ScalarBackend<ark_bls12_381::Bls12_381> for Vault {
+    type Handle = usize;
+
+    fn random<R: RngCore + CryptoRng>(&self, rng: &mut R) -> usize {
+        self.store(Fr::rand(rng))
+    }
+
+    fn import(&self, s: Fr) -> usize {
+        self.store(s)
+    }
+
+    fn mul_scalars(&self, a: &usize, b: &usize) -> usize {
+        self.store(self.get(*a) * self.get(*b))
+    }
+
+    fn invert(&self, a: &usize) -> usize {
+        self.store(Fr::from(1u64) / self.get(*a))
+    }
+
+    fn mul_g1(&self, base: &G1, secret: &usize) -> G1 {
+        *base * self.get(*secret)
+    }
+
+    fn mul_g2(&self, base: &G2, secret: &usize) -> G2 {
+        *base * self.get(*secret)
+    }
+}
+
+/// Test signing and converting against the backends produces the same keys and signatures as
+/// the direct implementation with the same randomness.
+#[test]
+fn backend_signatures_same_as_direct() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let message = (0..5).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let p = Fr::rand(&mut rng);
+    let seed = rng.next_u64();
+
+    let mut direct_rng = StdRng::seed_from_u64(seed);
+    let (mut pk, mut sk) = pp.key_gen(&mut direct_rng, 5);
+    let sig = sk.sign(&mut direct_rng, &pp, &message);
+    pk.convert(p);
+    sk.convert(p);
+    let converted_sig = sk.sign(&mut direct_rng, &pp, &message);
+    assert!(pk.verify(&pp, &message, &converted_sig));
+
+    let mut memory_rng = StdRng::seed_from_u64(seed);
+    let (memory_pk, mut memory_sk) =
+        BackendKey::generate(&InMemoryBackend, &mut memory_rng, &pp, 5);
+    assert!(memory_sk.sign(&InMemoryBackend, &mut memory_rng, &pp, &message) == sig);
+    memory_sk.convert(&InMemoryBackend, p);
+    assert!(memory_sk.sign(&InMemoryBackend, &mut memory_rng, &pp, &message) == converted_sig);
+
+    let vault = Vault::default();
+    let mut vault_rng = StdRng::seed_from_u64(seed);
+    let (vault_pk, mut vault_sk) = BackendKey::generate(&vault, &mut vault_rng, &pp, 5);
+    let vault_sig = vault_sk.sign(&vault, &mut vault_rng, &pp, &message);
+    vault_sk.convert(&vault, p);
+    let vault_converted_sig = vault_sk.sign(&vault, &mut vault_rng, &pp, &message);
+
+    assert!(vault_sig == sig);
+    assert!(vault_converted_sig == converted_sig);
+    assert!(memory_pk == vault_pk);
+    assert_eq!(vault_sk.length(), 5);
+}
+
+/// Test the number of operations of key generation, signing and conversion in the backend.
+#[cfg(feature = "test-utils")]
+#[test]
+fn counting_backend_counts_operations() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let backend = CountingBackend::new();
+
+    let (pk, mut sk) = BackendKey::generate(&backend, &mut rng, &pp, 5);
+    assert_eq!(
+        backend.counts(),
+        OperationCounts {
+            random: 5,
+            mul_g2: 5,
+            ..Default::default()
+        }
+    );
+
+    // a message shorter than the key uses as many elements of the key
+    backend.reset();
+    let message = (0..4).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let sig = sk.sign(&backend, &mut rng, &pp, &message);
+    assert!(pk.verify(&pp, &message, &sig));
+    assert_eq!(
+        backend.counts(),
+        OperationCounts {
+            random: 1,
+            mul_scalars: 4,
+            invert: 1,
+            mul_g1: 5,
+            mul_g2: 1,
+            ..Default::default()
+        }
+    );
+
+    backend.reset();
+    sk.convert(&backend, Fr::rand(&mut rng));
+    assert_eq!(
+        backend.counts(),
+        OperationCounts {
+            import: 1,
+            mul_scalars: 5,
+            ..Default::default()
+        }
+    );
+}
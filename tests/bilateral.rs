@@ -0,0 +1,105 @@
+#![cfg(feature = "bilateral")]
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use mercurial_signature::{
+    change_representation_bilateral, BilateralSignature, Fr, PublicParams, UniformRand, G1, G2,
+};
+
+/// Scale the elements of the signature at the offsets of its compressed serialization
+/// `(z, z', y1, y2, v1, v2)` by `u`, where the elements in G1 are at `g1` and in G2 at `g2`.
+fn scale(sig: &BilateralSignature, u: Fr, g1: &[usize], g2: &[usize]) -> BilateralSignature {
+    let mut bytes = Vec::new();
+    sig.serialize_compressed(&mut bytes).unwrap();
+    for &offset in g1 {
+        let element = G1::deserialize_compressed(&bytes[offset..offset + 48]).unwrap() * u;
+        element
+            .serialize_compressed(&mut bytes[offset..offset + 48])
+            .unwrap();
+    }
+    for &offset in g2 {
+        let element = G2::deserialize_compressed(&bytes[offset..offset + 96]).unwrap() * u;
+        element
+            .serialize_compressed(&mut bytes[offset..offset + 96])
+            .unwrap();
+    }
+    BilateralSignature::deserialize_compressed(&bytes[..]).unwrap()
+}
+
+// offsets of z, z', v1 and v2 in the compressed serialization
+const Z: usize = 0;
+const Z2: usize = 48;
+const V1: usize = 288;
+const V2: usize = 336;
+
+/// Test the signature on a bilateral message verifies after conversion and change of representation.
+#[test]
+fn verify_ok_with_bilateral_message() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (mut pk, mut sk) = pp.key_gen_bilateral(&mut rng, 5, 3);
+    let mut msg_g1 = (0..5).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let mut msg_g2 = (0..3).map(|_| G2::rand(&mut rng)).collect::<Vec<G2>>();
+    let mut sig = sk.sign_bilateral(&mut rng, &pp, &msg_g1, &msg_g2);
+    assert!(pk.verify(&pp, &msg_g1, &msg_g2, &sig));
+
+    let p = Fr::rand(&mut rng);
+    let pk_orig = pk.clone();
+    pk.convert(p);
+    sk.convert(p);
+    sig.convert(&mut rng, p);
+    assert!(pk.verify(&pp, &msg_g1, &msg_g2, &sig));
+    assert!(!pk_orig.verify(&pp, &msg_g1, &msg_g2, &sig));
+
+    let u = Fr::rand(&mut rng);
+    change_representation_bilateral(&mut rng, &mut msg_g1, &mut msg_g2, &mut sig, u);
+    assert!(pk.verify(&pp, &msg_g1, &msg_g2, &sig));
+
+    let sig2 = sk.sign_bilateral(&mut rng, &pp, &msg_g1, &msg_g2);
+    assert!(pk.verify(&pp, &msg_g1, &msg_g2, &sig2));
+}
+
+/// Test the verification fails if only one half of the message is scaled.
+#[test]
+fn verify_fail_if_only_one_half_is_scaled() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk, sk) = pp.key_gen_bilateral(&mut rng, 5, 3);
+    let msg_g1 = (0..5).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let msg_g2 = (0..3).map(|_| G2::rand(&mut rng)).collect::<Vec<G2>>();
+    let sig = sk.sign_bilateral(&mut rng, &pp, &msg_g1, &msg_g2);
+
+    let u = Fr::rand(&mut rng);
+    let mut msg_g1_2 = msg_g1.clone();
+    let mut msg_g2_2 = msg_g2.clone();
+    let mut sig2 = sig.clone();
+    change_representation_bilateral(&mut rng, &mut msg_g1_2, &mut msg_g2_2, &mut sig2, u);
+
+    assert!(!pk.verify(&pp, &msg_g1_2, &msg_g2, &sig2));
+    assert!(!pk.verify(&pp, &msg_g1, &msg_g2_2, &sig2));
+    assert!(!pk.verify(&pp, &msg_g1_2, &msg_g2_2, &sig));
+}
+
+/// Test the verification fails if the holder scales only the G1 half with `z`, and with the
+/// anchor in G1, but succeeds if both halves are scaled with the whole anchor.
+#[test]
+fn verify_fail_if_only_g1_half_and_z_are_scaled() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk, sk) = pp.key_gen_bilateral(&mut rng, 5, 3);
+    let msg_g1 = (0..5).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let msg_g2 = (0..3).map(|_| G2::rand(&mut rng)).collect::<Vec<G2>>();
+    let sig = sk.sign_bilateral(&mut rng, &pp, &msg_g1, &msg_g2);
+
+    let u = Fr::rand(&mut rng);
+    let scaled_g1 = msg_g1.iter().map(|m| *m * u).collect::<Vec<G1>>();
+    let scaled_g2 = msg_g2.iter().map(|n| *n * u).collect::<Vec<G2>>();
+
+    assert!(!pk.verify(&pp, &scaled_g1, &msg_g2, &scale(&sig, u, &[Z], &[])));
+    assert!(!pk.verify(&pp, &scaled_g1, &msg_g2, &scale(&sig, u, &[Z, V1], &[])));
+    assert!(pk.verify(
+        &pp,
+        &scaled_g1,
+        &scaled_g2,
+        &scale(&sig, u, &[Z, V1], &[Z2, V2])
+    ));
+}
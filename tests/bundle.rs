@@ -0,0 +1,135 @@
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use mercurial_signature::fixtures::Fixture;
+use mercurial_signature::{BundleError, KeyBundle, PublicKey, Signature, SignatureBundle, G1};
+
+/// Signatures with distinct `z`, by adding `p1` repeatedly to the `z` of the fixture, which is
+/// much faster than signing each of them.
+fn signatures(f: &Fixture, count: usize) -> Vec<Signature> {
+    let mut bytes = Vec::new();
+    f.sig.serialize_compressed(&mut bytes).unwrap();
+    let (mut z, y1, y2) =
+        <(G1, G1, mercurial_signature::G2)>::deserialize_compressed(&bytes[..]).unwrap();
+    (0..count)
+        .map(|_| {
+            z += f.pp.p1;
+            let mut bytes = Vec::new();
+            (z, y1, y2).serialize_compressed(&mut bytes).unwrap();
+            Signature::deserialize_compressed_unchecked(&bytes[..]).unwrap()
+        })
+        .collect()
+}
+
+/// Test the round trip of a bundle of 10000 signatures, which is smaller than the signatures
+/// serialized one by one with their length prefix.
+#[test]
+fn signature_bundle_round_trip() {
+    let f = Fixture::new(1);
+    let sigs = signatures(&f, 10000);
+    let bundle = sigs.iter().cloned().collect::<SignatureBundle>();
+    let bytes = bundle.to_bytes();
+    let mut individual = Vec::new();
+    sigs.serialize_compressed(&mut individual).unwrap();
+    assert!(bytes.len() < individual.len() + 64);
+
+    let read = SignatureBundle::from_bytes(&bytes).unwrap();
+    assert_eq!(read, bundle);
+    assert_eq!(read.length(), 10000);
+    assert_eq!(read.get(9999), Some(&sigs[9999]));
+    assert_eq!(read.get(10000), None);
+    assert!(read.iter().eq(sigs.iter()));
+
+    let mut bundle = SignatureBundle::new();
+    bundle.push(f.sig.clone());
+    let read = SignatureBundle::read(&bundle.to_bytes()[..]).unwrap();
+    assert!(f.pk.verify(&f.pp, &f.message, read.get(0).unwrap()));
+}
+
+/// Test the round trip of a bundle of keys of different lengths.
+#[test]
+fn key_bundle_round_trip() {
+    let mut f = Fixture::new(2);
+    let keys = [1, 5, 0, 3]
+        .into_iter()
+        .map(|len| f.pp.key_gen(&mut f.rng, len).0)
+        .collect::<Vec<PublicKey>>();
+    let mut bundle = KeyBundle::new();
+    keys.iter().for_each(|key| bundle.push(key.clone()));
+    let bytes = bundle.to_bytes();
+
+    let read = KeyBundle::from_bytes(&bytes).unwrap();
+    assert_eq!(read, bundle);
+    assert!(read.iter().eq(keys.iter()));
+    assert_eq!(KeyBundle::read(&bytes[..]).unwrap(), bundle);
+    assert_eq!(
+        KeyBundle::from_bytes(&KeyBundle::new().to_bytes())
+            .unwrap()
+            .length(),
+        0
+    );
+}
+
+/// Test corrupted and truncated bundles are rejected, and a large count is rejected before
+/// allocating.
+#[test]
+fn bundle_rejects_corruption() {
+    let mut f = Fixture::new(3);
+    let bundle = signatures(&f, 10).into_iter().collect::<SignatureBundle>();
+    let bytes = bundle.to_bytes();
+
+    // flip a bit of the checksum, the count, the header and the items
+    let mut corrupted = bytes.clone();
+    *corrupted.last_mut().unwrap() ^= 1;
+    assert!(matches!(
+        SignatureBundle::from_bytes(&corrupted),
+        Err(BundleError::ChecksumMismatch)
+    ));
+    let mut corrupted = bytes.clone();
+    corrupted[14] ^= 1;
+    assert!(matches!(
+        SignatureBundle::from_bytes(&corrupted),
+        Err(BundleError::LengthMismatch)
+    ));
+    let mut corrupted = bytes.clone();
+    corrupted[0] ^= 1;
+    assert!(matches!(
+        SignatureBundle::from_bytes(&corrupted),
+        Err(BundleError::InvalidHeader)
+    ));
+    let mut corrupted = bytes.clone();
+    corrupted[6] ^= 1;
+    assert!(matches!(
+        SignatureBundle::from_bytes(&corrupted),
+        Err(BundleError::CurveMismatch)
+    ));
+    for i in (22..bytes.len() - 32).step_by(37) {
+        let mut corrupted = bytes.clone();
+        corrupted[i] ^= 1;
+        assert!(SignatureBundle::from_bytes(&corrupted).is_err());
+    }
+
+    // truncated
+    assert!(SignatureBundle::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    assert!(SignatureBundle::read(&bytes[..bytes.len() - 1]).is_err());
+    assert!(SignatureBundle::from_bytes(&bytes[..10]).is_err());
+
+    // a count of u64::MAX
+    let mut corrupted = bytes.clone();
+    corrupted[14..22].copy_from_slice(&u64::MAX.to_le_bytes());
+    assert!(SignatureBundle::from_bytes(&corrupted).is_err());
+    assert!(SignatureBundle::read(&corrupted[..]).is_err());
+
+    // a key of a large length, and a signature bundle read as a key bundle
+    let key_bundle = [f.pp.key_gen(&mut f.rng, 2).0]
+        .into_iter()
+        .collect::<KeyBundle>();
+    let mut corrupted = key_bundle.to_bytes();
+    corrupted[22..30].copy_from_slice(&u64::MAX.to_le_bytes());
+    assert!(matches!(
+        KeyBundle::from_bytes(&corrupted),
+        Err(BundleError::LengthMismatch)
+    ));
+    assert!(matches!(
+        KeyBundle::from_bytes(&bytes),
+        Err(BundleError::InvalidHeader)
+    ));
+}
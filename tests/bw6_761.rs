@@ -0,0 +1,35 @@
+#![cfg(feature = "bw6_761")]
+
+use mercurial_signature::{
+    change_representation, FrBw6_761, G1Bw6_761, PublicParamsBw6_761, UniformRand,
+};
+
+/// Test the signature on the curve BW6-761 verifies after conversion and change of representation.
+#[test]
+fn verify_ok_on_bw6_761() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParamsBw6_761::new(&mut rng);
+    let (mut pk, mut sk) = pp.key_gen(&mut rng, 5);
+    let mut message = (0..5)
+        .map(|_| G1Bw6_761::rand(&mut rng))
+        .collect::<Vec<G1Bw6_761>>();
+    let mut sig = sk.sign(&mut rng, &pp, &message);
+    assert!(pk.verify(&pp, &message, &sig));
+
+    let p = FrBw6_761::rand(&mut rng);
+    let pk_orig = pk.clone();
+    pk.convert(p);
+    sk.convert(p);
+    sig.convert(&mut rng, p);
+    assert!(pk.verify(&pp, &message, &sig));
+    assert!(!pk_orig.verify(&pp, &message, &sig));
+
+    let u = FrBw6_761::rand(&mut rng);
+    let message_orig = message.clone();
+    change_representation(&mut rng, &mut message, &mut sig, u);
+    assert!(pk.verify(&pp, &message, &sig));
+    assert!(!pk.verify(&pp, &message_orig, &sig));
+
+    let sig2 = sk.sign(&mut rng, &pp, &message);
+    assert!(pk.verify(&pp, &message, &sig2));
+}
@@ -0,0 +1,155 @@
+use std::collections::BTreeSet;
+
+use mercurial_signature::{
+    sign_claims, verify_claims, ClaimValue, Claims, ClaimsError, PublicParams, SignedClaims,
+    Validation,
+};
+
+fn sample_claims() -> Claims {
+    let mut claims = Claims::new();
+    claims.insert("iss", ClaimValue::String("issuer".to_string()));
+    claims.insert("sub", ClaimValue::String("alice".to_string()));
+    claims.insert("nbf", ClaimValue::Number(1000));
+    claims.insert("exp", ClaimValue::Number(2000));
+    claims.insert("admin", ClaimValue::Bool(false));
+    claims
+}
+
+/// Test the `exp`, `nbf` and `iss` checks of the validation.
+#[test]
+fn verify_claims_enforces_time_and_issuer() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk, sk) = pp.key_gen(&mut rng, 5);
+    let signed = sign_claims(&mut rng, &sk, &pp, &sample_claims());
+
+    assert_eq!(
+        verify_claims(&pk, &pp, &signed, &Validation::new(1500)),
+        Ok(())
+    );
+    assert_eq!(
+        verify_claims(&pk, &pp, &signed, &Validation::new(2000)),
+        Err(ClaimsError::Expired)
+    );
+    assert_eq!(
+        verify_claims(&pk, &pp, &signed, &Validation::new(999)),
+        Err(ClaimsError::NotYetValid)
+    );
+
+    let mut validation = Validation::new(2005);
+    validation.leeway = 10;
+    assert_eq!(verify_claims(&pk, &pp, &signed, &validation), Ok(()));
+
+    let mut validation = Validation::new(1500);
+    validation.issuer = Some("issuer".to_string());
+    assert_eq!(verify_claims(&pk, &pp, &signed, &validation), Ok(()));
+    validation.issuer = Some("other".to_string());
+    assert_eq!(
+        verify_claims(&pk, &pp, &signed, &validation),
+        Err(ClaimsError::InvalidIssuer)
+    );
+
+    // the signature does not verify under another key
+    let (other_pk, _) = pp.key_gen(&mut rng, 5);
+    assert_eq!(
+        verify_claims(&other_pk, &pp, &signed, &Validation::new(1500)),
+        Err(ClaimsError::InvalidSignature)
+    );
+
+    // a registered claim of the wrong type
+    let mut claims = Claims::new();
+    claims.insert("exp", ClaimValue::String("tomorrow".to_string()));
+    let signed = sign_claims(&mut rng, &sk, &pp, &claims);
+    assert_eq!(
+        verify_claims(&pk, &pp, &signed, &Validation::new(1500)),
+        Err(ClaimsError::InvalidClaim("exp".to_string()))
+    );
+}
+
+/// Test the claims not in the allowed claims are rejected only if the allowed claims are set.
+#[test]
+fn verify_claims_handles_unknown_claims() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk, sk) = pp.key_gen(&mut rng, 5);
+    let signed = sign_claims(&mut rng, &sk, &pp, &sample_claims());
+
+    let mut validation = Validation::new(1500);
+    validation.allowed_claims = Some(
+        ["iss", "sub", "nbf", "exp", "admin"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<BTreeSet<_>>(),
+    );
+    assert_eq!(verify_claims(&pk, &pp, &signed, &validation), Ok(()));
+
+    validation.allowed_claims = Some(
+        ["iss", "sub", "nbf", "exp"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<BTreeSet<_>>(),
+    );
+    assert_eq!(
+        verify_claims(&pk, &pp, &signed, &validation),
+        Err(ClaimsError::UnknownClaim("admin".to_string()))
+    );
+}
+
+/// Test the compact token round trip, and the token with modified claims fails to verify.
+#[test]
+fn signed_claims_compact_round_trip() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk, sk) = pp.key_gen(&mut rng, 5);
+    let signed = sign_claims(&mut rng, &sk, &pp, &sample_claims());
+
+    let token = signed.to_compact();
+    let decoded = SignedClaims::from_compact(&token).unwrap();
+    assert!(decoded == signed);
+    assert_eq!(decoded.claims(), &sample_claims());
+    assert_eq!(
+        verify_claims(&pk, &pp, &decoded, &Validation::new(1500)),
+        Ok(())
+    );
+
+    let (claims_part, sig_part) = token.split_once('.').unwrap();
+    assert_eq!(
+        SignedClaims::from_compact(claims_part),
+        Err(ClaimsError::InvalidToken)
+    );
+    assert_eq!(
+        SignedClaims::from_compact(&format!("{}.{}", claims_part, &sig_part[1..])),
+        Err(ClaimsError::InvalidToken)
+    );
+
+    let mut claims = sample_claims();
+    claims.insert("admin", ClaimValue::Bool(true));
+    let forged = sign_claims(&mut rng, &sk, &pp, &claims).to_compact();
+    let forged = format!("{}.{}", forged.split_once('.').unwrap().0, sig_part);
+    let forged = SignedClaims::from_compact(&forged).unwrap();
+    assert_eq!(
+        verify_claims(&pk, &pp, &forged, &Validation::new(1500)),
+        Err(ClaimsError::InvalidSignature)
+    );
+}
+
+/// Test the re-randomized signed claims still verify, with a different signature.
+#[test]
+fn signed_claims_randomize_is_unlinkable() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk, sk) = pp.key_gen(&mut rng, 5);
+    let signed = sign_claims(&mut rng, &sk, &pp, &sample_claims());
+
+    let mut randomized = signed.clone();
+    randomized.randomize(&mut rng);
+    assert!(randomized != signed);
+    assert!(
+        randomized.to_compact().split_once('.').unwrap().1
+            != signed.to_compact().split_once('.').unwrap().1
+    );
+    assert_eq!(
+        verify_claims(&pk, &pp, &randomized, &Validation::new(1500)),
+        Ok(())
+    );
+}
@@ -0,0 +1,187 @@
+#![cfg(feature = "cli")]
+
+use std::path::Path;
+
+use ark_serialize::CanonicalSerialize;
+use assert_cmd::Command;
+use mercurial_signature::{Fr, UniformRand, G1};
+
+fn mercurial_sig(dir: &Path, args: &[&str]) -> assert_cmd::assert::Assert {
+    Command::cargo_bin("mercurial-sig")
+        .unwrap()
+        .current_dir(dir)
+        .args(args)
+        .assert()
+}
+
+fn write_hex<T: CanonicalSerialize>(path: &Path, value: &T) {
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes).unwrap();
+    let hex = bytes
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>();
+    std::fs::write(path, hex).unwrap();
+}
+
+/// Test the issue, convert, change of representation and verify round-trip through the binary.
+#[test]
+fn cli_issue_convert_change_rep_verify() {
+    let mut rng = rand::thread_rng();
+    let dir = tempfile::tempdir().unwrap();
+    let dir = dir.path();
+    let message = (0..3).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    write_hex(&dir.join("msg.hex"), &message);
+    write_hex(&dir.join("p.hex"), &Fr::rand(&mut rng));
+    write_hex(&dir.join("u.hex"), &Fr::rand(&mut rng));
+
+    mercurial_sig(dir, &["params", "new", "--out", "pp.pem"]).success();
+    mercurial_sig(dir, &["params", "show", "pp.pem"])
+        .success()
+        .stdout(predicates::str::contains("fingerprint: "));
+    mercurial_sig(
+        dir,
+        &[
+            "keygen", "--params", "pp.pem", "--len", "3", "--pk-out", "pk.pem", "--sk-out",
+            "sk.pem",
+        ],
+    )
+    .success();
+    mercurial_sig(
+        dir,
+        &[
+            "sign",
+            "--params",
+            "pp.pem",
+            "--key",
+            "sk.pem",
+            "--message",
+            "msg.hex",
+            "--out",
+            "sig.pem",
+        ],
+    )
+    .success();
+    let verify = |key: &str, message: &str, signature: &str| {
+        mercurial_sig(
+            dir,
+            &[
+                "verify",
+                "--params",
+                "pp.pem",
+                "--key",
+                key,
+                "--message",
+                message,
+                "--signature",
+                signature,
+            ],
+        )
+    };
+    verify("pk.pem", "msg.hex", "sig.pem").success();
+
+    for (input, out) in [
+        ("pk.pem", "pk2.pem"),
+        ("sk.pem", "sk2.pem"),
+        ("sig.pem", "sig2.pem"),
+    ] {
+        mercurial_sig(
+            dir,
+            &[
+                "convert",
+                "--scalar-file",
+                "p.hex",
+                "--in",
+                input,
+                "--out",
+                out,
+            ],
+        )
+        .success();
+    }
+    verify("pk2.pem", "msg.hex", "sig2.pem").success();
+    verify("pk.pem", "msg.hex", "sig2.pem").failure().code(1);
+
+    mercurial_sig(
+        dir,
+        &[
+            "change-rep",
+            "--scalar-file",
+            "u.hex",
+            "--message",
+            "msg.hex",
+            "--signature",
+            "sig2.pem",
+            "--message-out",
+            "msg2.hex",
+            "--signature-out",
+            "sig3.pem",
+        ],
+    )
+    .success();
+    verify("pk2.pem", "msg2.hex", "sig3.pem").success();
+    verify("pk2.pem", "msg.hex", "sig3.pem").failure().code(1);
+
+    mercurial_sig(dir, &["inspect", "pk2.pem"])
+        .success()
+        .stdout(predicates::str::contains("type: MERCURIAL PUBLIC KEY"))
+        .stdout(predicates::str::contains("length: 3"))
+        .stdout(predicates::str::contains("compressed size: "));
+}
+
+/// Test the I/O errors and invalid files exit with 3, distinct from an invalid signature.
+#[test]
+fn cli_errors_exit_code() {
+    let dir = tempfile::tempdir().unwrap();
+    let dir = dir.path();
+    mercurial_sig(dir, &["params", "show", "missing.pem"])
+        .failure()
+        .code(3);
+
+    mercurial_sig(dir, &["params", "new", "--out", "pp.pem"]).success();
+    std::fs::write(dir.join("msg.hex"), "ffffffffffffffff").unwrap();
+    mercurial_sig(
+        dir,
+        &[
+            "keygen", "--params", "pp.pem", "--len", "2", "--pk-out", "pk.pem", "--sk-out",
+            "sk.pem",
+        ],
+    )
+    .success();
+    mercurial_sig(
+        dir,
+        &[
+            "sign",
+            "--params",
+            "pp.pem",
+            "--key",
+            "sk.pem",
+            "--message",
+            "msg.hex",
+            "--out",
+            "sig.pem",
+        ],
+    )
+    .failure()
+    .code(3);
+    mercurial_sig(
+        dir,
+        &[
+            "sign",
+            "--params",
+            "pp.pem",
+            "--key",
+            "pk.pem",
+            "--message",
+            "msg.hex",
+            "--out",
+            "sig.pem",
+        ],
+    )
+    .failure()
+    .code(3)
+    .stderr(predicates::str::contains("expected MERCURIAL SECRET KEY"));
+    mercurial_sig(dir, &["keygen", "--len", "2"])
+        .failure()
+        .code(2);
+}
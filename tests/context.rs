@@ -0,0 +1,70 @@
+use mercurial_signature::{change_representation, Fr, PublicParams, UniformRand, G1};
+
+/// Test the signature with a context verifies only with the same context, and not as a plain
+/// signature on the message.
+#[test]
+fn verify_fail_with_different_context() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk, sk) = pp.key_gen(&mut rng, 11);
+    let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let sig = sk.sign_with_context(&mut rng, &pp, b"protocol-a", &message);
+    assert!(pk.verify_with_context(&pp, b"protocol-a", &message, &sig));
+    assert!(!pk.verify_with_context(&pp, b"protocol-b", &message, &sig));
+    assert!(!pk.verify_with_context(&pp, b"", &message, &sig));
+    assert!(!pk.verify(&pp, &message, &sig));
+    assert!(pk.verify(&pp, &pk.context_message(b"protocol-a", &message), &sig));
+
+    // a plain signature does not verify with a context
+    let sig = sk.sign(&mut rng, &pp, &message);
+    assert!(!pk.verify_with_context(&pp, b"protocol-a", &message, &sig));
+
+    // the message must leave a slot for the context
+    let message = (0..11).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    assert!(!pk.verify_with_context(&pp, b"protocol-a", &message, &sig));
+}
+
+/// Test the context is checked with the scalar of the change of representation.
+#[test]
+fn verify_with_context_across_representation_changes() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (mut pk, mut sk) = pp.key_gen(&mut rng, 6);
+    let message = (0..5).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let sig = sk.sign_with_context(&mut rng, &pp, b"protocol-a", &message);
+
+    let mut full = pk.context_message(b"protocol-a", &message);
+    let mut sig2 = sig.clone();
+    let u = Fr::rand(&mut rng);
+    change_representation(&mut rng, &mut full, &mut sig2, u);
+    assert!(pk.verify_with_context_representation(&pp, b"protocol-a", u, &full[1..], &sig2));
+    assert!(!pk.verify_with_context_representation(&pp, b"protocol-b", u, &full[1..], &sig2));
+    assert!(!pk.verify_with_context_representation(
+        &pp,
+        b"protocol-a",
+        Fr::rand(&mut rng),
+        &full[1..],
+        &sig2
+    ));
+    assert!(!pk.verify_with_context(&pp, b"protocol-a", &full[1..], &sig2));
+
+    // converting the key keeps the context
+    let p = Fr::rand(&mut rng);
+    pk.convert(p);
+    sk.convert(p);
+    sig2.convert(&mut rng, p);
+    assert!(pk.verify_with_context_representation(&pp, b"protocol-a", u, &full[1..], &sig2));
+    let sig3 = sk.sign_with_context(&mut rng, &pp, b"protocol-b", &message);
+    assert!(pk.verify_with_context(&pp, b"protocol-b", &message, &sig3));
+}
+
+/// Test signing a message without a slot for the context panics.
+#[test]
+#[should_panic]
+fn sign_with_context_panics_without_slot() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (_, sk) = pp.key_gen(&mut rng, 5);
+    let message = (0..5).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    sk.sign_with_context(&mut rng, &pp, b"protocol-a", &message);
+}
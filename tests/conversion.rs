@@ -0,0 +1,61 @@
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use mercurial_signature::{
+    ContributedConversion, ConversionCommitment, ConversionPass, PublicParams, UniformRand, G1,
+};
+
+fn transfer<T: CanonicalSerialize + CanonicalDeserialize>(value: &T) -> T {
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes).unwrap();
+    T::deserialize_compressed(bytes.as_slice()).unwrap()
+}
+
+/// Test the honest run of the two-party conversion results in a converted public key
+/// and signature that verify the message.
+#[test]
+fn verify_ok_with_contributed_conversion() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk, sk) = pp.key_gen(&mut rng, 10);
+    let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let sig = sk.sign(&mut rng, &pp, &message);
+
+    let (session_a, commitment_a) = ContributedConversion::new(&mut rng, &pp);
+    let (session_b, commitment_b) = ContributedConversion::new(&mut rng, &pp);
+    let commitment_a: ConversionCommitment = transfer(&commitment_a);
+    let commitment_b: ConversionCommitment = transfer(&commitment_b);
+
+    // party A converts first, party B checks
+    let pass_a: ConversionPass = transfer(&session_a.apply(&mut rng, &pk, &sig));
+    assert!(pass_a.verify(&pp, &commitment_a, &pk, &message));
+
+    // party B converts then, party A checks
+    let pass_b: ConversionPass =
+        transfer(&session_b.apply(&mut rng, pass_a.public_key(), pass_a.signature()));
+    assert!(pass_b.verify(&pp, &commitment_b, pass_a.public_key(), &message));
+
+    assert!(pass_b.public_key() != &pk);
+    assert!(pass_b
+        .public_key()
+        .verify(&pp, &message, pass_b.signature()));
+}
+
+/// Test a party substituting a different scalar in the second pass is detected.
+#[test]
+fn verify_fail_if_scalar_is_substituted() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk, sk) = pp.key_gen(&mut rng, 10);
+    let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let sig = sk.sign(&mut rng, &pp, &message);
+
+    let (session_a, commitment_a) = ContributedConversion::new(&mut rng, &pp);
+    let (_, commitment_b) = ContributedConversion::new(&mut rng, &pp);
+
+    let pass_a = session_a.apply(&mut rng, &pk, &sig);
+    assert!(pass_a.verify(&pp, &commitment_a, &pk, &message));
+
+    // party B converts with another scalar than the committed one
+    let (other_session_b, _) = ContributedConversion::new(&mut rng, &pp);
+    let pass_b = other_session_b.apply(&mut rng, pass_a.public_key(), pass_a.signature());
+    assert!(!pass_b.verify(&pp, &commitment_b, pass_a.public_key(), &message));
+}
@@ -0,0 +1,83 @@
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use mercurial_signature::{
+    CoSignature, CoSigners, Fr, ProofOfPossession, PublicParams, UniformRand, G1,
+};
+
+/// Test the co-signature of three signers verifies, also after serialization.
+#[test]
+fn verify_ok_with_co_signature() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let keys = (0..3).map(|_| pp.key_gen(&mut rng, 10)).collect::<Vec<_>>();
+    let registrations = keys
+        .iter()
+        .map(|(pk, sk)| (pk.clone(), ProofOfPossession::prove(&mut rng, &pp, sk)))
+        .collect::<Vec<_>>();
+    let signers = CoSigners::new(&pp, &registrations).unwrap();
+
+    let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let sigs = keys
+        .iter()
+        .map(|(_, sk)| sk.sign(&mut rng, &pp, &message))
+        .collect::<Vec<_>>();
+    let cosig = CoSignature::combine(&sigs);
+    assert!(cosig.verify(&mut rng, &pp, &signers, &message));
+
+    let mut bytes = Vec::new();
+    cosig.serialize_compressed(&mut bytes).unwrap();
+    let cosig = CoSignature::deserialize_compressed(bytes.as_slice()).unwrap();
+    assert!(cosig.verify(&mut rng, &pp, &signers, &message));
+}
+
+/// Test the co-signature fails if a signature is on another message, is missing,
+/// or the signatures are in the wrong order.
+#[test]
+fn verify_fail_with_invalid_co_signature() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk_a, sk_a) = pp.key_gen(&mut rng, 10);
+    let (pk_b, sk_b) = pp.key_gen(&mut rng, 10);
+    let pop_a = ProofOfPossession::prove(&mut rng, &pp, &sk_a);
+    let pop_b = ProofOfPossession::prove(&mut rng, &pp, &sk_b);
+    let signers = CoSigners::new(&pp, &[(pk_a, pop_a), (pk_b, pop_b)]).unwrap();
+
+    let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let other_message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let sig_a = sk_a.sign(&mut rng, &pp, &message);
+    let sig_b = sk_b.sign(&mut rng, &pp, &other_message);
+    let cosig = CoSignature::combine(&[sig_a.clone(), sig_b]);
+    assert!(!cosig.verify(&mut rng, &pp, &signers, &message));
+
+    let cosig = CoSignature::combine(std::slice::from_ref(&sig_a));
+    assert!(!cosig.verify(&mut rng, &pp, &signers, &message));
+
+    let sig_b = sk_b.sign(&mut rng, &pp, &message);
+    let cosig = CoSignature::combine(&[sig_b, sig_a]);
+    assert!(!cosig.verify(&mut rng, &pp, &signers, &message));
+}
+
+/// Test a rogue key cannot be registered without knowing its secret key.
+#[test]
+fn register_fail_with_rogue_key() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk_a, sk_a) = pp.key_gen(&mut rng, 10);
+    let (pk_b, sk_b) = pp.key_gen(&mut rng, 10);
+    let pop_a = ProofOfPossession::prove(&mut rng, &pp, &sk_a);
+    let pop_b = ProofOfPossession::prove(&mut rng, &pp, &sk_b);
+
+    // the attacker claims a key derived from the honest key A, reusing the proof of A
+    let mut rogue_pk = pk_a.clone();
+    rogue_pk.convert(Fr::rand(&mut rng));
+    assert!(!pop_a.verify(&pp, &rogue_pk));
+    assert!(CoSigners::new(
+        &pp,
+        &[(pk_a.clone(), pop_a.clone()), (rogue_pk, pop_a.clone())]
+    )
+    .is_none());
+
+    // the attacker registers the key B with the proof of another key
+    assert!(CoSigners::new(&pp, &[(pk_a, pop_a), (pk_b.clone(), pop_b.clone())]).is_some());
+    let pop_c = ProofOfPossession::prove(&mut rng, &pp, &sk_a);
+    assert!(CoSigners::new(&pp, &[(pk_b, pop_c)]).is_none());
+}
@@ -0,0 +1,93 @@
+use ark_serialize::CanonicalSerialize;
+use mercurial_signature::{fixtures::Fixture, MasterKey, PublicKey, SecretKey};
+
+fn compressed_hex<T: CanonicalSerialize>(value: &T) -> String {
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes).unwrap();
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Test the derived secret keys match the pinned vectors, so that the derivation never changes.
+#[test]
+fn derive_matches_test_vectors() {
+    let f = Fixture::new(0);
+    let master = MasterKey::new(core::array::from_fn(|i| i as u8));
+    let vectors: [(&[u32], u32, &str); 3] = [
+        (
+            &[],
+            1,
+            "0100000000000000\
+             8114275642febcd1bec7eaf6e38256abd95652711f9138b28daf483e29236e4b",
+        ),
+        (
+            &[0],
+            2,
+            "0200000000000000\
+             527422686778b81e2d54822152fdb2596a88a3e2ab4fba64001268156d48cb10\
+             a3625f8ebba4d08ea052e6b15a61074c6f1176e6b13d5e7c6d889eb26a1be569",
+        ),
+        (
+            &[42, 7],
+            2,
+            "0200000000000000\
+             28bc0a0ae590851614b0fdd93af512bca19e7a9084a767409f525aa63d180339\
+             141640d833f345d187241aa67faad036b71476d3d249b58405f0e8f3126d2e2e",
+        ),
+    ];
+    for (path, len, expected) in vectors {
+        let (pk, sk): (PublicKey, SecretKey) = master.derive(path, &f.pp, len);
+        assert_eq!(compressed_hex(&sk), expected);
+        assert!(pk == PublicKey::from_noisy_sk(&f.pp, &sk));
+    }
+}
+
+/// Test the derivation is deterministic, and the keys of different paths, lengths and seeds
+/// are distinct.
+#[test]
+fn derive_is_deterministic_and_distinct() {
+    let f = Fixture::new(1);
+    let master = MasterKey::new([1; 32]);
+    // the hex of the elements of the secret key
+    let derive = |master: &MasterKey, path: &[u32], len| {
+        let (_, sk): (PublicKey, SecretKey) = master.derive(path, &f.pp, len);
+        let hex = compressed_hex(&sk);
+        (16..hex.len())
+            .step_by(64)
+            .map(|i| hex[i..i + 64].to_string())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        derive(&master, &[3, 4], 5),
+        derive(&MasterKey::new([1; 32]), &[3, 4], 5)
+    );
+    let keys = [
+        derive(&master, &[], 5),
+        derive(&master, &[3], 5),
+        derive(&master, &[4], 5),
+        derive(&master, &[3, 4], 5),
+        derive(&master, &[4, 3], 5),
+        derive(&master, &[3, 4, 0], 5),
+        derive(&master, &[3, 4], 6),
+        derive(&MasterKey::new([2; 32]), &[3, 4], 5),
+    ];
+    for (i, a) in keys.iter().enumerate() {
+        for b in keys.iter().skip(i + 1) {
+            assert!(!a.iter().any(|x| b.contains(x)));
+        }
+    }
+    assert_eq!(format!("{:?}", master), "MasterKey([REDACTED])");
+}
+
+/// Test a derived key pair signs and verifies, and a key of another path does not verify it.
+#[test]
+fn derive_sign_and_verify() {
+    let mut f = Fixture::new(2);
+    let master = MasterKey::new([9; 32]);
+    let (pk, sk) = master.derive(&[1, 2, 3], &f.pp, 10);
+    let (other_pk, _) = master.derive(&[1, 2, 4], &f.pp, 10);
+
+    let sig = sk.sign(&mut f.rng, &f.pp, &f.message);
+    assert!(pk.verify(&f.pp, &f.message, &sig));
+    assert!(!other_pk.verify(&f.pp, &f.message, &sig));
+}
@@ -0,0 +1,56 @@
+use mercurial_signature::{DidKeyError, PublicKey, PublicParams};
+
+const VECTOR: &str = "did:key:z6ejwpURANVxMRtYrV6tMVg7ywAmaRXp55zP1nnFLg7vstZBoLboRyJB3v6g76MDW4imNhu36aGkFUZtMCnzUE4R8PKJVUyLQPSa2NMNS4kKzpHbhcY7Cp1Ey3mEw3SWrJWzAinvpG9eLPmnLEA9qBtNpwBG7qG1XsR4S62rKsf843UwoptfYthJz6DFrRD7eDtu5KBLeP1hvgAgLpvq9epVbD7N9LwqegebtLGsLRspCQJzR6bY1JBDvhx2d3qHqrcQ2ydZEeZ1sT4N1iBcpcWG";
+
+/// Test the encoding of a fixed public key matches the test vector, and decodes back.
+#[test]
+fn did_key_matches_test_vector() {
+    let pp = PublicParams::derive_from_ceremony_transcript(b"did-key-test-vector").unwrap();
+    let (pk, _) = pp.key_gen_deterministic(&[2u8; 64], 2);
+    assert_eq!(pk.to_did_key(), VECTOR);
+    assert!(PublicKey::from_did_key(VECTOR).unwrap() == pk);
+}
+
+/// Test the identifiers of other methods, codecs, or with invalid key bytes are rejected.
+#[test]
+fn did_key_rejects_invalid_identifiers() {
+    // ed25519 did:key
+    assert_eq!(
+        PublicKey::from_did_key("did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK"),
+        Err(DidKeyError::UnsupportedCodec(0xed))
+    );
+    assert_eq!(
+        PublicKey::from_did_key("did:web:example.com"),
+        Err(DidKeyError::InvalidPrefix)
+    );
+    // base64 instead of base58btc
+    assert_eq!(
+        PublicKey::from_did_key(&VECTOR.replacen("did:key:z", "did:key:m", 1)),
+        Err(DidKeyError::InvalidPrefix)
+    );
+    assert_eq!(
+        PublicKey::from_did_key("did:key:z0OIl"),
+        Err(DidKeyError::InvalidMultibase)
+    );
+
+    let bytes = bs58::decode(&VECTOR["did:key:z".len()..])
+        .into_vec()
+        .unwrap();
+    let encode = |bytes: &[u8]| format!("did:key:z{}", bs58::encode(bytes).into_string());
+    // truncated key
+    assert_eq!(
+        PublicKey::from_did_key(&encode(&bytes[..bytes.len() - 1])),
+        Err(DidKeyError::InvalidKey)
+    );
+    // trailing bytes
+    assert_eq!(
+        PublicKey::from_did_key(&encode(&[bytes.as_slice(), &[0]].concat())),
+        Err(DidKeyError::InvalidKey)
+    );
+    // empty key
+    let empty = [&bytes[..4], &[0u8; 8][..]].concat();
+    assert_eq!(
+        PublicKey::from_did_key(&encode(&empty)),
+        Err(DidKeyError::InvalidKey)
+    );
+}
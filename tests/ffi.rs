@@ -0,0 +1,250 @@
+#![cfg(feature = "ffi")]
+
+use std::{ffi::CStr, ptr};
+
+use ark_serialize::CanonicalSerialize;
+use mercurial_signature::{ffi::*, Fr, UniformRand, G1};
+
+const HEADER: &str = "include/mercurial_signature.h";
+
+fn bytes<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes).unwrap();
+    bytes
+}
+
+/// Test the checked-in header is the one generated by cbindgen from the sources. Set
+/// `MSIG_UPDATE_HEADER=1` to regenerate it.
+#[test]
+fn ffi_header_is_up_to_date() {
+    let dir = env!("CARGO_MANIFEST_DIR");
+    let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", dir)).unwrap();
+    let mut generated = Vec::new();
+    cbindgen::Builder::new()
+        .with_crate(dir)
+        .with_config(config)
+        .generate()
+        .unwrap()
+        .write(&mut generated);
+    let generated = String::from_utf8(generated).unwrap();
+
+    let path = format!("{}/{}", dir, HEADER);
+    if std::env::var("MSIG_UPDATE_HEADER").is_ok() {
+        std::fs::write(&path, &generated).unwrap();
+    }
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), generated);
+}
+
+/// Test a full round trip of sign, serialization, conversion, change of representation and
+/// verification through the C ABI.
+#[test]
+fn ffi_round_trip() {
+    let mut rng = rand::thread_rng();
+    unsafe {
+        let seed = b"ffi-seed";
+        let mut pp = ptr::null_mut();
+        assert_eq!(
+            msig_params_new_from_seed(seed.as_ptr(), seed.len(), &mut pp),
+            MsigStatus::Ok
+        );
+        let (mut pk, mut sk) = (ptr::null_mut(), ptr::null_mut());
+        assert_eq!(msig_keygen(pp, 5, &mut pk, &mut sk), MsigStatus::Ok);
+
+        let message = bytes(&(0..5).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>());
+        let mut sig = ptr::null_mut();
+        assert_eq!(
+            msig_sign(pp, sk, message.as_ptr(), message.len(), &mut sig),
+            MsigStatus::Ok
+        );
+
+        // serialization round trip of the signature, asking for the length first
+        let mut len = 0;
+        assert_eq!(
+            msig_signature_to_bytes(sig, ptr::null_mut(), 0, &mut len),
+            MsigStatus::BufferTooSmall
+        );
+        let mut sig_bytes = vec![0u8; len];
+        assert_eq!(
+            msig_signature_to_bytes(sig, sig_bytes.as_mut_ptr(), sig_bytes.len(), &mut len),
+            MsigStatus::Ok
+        );
+        msig_signature_free(sig);
+        let mut sig = ptr::null_mut();
+        assert_eq!(
+            msig_signature_from_bytes(sig_bytes.as_ptr(), sig_bytes.len(), &mut sig),
+            MsigStatus::Ok
+        );
+
+        let p = bytes(&Fr::rand(&mut rng));
+        assert_eq!(msig_convert_pk(pk, p.as_ptr(), p.len()), MsigStatus::Ok);
+        assert_eq!(msig_convert_sk(sk, p.as_ptr(), p.len()), MsigStatus::Ok);
+        assert_eq!(msig_convert_sig(sig, p.as_ptr(), p.len()), MsigStatus::Ok);
+
+        let u = bytes(&Fr::rand(&mut rng));
+        let mut new_message = vec![0u8; message.len()];
+        let mut new_len = 0;
+        assert_eq!(
+            msig_change_representation(
+                message.as_ptr(),
+                message.len(),
+                sig,
+                u.as_ptr(),
+                u.len(),
+                new_message.as_mut_ptr(),
+                new_message.len(),
+                &mut new_len,
+            ),
+            MsigStatus::Ok
+        );
+        assert_eq!(new_len, message.len());
+
+        let mut valid = false;
+        assert_eq!(
+            msig_verify(
+                pp,
+                pk,
+                new_message.as_ptr(),
+                new_message.len(),
+                sig,
+                &mut valid
+            ),
+            MsigStatus::Ok
+        );
+        assert!(valid);
+        assert_eq!(
+            msig_verify(pp, pk, message.as_ptr(), message.len(), sig, &mut valid),
+            MsigStatus::Ok
+        );
+        assert!(!valid);
+
+        msig_signature_free(sig);
+        msig_secret_key_free(sk);
+        msig_public_key_free(pk);
+        msig_params_free(pp);
+    }
+}
+
+/// Test the errors are reported by the status and the last error message.
+#[test]
+fn ffi_reports_errors() {
+    unsafe {
+        let mut pp = ptr::null_mut();
+        assert_eq!(
+            msig_params_new_from_seed(ptr::null(), 0, &mut pp),
+            MsigStatus::InvalidInput
+        );
+        assert!(!CStr::from_ptr(msig_last_error_message())
+            .to_bytes()
+            .is_empty());
+
+        assert_eq!(
+            msig_params_new_from_seed(b"seed".as_ptr(), 4, ptr::null_mut()),
+            MsigStatus::NullPointer
+        );
+        assert_eq!(
+            msig_params_from_bytes([1u8, 2, 3].as_ptr(), 3, &mut pp),
+            MsigStatus::InvalidInput
+        );
+        assert_eq!(
+            CStr::from_ptr(msig_last_error_message())
+                .to_str()
+                .unwrap()
+                .split(':')
+                .next(),
+            Some("invalid params")
+        );
+
+        // the message is longer than the key
+        let seed = b"seed";
+        assert_eq!(
+            msig_params_new_from_seed(seed.as_ptr(), seed.len(), &mut pp),
+            MsigStatus::Ok
+        );
+        assert!(CStr::from_ptr(msig_last_error_message())
+            .to_bytes()
+            .is_empty());
+        let (mut pk, mut sk) = (ptr::null_mut(), ptr::null_mut());
+        assert_eq!(msig_keygen(pp, 1, &mut pk, &mut sk), MsigStatus::Ok);
+        let message = bytes(&vec![G1::rand(&mut rand::thread_rng()); 2]);
+        let mut sig = ptr::null_mut();
+        assert_eq!(
+            msig_sign(pp, sk, message.as_ptr(), message.len(), &mut sig),
+            MsigStatus::InvalidInput
+        );
+        assert!(sig.is_null());
+
+        msig_secret_key_free(sk);
+        msig_public_key_free(pk);
+        msig_params_free(pp);
+    }
+}
+
+/// Test a message whose length prefix is far longer than the input is rejected instead of
+/// reserving memory for it.
+#[test]
+fn ffi_rejects_oversized_message_prefix() {
+    unsafe {
+        let seed = b"seed";
+        let mut pp = ptr::null_mut();
+        assert_eq!(
+            msig_params_new_from_seed(seed.as_ptr(), seed.len(), &mut pp),
+            MsigStatus::Ok
+        );
+        let (mut pk, mut sk) = (ptr::null_mut(), ptr::null_mut());
+        assert_eq!(msig_keygen(pp, 2, &mut pk, &mut sk), MsigStatus::Ok);
+        let mut rng = rand::thread_rng();
+        let valid = bytes(&vec![G1::rand(&mut rng); 2]);
+        let mut sig = ptr::null_mut();
+        assert_eq!(
+            msig_sign(pp, sk, valid.as_ptr(), valid.len(), &mut sig),
+            MsigStatus::Ok
+        );
+
+        // the prefix claims 2^40 elements, followed by two
+        let mut message = (1u64 << 40).to_le_bytes().to_vec();
+        message.extend_from_slice(&valid[8..]);
+
+        let mut oversized_sig = ptr::null_mut();
+        assert_eq!(
+            msig_sign(pp, sk, message.as_ptr(), message.len(), &mut oversized_sig),
+            MsigStatus::InvalidInput
+        );
+        assert!(oversized_sig.is_null());
+
+        let mut valid_out = true;
+        assert_eq!(
+            msig_verify(pp, pk, message.as_ptr(), message.len(), sig, &mut valid_out),
+            MsigStatus::InvalidInput
+        );
+
+        let u = bytes(&Fr::rand(&mut rng));
+        let mut out = vec![0u8; valid.len()];
+        let mut out_len = 0;
+        assert_eq!(
+            msig_change_representation(
+                message.as_ptr(),
+                message.len(),
+                sig,
+                u.as_ptr(),
+                u.len(),
+                out.as_mut_ptr(),
+                out.len(),
+                &mut out_len,
+            ),
+            MsigStatus::InvalidInput
+        );
+        assert_eq!(
+            CStr::from_ptr(msig_last_error_message())
+                .to_str()
+                .unwrap()
+                .split(':')
+                .next(),
+            Some("invalid message")
+        );
+
+        msig_signature_free(sig);
+        msig_secret_key_free(sk);
+        msig_public_key_free(pk);
+        msig_params_free(pp);
+    }
+}
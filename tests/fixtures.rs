@@ -0,0 +1,35 @@
+use mercurial_signature::fixtures::{Fixture, SignatureComponent};
+
+/// Test the fixtures are reproduced from the same seed only.
+#[test]
+fn fixture_depends_on_seed() {
+    let f1 = Fixture::new(42);
+    let f2 = Fixture::new(42);
+    let f3 = Fixture::new(43);
+    assert!(f1.pp == f2.pp && f1.pk == f2.pk && f1.sig == f2.sig);
+    assert!(f1.pk_g1 == f2.pk_g1 && f1.sig_g2 == f2.sig_g2);
+    assert!(f1.pp != f3.pp && f1.pk != f3.pk);
+
+    let f4 = Fixture::with_length(42, 3);
+    assert_eq!(f4.pk.length(), 3);
+    assert_eq!(f4.message_g2.len(), 3);
+}
+
+/// Test the signature with any of its components corrupted does not verify, in both schemes.
+#[test]
+fn corrupted_signatures_do_not_verify() {
+    let f = Fixture::with_length(7, 3);
+    assert!(f.pk.verify(&f.pp, &f.message, &f.sig));
+    assert!(f.pk_g1.verify(&f.pp, &f.message_g2, &f.sig_g2));
+
+    for which in [
+        SignatureComponent::Z,
+        SignatureComponent::Y1,
+        SignatureComponent::Y2,
+    ] {
+        assert!(!f.pk.verify(&f.pp, &f.message, &f.corrupt_signature(which)));
+        assert!(!f
+            .pk_g1
+            .verify(&f.pp, &f.message_g2, &f.corrupt_signature_g2(which)));
+    }
+}
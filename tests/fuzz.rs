@@ -0,0 +1,32 @@
+use std::{fs, path::Path};
+
+#[path = "../fuzz/src/lib.rs"]
+mod fuzz;
+
+/// Run the check of a fuzz target on the inputs under `tests/fuzz_corpus/<target>/`, e.g. the
+/// inputs of the crashes found by fuzzing.
+fn replay(target: &str, check: fn(&[u8])) {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fuzz_corpus")
+        .join(target);
+    for entry in fs::read_dir(dir).unwrap() {
+        check(&fs::read(entry.unwrap().path()).unwrap());
+    }
+}
+
+/// Test the length prefixes of vectors larger than the input are rejected without reserving
+/// their capacity.
+#[test]
+fn replay_deserialize_corpus() {
+    replay("deserialize", fuzz::deserialize);
+}
+
+#[test]
+fn replay_verify_mutation_corpus() {
+    replay("verify_mutation", fuzz::verify_mutation);
+}
+
+#[test]
+fn replay_envelopes_corpus() {
+    replay("envelopes", fuzz::envelopes);
+}
@@ -0,0 +1,84 @@
+use mercurial_signature::{
+    change_representation, change_representation_g2, fixtures::Fixture, Fr, PublicParams,
+    UniformRand, G2,
+};
+
+/// Test the converted public key, secret key, and signature on a message in G2 verify the message.
+#[test]
+fn verify_ok_for_g2_message_with_converted_keys_and_sigs() {
+    let mut f = Fixture::new(1);
+    assert!(f.pk_g1.verify(&f.pp, &f.message_g2, &f.sig_g2));
+    assert!(f.sk.public_key_g1(&f.pp) == f.pk_g1);
+
+    let p = f.random_scalar();
+    let mut f2 = f.converted_copy(p);
+    assert!(f.pk_g1 != f2.pk_g1);
+    assert!(f.sig_g2 != f2.sig_g2);
+
+    assert!(f2.pk_g1.verify(&f.pp, &f.message_g2, &f2.sig_g2));
+    assert!(!f2.pk_g1.verify(&f.pp, &f.message_g2, &f.sig_g2));
+    assert!(!f.pk_g1.verify(&f.pp, &f.message_g2, &f2.sig_g2));
+
+    let sig3 = f2.sk.sign_g2(&mut f2.rng, &f.pp, &f.message_g2);
+    assert!(f2.pk_g1.verify(&f.pp, &f.message_g2, &sig3));
+}
+
+#[test]
+fn verify_ok_for_g2_message_if_key_length_is_greater_than_message_length() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk, sk) = pp.key_gen_g1(&mut rng, 10);
+
+    let message = (0..5).map(|_| G2::rand(&mut rng)).collect::<Vec<G2>>();
+    let sig = sk.sign_g2(&mut rng, &pp, &message);
+    assert!(pk.verify(&pp, &message, &sig));
+}
+
+/// Test the change representation function on a message in G2.
+#[test]
+fn verify_ok_for_g2_message_with_change_representation() {
+    let mut f = Fixture::new(2);
+    let u = f.random_scalar();
+    let f2 = f.rerepresented_copy(u);
+    assert!(f.pk_g1.verify(&f.pp, &f2.message_g2, &f2.sig_g2));
+
+    assert!(!f.pk_g1.verify(&f.pp, &f2.message_g2, &f.sig_g2));
+    assert!(!f.pk_g1.verify(&f.pp, &f.message_g2, &f2.sig_g2));
+}
+
+/// Test the alternation of levels in delegatable credentials: a key with elements in G1 signs
+/// a public key with elements in G2, whose secret key signs a public key with elements in G1.
+#[test]
+fn verify_ok_with_alternating_levels() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (root_pk, root_sk) = pp.key_gen_g1(&mut rng, 10);
+    let (pk1, sk1) = pp.key_gen(&mut rng, 10);
+    let (pk2, _) = pp.key_gen_g1(&mut rng, 10);
+
+    // level 1: the root signs the public key in G2
+    let mut message1 = pk1.elements().to_vec();
+    let mut sig1 = root_sk.sign_g2(&mut rng, &pp, &message1);
+    assert!(root_pk.verify(&pp, &message1, &sig1));
+
+    // level 2: the level 1 key signs the public key in G1
+    let mut message2 = pk2.elements().to_vec();
+    let mut sig2 = sk1.sign(&mut rng, &pp, &message2);
+    assert!(pk1.verify(&pp, &message2, &sig2));
+
+    // both levels are re-randomized consistently
+    let u1 = Fr::rand(&mut rng);
+    change_representation_g2(&mut rng, &mut message1, &mut sig1, u1);
+    assert!(root_pk.verify(&pp, &message1, &sig1));
+
+    let mut pk1 = pk1.clone();
+    pk1.convert(u1);
+    assert!(pk1.elements() == message1.as_slice());
+    sig2.convert(&mut rng, u1);
+    assert!(pk1.verify(&pp, &message2, &sig2));
+
+    let u2 = Fr::rand(&mut rng);
+    change_representation(&mut rng, &mut message2, &mut sig2, u2);
+    assert!(pk1.verify(&pp, &message2, &sig2));
+    assert!(message2.as_slice() != pk2.elements());
+}
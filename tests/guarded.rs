@@ -0,0 +1,66 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+use mercurial_signature::{fixtures::Fixture, GuardedSecretKey};
+
+/// The system allocator, recording on the watching thread whether the freed buffers were
+/// zeroized.
+struct ZeroCheckingAllocator;
+
+thread_local! {
+    static WATCHING: Cell<bool> = const { Cell::new(false) };
+    // (size, whether all zero) of the last buffer freed while watching
+    static FREED: Cell<Option<(usize, bool)>> = const { Cell::new(None) };
+}
+
+unsafe impl GlobalAlloc for ZeroCheckingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if WATCHING.get() {
+            let bytes = std::slice::from_raw_parts(ptr, layout.size());
+            FREED.set(Some((layout.size(), bytes.iter().all(|b| *b == 0))));
+        }
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: ZeroCheckingAllocator = ZeroCheckingAllocator;
+
+/// Test the key is used through `expose`, and its `Debug` output is redacted.
+#[test]
+fn guarded_secret_key_api() {
+    let mut f = Fixture::new(1);
+    let sk = GuardedSecretKey::from(f.sk.clone());
+    assert_eq!(sk.length(), f.sk.length());
+    assert_eq!(format!("{:?}", sk), "GuardedSecretKey([REDACTED; 10])");
+
+    let sig = sk.expose(|sk| sk.sign(&mut f.rng, &f.pp, &f.message));
+    assert!(f.pk.verify(&f.pp, &f.message, &sig));
+    assert!(sk.expose(|sk| *sk == f.sk));
+}
+
+/// Test the elements are locked in memory with the feature `secure-memory`.
+#[cfg(all(feature = "secure-memory", any(unix, windows)))]
+#[test]
+fn guarded_secret_key_is_locked() {
+    let f = Fixture::new(2);
+    assert!(GuardedSecretKey::new(f.sk).is_locked());
+}
+
+/// Test the buffer of the elements is zeroized before it is freed.
+#[test]
+fn guarded_secret_key_zeroizes_on_drop() {
+    let f = Fixture::with_length(3, 37);
+    let sk = GuardedSecretKey::new(f.sk.clone());
+
+    WATCHING.set(true);
+    drop(sk);
+    WATCHING.set(false);
+    assert_eq!(FREED.get(), Some((37 * 32, true)));
+}
@@ -0,0 +1,191 @@
+#![cfg(feature = "keystore")]
+
+use std::{fs, sync::Arc, thread};
+
+use mercurial_signature::{
+    fixtures::Fixture, EntryKind, GuardedSecretKey, Keypair, Keystore, KeystoreEntry, KeystoreError,
+};
+
+fn keypair(f: &Fixture) -> Keypair {
+    Keypair {
+        pk: f.pk.clone(),
+        sk: GuardedSecretKey::new(f.sk.clone()),
+    }
+}
+
+fn assert_keypair(keypair: Keypair, f: &Fixture) {
+    assert_eq!(keypair.pk, f.pk);
+    assert!(keypair.sk.expose(|sk| *sk == f.sk));
+}
+
+/// Test the parameters, the key pairs and the credentials are read as they were written, and
+/// listed by label and kind.
+#[test]
+fn keystore_round_trips() {
+    let dir = tempfile::tempdir().unwrap();
+    let keystore = Keystore::open(dir.path().join("store")).unwrap();
+    let f = Fixture::new(1);
+
+    keystore.put_params("default", &f.pp).unwrap();
+    keystore.put_keypair("issuer", &keypair(&f)).unwrap();
+    keystore
+        .put_credential("issuer", &f.message, &f.sig)
+        .unwrap();
+    assert_eq!(keystore.get_params("default").unwrap(), f.pp);
+    assert_keypair(keystore.get_keypair("issuer").unwrap(), &f);
+    let (message, sig) = keystore.get_credential("issuer").unwrap();
+    assert_eq!(message, f.message);
+    assert!(f.pk.verify(&f.pp, &message, &sig));
+
+    // an entry is replaced
+    let g = Fixture::new(2);
+    keystore.put_keypair("issuer", &keypair(&g)).unwrap();
+    assert_keypair(keystore.get_keypair("issuer").unwrap(), &g);
+
+    // the temporary files are not left behind, and other files are not listed
+    fs::write(dir.path().join("store/notes.txt"), "").unwrap();
+    let entry = |label: &str, kind| KeystoreEntry {
+        label: label.to_string(),
+        kind,
+    };
+    assert_eq!(
+        keystore.list().unwrap(),
+        vec![
+            entry("default", EntryKind::Params),
+            entry("issuer", EntryKind::Keypair),
+            entry("issuer", EntryKind::Credential),
+        ]
+    );
+    assert_eq!(fs::read_dir(dir.path().join("store")).unwrap().count(), 4);
+
+    assert!(matches!(
+        keystore.get_params("issuer"),
+        Err(KeystoreError::NotFound)
+    ));
+    assert!(matches!(
+        keystore.put_params("../escape", &f.pp),
+        Err(KeystoreError::InvalidLabel)
+    ));
+    assert!(matches!(
+        keystore.get_params(""),
+        Err(KeystoreError::InvalidLabel)
+    ));
+}
+
+/// Test the encrypted secret key is read only with the right passphrase.
+#[test]
+fn keystore_passphrase() {
+    let dir = tempfile::tempdir().unwrap();
+    let keystore = Keystore::open(dir.path()).unwrap();
+    let f = Fixture::new(3);
+
+    keystore
+        .put_keypair_with_passphrase("holder", &keypair(&f), "correct horse")
+        .unwrap();
+    let text = fs::read_to_string(dir.path().join("holder.keypair.pem")).unwrap();
+    assert!(text.contains("MERCURIAL ENCRYPTED SECRET KEY"));
+    assert!(!text.contains("MERCURIAL SECRET KEY"));
+
+    assert_keypair(
+        keystore
+            .get_keypair_with_passphrase("holder", "correct horse")
+            .unwrap(),
+        &f,
+    );
+    assert!(matches!(
+        keystore.get_keypair_with_passphrase("holder", "battery staple"),
+        Err(KeystoreError::WrongPassphrase)
+    ));
+    assert!(matches!(
+        keystore.get_keypair("holder"),
+        Err(KeystoreError::PassphraseRequired)
+    ));
+}
+
+/// Test a corrupted file is detected, whether it is still a valid encoding or not.
+#[test]
+fn keystore_detects_corrupted_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let keystore = Keystore::open(dir.path()).unwrap();
+    let f = Fixture::new(4);
+    keystore.put_keypair("issuer", &keypair(&f)).unwrap();
+    let path = dir.path().join("issuer.keypair.pem");
+    let text = fs::read_to_string(&path).unwrap();
+
+    // a changed character of base64 in every line of every block
+    for (i, line) in text.lines().enumerate() {
+        if line.starts_with("-----") {
+            continue;
+        }
+        let mut lines = text.lines().map(str::to_string).collect::<Vec<_>>();
+        let c = if line.starts_with('A') { 'B' } else { 'A' };
+        lines[i].replace_range(0..1, &c.to_string());
+        fs::write(&path, lines.join("\n")).unwrap();
+        assert!(matches!(
+            keystore.get_keypair("issuer"),
+            Err(KeystoreError::Corrupted)
+        ));
+    }
+
+    // a truncated file, and a file without the checksum
+    fs::write(&path, &text[..text.len() / 2]).unwrap();
+    assert!(matches!(
+        keystore.get_keypair("issuer"),
+        Err(KeystoreError::Corrupted)
+    ));
+    let end = text.find("-----BEGIN MERCURIAL CHECKSUM-----").unwrap();
+    fs::write(&path, &text[..end]).unwrap();
+    assert!(matches!(
+        keystore.get_keypair("issuer"),
+        Err(KeystoreError::Corrupted)
+    ));
+}
+
+/// Test the readers see complete entries while the entry is rewritten.
+#[test]
+fn keystore_concurrent_readers() {
+    let dir = tempfile::tempdir().unwrap();
+    let keystore = Arc::new(Keystore::open(dir.path()).unwrap());
+    let fixtures = [Fixture::with_length(5, 3), Fixture::with_length(6, 3)];
+    keystore
+        .put_credential("c", &fixtures[0].message, &fixtures[0].sig)
+        .unwrap();
+
+    let readers = (0..4)
+        .map(|_| {
+            let keystore = keystore.clone();
+            let fixtures = fixtures.clone();
+            thread::spawn(move || {
+                for _ in 0..50 {
+                    let (message, sig) = keystore.get_credential("c").unwrap();
+                    assert!(fixtures
+                        .iter()
+                        .any(|f| message == f.message && sig == f.sig));
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for i in 0..50 {
+        let f = &fixtures[i % 2];
+        keystore.put_credential("c", &f.message, &f.sig).unwrap();
+    }
+    for reader in readers {
+        reader.join().unwrap();
+    }
+}
+
+/// Test the directory and the files are accessible only by the owner.
+#[cfg(unix)]
+#[test]
+fn keystore_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let keystore = Keystore::open(dir.path().join("store")).unwrap();
+    let f = Fixture::new(7);
+    keystore.put_keypair("issuer", &keypair(&f)).unwrap();
+
+    let mode = |path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+    assert_eq!(mode(dir.path().join("store")), 0o700);
+    assert_eq!(mode(dir.path().join("store/issuer.keypair.pem")), 0o600);
+}
@@ -0,0 +1,67 @@
+#![cfg(feature = "merlin")]
+
+use ark_bls12_381::Bls12_381;
+use mercurial_signature::{fixtures::Fixture, Fr, MercurialTranscript, G1};
+
+/// A Schnorr proof of knowledge of `w` with `w_p1 = w * p1`, bound to a signed message.
+fn prove(f: &mut Fixture, w: Fr, w_p1: &G1) -> (G1, Fr) {
+    let r = f.random_scalar();
+    let commitment = f.pp.p1 * r;
+    let c = challenge(f, w_p1, &commitment);
+    (commitment, r + c * w)
+}
+
+fn challenge(f: &Fixture, w_p1: &G1, commitment: &G1) -> Fr {
+    let mut transcript = MercurialTranscript::new(b"test/schnorr");
+    transcript.append_public_params(b"pp", &f.pp);
+    transcript.append_public_key(b"pk", &f.pk);
+    transcript.append_message::<Bls12_381>(b"message", &f.message);
+    transcript.append_signature(b"sig", &f.sig);
+    transcript.append_message::<Bls12_381>(b"statement", &[*w_p1]);
+    transcript.append_message::<Bls12_381>(b"commitment", &[*commitment]);
+    transcript.challenge_scalar::<Bls12_381>(b"challenge")
+}
+
+/// Test a sign-commit-challenge-respond cycle, and that the proof is bound to the signature.
+#[test]
+fn merlin_transcript_proof_cycle() {
+    let mut f = Fixture::new(1);
+    assert!(f.pk.verify(&f.pp, &f.message, &f.sig));
+
+    let w = f.random_scalar();
+    let w_p1 = f.pp.p1 * w;
+    let (commitment, response) = prove(&mut f, w, &w_p1);
+    let c = challenge(&f, &w_p1, &commitment);
+    assert_eq!(f.pp.p1 * response, commitment + w_p1 * c);
+
+    // the challenge differs for another signature on the same message
+    let mut other = f.clone();
+    other.sig = other.sk.sign(&mut other.rng, &other.pp, &other.message);
+    let c_other = challenge(&other, &w_p1, &commitment);
+    assert!(c_other != c);
+    assert!(f.pp.p1 * response != commitment + w_p1 * c_other);
+}
+
+/// Test the challenge is deterministic for fixed inputs, and depends on the domain.
+#[test]
+fn merlin_transcript_challenge_is_deterministic() {
+    let f = Fixture::new(2);
+    let challenge_of = |domain: &'static [u8]| {
+        let mut transcript = MercurialTranscript::new(domain);
+        transcript.append_public_params(b"pp", &f.pp);
+        transcript.append_public_key(b"pk", &f.pk);
+        transcript.append_message::<Bls12_381>(b"message", &f.message);
+        transcript.append_signature(b"sig", &f.sig);
+        transcript.challenge_scalar::<Bls12_381>(b"challenge")
+    };
+    let c = challenge_of(b"test/a");
+    assert_eq!(c, challenge_of(b"test/a"));
+    assert_eq!(c, challenge_of(b"test/a"));
+    assert!(c != challenge_of(b"test/b"));
+
+    // successive challenges differ
+    let mut transcript = MercurialTranscript::new(b"test/a");
+    let c1 = transcript.challenge_scalar::<Bls12_381>(b"challenge");
+    let c2 = transcript.challenge_scalar::<Bls12_381>(b"challenge");
+    assert!(c1 != c2);
+}
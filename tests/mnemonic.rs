@@ -0,0 +1,108 @@
+#![cfg(feature = "mnemonic")]
+
+use ark_serialize::CanonicalSerialize;
+use mercurial_signature::{
+    fixtures::Fixture, key_gen_from_mnemonic, Mnemonic, MnemonicError, PublicKey, PublicParams,
+};
+use sha2::{Digest, Sha256};
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// The first 16 bytes of the SHA-256 of the compressed public key, as printed by the CLI.
+fn fingerprint(pk: &PublicKey) -> String {
+    let mut bytes = Vec::new();
+    pk.serialize_compressed(&mut bytes).unwrap();
+    to_hex(&Sha256::digest(&bytes)[..16])
+}
+
+/// Test the seeds of the standard BIP39 test vectors, with the passphrase "TREZOR".
+#[test]
+fn mnemonic_to_seed_test_vectors() {
+    let vectors = [
+        (
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04",
+        ),
+        (
+            "legal winner thank year wave sausage worth useful legal winner thank yellow",
+            "2e8905819b8723fe2c1d161860e5ee1830318dbf49a83bd451cfb8440c28bd6fa457fe1296106559a3c80937a1c1069be3a3a5bd381ee6260e8d9739fce1f607",
+        ),
+        (
+            "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo wrong",
+            "ac27495480225222079d7be181583751e86f571027b0497b5b5d11218e0a8a13332572917f0f8e5a589620c6f15b11c61dee327651a14c34e18231052e48c069",
+        ),
+        (
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon art",
+            "bda85446c68413707090a52022edd26a1c9462295029f2e60cd7c4f2bbd3097170af7a4d73245cafa9c3cca8d561a7c3de6f5d4a10be8ed2a5e608d68f92fcc8",
+        ),
+    ];
+    for (phrase, seed) in vectors {
+        let mnemonic = Mnemonic::parse(phrase).unwrap();
+        assert_eq!(mnemonic.to_string(), phrase);
+        assert_eq!(to_hex(mnemonic.to_seed("TREZOR").as_slice()), seed);
+    }
+}
+
+/// Test the phrases entered by users are validated by the word count, the wordlist and the
+/// checksum, and are normalized.
+#[test]
+fn mnemonic_parse_errors() {
+    let phrase = "legal winner thank year wave sausage worth useful legal winner thank yellow";
+    let entered =
+        "  Legal winner\tTHANK year wave sausage\nworth useful legal winner thank yellow ";
+    assert!(Mnemonic::parse(entered).unwrap() == Mnemonic::parse(phrase).unwrap());
+
+    assert_eq!(
+        Mnemonic::parse("legal winner thank year wave sausage worth useful legal winner thank"),
+        Err(MnemonicError::WordCount(11))
+    );
+    assert_eq!(
+        Mnemonic::parse(
+            "legal winner thank year wave sausage worth useful legal winner thank yelow"
+        ),
+        Err(MnemonicError::UnknownWord(11))
+    );
+    assert_eq!(
+        Mnemonic::parse("legal winner thank year wave sausage worth useful legal winner thank zoo"),
+        Err(MnemonicError::InvalidChecksum)
+    );
+    assert_eq!(
+        MnemonicError::UnknownWord(11).to_string(),
+        "the word 12 of the mnemonic is unknown"
+    );
+}
+
+/// Test a generated phrase has 24 words, and restores the same key pair from its text, while
+/// another passphrase gives another key pair.
+#[test]
+fn mnemonic_restores_key_pair() {
+    let mut f = Fixture::new(1);
+    let mnemonic = Mnemonic::generate(&mut f.rng);
+    assert_eq!(mnemonic.word_count(), 24);
+    assert_eq!(format!("{:?}", mnemonic), "Mnemonic([REDACTED; 24])");
+
+    let (pk, sk) = key_gen_from_mnemonic(&f.pp, &mnemonic, "passphrase", 10);
+    let restored = Mnemonic::parse(&mnemonic.to_string()).unwrap();
+    let (restored_pk, restored_sk) = key_gen_from_mnemonic(&f.pp, &restored, "passphrase", 10);
+    assert_eq!(fingerprint(&restored_pk), fingerprint(&pk));
+    assert!(restored_sk == sk);
+    let sig = restored_sk.sign(&mut f.rng, &f.pp, &f.message);
+    assert!(pk.verify(&f.pp, &f.message, &sig));
+
+    let (other_pk, _) = key_gen_from_mnemonic(&f.pp, &mnemonic, "", 10);
+    assert_ne!(fingerprint(&other_pk), fingerprint(&pk));
+}
+
+/// Test the fingerprint of the public key of a fixed phrase never changes.
+#[test]
+fn mnemonic_public_key_fingerprint_is_stable() {
+    let pp = PublicParams::derive_from_ceremony_transcript(b"mnemonic test vectors").unwrap();
+    let mnemonic = Mnemonic::parse(
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon art",
+    )
+    .unwrap();
+    let (pk, _) = key_gen_from_mnemonic(&pp, &mnemonic, "TREZOR", 3);
+    assert_eq!(fingerprint(&pk), "eecf6ce4e593885bd7a48a535e5135a4");
+}
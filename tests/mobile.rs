@@ -0,0 +1,113 @@
+#![cfg(feature = "uniffi")]
+
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+use mercurial_signature::mobile::{
+    mobile_random_message, mobile_random_scalar, MobileError, MobileKeypair, MobilePublicKey,
+    MobilePublicParams, MobileSignature, RandomSource,
+};
+
+/// A deterministic source standing in for the host's generator.
+struct CounterSource(AtomicU64);
+
+impl RandomSource for CounterSource {
+    fn fill_bytes(&self, len: u32) -> Vec<u8> {
+        (0..len)
+            .map(|_| self.0.fetch_add(0x9e37_79b9_7f4a_7c15, Ordering::Relaxed) as u8)
+            .collect()
+    }
+}
+
+/// A broken source which always returns too few bytes.
+struct ShortSource;
+
+impl RandomSource for ShortSource {
+    fn fill_bytes(&self, len: u32) -> Vec<u8> {
+        vec![0; len as usize / 2]
+    }
+}
+
+/// Test the full cycle of sign, convert, change of representation and verify through the
+/// exported interface, with the values passed as bytes.
+#[test]
+fn mobile_sign_convert_change_representation_verify() {
+    let pp = MobilePublicParams::new(None).unwrap();
+    let pp = MobilePublicParams::from_bytes(pp.to_bytes()).unwrap();
+    let keypair = MobileKeypair::new(pp.clone(), 5, None).unwrap();
+    let message = mobile_random_message(5, None).unwrap();
+    let sig = keypair.sign(pp.clone(), message.clone(), None).unwrap();
+    let sig = MobileSignature::from_bytes(sig.to_bytes()).unwrap();
+    assert!(keypair
+        .verify(pp.clone(), message.clone(), sig.clone())
+        .unwrap());
+
+    let p = mobile_random_scalar(None).unwrap();
+    let keypair = keypair.convert(p.clone()).unwrap();
+    let sig = sig.convert(p.clone(), None).unwrap();
+    assert!(keypair
+        .verify(pp.clone(), message.clone(), sig.clone())
+        .unwrap());
+
+    let changed = sig
+        .change_representation(message.clone(), mobile_random_scalar(None).unwrap(), None)
+        .unwrap();
+    assert!(changed.message != message);
+    let pk = MobilePublicKey::from_bytes(keypair.public_key().to_bytes()).unwrap();
+    assert!(pk
+        .verify(pp.clone(), changed.message, changed.signature.clone())
+        .unwrap());
+    assert!(!pk.verify(pp, message, changed.signature).unwrap());
+}
+
+/// Test the randomness supplied by the host is used, and a short answer is an error.
+#[test]
+fn mobile_random_source() {
+    let source = || -> Option<Arc<dyn RandomSource>> { Some(Arc::new(CounterSource(1.into()))) };
+    let pp = MobilePublicParams::new(source()).unwrap();
+    assert!(pp.to_bytes() == MobilePublicParams::new(source()).unwrap().to_bytes());
+
+    let keypair = MobileKeypair::new(pp.clone(), 2, source()).unwrap();
+    let message = mobile_random_message(2, source()).unwrap();
+    let sig = keypair.sign(pp.clone(), message.clone(), source()).unwrap();
+    assert!(keypair.verify(pp.clone(), message, sig).unwrap());
+
+    let short = Some(Arc::new(ShortSource) as Arc<dyn RandomSource>);
+    assert!(matches!(
+        MobileKeypair::new(pp, 2, short),
+        Err(MobileError::RandomSource { .. })
+    ));
+}
+
+/// Test the invalid inputs are returned as errors instead of panics.
+#[test]
+fn mobile_invalid_inputs_are_errors() {
+    let pp = MobilePublicParams::new(None).unwrap();
+    let keypair = MobileKeypair::new(pp.clone(), 2, None).unwrap();
+    let other = MobileKeypair::new(pp.clone(), 3, None).unwrap();
+    let long_message = mobile_random_message(3, None).unwrap();
+
+    assert!(matches!(
+        MobilePublicParams::from_bytes(vec![1, 2, 3]),
+        Err(MobileError::InvalidInput { .. })
+    ));
+    assert!(keypair.sign(pp.clone(), long_message, None).is_err());
+    assert!(keypair.sign(pp, vec![0xff; 10], None).is_err());
+    assert!(keypair.convert(vec![0xff; 32]).is_err());
+    assert!(
+        MobileKeypair::from_bytes(keypair.public_key().to_bytes(), other.secret_key_bytes())
+            .is_err()
+    );
+}
+
+/// Test the objects can be shared between threads.
+#[test]
+fn mobile_objects_are_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<MobilePublicParams>();
+    assert_send_sync::<MobilePublicKey>();
+    assert_send_sync::<MobileKeypair>();
+    assert_send_sync::<MobileSignature>();
+}
@@ -0,0 +1,45 @@
+use std::process::Command;
+
+/// Test the library builds without the default features, i.e. without std, on the host. It
+/// uses its own target directory so that it does not wait for the lock of the running build.
+#[test]
+fn lib_builds_without_default_features() {
+    let dir = env!("CARGO_MANIFEST_DIR");
+    let status = Command::new(env!("CARGO"))
+        .args([
+            "check",
+            "--lib",
+            "--no-default-features",
+            "--features",
+            "alloc",
+            "--manifest-path",
+        ])
+        .arg(format!("{}/Cargo.toml", dir))
+        .arg("--target-dir")
+        .arg(format!("{}/target/no-std-check", dir))
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+/// Test the crate links without std for an embedded target, by building the example in
+/// `no-std-example`. Ignored by default as it needs the target, e.g.
+/// `rustup target add thumbv7em-none-eabihf`.
+#[test]
+#[ignore = "needs the thumbv7em-none-eabihf target"]
+fn no_std_example_links_for_embedded_target() {
+    let status = Command::new(env!("CARGO"))
+        .args([
+            "build",
+            "--target",
+            "thumbv7em-none-eabihf",
+            "--manifest-path",
+        ])
+        .arg(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/no-std-example/Cargo.toml"
+        ))
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
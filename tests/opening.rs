@@ -0,0 +1,105 @@
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use mercurial_signature::{
+    Fr, LinkResult, OpeningAuthority, PublicParams, RegistrationRecord, UniformRand, G1,
+};
+
+/// Test the authority opens a pseudonym showing a credential to the registered public key,
+/// and the opening proof verifies.
+#[test]
+fn open_pseudonym_to_registered_key() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let authority = OpeningAuthority::new(&mut rng, &pp);
+
+    // registration, the issuer signs only if the record verifies
+    let (pk, mut sk) = pp.key_gen(&mut rng, 5);
+    let record = RegistrationRecord::new(&mut rng, &pp, authority.public_key(), &pk, &sk);
+    let mut bytes = Vec::new();
+    record.serialize_compressed(&mut bytes).unwrap();
+    let record = RegistrationRecord::deserialize_compressed(&bytes[..]).unwrap();
+    assert!(record.verify(&pp, authority.public_key()));
+    assert!(record.public_key() == &pk);
+
+    // the user shows a signature under a pseudonym
+    let p = Fr::rand(&mut rng);
+    let mut pseudonym = pk.clone();
+    pseudonym.convert(p);
+    sk.convert(p);
+    let message = (0..5).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let sig = sk.sign(&mut rng, &pp, &message);
+    assert!(pseudonym.verify(&pp, &message, &sig));
+
+    let proof = match authority.open(&mut rng, &pp, &pseudonym, &record) {
+        LinkResult::Linked(proof) => proof,
+        LinkResult::NotLinked => panic!("the pseudonym is linked"),
+    };
+    assert!(proof.verify(&pp, authority.public_key(), &pseudonym, &record));
+
+    // the proof does not link another pseudonym or verify under another authority
+    let (other_pk, _) = pp.key_gen(&mut rng, 5);
+    assert!(!proof.verify(&pp, authority.public_key(), &other_pk, &record));
+    let other_authority = OpeningAuthority::new(&mut rng, &pp);
+    assert!(!proof.verify(&pp, other_authority.public_key(), &pseudonym, &record));
+}
+
+/// Test the authority refuses to open a pseudonym against an unrelated record, and records
+/// with a wrong encryption are rejected.
+#[test]
+fn open_fail_for_unrelated_record() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let authority = OpeningAuthority::new(&mut rng, &pp);
+
+    let (pk_a, sk_a) = pp.key_gen(&mut rng, 5);
+    let (pk_b, sk_b) = pp.key_gen(&mut rng, 5);
+    let record_a = RegistrationRecord::new(&mut rng, &pp, authority.public_key(), &pk_a, &sk_a);
+
+    let mut pseudonym_b = pk_b.clone();
+    pseudonym_b.convert(Fr::rand(&mut rng));
+    assert!(authority.open(&mut rng, &pp, &pseudonym_b, &record_a) == LinkResult::NotLinked);
+
+    // a record encrypted to another authority
+    let other_authority = OpeningAuthority::new(&mut rng, &pp);
+    let record_b =
+        RegistrationRecord::new(&mut rng, &pp, other_authority.public_key(), &pk_b, &sk_b);
+    assert!(!record_b.verify(&pp, authority.public_key()));
+    assert!(authority.open(&mut rng, &pp, &pseudonym_b, &record_b) == LinkResult::NotLinked);
+
+    // a record with the public key of another user
+    let record_c = RegistrationRecord::new(&mut rng, &pp, authority.public_key(), &pk_a, &sk_b);
+    assert!(!record_c.verify(&pp, authority.public_key()));
+}
+
+/// Test a key of length 1 cannot be registered, as it has no linking information and every
+/// pseudonym of length 1 would be linked to it.
+#[test]
+#[should_panic]
+fn register_fail_with_key_of_length_one() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let authority = OpeningAuthority::new(&mut rng, &pp);
+    let (pk, sk) = pp.key_gen(&mut rng, 1);
+    RegistrationRecord::new(&mut rng, &pp, authority.public_key(), &pk, &sk);
+}
+
+/// Test a record of a key of length 1 is rejected, so the authority does not link the
+/// pseudonym of an unrelated key of length 1 to it.
+#[test]
+fn open_fail_for_record_of_key_of_length_one() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let authority = OpeningAuthority::new(&mut rng, &pp);
+    let (pk_a, _) = pp.key_gen(&mut rng, 1);
+    let (pk_b, _) = pp.key_gen(&mut rng, 1);
+
+    // the record of pk_a with the empty linking information
+    let mut bytes = Vec::new();
+    pk_a.serialize_compressed(&mut bytes).unwrap();
+    (0..7).for_each(|_| bytes.extend_from_slice(&0u64.to_le_bytes()));
+    let record_a = RegistrationRecord::deserialize_compressed(&bytes[..]).unwrap();
+    assert!(!record_a.verify(&pp, authority.public_key()));
+
+    let mut pseudonym_b = pk_b.clone();
+    pseudonym_b.convert(Fr::rand(&mut rng));
+    assert!(authority.open(&mut rng, &pp, &pseudonym_b, &record_a) == LinkResult::NotLinked);
+}
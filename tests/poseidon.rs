@@ -0,0 +1,52 @@
+#![cfg(feature = "poseidon")]
+
+use std::str::FromStr;
+
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+use mercurial_signature::{poseidon_hash_to_fr, Fr};
+use sha2::{Digest, Sha512};
+
+/// Test the known answers for the fixed parameters.
+#[test]
+fn poseidon_known_answers() {
+    let vectors = [
+        (
+            vec![],
+            "7537180076518580051102512563888216212546078608480875576648073859804775902526",
+        ),
+        (
+            vec![Fr::from(0u64)],
+            "35664261923486066629639794057550894686018896535632583950811439567074987487751",
+        ),
+        (
+            vec![Fr::from(1u64), Fr::from(2u64)],
+            "12698935019503194293212068959828728084778663550451729990203745186025134198536",
+        ),
+        (
+            vec![Fr::from(1u64), Fr::from(2u64), Fr::from(3u64)],
+            "25078018524775892311449466149021002759316299742751555839689739675164163636126",
+        ),
+    ];
+    for (inputs, expected) in vectors {
+        assert!(poseidon_hash_to_fr(&inputs) == Fr::from_str(expected).unwrap());
+    }
+}
+
+/// Test the same attributes encode identically across runs, and differently from the SHA-512 based encoding.
+#[test]
+fn poseidon_encoding_is_deterministic_and_differs_from_sha() {
+    let attributes = (0..5).map(|i| Fr::from(100u64 + i)).collect::<Vec<Fr>>();
+    let first = poseidon_hash_to_fr(&attributes);
+    assert!(first == poseidon_hash_to_fr(&attributes.clone()));
+
+    let mut bytes = Vec::new();
+    attributes.serialize_compressed(&mut bytes).unwrap();
+    let sha = Fr::from_le_bytes_mod_order(&Sha512::digest(&bytes));
+    assert!(first != sha);
+
+    // trailing zeros change the encoding
+    let mut padded = attributes.clone();
+    padded.push(Fr::from(0u64));
+    assert!(first != poseidon_hash_to_fr(&padded));
+}
@@ -0,0 +1,87 @@
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use mercurial_signature::{
+    change_representation,
+    strategies::{key_pair_and_message, nonzero_scalar, public_params, rng},
+};
+use proptest::prelude::*;
+
+// the pairings are slow in debug builds
+const CASES: u32 = 16;
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(CASES))]
+
+    /// A signature by the secret key always verifies under the public key.
+    #[test]
+    fn sign_then_verify(
+        (pp, pk, sk, message) in key_pair_and_message(6),
+        mut rng in rng(),
+    ) {
+        let sig = sk.sign(&mut rng, &pp, &message);
+        prop_assert!(pk.verify(&pp, &message, &sig));
+    }
+
+    /// Converting the keys and the signature by the same `p` preserves the verification, and
+    /// by different scalars breaks it.
+    #[test]
+    fn convert_with_same_p_verifies(
+        (pp, mut pk, mut sk, message) in key_pair_and_message(6),
+        p in nonzero_scalar(),
+        q in nonzero_scalar(),
+        mut rng in rng(),
+    ) {
+        prop_assume!(p != q);
+        let sig = sk.sign(&mut rng, &pp, &message);
+        let mut sig_p = sig.clone();
+        sig_p.convert(&mut rng, p);
+        let mut sig_q = sig;
+        sig_q.convert(&mut rng, q);
+
+        pk.convert(p);
+        sk.convert(p);
+        prop_assert!(pk.verify(&pp, &message, &sig_p));
+        prop_assert!(!pk.verify(&pp, &message, &sig_q));
+        prop_assert!(pk.verify(&pp, &message, &sk.sign(&mut rng, &pp, &message)));
+    }
+
+    /// Changing the representation of the message and the signature preserves the verification,
+    /// and the changed signature does not verify the original message.
+    #[test]
+    fn change_representation_verifies(
+        (pp, pk, sk, message) in key_pair_and_message(6),
+        u in nonzero_scalar(),
+        mut rng in rng(),
+    ) {
+        prop_assume!(u != 1u64.into());
+        let sig = sk.sign(&mut rng, &pp, &message);
+        let mut message2 = message.clone();
+        let mut sig2 = sig;
+        change_representation(&mut rng, &mut message2, &mut sig2, u);
+        prop_assert!(pk.verify(&pp, &message2, &sig2));
+        prop_assert!(!pk.verify(&pp, &message, &sig2));
+    }
+
+    /// The serialization of the parameters, the keys and the signatures round-trips, both
+    /// compressed and uncompressed.
+    #[test]
+    fn serialization_round_trips(
+        (pp, pk, sk, message) in key_pair_and_message(6),
+        other_pp in public_params(),
+        mut rng in rng(),
+    ) {
+        let sig = sk.sign(&mut rng, &pp, &message);
+        prop_assert!(round_trip(&pp) && round_trip(&other_pp));
+        prop_assert!(round_trip(&pk));
+        prop_assert!(round_trip(&sk));
+        prop_assert!(round_trip(&sig));
+    }
+}
+
+fn round_trip<T: CanonicalSerialize + CanonicalDeserialize + PartialEq>(value: &T) -> bool {
+    let mut compressed = Vec::new();
+    value.serialize_compressed(&mut compressed).unwrap();
+    let mut uncompressed = Vec::new();
+    value.serialize_uncompressed(&mut uncompressed).unwrap();
+    T::deserialize_compressed(&compressed[..]).unwrap() == *value
+        && T::deserialize_uncompressed(&uncompressed[..]).unwrap() == *value
+}
@@ -0,0 +1,34 @@
+#![cfg(feature = "python")]
+
+use mercurial_signature::python::mercurial_signature;
+use pyo3::{ffi::c_str, prelude::*, types::PyDict};
+
+/// Run the functions `test_*` of `tests/python/test_bindings.py` against the module, as pytest
+/// would, so that the Python tests run without maturin.
+#[test]
+fn python_test_bindings() {
+    pyo3::append_to_inittab!(mercurial_signature);
+    pyo3::prepare_freethreaded_python();
+
+    Python::with_gil(|py| {
+        let globals = PyDict::new(py);
+        py.run(
+            c_str!(include_str!("python/test_bindings.py")),
+            Some(&globals),
+            None,
+        )
+        .unwrap();
+
+        let mut count = 0;
+        for (name, value) in globals.iter() {
+            let name: String = name.extract().unwrap();
+            if name.starts_with("test_") {
+                value
+                    .call0()
+                    .unwrap_or_else(|e| panic!("{} failed: {}", name, e));
+                count += 1;
+            }
+        }
+        assert_eq!(count, 4);
+    });
+}
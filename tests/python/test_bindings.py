@@ -0,0 +1,72 @@
+# Tests of the Python module. They run with pytest after `maturin develop`, or from
+# `cargo test --features python`, see tests/python.rs.
+import mercurial_signature as ms
+
+
+def raises(error, f, *args):
+    try:
+        f(*args)
+    except error:
+        return True
+    return False
+
+
+def test_sign_convert_change_representation_verify():
+    pp = ms.PublicParams.from_bytes(ms.PublicParams().to_bytes())
+    keypair = ms.Keypair(pp, 5)
+    message = ms.random_message(5)
+    sig = ms.Signature.from_bytes(keypair.sign(pp, message).to_bytes())
+    assert keypair.verify(pp, message, sig)
+
+    p = ms.random_scalar()
+    keypair = keypair.convert(p)
+    sig = sig.convert(p)
+    assert keypair.verify(pp, message, sig)
+
+    new_message, new_sig = sig.change_representation(message, ms.random_scalar())
+    assert new_message != message
+    pk = ms.PublicKey.from_bytes(keypair.public_key.to_bytes())
+    assert len(pk) == 5
+    assert pk.verify(pp, new_message, new_sig)
+    assert not pk.verify(pp, message, new_sig)
+
+
+def test_seed_is_reproducible():
+    assert ms.PublicParams(seed=1) == ms.PublicParams(seed=1)
+    assert ms.PublicParams(seed=1) != ms.PublicParams(seed=2)
+    pp = ms.PublicParams(seed=1)
+    assert ms.Keypair(pp, 3, seed=7) == ms.Keypair(pp, 3, seed=7)
+    assert ms.random_message(3, seed=7) == ms.random_message(3, seed=7)
+    assert ms.random_scalar(seed=7) == ms.random_scalar(seed=7)
+
+    keypair = ms.Keypair(pp, 3, seed=7)
+    message = ms.random_message(3, seed=8)
+    assert keypair.sign(pp, message, seed=9) == keypair.sign(pp, message, seed=9)
+
+
+def test_repr_and_eq():
+    pp = ms.PublicParams()
+    keypair = ms.Keypair(pp, 2)
+    assert repr(keypair.public_key).startswith("PublicKey(length=2, ")
+    assert repr(keypair).startswith("Keypair(length=2, ")
+    assert keypair.public_key == ms.PublicKey.from_bytes(keypair.public_key.to_bytes())
+    assert keypair == ms.Keypair.from_bytes(
+        keypair.public_key.to_bytes(), keypair.secret_key_bytes()
+    )
+    assert keypair != ms.Keypair(pp, 2)
+
+
+def test_invalid_inputs_raise():
+    pp = ms.PublicParams()
+    keypair = ms.Keypair(pp, 2)
+    assert issubclass(ms.MercurialSignatureError, ValueError)
+    assert raises(ms.MercurialSignatureError, ms.PublicParams.from_bytes, b"\x01\x02\x03")
+    assert raises(ms.MercurialSignatureError, keypair.sign, pp, ms.random_message(3))
+    assert raises(ms.MercurialSignatureError, keypair.sign, pp, b"\xff" * 10)
+    assert raises(ms.MercurialSignatureError, keypair.convert, b"\xff" * 32)
+    assert raises(
+        ms.MercurialSignatureError,
+        ms.Keypair.from_bytes,
+        keypair.public_key.to_bytes(),
+        ms.Keypair(pp, 3).secret_key_bytes(),
+    )
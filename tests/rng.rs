@@ -0,0 +1,53 @@
+use mercurial_signature::{change_representation, Fr, PublicParams, RngAdapter, UniformRand, G1};
+
+/// Sign, convert and change the representation with the randomness of `rng`, and verify.
+fn sign_convert_verify(rng: &mut RngAdapter) {
+    let pp = PublicParams::new(rng);
+    let (mut pk, mut sk) = pp.key_gen(rng, 5);
+    let mut message = (0..5).map(|_| G1::rand(rng)).collect::<Vec<G1>>();
+    let mut sig = sk.sign(rng, &pp, &message);
+    assert!(pk.verify(&pp, &message, &sig));
+
+    let p = Fr::rand(rng);
+    pk.convert(p);
+    sk.convert(p);
+    sig.convert(rng, p);
+    assert!(pk.verify(&pp, &message, &sig));
+
+    let u = Fr::rand(rng);
+    change_representation(rng, &mut message, &mut sig, u);
+    assert!(pk.verify(&pp, &message, &sig));
+}
+
+/// Test a generator of rand_core 0.6 through the adapter.
+#[test]
+fn adapter_with_rand_core_0_6() {
+    let mut thread_rng = rand::thread_rng();
+    sign_convert_verify(&mut RngAdapter::from_rand_core_0_6(&mut thread_rng));
+}
+
+/// Test a generator of rand_core 0.9 through the adapter.
+#[cfg(feature = "rand_core_0_9")]
+#[test]
+fn adapter_with_rand_core_0_9() {
+    use rand_chacha_0_9::{rand_core::SeedableRng, ChaCha20Rng};
+
+    let mut chacha = ChaCha20Rng::seed_from_u64(7);
+    sign_convert_verify(&mut RngAdapter::from_rand_core_0_9(&mut chacha));
+}
+
+/// Test a deterministic generator is accepted through the escape hatch, and reproduces the
+/// same parameters and keys.
+#[cfg(feature = "test-utils")]
+#[test]
+fn insecure_test_rng_is_deterministic() {
+    use mercurial_signature::InsecureTestRng;
+    use rand::rngs::mock::StepRng;
+
+    let mut rng1 = InsecureTestRng(StepRng::new(1, 3));
+    let mut rng2 = InsecureTestRng(StepRng::new(1, 3));
+    let pp1 = PublicParams::new(&mut rng1);
+    let pp2 = PublicParams::new(&mut rng2);
+    assert!(pp1 == pp2);
+    assert!(pp1.key_gen(&mut rng1, 3).0 == pp2.key_gen(&mut rng2, 3).0);
+}
@@ -0,0 +1,63 @@
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use mercurial_signature::{
+    change_representation, prove_same_message, Fr, PublicParams, SameMessageProof, UniformRand, G1,
+};
+
+/// Test the proof verifies for signatures under different keys on messages of the same class,
+/// and only with the nonce it is bound to.
+#[test]
+fn same_message_proof_ok_for_same_class() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk_a, sk_a) = pp.key_gen(&mut rng, 10);
+    let (pk_b, sk_b) = pp.key_gen(&mut rng, 10);
+    let msg_a = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let sig_a = sk_a.sign(&mut rng, &pp, &msg_a);
+    let mut msg_b = msg_a.clone();
+    let mut sig_b = sk_b.sign(&mut rng, &pp, &msg_b);
+
+    // the message signed by B is another representative of the class
+    let k = Fr::rand(&mut rng);
+    change_representation(&mut rng, &mut msg_b, &mut sig_b, k);
+
+    let proof = prove_same_message(
+        &mut rng,
+        &pp,
+        (&pk_a, &sig_a, &msg_a),
+        (&pk_b, &sig_b, &msg_b),
+        k,
+        b"nonce-1",
+    );
+    assert!(proof.verify(&pp, &pk_a, &pk_b, b"nonce-1"));
+    assert!(!proof.verify(&pp, &pk_a, &pk_b, b"nonce-2"));
+    assert!(!proof.verify(&pp, &pk_b, &pk_a, b"nonce-1"));
+
+    // serialization round trip
+    let mut bytes = Vec::new();
+    proof.serialize_compressed(&mut bytes).unwrap();
+    let proof2 = SameMessageProof::deserialize_compressed(&bytes[..]).unwrap();
+    assert!(proof2.verify(&pp, &pk_a, &pk_b, b"nonce-1"));
+}
+
+/// Test the proof fails for messages of different classes.
+#[test]
+fn same_message_proof_fail_for_different_class() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk_a, sk_a) = pp.key_gen(&mut rng, 10);
+    let (pk_b, sk_b) = pp.key_gen(&mut rng, 10);
+    let msg_a = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let msg_b = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let sig_a = sk_a.sign(&mut rng, &pp, &msg_a);
+    let sig_b = sk_b.sign(&mut rng, &pp, &msg_b);
+
+    let proof = prove_same_message(
+        &mut rng,
+        &pp,
+        (&pk_a, &sig_a, &msg_a),
+        (&pk_b, &sig_b, &msg_b),
+        Fr::from(1u64),
+        b"nonce",
+    );
+    assert!(!proof.verify(&pp, &pk_a, &pk_b, b"nonce"));
+}
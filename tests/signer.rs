@@ -0,0 +1,131 @@
+use std::{
+    future::Future,
+    pin::pin,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
+};
+
+use mercurial_signature::{
+    issue_tokens_with_signer, sign_claims_with_signer, verify_claims, AsyncSigner, ClaimValue,
+    Claims, LocalSigner, LocalSignerError, PublicParams, TokenClient, TokenRedeemer, UniformRand,
+    Validation, G1,
+};
+#[cfg(feature = "test-utils")]
+use mercurial_signature::{sign_many_with_signer, MockRemoteSigner, MockSignerError};
+
+/// Test issuing tokens and signing claims over the async path with a local signer.
+#[test]
+fn issue_with_local_signer() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk, sk) = pp.key_gen(&mut rng, 2);
+    let signer = LocalSigner::new(&mut rng, &pp, pk, sk);
+    let mut redeemer = TokenRedeemer::new(signer.public_key());
+
+    let mut client = TokenClient::new();
+    let request = client.request(&mut rng, &pp, 5);
+    let response = block_on(issue_tokens_with_signer(&signer, &pp, &request)).unwrap();
+    assert!(client.receive(&pp, signer.public_key(), &response));
+    let redemption = client.redeem(&mut rng, &pp).unwrap();
+    assert!(redeemer.redeem(&pp, &redemption));
+
+    let mut claims = Claims::new();
+    claims.insert("exp", ClaimValue::Number(2000));
+    let signed = block_on(sign_claims_with_signer(&signer, &pp, &claims)).unwrap();
+    assert!(verify_claims(signer.public_key(), &pp, &signed, &Validation::new(1000)).is_ok());
+}
+
+/// Test the local signer rejects other public parameters and a message longer than the key.
+#[test]
+fn local_signer_rejects_invalid_requests() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk, sk) = pp.key_gen(&mut rng, 2);
+    let signer = LocalSigner::new(&mut rng, &pp, pk, sk);
+
+    let message = (0..2).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let sig = block_on(signer.sign(&pp.digest(), &message)).unwrap();
+    assert!(signer.public_key().verify(&pp, &message, &sig));
+
+    let other = PublicParams::new(&mut rng);
+    let result = block_on(signer.sign(&other.digest(), &message));
+    assert_eq!(result.unwrap_err(), LocalSignerError::UnknownParams);
+
+    let message = (0..3).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let result = block_on(signer.sign(&pp.digest(), &message));
+    assert_eq!(result.unwrap_err(), LocalSignerError::MessageTooLong);
+}
+
+/// Test the requests of a batch are in flight together, so that the latencies overlap.
+#[cfg(feature = "test-utils")]
+#[test]
+fn sign_many_pipelines_requests() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk, sk) = pp.key_gen(&mut rng, 3);
+    let signer = MockRemoteSigner::new(LocalSigner::new(&mut rng, &pp, pk, sk))
+        .with_latency(std::time::Duration::from_millis(50));
+
+    let messages = (0..8)
+        .map(|_| (0..3).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>())
+        .collect::<Vec<_>>();
+    let messages = messages.iter().map(|m| m.as_slice()).collect::<Vec<_>>();
+    let sigs = block_on(sign_many_with_signer(&signer, &pp, &messages)).unwrap();
+
+    assert_eq!(signer.requests(), 8);
+    assert_eq!(signer.max_in_flight(), 8);
+    assert!(messages
+        .iter()
+        .zip(sigs.iter())
+        .all(|(m, sig)| signer.public_key().verify(&pp, m, sig)));
+}
+
+/// Test an injected failure fails the issuance, and the client accepts the response of a retry.
+#[cfg(feature = "test-utils")]
+#[test]
+fn issue_with_failing_remote_signer() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk, sk) = pp.key_gen(&mut rng, 2);
+    let signer = MockRemoteSigner::new(LocalSigner::new(&mut rng, &pp, pk, sk))
+        .with_latency(std::time::Duration::from_millis(10))
+        .with_failure_every(6);
+
+    let mut client = TokenClient::new();
+    let request = client.request(&mut rng, &pp, 3);
+    // requests 1 to 3 succeed
+    let response = block_on(issue_tokens_with_signer(&signer, &pp, &request)).unwrap();
+    assert!(client.receive(&pp, signer.public_key(), &response));
+
+    // request 6 fails
+    let request = client.request(&mut rng, &pp, 3);
+    let result = block_on(issue_tokens_with_signer(&signer, &pp, &request));
+    assert_eq!(result.err(), Some(MockSignerError::Unavailable));
+
+    // requests 7 to 9 succeed
+    let response = block_on(issue_tokens_with_signer(&signer, &pp, &request)).unwrap();
+    assert!(client.receive(&pp, signer.public_key(), &response));
+    assert_eq!(client.length(), 6);
+}
+
+/// A minimal executor, parking the thread until the future is woken.
+fn block_on<F: Future>(future: F) -> F::Output {
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
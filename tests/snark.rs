@@ -0,0 +1,39 @@
+#![cfg(feature = "snark")]
+
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem, SynthesisError};
+use mercurial_signature::{
+    fixtures::{Fixture, SignatureComponent},
+    Signature, VerifyCircuit, G1,
+};
+
+fn is_satisfied(f: &Fixture, message: &[G1], sig: &Signature) -> Result<bool, SynthesisError> {
+    let cs = ConstraintSystem::new_ref();
+    VerifyCircuit::new(f.pp.clone(), f.pk.clone(), message.to_vec(), sig.clone())
+        .generate_constraints(cs.clone())?;
+    cs.is_satisfied()
+}
+
+/// Test the constraints are satisfied by a valid signature only.
+#[test]
+fn verify_circuit_is_satisfied_by_valid_signature() {
+    let f = Fixture::with_length(1, 2);
+    assert!(is_satisfied(&f, &f.message, &f.sig).unwrap());
+
+    let invalid = f.corrupt_signature(SignatureComponent::Z);
+    assert!(!f.pk.verify(&f.pp, &f.message, &invalid));
+    assert!(!is_satisfied(&f, &f.message, &invalid).unwrap());
+    let invalid = f.corrupt_signature(SignatureComponent::Y1);
+    assert!(!is_satisfied(&f, &f.message, &invalid).unwrap());
+    assert!(!is_satisfied(&f, &[f.message[1], f.message[0]], &f.sig).unwrap());
+}
+
+/// Test a message longer than the public key cannot be verified.
+#[test]
+fn verify_circuit_rejects_long_message() {
+    let mut f = Fixture::with_length(2, 2);
+    let message = f.random_message(3);
+    assert!(matches!(
+        is_satisfied(&f, &message, &f.sig),
+        Err(SynthesisError::Unsatisfiable)
+    ));
+}
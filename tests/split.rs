@@ -0,0 +1,93 @@
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use mercurial_signature::{
+    convert_shares, split_secret_key, Fr, PublicParams, SplitError, SplitRound1, SplitRound2,
+    UniformRand, G1,
+};
+
+/// Test the two-party signing produces a signature verifiable by the public key,
+/// with the round messages passing through serialization.
+#[test]
+fn verify_ok_with_split_signing() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk, sk) = pp.key_gen(&mut rng, 10);
+    let (share_a, share_b) = split_secret_key(&mut rng, &sk);
+    let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+
+    let (state, round1) = share_a.sign_round1(&mut rng, &message);
+    let mut bytes = Vec::new();
+    round1.serialize_compressed(&mut bytes).unwrap();
+    let round1 = SplitRound1::deserialize_compressed(bytes.as_slice()).unwrap();
+
+    let round2 = share_b.sign_round2(&mut rng, &pp, &round1);
+    let mut bytes = Vec::new();
+    round2.serialize_compressed(&mut bytes).unwrap();
+    let round2 = SplitRound2::deserialize_compressed(bytes.as_slice()).unwrap();
+
+    let sig = share_a.finalize(&pp, &pk, state, &round2).unwrap();
+    assert!(pk.verify(&pp, &message, &sig));
+}
+
+/// Test the converted shares produce a signature verifiable by the converted public key.
+#[test]
+fn verify_ok_with_converted_shares() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (mut pk, sk) = pp.key_gen(&mut rng, 10);
+    let (mut share_a, mut share_b) = split_secret_key(&mut rng, &sk);
+    let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+
+    let p = Fr::rand(&mut rng);
+    pk.convert(p);
+    convert_shares(&mut share_a, &mut share_b, p);
+
+    let (state, round1) = share_a.sign_round1(&mut rng, &message);
+    let round2 = share_b.sign_round2(&mut rng, &pp, &round1);
+    let sig = share_a.finalize(&pp, &pk, state, &round2).unwrap();
+    assert!(pk.verify(&pp, &message, &sig));
+}
+
+/// Test a share alone cannot produce a verifying signature, i.e. when the round 2
+/// message does not come from the matching share of party B, and party A detects it.
+#[test]
+fn finalize_fail_without_matching_co_signer() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk, sk) = pp.key_gen(&mut rng, 10);
+    let (share_a, _) = split_secret_key(&mut rng, &sk);
+    let (_, other_share_b) = split_secret_key(&mut rng, &sk);
+    let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+
+    let (state, round1) = share_a.sign_round1(&mut rng, &message);
+    let round2 = other_share_b.sign_round2(&mut rng, &pp, &round1);
+    assert_eq!(
+        share_a.finalize(&pp, &pk, state, &round2).err(),
+        Some(SplitError::InvalidSignature)
+    );
+}
+
+/// Test party A rejects a round 2 message with an inconsistent nonce.
+#[test]
+fn finalize_fail_with_invalid_nonce() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk, sk) = pp.key_gen(&mut rng, 10);
+    let (share_a, share_b) = split_secret_key(&mut rng, &sk);
+    let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+
+    let (state, round1) = share_a.sign_round1(&mut rng, &message);
+    let round2 = share_b.sign_round2(&mut rng, &pp, &round1);
+
+    // replace y1 with a random element, after the message, z and the length prefix
+    let mut bytes = Vec::new();
+    round2.serialize_compressed(&mut bytes).unwrap();
+    let offset = 8 + 10 * 48 + 48;
+    let mut y1 = Vec::new();
+    G1::rand(&mut rng).serialize_compressed(&mut y1).unwrap();
+    bytes[offset..offset + 48].copy_from_slice(&y1);
+    let round2 = SplitRound2::deserialize_compressed(bytes.as_slice()).unwrap();
+    assert_eq!(
+        share_a.finalize(&pp, &pk, state, &round2).err(),
+        Some(SplitError::InvalidNonce)
+    );
+}
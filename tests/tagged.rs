@@ -0,0 +1,122 @@
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use mercurial_signature::{
+    change_representation_tagged, Fr, PublicParams, Signature, TaggedSignature, UniformRand, G1,
+};
+
+/// Test the tagged signature verifies with the same tag across conversion and change of
+/// representation, and fails with a different tag.
+#[test]
+fn verify_ok_with_same_tag_across_representation_changes() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (mut pk, mut sk) = pp.key_gen(&mut rng, 20);
+    let mut message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let mut sig = sk.sign_tagged(&mut rng, &pp, b"epoch-1", &message);
+    assert!(pk.verify_tagged(&pp, b"epoch-1", &message, &sig));
+    assert!(!pk.verify_tagged(&pp, b"epoch-2", &message, &sig));
+
+    let u = Fr::rand(&mut rng);
+    change_representation_tagged(&mut rng, &mut message, &mut sig, u);
+    assert!(pk.verify_tagged(&pp, b"epoch-1", &message, &sig));
+    assert!(!pk.verify_tagged(&pp, b"epoch-2", &message, &sig));
+
+    let p = Fr::rand(&mut rng);
+    pk.convert(p);
+    sk.convert(p);
+    sig.convert(&mut rng, p);
+    assert!(pk.verify_tagged(&pp, b"epoch-1", &message, &sig));
+    assert!(!pk.verify_tagged(&pp, b"", &message, &sig));
+
+    let sig2 = sk.sign_tagged(&mut rng, &pp, b"", &message);
+    assert!(pk.verify_tagged(&pp, b"", &message, &sig2));
+}
+
+/// Test the tagged signature fails if the message representation is not changed with the signature.
+#[test]
+fn verify_fail_with_tagged_signature_if_representation_has_not_changed() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk, sk) = pp.key_gen(&mut rng, 20);
+    let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let sig = sk.sign_tagged(&mut rng, &pp, b"context", &message);
+
+    let mut message2 = message.clone();
+    let mut sig2 = sig.clone();
+    let u = Fr::rand(&mut rng);
+    change_representation_tagged(&mut rng, &mut message2, &mut sig2, u);
+    assert!(!pk.verify_tagged(&pp, b"context", &message, &sig2));
+    assert!(!pk.verify_tagged(&pp, b"context", &message2, &sig));
+}
+
+/// Test the tagged signature fails for a message longer than half the key.
+#[test]
+fn verify_fail_with_tagged_signature_if_message_longer_than_half_key() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk, sk) = pp.key_gen(&mut rng, 20);
+    let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let sig = sk.sign_tagged(&mut rng, &pp, b"context", &message);
+
+    let mut longer = message.clone();
+    longer.push(G1::rand(&mut rng));
+    assert!(!pk.verify_tagged(&pp, b"context", &longer, &sig));
+}
+
+/// Test the tag cannot be stripped: the tagged signature read as a plain signature does not
+/// verify on the message, neither under the key nor under a converted key.
+#[test]
+fn verify_fail_with_tagged_signature_stripped_of_its_tag() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk, sk) = pp.key_gen(&mut rng, 20);
+    let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let sig = sk.sign_tagged(&mut rng, &pp, b"epoch-1", &message);
+
+    let mut bytes = Vec::new();
+    sig.serialize_compressed(&mut bytes).unwrap();
+    let mut stripped = Signature::deserialize_compressed(&bytes[..]).unwrap();
+    assert!(!pk.verify(&pp, &message, &stripped));
+
+    let p = Fr::rand(&mut rng);
+    let mut converted_pk = pk.clone();
+    converted_pk.convert(p);
+    stripped.convert(&mut rng, p);
+    assert!(!converted_pk.verify(&pp, &message, &stripped));
+}
+
+/// Test a linear combination of tagged signatures does not verify on the linear combination
+/// of their messages, with the same tag or with different tags.
+#[test]
+fn verify_fail_with_linear_combination_of_tagged_signatures() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk, sk) = pp.key_gen(&mut rng, 20);
+    let message1 = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let message2 = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let a = Fr::rand(&mut rng);
+    let combined = message1
+        .iter()
+        .zip(message2.iter())
+        .map(|(m1, m2)| *m1 + *m2 * a)
+        .collect::<Vec<G1>>();
+
+    for tag2 in [&b"epoch-1"[..], &b"epoch-2"[..]] {
+        let sig1 = sk.sign_tagged(&mut rng, &pp, b"epoch-1", &message1);
+        let sig2 = sk.sign_tagged(&mut rng, &pp, tag2, &message2);
+
+        // (z1 + a z2, y1, y2) of the first signature
+        let mut bytes1 = Vec::new();
+        sig1.serialize_compressed(&mut bytes1).unwrap();
+        let mut bytes2 = Vec::new();
+        sig2.serialize_compressed(&mut bytes2).unwrap();
+        let z1 = G1::deserialize_compressed(&bytes1[..48]).unwrap();
+        let z2 = G1::deserialize_compressed(&bytes2[..48]).unwrap();
+        let mut forged = Vec::new();
+        (z1 + z2 * a).serialize_compressed(&mut forged).unwrap();
+        forged.extend_from_slice(&bytes1[48..]);
+        let forged = TaggedSignature::deserialize_compressed(&forged[..]).unwrap();
+
+        assert!(!pk.verify_tagged(&pp, b"epoch-1", &combined, &forged));
+        assert!(!pk.verify_tagged(&pp, tag2, &combined, &forged));
+    }
+}
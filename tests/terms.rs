@@ -0,0 +1,57 @@
+use mercurial_signature::fixtures::{Fixture, SignatureComponent};
+use mercurial_signature::MultiPairingAccumulator;
+
+/// Test checking the terms agrees with the verification on valid and invalid inputs.
+#[test]
+fn check_agrees_with_verify() {
+    let mut f = Fixture::with_length(1, 3);
+    let other_message = f.random_message(3);
+    let (pk2, _) = f.pp.key_gen(&mut f.rng, 3);
+    let sigs = [
+        f.sig.clone(),
+        f.corrupt_signature(SignatureComponent::Z),
+        f.corrupt_signature(SignatureComponent::Y1),
+        f.corrupt_signature(SignatureComponent::Y2),
+    ];
+    for sig in sigs.iter() {
+        for message in [&f.message, &other_message] {
+            for pk in [&f.pk, &pk2] {
+                let terms = pk.verification_terms(&f.pp, message, sig);
+                assert_eq!(terms.check(), pk.verify(&f.pp, message, sig));
+            }
+        }
+    }
+
+    // the key is shorter than the message
+    let long_message = f.random_message(4);
+    let terms = f.pk.verification_terms(&f.pp, &long_message, &f.sig);
+    assert!(!terms.is_well_formed());
+    assert!(!terms.check());
+    let mut acc = MultiPairingAccumulator::new();
+    terms.fold_into(&mut acc, f.random_scalar());
+    assert!(!acc.verify());
+}
+
+/// Test the accumulated verification of two signatures accepts if and only if both verify.
+#[test]
+fn accumulated_verification_accepts_iff_both_verify() {
+    let mut f1 = Fixture::with_length(2, 3);
+    let f2 = Fixture::with_length(3, 5);
+    let mut challenges = (0..8).map(|_| f1.random_scalar()).collect::<Vec<_>>();
+    let invalid1 = f1.corrupt_signature(SignatureComponent::Z);
+    let invalid2 = f2.corrupt_signature(SignatureComponent::Y1);
+
+    for (sig1, valid1) in [(&f1.sig, true), (&invalid1, false)] {
+        for (sig2, valid2) in [(&f2.sig, true), (&invalid2, false)] {
+            let mut acc = MultiPairingAccumulator::new();
+            f1.pk
+                .verification_terms(&f1.pp, &f1.message, sig1)
+                .fold_into(&mut acc, challenges.pop().unwrap());
+            f2.pk
+                .verification_terms(&f2.pp, &f2.message, sig2)
+                .fold_into(&mut acc, challenges.pop().unwrap());
+            assert_eq!(acc.length(), 2 + 4 + 2 + 6);
+            assert_eq!(acc.verify(), valid1 && valid2);
+        }
+    }
+}
@@ -1,178 +1,839 @@
-use mercurial_signature::{change_representation, Fr, PublicParams, UniformRand, G1};
+use ark_bls12_381::G1Affine;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::Zero;
+use mercurial_signature::fixtures::Fixture;
+use mercurial_signature::{
+    change_representation, combine_partial_signatures, commit_public_key,
+    inverse_change_representation, permute_message, CtFr, Fr, KeyError, ParamsError, PublicKey,
+    PublicParams, RepresentationChangeProof, SecretKey, Signature, UniformRand, G1, G2,
+};
+use subtle::ConstantTimeEq;
 
 /// Test the conversion function for the public key, secret key, and signature.
 /// The converted public key, secret key, and signature should be able to verify the message.
 #[test]
 fn verify_ok_for_original_message_with_converted_keys_and_sigs() {
+    let mut f = Fixture::new(1);
+    let p = f.random_scalar();
+    let mut f2 = f.converted_copy(p);
+    assert!(f.pk != f2.pk);
+    assert!(f.sk != f2.sk);
+    assert!(f.sig != f2.sig);
+
+    // The converted public key, secret key, and signature should be able to verify the message.
+    assert!(f2.pk.verify(&f.pp, &f.message, &f2.sig));
+
+    // The converted secret key should be able to sign the message.
+    let sig3 = f2.sk.sign(&mut f2.rng, &f.pp, &f.message);
+    assert!(f2.pk.verify(&f.pp, &f.message, &sig3));
+}
+
+/// Test the conversion function for the public key, secret key, and signature.
+/// The converted public key and secret key should be able to sign and verify the message.
+#[test]
+fn verify_ok_with_converted_keys() {
+    let mut f = Fixture::new(2);
+    let p = f.random_scalar();
+    let mut f2 = f.converted_copy(p);
+    assert!(f.pk != f2.pk);
+    assert!(f.sk != f2.sk);
+
+    // verify the converted public key and secret key can sign and verify the message
+    let message = f2.random_message(10);
+    let sig2 = f2.sk.sign(&mut f2.rng, &f.pp, &message);
+    assert!(f2.pk.verify(&f.pp, &message, &sig2));
+}
+
+/// Test the conversion function for the public key and secret key.
+/// The converted key should not be able to verify the message with another unconverted key.
+#[test]
+fn verify_fail_if_key_is_not_converted() {
+    let mut f = Fixture::new(3);
+    let p = f.random_scalar();
+    let mut f2 = f.converted_copy(p);
+    assert!(f.pk != f2.pk);
+    assert!(f.sk != f2.sk);
+
+    // use sk2 to sign and pk to verify
+    let sig = f2.sk.sign(&mut f2.rng, &f.pp, &f.message);
+    assert!(!f.pk.verify(&f.pp, &f.message, &sig));
+
+    // use sk to sign and pk2 to verify
+    let sig2 = f.sk.sign(&mut f.rng, &f.pp, &f.message);
+    assert!(!f2.pk.verify(&f.pp, &f.message, &sig2));
+}
+
+/// Test the conversion function for the public key, secret key, and signature.
+/// The converted public key, secret key should not be able to verify the message
+/// with the original signature.
+#[test]
+fn verify_fail_if_signature_is_not_converted() {
+    let mut f = Fixture::new(4);
+    let p = f.random_scalar();
+    let f2 = f.converted_copy(p);
+    assert!(f.pk != f2.pk);
+
+    // use the converted keys to verify the original signature
+    assert!(!f2.pk.verify(&f.pp, &f.message, &f.sig));
+}
+
+#[test]
+fn verify_ok_if_key_length_is_greater_than_message_length() {
+    let mut f = Fixture::new(5);
+    let message = f.random_message(5);
+    let sig = f.sk.sign(&mut f.rng, &f.pp, &message);
+    assert!(f.pk.verify(&f.pp, &message, &sig));
+}
+
+/// Test the conversion function works with the change representation function.
+#[test]
+fn verify_ok_with_conversion_and_then_change_representation() {
+    let mut f = Fixture::new(6);
+    let p = f.random_scalar();
+    let mut f = f.converted_copy(p);
+    let u = f.random_scalar();
+    let f = f.rerepresented_copy(u);
+    assert!(f.pk.verify(&f.pp, &f.message, &f.sig));
+}
+
+#[test]
+fn verify_ok_with_change_representation_and_then_conversion() {
+    let mut f = Fixture::new(7);
+    let u = f.random_scalar();
+    let mut f = f.rerepresented_copy(u);
+    let p = f.random_scalar();
+    let f = f.converted_copy(p);
+    assert!(f.pk.verify(&f.pp, &f.message, &f.sig));
+}
+
+/// Test the change representation function -
+/// 1. The original message and changed signature should not be able to verify.
+/// 2. The changed message and original signature should not be able to verify.
+#[test]
+fn verify_fail_if_representation_has_not_changed() {
+    let mut f = Fixture::new(8);
+    let u = f.random_scalar();
+    let f2 = f.rerepresented_copy(u);
+
+    // verify the original message and changed signature
+    assert!(!f.pk.verify(&f.pp, &f2.message, &f.sig));
+    // verify the changed message and original signature
+    assert!(!f.pk.verify(&f.pp, &f.message, &f2.sig));
+}
+
+/// Test the partial signature covering all key components combines to a valid signature.
+#[test]
+fn verify_ok_with_partial_signature_on_all_indices() {
     let mut rng = rand::thread_rng();
     let pp = PublicParams::new(&mut rng);
     let (pk, sk) = pp.key_gen(&mut rng, 10);
     let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
-    let sig = sk.sign(&mut rng, &pp, &message);
 
-    let p = Fr::rand(&mut rng);
+    let partial = sk.partial_sign(&message, &(0..10).collect::<Vec<usize>>());
+    let sig = combine_partial_signatures(&mut rng, &pp, &[partial]);
+    assert!(pk.verify(&pp, &message, &sig));
+}
 
-    let mut pk2 = pk.clone();
-    pk2.convert(p);
-    assert!(pk != pk2);
+/// Test the partial signatures from disjoint index sets combine to a valid signature,
+/// while missing a partial signature fails the verification.
+#[test]
+fn verify_ok_with_partial_signatures_on_disjoint_indices() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk, sk) = pp.key_gen(&mut rng, 10);
+    let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
 
-    let mut sk2 = sk.clone();
-    sk2.convert(p);
-    assert!(sk != sk2);
+    let partial1 = sk.partial_sign(&message, &[0, 1, 2, 3]);
+    let partial2 = sk.partial_sign(&message, &[4, 5, 6, 7, 8, 9]);
+    let sig = combine_partial_signatures(&mut rng, &pp, &[partial1.clone(), partial2]);
+    assert!(pk.verify(&pp, &message, &sig));
 
-    let mut sig2 = sig.clone();
-    sig2.convert(&mut rng, p);
-    assert!(sig != sig2);
+    let sig = combine_partial_signatures(&mut rng, &pp, &[partial1]);
+    assert!(!pk.verify(&pp, &message, &sig));
+}
 
-    // The converted public key, secret key, and signature should be able to verify the message.
-    assert!(pk2.verify(&pp, &message, &sig2));
+/// Test the key with noise signs messages verified by the noisy public key but not by the
+/// original one, and the noise of each element is within the bound.
+#[test]
+fn verify_ok_with_noisy_key() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk, mut sk) = pp.key_gen(&mut rng, 10);
+    let original = sk.clone();
+
+    sk.add_noise(&mut rng, Fr::from(0u64));
+    assert!(sk == original);
+
+    let epsilon = 1000u64;
+    sk.add_noise(&mut rng, Fr::from(epsilon));
+    let noisy_pk = PublicKey::from_noisy_sk(&pp, &sk);
+    assert!(noisy_pk != pk);
+
+    let to_scalars = |sk: &SecretKey| {
+        let mut bytes = Vec::new();
+        sk.serialize_compressed(&mut bytes).unwrap();
+        Vec::<Fr>::deserialize_compressed(&bytes[..]).unwrap()
+    };
+    to_scalars(&sk)
+        .iter()
+        .zip(to_scalars(&original).iter())
+        .for_each(|(noisy, xi)| assert!(*noisy - xi <= Fr::from(epsilon)));
 
-    // The converted secret key should be able to sign the message.
-    let sig3 = sk2.sign(&mut rng, &pp, &message);
-    assert!(pk2.verify(&pp, &message, &sig3));
+    let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let sig = sk.sign(&mut rng, &pp, &message);
+    assert!(noisy_pk.verify(&pp, &message, &sig));
+    assert!(!pk.verify(&pp, &message, &sig));
 }
 
-/// Test the conversion function for the public key, secret key, and signature.
-/// The converted public key and secret key should be able to sign and verify the message.
+/// Test the signature on multiple messages verifies only with the messages in the same order.
 #[test]
-fn verify_ok_with_converted_keys() {
+fn verify_ok_with_multi_message_signature() {
     let mut rng = rand::thread_rng();
     let pp = PublicParams::new(&mut rng);
     let (pk, sk) = pp.key_gen(&mut rng, 10);
+    let m1 = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let m2 = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let sig = sk.sign_multi_message(&mut rng, &pp, &[&m1, &m2]);
+
+    assert!(pk.verify_multi_message(&pp, &[&m1, &m2], &sig));
+    assert!(!pk.verify_multi_message(&pp, &[&m2, &m1], &sig));
+    assert!(!pk.verify_multi_message(&pp, &[&m1], &sig));
+    assert!(!pk.verify_multi_message(&pp, &[&m1, &m2[..5]], &sig));
+    assert!(!pk.verify_multi_message(&pp, &[], &sig));
+}
+
+/// Test the signature on the permuted message verifies the permuted message only.
+#[test]
+fn verify_ok_with_permuted_message() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk, sk) = pp.key_gen(&mut rng, 5);
+    let message = (0..5).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let permutation = [4, 3, 2, 1, 0];
+    let sig = sk.sign_permuted(&mut rng, &pp, &message, &permutation);
+
+    let permuted = permute_message(&message, &permutation);
+    assert_eq!(permuted[0], message[4]);
+    assert!(pk.verify(&pp, &permuted, &sig));
+    assert!(!pk.verify(&pp, &message, &sig));
+}
+
+/// Test a repeated index is not a permutation.
+#[test]
+#[should_panic]
+fn permute_message_rejects_repeated_index() {
+    let mut rng = rand::thread_rng();
+    let message = (0..3).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    permute_message(&message, &[0, 1, 1]);
+}
+
+/// Test the batch conversion of the secret keys is the same as converting each key.
+#[test]
+fn batch_convert_same_as_convert() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let mut keys = (0..5)
+        .map(|_| pp.key_gen(&mut rng, 10))
+        .collect::<Vec<(PublicKey, SecretKey)>>();
+    let mut batch = keys.iter().map(|(_, sk)| sk.clone()).collect::<Vec<_>>();
 
     let p = Fr::rand(&mut rng);
+    SecretKey::batch_convert(&mut batch, p);
+    keys.iter_mut()
+        .zip(batch.iter())
+        .for_each(|((pk, sk), converted)| {
+            sk.convert(p);
+            assert!(sk == converted);
+
+            pk.convert(p);
+            let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+            let sig = converted.sign(&mut rng, &pp, &message);
+            assert!(pk.verify(&pp, &message, &sig));
+        });
+}
+
+/// Test the deterministic key generation outputs the same keys for the same seed
+/// and different keys for different seeds.
+#[test]
+fn key_gen_deterministic_depends_on_seed() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
 
-    let mut pk2 = pk.clone();
-    pk2.convert(p);
-    assert!(pk != pk2);
+    let (pk1, sk1) = pp.key_gen_deterministic(&[1u8; 64], 10);
+    let (pk2, sk2) = pp.key_gen_deterministic(&[1u8; 64], 10);
+    assert!(pk1 == pk2);
+    assert!(sk1 == sk2);
 
-    let mut sk2 = sk.clone();
-    sk2.convert(p);
-    assert!(sk != sk2);
+    let (pk3, sk3) = pp.key_gen_deterministic(&[2u8; 64], 10);
+    assert!(pk1 != pk3);
+    assert!(sk1 != sk3);
 
-    // verify the converted public key and secret key can sign and verify the message
     let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
-    let sig2 = sk2.sign(&mut rng, &pp, &message);
-    assert!(pk2.verify(&pp, &message, &sig2));
+    let sig = sk1.sign(&mut rng, &pp, &message);
+    assert!(pk2.verify(&pp, &message, &sig));
 }
 
-/// Test the conversion function for the public key and secret key.
-/// The converted key should not be able to verify the message with another unconverted key.
+/// Test the public parameters derived from a ceremony transcript are the same for the same
+/// transcript and different for different transcripts, and an empty transcript is rejected.
 #[test]
-fn verify_fail_if_key_is_not_converted() {
+fn derive_from_ceremony_transcript_depends_on_transcript() {
+    let mut rng = rand::thread_rng();
+
+    let pp1 = PublicParams::derive_from_ceremony_transcript(b"transcript-1").unwrap();
+    let pp2 = PublicParams::derive_from_ceremony_transcript(b"transcript-1").unwrap();
+    assert!(pp1 == pp2);
+
+    let pp3 = PublicParams::derive_from_ceremony_transcript(b"transcript-2").unwrap();
+    assert!(pp1.p1 != pp3.p1);
+    assert!(pp1.p2 != pp3.p2);
+
+    assert_eq!(
+        PublicParams::derive_from_ceremony_transcript(b""),
+        Err(ParamsError::EmptyTranscript)
+    );
+
+    let (pk, sk) = pp1.key_gen(&mut rng, 10);
+    let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let sig = sk.sign(&mut rng, &pp1, &message);
+    assert!(pk.verify(&pp1, &message, &sig));
+}
+
+/// Test the aggregate of signatures by the same key verifies each message individually,
+/// and fails if one of the signatures is corrupted.
+#[test]
+fn verify_ok_with_aggregate_signature_of_same_key() {
     let mut rng = rand::thread_rng();
     let pp = PublicParams::new(&mut rng);
     let (pk, sk) = pp.key_gen(&mut rng, 10);
+    let messages = (0..5)
+        .map(|_| (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>())
+        .collect::<Vec<_>>();
+    let messages = messages.iter().map(|m| m.as_slice()).collect::<Vec<_>>();
+
+    let sigs = sk.sign_for_aggregation(&mut rng, &pp, &messages);
+    let aggregate = Signature::aggregate_same_key(&sigs.iter().collect::<Vec<_>>()).unwrap();
+    assert!(aggregate.verify_individual(&pk, &pp, &messages));
+
+    // the signature at index 2 is on another message
+    let other_message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let mut messages2 = messages.clone();
+    messages2[2] = &other_message;
+    let sigs2 = sk.sign_for_aggregation(&mut rng, &pp, &messages2);
+    let mut sigs3 = sk.sign_for_aggregation(&mut rng, &pp, &messages);
+    sigs3[2] = sigs2[2].clone();
+    assert!(Signature::aggregate_same_key(&sigs3.iter().collect::<Vec<_>>()).is_none());
+
+    let aggregate = Signature::aggregate_same_key(&sigs2.iter().collect::<Vec<_>>()).unwrap();
+    assert!(!aggregate.verify_individual(&pk, &pp, &messages));
+}
 
-    let p = Fr::rand(&mut rng);
+/// Test the signature on the empty message verifies under any public key, and a signature
+/// on a non-empty message does not verify the empty message.
+#[test]
+fn verify_zero_message() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk, sk) = pp.key_gen(&mut rng, 10);
+    let (pk2, _) = pp.key_gen(&mut rng, 10);
 
-    let mut pk2 = pk.clone();
-    pk2.convert(p);
-    assert!(pk != pk2);
+    let sig = sk.sign(&mut rng, &pp, &[]);
+    assert!(pk.verify_zero_message(&pp, &sig));
+    assert!(pk.verify(&pp, &[], &sig));
+    assert!(pk2.verify_zero_message(&pp, &sig));
 
-    let mut sk2 = sk.clone();
-    sk2.convert(p);
-    assert!(sk != sk2);
+    let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let sig = sk.sign(&mut rng, &pp, &message);
+    assert!(!pk.verify_zero_message(&pp, &sig));
+}
 
+/// Test the round trip of changing the representation and undoing it recovers the original
+/// message with a signature verifying it.
+#[test]
+fn verify_ok_with_inverse_change_representation() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk, sk) = pp.key_gen(&mut rng, 10);
     let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let sig = sk.sign(&mut rng, &pp, &message);
 
-    // use sk to sign and pk2 to verify
-    let sig = sk2.sign(&mut rng, &pp, &message);
-    assert!(!pk.verify(&pp, &message, &sig));
+    let u = Fr::rand(&mut rng);
+    let mut message2 = message.clone();
+    let mut sig2 = sig.clone();
+    change_representation(&mut rng, &mut message2, &mut sig2, u);
+    assert!(message2 != message);
+
+    inverse_change_representation(&mut message2, &mut sig2, u);
+    assert!(message2 == message);
+    assert!(pk.verify(&pp, &message2, &sig2));
+
+    // undoing with another scalar does not recover the message
+    let mut message3 = message.clone();
+    let mut sig3 = sig.clone();
+    change_representation(&mut rng, &mut message3, &mut sig3, u);
+    inverse_change_representation(&mut message3, &mut sig3, Fr::from(2u64));
+    assert!(message3 != message);
+    assert!(!pk.verify(&pp, &message, &sig3));
+    assert!(pk.verify(&pp, &message3, &sig3));
+}
 
-    // use sk2 to sign and pk to verify
-    let sig2 = sk.sign(&mut rng, &pp, &message);
-    assert!(!pk2.verify(&pp, &message, &sig2));
+/// Test the proof of the representation change verifies for the scalar used, and fails for
+/// messages that are not related by the same scalar.
+#[test]
+fn verify_ok_with_representation_change_proof() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk, sk) = pp.key_gen(&mut rng, 10);
+    let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let mut sig = sk.sign(&mut rng, &pp, &message);
+
+    let u = Fr::rand(&mut rng);
+    let mut new_message = message.clone();
+    change_representation(&mut rng, &mut new_message, &mut sig, u);
+    assert!(pk.verify(&pp, &new_message, &sig));
+
+    let proof = RepresentationChangeProof::prove(&mut rng, &message, &new_message, u);
+    assert!(proof.verify(&message, &new_message));
+
+    // proof with a wrong scalar
+    let proof = RepresentationChangeProof::prove(&mut rng, &message, &new_message, Fr::from(2u64));
+    assert!(!proof.verify(&message, &new_message));
+
+    // one element is scaled by another scalar
+    let mut other_message = new_message.clone();
+    other_message[3] *= Fr::from(2u64);
+    let proof = RepresentationChangeProof::prove(&mut rng, &message, &other_message, u);
+    assert!(!proof.verify(&message, &other_message));
 }
 
-/// Test the conversion function for the public key, secret key, and signature.
-/// The converted public key, secret key should not be able to verify the message
-/// with the original signature.
+/// Test the randomised message and its signature verify, and the input message is unchanged.
 #[test]
-fn verify_fail_if_signature_is_not_converted() {
+fn verify_ok_with_randomise_and_sign() {
     let mut rng = rand::thread_rng();
     let pp = PublicParams::new(&mut rng);
     let (pk, sk) = pp.key_gen(&mut rng, 10);
     let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
-    let sig = sk.sign(&mut rng, &pp, &message);
+    let original = message.clone();
 
-    let p = Fr::rand(&mut rng);
+    let (randomised, sig) = sk.randomise_and_sign(&mut rng, &pp, &message);
+    assert!(message == original);
+    assert!(randomised != message);
+    assert!(pk.verify(&pp, &randomised, &sig));
+    assert!(!pk.verify(&pp, &message, &sig));
+}
+
+/// Test the signature verifies with the opening of the commitment to the public key, and fails
+/// with a wrong blinding or another public key.
+#[test]
+fn verify_ok_with_commitment_opening() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk, sk) = pp.key_gen(&mut rng, 10);
+    let blinding = Fr::rand(&mut rng);
+    let commitment = commit_public_key(&pk, blinding, &pp);
+
+    let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let sig = sk.sign(&mut rng, &pp, &message);
+    assert!(pk.verify_commitment_opening(&pp, commitment, blinding, &message, &sig));
 
-    let mut pk2 = pk.clone();
-    pk2.convert(p);
-    assert!(pk != pk2);
+    // wrong blinding
+    let wrong_blinding = Fr::rand(&mut rng);
+    assert!(!pk.verify_commitment_opening(&pp, commitment, wrong_blinding, &message, &sig));
 
-    // use the converted keys to verify the original signature
-    assert!(!pk2.verify(&pp, &message, &sig));
+    // another public key with its valid signature
+    let (pk2, sk2) = pp.key_gen(&mut rng, 10);
+    let sig2 = sk2.sign(&mut rng, &pp, &message);
+    assert!(!pk2.verify_commitment_opening(&pp, commitment, blinding, &message, &sig2));
 }
 
+/// Test the debug output of the secret key redacts its elements.
 #[test]
-fn verify_ok_if_key_length_is_greater_than_message_length() {
+fn debug_redacts_secret_key() {
     let mut rng = rand::thread_rng();
     let pp = PublicParams::new(&mut rng);
     let (pk, sk) = pp.key_gen(&mut rng, 10);
+    assert_eq!(format!("{:?}", sk), "SecretKey { x: [REDACTED; 10] }");
+    assert!(format!("{:?}", pk).starts_with("PublicKey { bx: ["));
+}
+
+/// Test the constant time comparison of scalars and of secret keys.
+#[test]
+fn constant_time_eq_of_scalars() {
+    let mut rng = rand::thread_rng();
+    let a = Fr::rand(&mut rng);
+    let b = Fr::rand(&mut rng);
+    assert!(bool::from(CtFr::new(a).ct_eq(&CtFr::new(a))));
+    assert!(!bool::from(CtFr::new(a).ct_eq(&CtFr::new(b))));
 
+    let pp = PublicParams::new(&mut rng);
+    let (_, sk) = pp.key_gen(&mut rng, 10);
+    let mut sk2 = sk.clone();
+    assert!(sk == sk2);
+    sk2.convert(a);
+    assert!(sk != sk2);
+}
+
+/// Test the batch conversion of the public keys is the same as converting each key, also for
+/// keys of different lengths and the scalars zero and one.
+#[test]
+fn batch_convert_public_keys_same_as_convert() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let keys = [1, 5, 10]
+        .into_iter()
+        .map(|size| pp.key_gen(&mut rng, size).0)
+        .collect::<Vec<PublicKey>>();
+
+    for p in [
+        Fr::rand(&mut rng),
+        Fr::from(0u64),
+        Fr::from(1u64),
+        -Fr::from(1u64),
+    ] {
+        let mut batch = keys.clone();
+        PublicKey::batch_convert(&mut batch, p);
+        keys.iter().zip(batch.iter()).for_each(|(pk, converted)| {
+            let mut pk = pk.clone();
+            pk.convert(p);
+            assert!(&pk == converted);
+        });
+    }
+}
+
+/// Test the signature round-trips through a fixed-size array, also a larger one, and an array
+/// smaller than the compressed size is an error.
+#[test]
+fn signature_to_bytes_fixed() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk, sk) = pp.key_gen(&mut rng, 5);
     let message = (0..5).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
     let sig = sk.sign(&mut rng, &pp, &message);
-    assert!(pk.verify(&pp, &message, &sig));
+    assert_eq!(sig.compressed_size(), 192);
+
+    let bytes = sig.to_bytes_fixed::<192>().unwrap();
+    let mut expected = Vec::new();
+    sig.serialize_compressed(&mut expected).unwrap();
+    assert!(bytes[..] == expected[..]);
+    let deserialized = Signature::deserialize_compressed(&bytes[..]).unwrap();
+    assert!(pk.verify(&pp, &message, &deserialized));
+
+    let bytes = sig.to_bytes_fixed::<200>().unwrap();
+    assert!(bytes[192..].iter().all(|b| *b == 0));
+    assert!(Signature::deserialize_compressed(&bytes[..]).unwrap() == sig);
+
+    assert!(sig.to_bytes_fixed::<191>().is_err());
 }
 
-/// Test the conversion function works with the change representation function.
+/// Test the serialized sizes match the number of bytes of the serializations.
 #[test]
-fn verify_ok_with_conversion_and_then_change_representation() {
+fn serialized_sizes_match_serialization() {
+    fn check<T: CanonicalSerialize>(value: &T, size: usize, compressed_size: usize) {
+        let mut bytes = Vec::new();
+        value.serialize_uncompressed(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), size);
+        bytes.clear();
+        value.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), compressed_size);
+    }
+
     let mut rng = rand::thread_rng();
     let pp = PublicParams::new(&mut rng);
-    let (mut pk, mut sk) = pp.key_gen(&mut rng, 10);
-    let mut message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
-    let mut sig = sk.sign(&mut rng, &pp, &message);
-
-    let p = Fr::rand(&mut rng);
-    pk.convert(p);
-    sk.convert(p);
-    sig.convert(&mut rng, p);
+    let (pk, sk) = pp.key_gen(&mut rng, 5);
+    let message = (0..5).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let sig = sk.sign(&mut rng, &pp, &message);
 
-    let u = Fr::rand(&mut rng);
-    change_representation(&mut rng, &mut message, &mut sig, u);
-    assert!(pk.verify(&pp, &message, &sig));
+    check(&pp, pp.serialized_size(), pp.compressed_serialized_size());
+    check(&pk, pk.serialized_size(), pk.compressed_serialized_size());
+    check(&sk, sk.serialized_size(), sk.compressed_serialized_size());
+    check(
+        &sig,
+        sig.serialized_size(),
+        sig.compressed_serialized_size(),
+    );
+    assert!(sig.compressed_serialized_size() < sig.serialized_size());
 }
 
+/// Test the partial verification of `z` agrees with the full verification when `y1, y2` are
+/// valid, also after changing the representation, and does not check `y1`.
 #[test]
-fn verify_ok_with_change_representation_and_then_conversion() {
+fn partial_verify_z_same_as_verify() {
     let mut rng = rand::thread_rng();
     let pp = PublicParams::new(&mut rng);
-    let (mut pk, mut sk) = pp.key_gen(&mut rng, 10);
-    let mut message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let (pk, sk) = pp.key_gen(&mut rng, 5);
+    let mut message = (0..5).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
     let mut sig = sk.sign(&mut rng, &pp, &message);
+    let other = (0..5).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+
+    for _ in 0..2 {
+        let y2 = *sig.y2();
+        assert!(pk.verify(&pp, &message, &sig));
+        assert!(sig.partial_verify_z(&pk, &message, &y2));
+        assert!(!pk.verify(&pp, &other, &sig));
+        assert!(!sig.partial_verify_z(&pk, &other, &y2));
+        let u = Fr::rand(&mut rng);
+        change_representation(&mut rng, &mut message, &mut sig, u);
+    }
+
+    // replace y1, i.e. the bytes after z in the serialization
+    let mut bytes = Vec::new();
+    sig.serialize_compressed(&mut bytes).unwrap();
+    let mut y1 = Vec::new();
+    G1::rand(&mut rng).serialize_compressed(&mut y1).unwrap();
+    bytes[y1.len()..2 * y1.len()].copy_from_slice(&y1);
+    let forged = Signature::deserialize_compressed(&bytes[..]).unwrap();
+    assert!(!pk.verify(&pp, &message, &forged));
+    assert!(forged.partial_verify_z(&pk, &message, forged.y2()));
+}
 
-    let u = Fr::rand(&mut rng);
-    change_representation(&mut rng, &mut message, &mut sig, u);
+/// Test the signatures with the randomness of the operating system verify, also after the
+/// conversion and the change of representation.
+#[cfg(feature = "getrandom")]
+#[test]
+fn default_rng_variants_verify() {
+    use mercurial_signature::randomize_representation_default;
+
+    let pp = PublicParams::new_default();
+    let (mut pk, mut sk) = pp.key_gen_default(5);
+    let mut message = (0..5)
+        .map(|_| G1::rand(&mut rand::rngs::OsRng))
+        .collect::<Vec<G1>>();
+    let mut sig = sk.sign_default(&pp, &message);
+    assert!(pk.verify(&pp, &message, &sig));
 
-    let p = Fr::rand(&mut rng);
+    let p = Fr::rand(&mut rand::rngs::OsRng);
     pk.convert(p);
     sk.convert(p);
-    sig.convert(&mut rng, p);
+    sig.convert_default(p);
+    assert!(pk.verify(&pp, &message, &sig));
 
+    let original = message.clone();
+    randomize_representation_default(&mut message, &mut sig);
+    assert!(message != original);
     assert!(pk.verify(&pp, &message, &sig));
+    assert!(pk.verify(&pp, &message, &sk.sign_default(&pp, &message)));
 }
 
-/// Test the change representation function -
-/// 1. The original message and changed signature should not be able to verify.
-/// 2. The changed message and original signature should not be able to verify.
+/// Test the sum of the keys of 3 parties of an additive threshold scheme signs and verifies as
+/// a single key, and the keys of different lengths are not added.
 #[test]
-fn verify_fail_if_representation_has_not_changed() {
+fn homomorphic_add_of_three_parties_verifies() {
     let mut rng = rand::thread_rng();
     let pp = PublicParams::new(&mut rng);
-    let (pk, sk) = pp.key_gen(&mut rng, 10);
+    let parties = (0..3).map(|_| pp.key_gen(&mut rng, 10)).collect::<Vec<_>>();
+
+    let (mut pk, mut sk) = parties[0].clone();
+    for (pki, ski) in &parties[1..] {
+        pk = pk.homomorphic_add(pki).unwrap();
+        sk = sk.homomorphic_add(ski).unwrap();
+    }
+    assert!(pk == PublicKey::from_noisy_sk(&pp, &sk));
+
     let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
     let sig = sk.sign(&mut rng, &pp, &message);
+    assert!(pk.verify(&pp, &message, &sig));
+    for (pki, _) in &parties {
+        assert!(!pki.verify(&pp, &message, &sig));
+    }
 
-    let u = Fr::rand(&mut rng);
-    let mut message2 = message.clone();
-    let mut sig2 = sig.clone();
-    change_representation(&mut rng, &mut message2, &mut sig2, u);
+    let (short_pk, short_sk) = pp.key_gen(&mut rng, 5);
+    assert_eq!(pk.homomorphic_add(&short_pk), Err(KeyError::LengthMismatch));
+    assert!(sk.homomorphic_add(&short_sk) == Err(KeyError::LengthMismatch));
+}
 
-    // verify the original message and changed signature
-    assert!(!pk.verify(&pp, &message2, &sig));
-    // verify the changed message and original signature
-    assert!(!pk.verify(&pp, &message, &sig2));
+/// Test the reference string is the powers of `p1` by `tau`, and the placeholder verification
+/// checks its first element and the subgroup of its elements.
+#[test]
+fn generate_srs_and_verify_srs() {
+    let mut f = Fixture::new(0);
+    let tau = f.random_scalar();
+    let srs = f.pp.generate_srs(4, tau);
+    assert_eq!(srs.len(), 5);
+    assert_eq!(srs[0], f.pp.p1);
+    assert!(srs.windows(2).all(|w| w[1] == w[0] * tau));
+    assert!(f.pp.verify_srs(&srs));
+
+    assert!(!f.pp.verify_srs(&[]));
+    assert!(!f.pp.verify_srs(&srs[1..]));
+
+    // a point on the curve outside of the prime order subgroup
+    let outside = (1u64..)
+        .filter_map(|x| G1Affine::get_point_from_x_unchecked(x.into(), false))
+        .find(|p| !p.is_in_correct_subgroup_assuming_on_curve())
+        .unwrap();
+    let mut invalid = srs.clone();
+    invalid[2] = outside.into();
+    assert!(!f.pp.verify_srs(&invalid));
+}
+
+/// Test the extended key pair keeps its prefix, verifies the old signatures, signs longer
+/// messages, and round-trips through serialization.
+#[test]
+fn extend_key_keeps_old_signatures() {
+    let mut f = Fixture::new(0);
+    let (mut pk, mut sk) = (f.pk.clone(), f.sk.clone());
+    f.pp.extend_key(&mut f.rng, &mut pk, &mut sk, 15).unwrap();
+    assert_eq!((pk.length(), sk.length()), (15, 15));
+    assert!(pk == PublicKey::from_noisy_sk(&f.pp, &sk));
+    assert!(pk.verify(&f.pp, &f.message, &f.sig));
+
+    let message = f.random_message(15);
+    let sig = sk.sign(&mut f.rng, &f.pp, &message);
+    assert!(pk.verify(&f.pp, &message, &sig));
+    assert!(!f.pk.verify(&f.pp, &message, &sig));
+
+    let mut bytes = Vec::new();
+    pk.serialize_compressed(&mut bytes).unwrap();
+    assert!(PublicKey::deserialize_compressed(bytes.as_slice()).unwrap() == pk);
+    let mut bytes = Vec::new();
+    sk.serialize_compressed(&mut bytes).unwrap();
+    assert!(SecretKey::deserialize_compressed(bytes.as_slice()).unwrap() == sk);
+
+    // the same length is a no-op
+    let (before_pk, before_sk) = (pk.clone(), sk.clone());
+    f.pp.extend_key(&mut f.rng, &mut pk, &mut sk, 15).unwrap();
+    assert!(pk == before_pk && sk == before_sk);
+
+    assert_eq!(
+        f.pp.extend_key(&mut f.rng, &mut pk, &mut sk, 14),
+        Err(KeyError::ShorterLength)
+    );
+    let (_, mut short_sk) = f.pp.key_gen(&mut f.rng, 5);
+    assert_eq!(
+        f.pp.extend_key(&mut f.rng, &mut pk, &mut short_sk, 20),
+        Err(KeyError::LengthMismatch)
+    );
+    assert_eq!(pk.length(), 15);
+}
+
+/// Test a signature on a short message verifies under the truncated key and its view, and a
+/// message longer than the truncation is rejected.
+#[test]
+fn verify_with_truncated_public_key() {
+    let mut f = Fixture::with_length(0, 100);
+    let message = f.random_message(5);
+    let sig = f.sk.sign(&mut f.rng, &f.pp, &message);
+    let long_message = f.random_message(6);
+    let long_sig = f.sk.sign(&mut f.rng, &f.pp, &long_message);
+    assert!(f.pk.verify(&f.pp, &long_message, &long_sig));
+
+    let pk = f.pk.truncated(5);
+    assert_eq!(pk.length(), 5);
+    assert_eq!(pk.elements(), &f.pk.elements()[..5]);
+    assert!(pk.verify(&f.pp, &message, &sig));
+    assert!(!pk.verify(&f.pp, &long_message, &long_sig));
+
+    let view = f.pk.view(5);
+    assert_eq!(view.length(), 5);
+    assert!(view.verify(&f.pp, &message, &sig));
+    assert!(!view.verify(&f.pp, &long_message, &long_sig));
+    assert!(view.to_public_key() == pk);
+}
+
+/// Test the verification with the prepared elements agrees with the verification.
+#[test]
+fn verify_with_prepared_g2_agrees_with_verify() {
+    let mut f = Fixture::new(0);
+    let prepared_bx = f.pk.prepared_elements();
+    let other = f.random_message(10);
+    let sigs = [f.sig.clone(), f.sk.sign(&mut f.rng, &f.pp, &other)];
+
+    for message in [&f.message[..], &other, &f.message[..5], &[]] {
+        for sig in sigs.iter() {
+            assert_eq!(
+                f.pk.verify_with_prepared_g2(&f.pp, message, sig, &prepared_bx),
+                f.pk.verify(&f.pp, message, sig)
+            );
+        }
+    }
+    assert!(f
+        .pk
+        .verify_with_prepared_g2(&f.pp, &f.message, &f.sig, &prepared_bx));
+    assert!(!f
+        .pk
+        .verify_with_prepared_g2(&f.pp, &f.message, &f.sig, &prepared_bx[..9]));
+}
+
+/// Test the same randomness gives the same signature bytes, which verify.
+#[test]
+fn sign_with_randomness_is_deterministic() {
+    let mut f = Fixture::new(0);
+    let y = Fr::rand(&mut f.rng);
+    let serialize = |sig: &Signature| {
+        let mut bytes = Vec::new();
+        sig.serialize_compressed(&mut bytes).unwrap();
+        bytes
+    };
+
+    let sig = f.sk.sign_with_randomness(&f.pp, &f.message, y);
+    assert!(f.pk.verify(&f.pp, &f.message, &sig));
+    assert_eq!(
+        serialize(&sig),
+        serialize(&f.sk.sign_with_randomness(&f.pp, &f.message, y))
+    );
+    let other =
+        f.sk.sign_with_randomness(&f.pp, &f.message, y + Fr::from(1u64));
+    assert!(f.pk.verify(&f.pp, &f.message, &other));
+    assert_ne!(serialize(&sig), serialize(&other));
+}
+
+/// Test verifying a signature against a commitment to the weighted sum of the key, which
+/// fails with the commitment of other weights or of another key.
+#[test]
+fn verify_without_public_key_with_committed_key() {
+    let mut f = Fixture::with_length(7, 5);
+    let r = (0..5).map(|_| f.random_scalar()).collect::<Vec<Fr>>();
+    let message = r.iter().map(|ri| f.pp.p1 * ri).collect::<Vec<G1>>();
+    let sig = f.sk.sign(&mut f.rng, &f.pp, &message);
+    assert!(f.pk.verify(&f.pp, &message, &sig));
+
+    let commit = |pk: &PublicKey, r: &[Fr]| {
+        r.iter()
+            .zip(pk.elements())
+            .map(|(ri, bxi)| *bxi * ri)
+            .sum::<G2>()
+    };
+    assert!(sig.verify_without_public_key(&f.pp, commit(&f.pk, &r)));
+
+    let mut r2 = r.clone();
+    r2.swap(0, 1);
+    assert!(!sig.verify_without_public_key(&f.pp, commit(&f.pk, &r2)));
+    let (pk2, _) = f.pp.key_gen(&mut f.rng, 5);
+    assert!(!sig.verify_without_public_key(&f.pp, commit(&pk2, &r)));
+    assert!(!f.sig.verify_without_public_key(&f.pp, commit(&f.pk, &r)));
+}
+
+/// Test hashing to G1 is deterministic for the same parameters, and differs across parameters
+/// and data.
+#[test]
+fn hash_to_g1_is_bound_to_public_params() {
+    let mut f = Fixture::new(8);
+    let pp2 = PublicParams::new(&mut f.rng);
+    let h = f.pp.with_hash_to_g1(b"data");
+    assert_eq!(h, f.pp.with_hash_to_g1(b"data"));
+    assert_eq!(h, f.pp.clone().with_hash_to_g1(b"data"));
+    assert_ne!(h, pp2.with_hash_to_g1(b"data"));
+    assert_ne!(h, f.pp.with_hash_to_g1(b"other data"));
+    assert!(!h.is_zero());
+}
+
+/// Test writing the secret key to a file and reading it back, and that a truncated file fails.
+#[test]
+fn secret_key_file_round_trip() {
+    let f = Fixture::new(9);
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("issuer.key");
+    f.sk.write_to_file(&path).unwrap();
+    assert!(SecretKey::read_from_file(&path).unwrap() == f.sk);
+
+    // overwrite with another key
+    let (_, sk2) = f.pp.key_gen(&mut Fixture::new(10).rng, 3);
+    sk2.write_to_file(&path).unwrap();
+    assert!(SecretKey::read_from_file(&path).unwrap() == sk2);
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+    let bytes = std::fs::read(&path).unwrap();
+    std::fs::write(&path, &bytes[..bytes.len() - 1]).unwrap();
+    let err = SecretKey::read_from_file(&path).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(SecretKey::read_from_file(&dir.path().join("missing.key")).is_err());
 }
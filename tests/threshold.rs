@@ -0,0 +1,185 @@
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use mercurial_signature::{
+    deal_shares, KeyShare, PublicKey, PublicParams, RandomizedMessage, Signature,
+    ThresholdAggregator, ThresholdError, UniformRand, VerificationShare, G1,
+};
+
+fn setup(
+    rng: &mut (impl rand::RngCore + rand::CryptoRng),
+) -> (
+    PublicParams,
+    PublicKey,
+    Vec<KeyShare>,
+    Vec<VerificationShare>,
+    Vec<G1>,
+) {
+    let pp = PublicParams::new(rng);
+    let (pk, sk) = pp.key_gen(rng, 10);
+    let (shares, vshares) = deal_shares(rng, &pp, &sk, 3, 5).unwrap();
+    let message = (0..10).map(|_| G1::rand(rng)).collect::<Vec<G1>>();
+    (pp, pk, shares, vshares, message)
+}
+
+/// Run the first round with the signers in order, returning the randomized message.
+fn run_round1(
+    rng: &mut (impl rand::RngCore + rand::CryptoRng),
+    aggregator: &mut ThresholdAggregator,
+    shares: &[KeyShare],
+    signers: &[u32],
+) -> RandomizedMessage {
+    let mut running = aggregator.running().clone();
+    for &j in signers.iter() {
+        let msg = shares[j as usize - 1].sign_round1(rng, &running);
+        running = aggregator.receive_round1(&msg).unwrap();
+    }
+    running
+}
+
+/// Test 3-of-5 threshold signing produces a signature verifiable by the public key, with a
+/// fresh nonce in each session.
+#[test]
+fn verify_ok_with_threshold_signature() {
+    let mut rng = rand::thread_rng();
+    let (pp, pk, shares, vshares, message) = setup(&mut rng);
+
+    let mut sigs = Vec::new();
+    for signers in [[1, 2, 3], [2, 4, 5], [1, 3, 5]] {
+        let mut aggregator =
+            ThresholdAggregator::new(&pp, &vshares, &message, &signers, 3).unwrap();
+        let randomized = run_round1(&mut rng, &mut aggregator, &shares, &signers);
+
+        // the round messages are serializable
+        let mut bytes = Vec::new();
+        randomized.serialize_compressed(&mut bytes).unwrap();
+        let randomized = RandomizedMessage::deserialize_compressed(&bytes[..]).unwrap();
+
+        let round2 = signers
+            .iter()
+            .map(|&j| shares[j as usize - 1].sign_round2(&randomized, &signers))
+            .collect::<Vec<_>>();
+        let sig: Signature = aggregator.finalize(&round2).unwrap();
+        assert!(pk.verify(&pp, &message, &sig));
+        sigs.push(sig);
+    }
+    assert!(sigs[0] != sigs[1] && sigs[1] != sigs[2]);
+}
+
+#[test]
+fn threshold_fail_if_not_enough_signers() {
+    let mut rng = rand::thread_rng();
+    let (pp, _, _, vshares, message) = setup(&mut rng);
+
+    let result = ThresholdAggregator::new(&pp, &vshares, &message, &[1, 2], 3);
+    assert_eq!(result.err(), Some(ThresholdError::NotEnoughSigners));
+
+    let result = ThresholdAggregator::new(&pp, &vshares, &message, &[1, 2, 6], 3);
+    assert_eq!(result.err(), Some(ThresholdError::UnknownSigner(6)));
+
+    let (_, sk) = pp.key_gen(&mut rng, 2);
+    for (t, n) in [(0, 5), (6, 5)] {
+        assert_eq!(
+            deal_shares(&mut rng, &pp, &sk, t, n).err(),
+            Some(ThresholdError::InvalidThreshold)
+        );
+    }
+}
+
+/// Test the aggregator identifies the signer who did not send its messages.
+#[test]
+fn threshold_fail_if_message_is_missing() {
+    let mut rng = rand::thread_rng();
+    let (pp, _, shares, vshares, message) = setup(&mut rng);
+    let signers = [1, 2, 3];
+
+    // missing round 1 message from party 3
+    let mut aggregator = ThresholdAggregator::new(&pp, &vshares, &message, &signers, 3).unwrap();
+    let randomized = run_round1(&mut rng, &mut aggregator, &shares, &[1, 2]);
+    let round2 = signers
+        .iter()
+        .map(|&j| shares[j as usize - 1].sign_round2(&randomized, &signers))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        aggregator.finalize(&round2).err(),
+        Some(ThresholdError::MissingMessage(3))
+    );
+
+    // missing round 2 message from party 2
+    let mut aggregator = ThresholdAggregator::new(&pp, &vshares, &message, &signers, 3).unwrap();
+    let randomized = run_round1(&mut rng, &mut aggregator, &shares, &signers);
+    let round2 = [1, 3]
+        .iter()
+        .map(|&j| shares[j as usize - 1].sign_round2(&randomized, &signers))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        aggregator.finalize(&round2).err(),
+        Some(ThresholdError::MissingMessage(2))
+    );
+}
+
+/// Test the aggregator identifies the signer who sent a corrupted message.
+#[test]
+fn threshold_fail_if_message_is_corrupted() {
+    let mut rng = rand::thread_rng();
+    let (pp, _, shares, vshares, message) = setup(&mut rng);
+    let other_message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let signers = [2, 4, 5];
+
+    // party 5 randomizes the running value of another message in round 1
+    let mut aggregator = ThresholdAggregator::new(&pp, &vshares, &message, &signers, 3).unwrap();
+    let other_aggregator =
+        ThresholdAggregator::new(&pp, &vshares, &other_message, &signers, 3).unwrap();
+    run_round1(&mut rng, &mut aggregator, &shares, &[2, 4]);
+    let msg = shares[4].sign_round1(&mut rng, other_aggregator.running());
+    assert_eq!(
+        aggregator.receive_round1(&msg).err(),
+        Some(ThresholdError::InvalidMessage(5))
+    );
+
+    // party 4 signs the message without the nonce in round 2
+    let mut aggregator = ThresholdAggregator::new(&pp, &vshares, &message, &signers, 3).unwrap();
+    let unrandomized = aggregator.running().clone();
+    let randomized = run_round1(&mut rng, &mut aggregator, &shares, &signers);
+    let round2 = signers
+        .iter()
+        .map(|&j| {
+            let m = if j == 4 { &unrandomized } else { &randomized };
+            shares[j as usize - 1].sign_round2(m, &signers)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        aggregator.finalize(&round2).err(),
+        Some(ThresholdError::InvalidMessage(4))
+    );
+}
+
+/// Test all the shares dealt with Feldman commitments are valid against them, and corrupting
+/// the bytes of one share makes its check fail.
+#[test]
+fn key_shares_verify_against_feldman_commitments() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (_, sk) = pp.key_gen(&mut rng, 4);
+    let (shares, commitments) = sk.to_shares_with_proof(&mut rng, &pp, 3, 5).unwrap();
+    assert_eq!(shares.len(), 5);
+    assert_eq!(commitments.len(), 4 * 3);
+    assert!(shares
+        .iter()
+        .all(|share| share.verify_against_commitments(&commitments, &pp)));
+
+    // flip the lowest byte of the first element, after the index and the length prefix
+    let mut bytes = Vec::new();
+    shares[2].serialize_compressed(&mut bytes).unwrap();
+    bytes[4 + 8] ^= 1;
+    let corrupted = KeyShare::deserialize_compressed(&bytes[..]).unwrap();
+    assert!(!corrupted.verify_against_commitments(&commitments, &pp));
+
+    // the commitments of another dealing do not match
+    let (_, other_commitments) = sk.to_shares_with_proof(&mut rng, &pp, 3, 5).unwrap();
+    assert!(!shares[0].verify_against_commitments(&other_commitments, &pp));
+    assert!(!shares[0].verify_against_commitments(&commitments[..11], &pp));
+
+    assert_eq!(
+        sk.to_shares_with_proof(&mut rng, &pp, 6, 5).err(),
+        Some(ThresholdError::InvalidThreshold)
+    );
+}
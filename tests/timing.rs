@@ -0,0 +1,184 @@
+//! Timing tests in the style of dudect, with the feature `timing-tests`:
+//!
+//! ```text
+//! cargo test --release --features timing-tests --test timing -- --nocapture --test-threads 1
+//! ```
+//!
+//! Each test measures an operation on inputs of two classes, interleaved in a random order,
+//! and compares the distributions of the durations by Welch's t-test. The durations above the
+//! 90th percentile are cropped, as they are dominated by interrupts and scheduling. A value of
+//! `|t|` above [THRESHOLD] is evidence that the duration depends on the class.
+//!
+//! Only the comparison of secret keys is claimed to be constant time, and fails above the
+//! threshold. The other operations are measured and reported, to document their current state.
+#![cfg(feature = "timing-tests")]
+
+use std::{hint::black_box, time::Instant};
+
+use mercurial_signature::fixtures::{Fixture, SignatureComponent};
+use mercurial_signature::{Fr, PublicParams, UniformRand, G1};
+use rand::Rng;
+
+/// The threshold of `|t|` of dudect, above which the timing leaks the class.
+const THRESHOLD: f64 = 4.5;
+
+/// Welch's t-test over the durations of two classes, by the online mean and variance of
+/// Welford's algorithm.
+#[derive(Default)]
+struct Welch {
+    n: [f64; 2],
+    mean: [f64; 2],
+    m2: [f64; 2],
+}
+
+impl Welch {
+    fn push(&mut self, class: usize, x: f64) {
+        self.n[class] += 1.0;
+        let delta = x - self.mean[class];
+        self.mean[class] += delta / self.n[class];
+        self.m2[class] += delta * (x - self.mean[class]);
+    }
+
+    fn t(&self) -> f64 {
+        let var = |c: usize| self.m2[c] / (self.n[c] - 1.0);
+        (self.mean[0] - self.mean[1]) / (var(0) / self.n[0] + var(1) / self.n[1]).sqrt()
+    }
+}
+
+/// Measure `op` on `samples` inputs of each class, and return the t-statistic. `input`
+/// prepares an input of the class outside of the measurement.
+fn measure<T>(samples: usize, mut input: impl FnMut(usize) -> T, mut op: impl FnMut(&T)) -> f64 {
+    let mut rng = rand::thread_rng();
+    let mut classes = (0..2 * samples).map(|i| i % 2).collect::<Vec<_>>();
+    for i in (1..classes.len()).rev() {
+        classes.swap(i, rng.gen_range(0..=i));
+    }
+
+    let durations = classes
+        .iter()
+        .map(|&class| {
+            let x = input(class);
+            let start = Instant::now();
+            op(black_box(&x));
+            (class, start.elapsed().as_nanos() as f64)
+        })
+        .collect::<Vec<_>>();
+
+    let mut sorted = durations.iter().map(|(_, d)| *d).collect::<Vec<_>>();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let crop = sorted[sorted.len() * 9 / 10];
+
+    let mut welch = Welch::default();
+    durations
+        .iter()
+        .filter(|(_, d)| *d <= crop)
+        .for_each(|(class, d)| welch.push(*class, *d));
+    welch.t()
+}
+
+fn report(name: &str, t: f64) {
+    println!("{}: t = {:.2} (threshold {})", name, t, THRESHOLD);
+}
+
+/// Test comparing secret keys takes the same time for equal and unequal keys.
+#[test]
+fn secret_key_equality_is_constant_time() {
+    let f = Fixture::with_length(1, 10);
+    let equal = f.sk.clone();
+    let mut unequal = f.sk.clone();
+    unequal.convert(Fr::from(2u64));
+
+    let t = measure(
+        20000,
+        |class| if class == 0 { &equal } else { &unequal },
+        |sk| {
+            black_box(f.sk == **sk);
+        },
+    );
+    report("secret key equality, equal vs unequal", t);
+    assert!(t.abs() < THRESHOLD);
+}
+
+/// Report the time of converting a secret key by a small or a large scalar.
+#[test]
+fn report_convert_small_vs_large_scalar() {
+    let mut f = Fixture::with_length(2, 10);
+    let large = f.random_scalar();
+    let sk = f.sk.clone();
+
+    let t = measure(
+        20000,
+        |class| {
+            let p = if class == 0 { Fr::from(2u64) } else { large };
+            (sk.clone(), p)
+        },
+        |(sk, p)| {
+            let mut sk = sk.clone();
+            sk.convert(*p);
+            black_box(sk);
+        },
+    );
+    report("convert, small vs large scalar", t);
+}
+
+/// Report the time of signing a fixed message or random messages.
+#[test]
+fn report_sign_fixed_vs_random_message() {
+    let mut f = Fixture::with_length(3, 5);
+    let mut rng = rand::thread_rng();
+    let messages = (0..64).map(|_| f.random_message(5)).collect::<Vec<_>>();
+
+    let t = measure(
+        1000,
+        |class| {
+            if class == 0 {
+                f.message.clone()
+            } else {
+                messages[rng.gen_range(0..messages.len())].clone()
+            }
+        },
+        |message| {
+            black_box(f.sk.sign(&mut rand::thread_rng(), &f.pp, message));
+        },
+    );
+    report("sign, fixed vs random message", t);
+}
+
+/// Report the time of rejecting a signature at the first or the second equation of the
+/// verification.
+#[test]
+fn report_verify_early_vs_late_fail() {
+    let f = Fixture::with_length(4, 5);
+    let early = f.corrupt_signature(SignatureComponent::Y1);
+    let late = f.corrupt_signature(SignatureComponent::Z);
+
+    let t = measure(
+        500,
+        |class| if class == 0 { &early } else { &late },
+        |sig| {
+            black_box(f.pk.verify(&f.pp, &f.message, sig));
+        },
+    );
+    report("verify, early vs late fail", t);
+}
+
+/// Test the t-test detects an operation whose time depends on the class, as a check of the
+/// harness.
+#[test]
+fn harness_detects_variable_time() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let g = G1::rand(&mut rng);
+
+    let t = measure(
+        200,
+        |class| if class == 0 { 1 } else { 4 },
+        |repeat| {
+            for _ in 0..*repeat {
+                let _ = black_box(g * Fr::rand(&mut rand::thread_rng()) + pp.p1);
+            }
+        },
+    );
+    report("harness, 1 vs 4 scalar multiplications", t);
+    assert!(t.abs() > THRESHOLD);
+}
@@ -0,0 +1,77 @@
+use ark_bls12_381::Bls12_381;
+use mercurial_signature::{Fr, Transcript, UniformRand, G1, G2};
+
+fn challenge_of(domain: &[u8], entries: &[(&[u8], &[u8])]) -> Fr {
+    let mut transcript = Transcript::new(domain);
+    for (label, bytes) in entries {
+        transcript.append_bytes(label, bytes);
+    }
+    transcript.challenge_scalar::<Bls12_381>(b"challenge")
+}
+
+/// Test the challenge is deterministic, and depends on the domain, the labels, the order and
+/// the framing of the messages.
+#[test]
+fn transcript_challenge_depends_on_labels_and_order() {
+    let c = challenge_of(b"mercurial-signature/test", &[(b"a", b"1"), (b"b", b"2")]);
+    assert_eq!(
+        c,
+        challenge_of(b"mercurial-signature/test", &[(b"a", b"1"), (b"b", b"2")])
+    );
+
+    // different domain
+    assert_ne!(
+        c,
+        challenge_of(b"mercurial-signature/other", &[(b"a", b"1"), (b"b", b"2")])
+    );
+    // different labels
+    assert_ne!(
+        c,
+        challenge_of(b"mercurial-signature/test", &[(b"a", b"1"), (b"c", b"2")])
+    );
+    // different order
+    assert_ne!(
+        c,
+        challenge_of(b"mercurial-signature/test", &[(b"b", b"2"), (b"a", b"1")])
+    );
+    // same concatenation of bytes, different framing
+    assert_ne!(
+        c,
+        challenge_of(b"mercurial-signature/test", &[(b"a", b"12"), (b"b", b"")])
+    );
+}
+
+/// Test the consecutive challenges are different, and the typed messages change the challenge.
+#[test]
+fn transcript_challenges_are_chained() {
+    let mut rng = rand::thread_rng();
+    let p1 = G1::rand(&mut rng);
+    let p2 = G2::rand(&mut rng);
+    let s = Fr::rand(&mut rng);
+
+    let mut transcript = Transcript::new(b"mercurial-signature/test");
+    transcript.append_point_g1::<Bls12_381>(b"p1", &p1);
+    transcript.append_point_g2::<Bls12_381>(b"p2", &p2);
+    transcript.append_scalar::<Bls12_381>(b"s", &s);
+    let c1 = transcript
+        .clone()
+        .challenge_scalar::<Bls12_381>(b"challenge");
+    let c2 = transcript.challenge_scalar::<Bls12_381>(b"challenge");
+    assert_eq!(c1, c2);
+    let c3 = transcript.challenge_scalar::<Bls12_381>(b"challenge");
+    assert_ne!(c2, c3);
+
+    let mut transcript = Transcript::new(b"mercurial-signature/test");
+    transcript.append_point_g1::<Bls12_381>(b"p1", &p1);
+    transcript.append_point_g2::<Bls12_381>(b"p2", &p2);
+    transcript.append_scalar::<Bls12_381>(b"s", &(s + Fr::from(1u64)));
+    assert_ne!(c1, transcript.challenge_scalar::<Bls12_381>(b"challenge"));
+}
+
+/// Test the labels out of the convention are rejected.
+#[test]
+#[should_panic]
+fn transcript_rejects_uppercase_label() {
+    let mut transcript = Transcript::new(b"mercurial-signature/test");
+    transcript.append_bytes(b"Label", b"");
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "test-utils")]
+
+use mercurial_signature::{
+    change_representation, prove_same_message, take_recorded_transcripts, Fr, ProofOfPossession,
+    PublicParams, UniformRand, G1,
+};
+
+/// Test the transcripts of two different proofs replay in their own domains only, so that a
+/// proof of one kind cannot be confused for the other.
+#[test]
+fn recorded_transcripts_replay_only_in_their_domain() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk_a, sk_a) = pp.key_gen(&mut rng, 5);
+    let (pk_b, sk_b) = pp.key_gen(&mut rng, 5);
+    let msg_a = (0..5).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let sig_a = sk_a.sign(&mut rng, &pp, &msg_a);
+    let mut msg_b = msg_a.clone();
+    let mut sig_b = sk_b.sign(&mut rng, &pp, &msg_b);
+    let k = Fr::rand(&mut rng);
+    change_representation(&mut rng, &mut msg_b, &mut sig_b, k);
+    take_recorded_transcripts();
+
+    let pop = ProofOfPossession::prove(&mut rng, &pp, &sk_a);
+    let proof = prove_same_message(
+        &mut rng,
+        &pp,
+        (&pk_a, &sig_a, &msg_a),
+        (&pk_b, &sig_b, &msg_b),
+        k,
+        b"nonce",
+    );
+    let recorded = take_recorded_transcripts();
+    assert_eq!(recorded.len(), 2);
+    let (pop_transcript, same_message_transcript) = (&recorded[0], &recorded[1]);
+    assert_eq!(
+        pop_transcript.domain,
+        b"mercurial-signature/proof-of-possession"
+    );
+    assert_eq!(
+        same_message_transcript.domain,
+        b"mercurial-signature/same-message"
+    );
+
+    assert!(pop_transcript.replay(&pop_transcript.domain));
+    assert!(same_message_transcript.replay(&same_message_transcript.domain));
+    assert!(!pop_transcript.replay(&same_message_transcript.domain));
+    assert!(!same_message_transcript.replay(&pop_transcript.domain));
+
+    // the verifiers derive the same transcripts again
+    assert!(pop.verify(&pp, &pk_a));
+    assert!(proof.verify(&pp, &pk_a, &pk_b, b"nonce"));
+    assert_eq!(take_recorded_transcripts(), recorded);
+}
@@ -0,0 +1,34 @@
+use ark_ff::Field;
+use ark_std::{test_rng, UniformRand, Zero};
+use mercurial_signature::{utils::batch_inverse, Fr};
+
+/// Test the batch inverses are the inverses of the scalars one by one.
+#[test]
+fn batch_inverse_matches_naive_inversion() {
+    let mut rng = test_rng();
+    for n in [0, 1, 2, 10, 33] {
+        let scalars = (0..n).map(|_| Fr::rand(&mut rng)).collect::<Vec<Fr>>();
+        let naive = scalars
+            .iter()
+            .map(|s| s.inverse().unwrap())
+            .collect::<Vec<Fr>>();
+        assert_eq!(batch_inverse(&scalars), naive);
+    }
+}
+
+/// Test a zero scalar is mapped to zero without affecting the other inverses.
+#[test]
+fn batch_inverse_maps_zero_to_zero() {
+    let mut rng = test_rng();
+    let mut scalars = (0..8).map(|_| Fr::rand(&mut rng)).collect::<Vec<Fr>>();
+    scalars[0] = Fr::zero();
+    scalars[3] = Fr::zero();
+    scalars[7] = Fr::zero();
+    let naive = scalars
+        .iter()
+        .map(|s| s.inverse().unwrap_or(Fr::zero()))
+        .collect::<Vec<Fr>>();
+    assert_eq!(batch_inverse(&scalars), naive);
+
+    assert_eq!(batch_inverse(&[Fr::zero(); 3]), vec![Fr::zero(); 3]);
+}
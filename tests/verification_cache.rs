@@ -0,0 +1,131 @@
+#![cfg(feature = "verification-cache")]
+
+use std::{sync::Arc, thread, time::Duration};
+
+use mercurial_signature::{
+    fixtures::{Fixture, SignatureComponent},
+    verify_cached, verify_cached_g2, VerificationCache,
+};
+
+/// Test the hits and the misses, for valid and invalid results of both schemes.
+#[test]
+fn verification_cache_hits_and_misses() {
+    let f = Fixture::with_length(1, 3);
+    let cache = VerificationCache::new(100);
+    let invalid = f.corrupt_signature(SignatureComponent::Z);
+
+    assert!(verify_cached(&cache, &f.pp, &f.pk, &f.message, &f.sig));
+    assert!(!verify_cached(&cache, &f.pp, &f.pk, &f.message, &invalid));
+    assert!(verify_cached_g2(
+        &cache,
+        &f.pp,
+        &f.pk_g1,
+        &f.message_g2,
+        &f.sig_g2
+    ));
+    assert_eq!((cache.hits(), cache.misses(), cache.len()), (0, 3, 3));
+
+    // the negative result is cached too
+    assert!(verify_cached(&cache, &f.pp, &f.pk, &f.message, &f.sig));
+    assert!(!verify_cached(&cache, &f.pp, &f.pk, &f.message, &invalid));
+    assert!(verify_cached_g2(
+        &cache,
+        &f.pp,
+        &f.pk_g1,
+        &f.message_g2,
+        &f.sig_g2
+    ));
+    assert_eq!((cache.hits(), cache.misses(), cache.len()), (3, 3, 3));
+
+    cache.clear();
+    assert!(cache.is_empty());
+    assert!(verify_cached(&cache, &f.pp, &f.pk, &f.message, &f.sig));
+    assert_eq!((cache.hits(), cache.misses()), (3, 4));
+}
+
+/// Test a tampered signature, message or key misses the cached result of the original.
+#[test]
+fn verification_cache_no_false_hit() {
+    let mut f = Fixture::with_length(2, 3);
+    let cache = VerificationCache::new(100);
+    assert!(verify_cached(&cache, &f.pp, &f.pk, &f.message, &f.sig));
+
+    for which in [
+        SignatureComponent::Z,
+        SignatureComponent::Y1,
+        SignatureComponent::Y2,
+    ] {
+        let tampered = f.corrupt_signature(which);
+        assert!(!verify_cached(&cache, &f.pp, &f.pk, &f.message, &tampered));
+    }
+    let message = f.random_message(3);
+    assert!(!verify_cached(&cache, &f.pp, &f.pk, &message, &f.sig));
+    assert!(!verify_cached(
+        &cache,
+        &f.pp,
+        &f.pk,
+        &f.message[..2],
+        &f.sig
+    ));
+    let other = Fixture::with_length(3, 3);
+    assert!(!verify_cached(&cache, &f.pp, &other.pk, &f.message, &f.sig));
+    assert_eq!((cache.hits(), cache.misses()), (0, 7));
+}
+
+/// Test the least recently used result is evicted, and an expired result is verified again.
+#[test]
+fn verification_cache_eviction_and_ttl() {
+    let fixtures = (0..3)
+        .map(|seed| Fixture::with_length(seed, 2))
+        .collect::<Vec<_>>();
+    let verify = |cache: &VerificationCache, f: &Fixture| {
+        assert!(verify_cached(cache, &f.pp, &f.pk, &f.message, &f.sig));
+    };
+
+    let cache = VerificationCache::new(2);
+    verify(&cache, &fixtures[0]);
+    verify(&cache, &fixtures[1]);
+    verify(&cache, &fixtures[0]);
+    // evicts the result of fixtures[1]
+    verify(&cache, &fixtures[2]);
+    assert_eq!(cache.len(), 2);
+    assert_eq!((cache.hits(), cache.misses()), (1, 3));
+    verify(&cache, &fixtures[0]);
+    verify(&cache, &fixtures[2]);
+    assert_eq!((cache.hits(), cache.misses()), (3, 3));
+    verify(&cache, &fixtures[1]);
+    assert_eq!((cache.hits(), cache.misses()), (3, 4));
+
+    let cache = VerificationCache::with_ttl(2, Duration::from_millis(50));
+    verify(&cache, &fixtures[0]);
+    verify(&cache, &fixtures[0]);
+    thread::sleep(Duration::from_millis(100));
+    verify(&cache, &fixtures[0]);
+    assert_eq!((cache.hits(), cache.misses()), (1, 2));
+}
+
+/// Test the results and the accounting under concurrent verifications.
+#[test]
+fn verification_cache_concurrent() {
+    let f = Arc::new(Fixture::with_length(4, 2));
+    let invalid = Arc::new(f.corrupt_signature(SignatureComponent::Y1));
+    let cache = Arc::new(VerificationCache::new(1000));
+
+    let threads = (0..4)
+        .map(|_| {
+            let (f, invalid, cache) = (f.clone(), invalid.clone(), cache.clone());
+            thread::spawn(move || {
+                for _ in 0..5 {
+                    assert!(verify_cached(&cache, &f.pp, &f.pk, &f.message, &f.sig));
+                    assert!(!verify_cached(&cache, &f.pp, &f.pk, &f.message, &invalid));
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for t in threads {
+        t.join().unwrap();
+    }
+    assert_eq!(cache.hits() + cache.misses(), 40);
+    assert!(cache.misses() >= 2);
+    assert_eq!(cache.len(), 2);
+}
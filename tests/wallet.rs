@@ -0,0 +1,115 @@
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use mercurial_signature::fixtures::Fixture;
+use mercurial_signature::{Wallet, WalletError};
+
+/// Test the lookup finds the credentials of an issuer in any representation of its key, and
+/// not those of another issuer.
+#[test]
+fn find_by_issuer_class_across_representations() {
+    let mut f = Fixture::with_length(1, 3);
+    let (pk2, sk2) = f.pp.key_gen(&mut f.rng, 3);
+    let mut wallet = Wallet::new(&mut f.rng);
+    for _ in 0..2 {
+        let sig = f.sk.sign(&mut f.rng, &f.pp, &f.message);
+        let pk_g1 = f.sk.public_key_g1(&f.pp);
+        wallet
+            .insert(&f.pp, f.message.clone(), sig, f.pk.clone(), pk_g1)
+            .unwrap();
+    }
+    let message2 = f.random_message(3);
+    let sig2 = sk2.sign(&mut f.rng, &f.pp, &message2);
+    let pk2_g1 = sk2.public_key_g1(&f.pp);
+    wallet
+        .insert(&f.pp, message2.clone(), sig2, pk2.clone(), pk2_g1)
+        .unwrap();
+    assert_eq!(wallet.length(), 3);
+    assert_eq!(wallet.issuer_classes(), 2);
+
+    // the issuer converts its keys and issues another credential
+    let p = f.random_scalar();
+    let f2 = f.converted_copy(p);
+    let sig = f2.sk.sign(&mut f.rng, &f.pp, &f.message);
+    let pk_g1 = f2.sk.public_key_g1(&f.pp);
+    wallet
+        .insert(&f.pp, f.message.clone(), sig, f2.pk.clone(), pk_g1)
+        .unwrap();
+    assert_eq!(wallet.issuer_classes(), 2);
+
+    let mut pk = f.pk.clone();
+    pk.convert(f.random_scalar());
+    for pk in [&f.pk, &f2.pk, &pk] {
+        let found = wallet.find_by_issuer_class(pk).collect::<Vec<_>>();
+        assert_eq!(found.len(), 3);
+        assert!(found
+            .iter()
+            .all(|credential| credential.message() == f.message));
+    }
+    let found = wallet.find_by_issuer_class(&pk2).collect::<Vec<_>>();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].message(), message2);
+
+    // a foreign issuer, and keys of other lengths
+    let (foreign, _) = f.pp.key_gen(&mut f.rng, 3);
+    assert_eq!(wallet.find_by_issuer_class(&foreign).count(), 0);
+    assert_eq!(wallet.find_by_issuer_class(&f.pk.truncated(2)).count(), 0);
+}
+
+/// Test inserting an invalid credential or mismatching keys fails.
+#[test]
+fn insert_rejects_invalid_credentials() {
+    let mut f = Fixture::with_length(2, 3);
+    let (_, sk2) = f.pp.key_gen(&mut f.rng, 3);
+    let mut wallet = Wallet::new(&mut f.rng);
+    let other_message = f.random_message(3);
+    let pk_g1 = f.sk.public_key_g1(&f.pp);
+    assert_eq!(
+        wallet.insert(&f.pp, other_message, f.sig.clone(), f.pk.clone(), pk_g1),
+        Err(WalletError::InvalidCredential)
+    );
+    let pk2_g1 = sk2.public_key_g1(&f.pp);
+    assert_eq!(
+        wallet.insert(
+            &f.pp,
+            f.message.clone(),
+            f.sig.clone(),
+            f.pk.clone(),
+            pk2_g1
+        ),
+        Err(WalletError::KeyMismatch)
+    );
+    assert_eq!(wallet.length(), 0);
+}
+
+/// Test the credentials verify after randomizing all of them, and the wallet round trips
+/// through its serialization.
+#[test]
+fn randomize_all_and_persist() {
+    let mut f = Fixture::with_length(3, 3);
+    let (pk2, sk2) = f.pp.key_gen(&mut f.rng, 3);
+    let mut wallet = Wallet::new(&mut f.rng);
+    let pk_g1 = f.sk.public_key_g1(&f.pp);
+    wallet
+        .insert(&f.pp, f.message.clone(), f.sig.clone(), f.pk.clone(), pk_g1)
+        .unwrap();
+    let message2 = f.random_message(2);
+    let sig2 = sk2.sign(&mut f.rng, &f.pp, &message2);
+    let pk2_g1 = sk2.public_key_g1(&f.pp);
+    wallet
+        .insert(&f.pp, message2, sig2, pk2.clone(), pk2_g1)
+        .unwrap();
+
+    let before = wallet.iter().cloned().collect::<Vec<_>>();
+    wallet.randomize_all(&mut f.rng);
+    assert!(wallet.iter().all(|credential| credential.verify(&f.pp)));
+    assert!(wallet.iter().zip(before.iter()).all(|(a, b)| a != b));
+
+    let mut bytes = Vec::new();
+    wallet.serialize_compressed(&mut bytes).unwrap();
+    assert_eq!(bytes.len(), wallet.compressed_size());
+    let read = Wallet::deserialize_compressed(&bytes[..]).unwrap();
+    assert!(read == wallet);
+    assert_eq!(read.find_by_issuer_class(&f.pk).count(), 1);
+    assert_eq!(read.find_by_issuer_class(&pk2).count(), 1);
+
+    assert!(Wallet::deserialize_compressed(&bytes[..bytes.len() - 1]).is_err());
+}
@@ -0,0 +1,83 @@
+#![cfg(feature = "wasm")]
+
+use mercurial_signature::{
+    random_message, random_scalar, randomize_representation_default, PublicParams, UniformRand,
+    WasmKeypair, WasmPublicKey, WasmPublicParams, WasmSignature, G1,
+};
+
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+/// Test the full cycle of sign, convert, change of representation and verify through the
+/// bindings, with the values passed as bytes.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn wasm_sign_convert_change_representation_verify() {
+    let pp = WasmPublicParams::from_bytes(&WasmPublicParams::new().to_bytes()).unwrap();
+    let mut keypair = WasmKeypair::new(&pp, 5);
+    let message = random_message(5);
+    let sig = keypair.sign(&pp, &message).unwrap();
+    let mut sig = WasmSignature::from_bytes(&sig.to_bytes()).unwrap();
+    assert!(keypair.verify(&pp, &message, &sig).unwrap());
+
+    let p = random_scalar();
+    keypair.convert(&p).unwrap();
+    sig.convert(&p).unwrap();
+    assert!(keypair.verify(&pp, &message, &sig).unwrap());
+
+    let new_message = sig
+        .change_representation(&message, &random_scalar())
+        .unwrap();
+    assert!(new_message != message);
+    let pk = WasmPublicKey::from_bytes(&keypair.public_key().to_bytes()).unwrap();
+    assert!(pk.verify(&pp, &new_message, &sig).unwrap());
+    assert!(!pk.verify(&pp, &message, &sig).unwrap());
+}
+
+/// Test the variants with the randomness of the operating system, which comes from the
+/// `crypto` API of the browser in wasm.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn default_rng_variants_verify() {
+    let pp = PublicParams::new_default();
+    let (pk, sk) = pp.key_gen_default(3);
+    let mut message = (0..3)
+        .map(|_| G1::rand(&mut rand::rngs::OsRng))
+        .collect::<Vec<G1>>();
+    let mut sig = sk.sign_default(&pp, &message);
+    randomize_representation_default(&mut message, &mut sig);
+    assert!(pk.verify(&pp, &message, &sig));
+}
+
+/// Test the invalid inputs are returned as errors instead of panics.
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen_test::wasm_bindgen_test]
+fn wasm_invalid_inputs_are_errors() {
+    let pp = WasmPublicParams::new();
+    let keypair = WasmKeypair::new(&pp, 2);
+    assert!(WasmPublicParams::from_bytes(&[1, 2, 3]).is_err());
+    assert!(keypair.sign(&pp, &random_message(3)).is_err());
+    assert!(keypair.sign(&pp, &[0xff; 10]).is_err());
+}
+
+/// Test a message whose length prefix is far longer than the input throws instead of trapping
+/// the instance on the allocation.
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen_test::wasm_bindgen_test]
+fn wasm_oversized_message_prefix_is_error() {
+    let pp = WasmPublicParams::new();
+    let keypair = WasmKeypair::new(&pp, 2);
+    let valid = random_message(2);
+    let mut sig = keypair.sign(&pp, &valid).unwrap();
+
+    // the prefix claims 2^40 elements, followed by two
+    let mut message = (1u64 << 40).to_le_bytes().to_vec();
+    message.extend_from_slice(&valid[8..]);
+
+    assert!(keypair.sign(&pp, &message).is_err());
+    assert!(keypair.verify(&pp, &message, &sig).is_err());
+    assert!(keypair.public_key().verify(&pp, &message, &sig).is_err());
+    assert!(sig
+        .change_representation(&message, &random_scalar())
+        .is_err());
+}
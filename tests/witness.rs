@@ -0,0 +1,71 @@
+use ark_serialize::CanonicalSerialize;
+use mercurial_signature::{PublicParams, UniformRand, VerifyWitness, G1};
+use rand::{rngs::StdRng, SeedableRng};
+
+/// The deterministic generator of `ark_std::test_rng`, whose return type does not expose that
+/// it is a `CryptoRng`.
+fn test_rng() -> StdRng {
+    StdRng::from_seed([
+        1, 0, 0, 0, 23, 0, 0, 0, 200, 1, 0, 0, 210, 30, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0,
+    ])
+}
+
+/// Test recomputing the verification equations from the witness reproduces the decision
+/// of the verification, for both valid and invalid signatures.
+#[test]
+fn witness_reproduces_verification_decision() {
+    let mut rng = rand::thread_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk, sk) = pp.key_gen(&mut rng, 10);
+    let message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let other_message = (0..10).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let sig = sk.sign(&mut rng, &pp, &message);
+
+    for m in [&message, &other_message] {
+        let witness = VerifyWitness::compute(&pp, &pk, m, &sig).unwrap();
+        assert!(witness.is_consistent());
+        assert_eq!(witness.accepts(), pk.verify(&pp, m, &sig));
+    }
+
+    // the key is shorter than the message
+    let (pk2, _) = pp.key_gen(&mut rng, 5);
+    assert!(VerifyWitness::compute(&pp, &pk2, &message, &sig).is_none());
+}
+
+/// Test the order of the field elements of the witness is stable, against the golden file
+/// `tests/golden/verify_witness.txt`. Set the environment variable `UPDATE_GOLDEN` to regenerate it.
+#[test]
+fn witness_field_elements_match_golden_file() {
+    let mut rng = test_rng();
+    let pp = PublicParams::new(&mut rng);
+    let (pk, sk) = pp.key_gen(&mut rng, 3);
+    let message = (0..3).map(|_| G1::rand(&mut rng)).collect::<Vec<G1>>();
+    let sig = sk.sign(&mut rng, &pp, &message);
+
+    let witness = VerifyWitness::compute(&pp, &pk, &message, &sig).unwrap();
+    let lines = witness
+        .to_field_elements()
+        .unwrap()
+        .iter()
+        .map(|e| {
+            let mut bytes = Vec::new();
+            e.serialize_compressed(&mut bytes).unwrap();
+            bytes
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/golden/verify_witness.txt"
+    );
+    if std::env::var("UPDATE_GOLDEN").is_ok() {
+        std::fs::write(path, &lines).unwrap();
+    }
+    let golden = std::fs::read_to_string(path).unwrap();
+    assert_eq!(golden, lines);
+}